@@ -76,6 +76,16 @@ pub fn show_config() -> Result<()> {
 pub fn set_league_path(path: String) -> Result<()> {
     let path = Utf8PathBuf::from(&path);
     if !league_path::is_valid_league_path(path.as_path()) {
+        if league_path::is_riot_client_path(path.as_path()) {
+            eprintln!(
+                "  {}",
+                "This looks like the Riot Client. Point to 'League of Legends.exe' in the League of Legends Game folder instead."
+                    .bright_yellow()
+            );
+            return Err(miette::miette!(
+                "Path is the Riot Client, not League of Legends"
+            ));
+        }
         eprintln!(
             "  {}",
             "The path must point to 'League of Legends.exe' in the Game folder.".bright_yellow()
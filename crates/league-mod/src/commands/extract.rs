@@ -128,6 +128,12 @@ fn extract_fantome_package(args: ExtractModPackageArgs) -> Result<()> {
 
 /// Map FantomeExtractError to CliError for user-friendly error messages.
 fn map_fantome_error(err: FantomeExtractError) -> CliError {
+    if err.is_invalid_archive() {
+        return CliError::InvalidFantomeArchive {
+            message: err.to_string(),
+        };
+    }
+
     match err {
         FantomeExtractError::Wad(e) => CliError::WadExtractionFailed {
             message: e.to_string(),
@@ -136,11 +142,17 @@ fn map_fantome_error(err: FantomeExtractError) -> CliError {
         FantomeExtractError::Zip(e) => CliError::IoError {
             source: std::io::Error::other(e),
         },
-        FantomeExtractError::Json(e) => CliError::IoError {
+        FantomeExtractError::Thumbnail(e) => CliError::IoError {
+            source: std::io::Error::other(e),
+        },
+        FantomeExtractError::ModpkgBuilder(e) => CliError::IoError {
             source: std::io::Error::other(e),
         },
-        FantomeExtractError::MissingMetadata => CliError::IoError {
-            source: std::io::Error::other("Missing info.json metadata file"),
+        FantomeExtractError::Json(_)
+        | FantomeExtractError::MissingMetadata
+        | FantomeExtractError::InvalidWadPath { .. }
+        | FantomeExtractError::InvalidLayerName { .. } => CliError::InvalidFantomeArchive {
+            message: err.to_string(),
         },
     }
 }
@@ -2,7 +2,7 @@ use std::fs::File;
 
 use crate::println_pad;
 use colored::Colorize;
-use ltk_modpkg::Modpkg;
+use ltk_modpkg::{utils::format_size, Modpkg};
 use miette::IntoDiagnostic;
 use serde_json::to_string_pretty;
 
@@ -35,6 +35,22 @@ pub fn info_mod_package(args: InfoModPackageArgs) -> miette::Result<()> {
             .bright_white()
     );
 
+    let (compressed_size, uncompressed_size) =
+        modpkg
+            .iter_chunks()
+            .fold((0u64, 0u64), |(compressed, uncompressed), chunk| {
+                (
+                    compressed + chunk.compressed_size,
+                    uncompressed + chunk.uncompressed_size,
+                )
+            });
+    println_pad!(
+        "{} {} {}",
+        "💾 Size:".bright_green(),
+        format_size(uncompressed_size).bright_white().bold(),
+        format!("({} compressed)", format_size(compressed_size)).dimmed()
+    );
+
     println_pad!("\n{}", "🏗️  Layers:".bright_magenta().bold());
     for layer in modpkg.layers.values() {
         // Try to find a matching layer metadata entry (to show display_name/description).
@@ -68,6 +84,11 @@ pub fn info_mod_package(args: InfoModPackageArgs) -> miette::Result<()> {
         }
     }
 
+    if let Ok(changelog) = modpkg.load_changelog() {
+        println_pad!("\n{}", "📜 Changelog:".bright_magenta().bold());
+        println_pad!("{}", String::from_utf8_lossy(&changelog).trim_end());
+    }
+
     println_pad!("\n{}", "🧾 Full metadata (JSON):".bright_magenta().bold());
     println_pad!("{}", pretty_metadata);
 
@@ -4,11 +4,11 @@ use std::{
 };
 
 use colored::Colorize;
-use ltk_mod_project::{ModProject, ModProjectAuthor};
+use ltk_mod_project::{is_valid_slug, ModProject, ModProjectAuthor};
 use miette::IntoDiagnostic;
 
 use crate::println_pad;
-use crate::utils::{is_valid_slug, validate_mod_name};
+use crate::utils::validate_mod_name;
 use inquire::{validator::Validation, Text};
 
 #[derive(Debug, Clone)]
@@ -102,7 +102,9 @@ fn create_default_mod_project(name: Option<String>, display_name: Option<String>
         tags: vec![],
         champions: vec![],
         maps: vec![],
+        dependencies: vec![],
         transformers: vec![],
+        compression: Default::default(),
         layers: ltk_mod_project::default_layers(),
         thumbnail: None,
     }
@@ -118,7 +120,7 @@ pub fn prompt_mod_name(suggested_name: impl AsRef<str>) -> miette::Result<String
             Ok(Validation::Valid)
         } else {
             Ok(Validation::Invalid(
-                "Mod name must be alphanumeric and can only contain hyphens (no spaces or special characters)".into()
+                "Mod name can only contain letters, digits, '_' and '-' (no spaces or special characters)".into()
             ))
         }
     };
@@ -0,0 +1,210 @@
+use super::pack::{load_config, resolve_correct_config_extension};
+use crate::println_pad;
+use crate::utils::{self, config};
+use camino::{Utf8Path, Utf8PathBuf};
+use colored::Colorize;
+use ltk_mod_project::{canonical_champion, ModMap, ModProject, ValidationSeverity};
+use ltk_modpkg::project as modpkg_project;
+use ltk_overlay::{EnabledMod, FsModContent, OverlayBuilder};
+use miette::{miette, IntoDiagnostic, Result, WrapErr};
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct LintModProjectArgs {
+    pub project_dir: Option<String>,
+    pub game_dir: Option<String>,
+}
+
+/// Findings collected while linting, in the order they were found.
+#[derive(Debug, Default)]
+struct Lints {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+pub fn lint_mod_project(args: LintModProjectArgs) -> Result<()> {
+    let project_dir = match args.project_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir().into_diagnostic()?,
+    };
+    let config_path = resolve_correct_config_extension(&project_dir)?;
+    let mod_project = load_config(&config_path)?;
+    let project_root = Utf8PathBuf::try_from(config_path.parent().unwrap().to_path_buf())
+        .into_diagnostic()
+        .wrap_err("Project path is not valid UTF-8")?;
+
+    println_pad!(
+        "{} {}",
+        "🔍 Linting mod project:".bright_blue().bold(),
+        mod_project.name.bright_cyan().bold()
+    );
+
+    let mut lints = Lints::default();
+    lints.warnings.extend(
+        mod_project
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == ValidationSeverity::Warning)
+            .map(|issue| issue.message),
+    );
+    // Run every check `pack` runs, rather than stopping at the first failure.
+    for check in [
+        modpkg_project::validate_project_config(&mod_project),
+        modpkg_project::validate_layer_dirs(&mod_project, &project_root),
+        modpkg_project::validate_project_thumbnail(&mod_project, &project_root),
+    ] {
+        if let Err(e) = check {
+            lints.errors.push(e.to_string());
+        }
+    }
+    lint_declarations(&mod_project, &mut lints);
+
+    let app_config = config::load_config();
+    let state_dir = app_config
+        .state_dir
+        .or_else(config::default_state_dir)
+        .unwrap_or_else(|| project_root.join("build"));
+    let game_dir = args
+        .game_dir
+        .map(Utf8PathBuf::from)
+        .or(app_config.league_path);
+    match game_dir {
+        Some(game_dir) => lint_game_content(
+            &mod_project,
+            &project_root,
+            &game_dir,
+            &state_dir,
+            &mut lints,
+        ),
+        None => println_pad!(
+            "{}",
+            "Skipping game content checks: no game directory given or configured".dimmed()
+        ),
+    }
+
+    print_lints(&lints);
+
+    if lints.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(miette!("Lint failed with {} error(s)", lints.errors.len()))
+    }
+}
+
+/// Warn about champions and maps the project declares that we don't recognize.
+fn lint_declarations(mod_project: &ModProject, lints: &mut Lints) {
+    for champion in &mod_project.champions {
+        if canonical_champion(champion).is_none() {
+            lints.warnings.push(format!(
+                "Unknown champion '{}' in project champions",
+                champion
+            ));
+        }
+    }
+    for map in &mod_project.maps {
+        if let ModMap::Custom(name) = map {
+            lints
+                .warnings
+                .push(format!("Unknown map '{}' in project maps", name));
+        }
+    }
+}
+
+/// Compare the project's content against the game's WADs.
+fn lint_game_content(
+    mod_project: &ModProject,
+    project_root: &Utf8Path,
+    game_dir: &Utf8Path,
+    state_dir: &Utf8Path,
+    lints: &mut Lints,
+) {
+    println_pad!(
+        "{} {}",
+        "🎮 Checking content against:".bright_yellow(),
+        game_dir.as_str().bright_white()
+    );
+
+    let mut enabled_mod = EnabledMod {
+        id: mod_project.name.clone(),
+        content: Box::new(FsModContent::new(project_root.to_path_buf())),
+        enabled_layers: None,
+    };
+
+    let report = match OverlayBuilder::lint_single_mod(game_dir, state_dir, &mut enabled_mod) {
+        Ok(report) => report,
+        Err(e) => {
+            lints
+                .errors
+                .push(format!("Failed to check content against the game: {}", e));
+            return;
+        }
+    };
+
+    for flagged in &report.subchunktoc_overrides {
+        lints.errors.push(format!(
+            "{} overrides a SubChunkTOC and would be stripped from the overlay",
+            flagged.rel_path
+        ));
+    }
+    for flagged in &report.lazy_overrides {
+        lints.warnings.push(format!(
+            "{} is identical to the game's copy and can be removed",
+            flagged.rel_path
+        ));
+    }
+    for suspect in &report.suspect_hex_overrides {
+        lints.warnings.push(format!(
+            "{} is named like hash {:016x}, which no game WAD contains",
+            suspect.rel_path, suspect.path_hash
+        ));
+    }
+    for shadowed in &report.layer_shadowing {
+        let layers = shadowed.layers.join(", ");
+        lints.warnings.push(if shadowed.identical {
+            format!(
+                "{} in {} is identical in layers {}; only one copy is needed",
+                shadowed.rel_path, shadowed.wad, layers
+            )
+        } else {
+            format!(
+                "{} in {} is overridden by layers {}; only '{}' is used",
+                shadowed.rel_path,
+                shadowed.wad,
+                layers,
+                shadowed
+                    .layers
+                    .last()
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            )
+        });
+    }
+}
+
+fn print_lints(lints: &Lints) {
+    if !lints.errors.is_empty() {
+        println_pad!("\n{}", "❌ Errors:".bright_red().bold());
+        for error in &lints.errors {
+            println_pad!("   {} {}", "•".bright_red(), error);
+        }
+    }
+    if !lints.warnings.is_empty() {
+        println_pad!("\n{}", "⚠️  Warnings:".bright_yellow().bold());
+        for warning in &lints.warnings {
+            println_pad!("   {} {}", "•".bright_yellow(), warning);
+        }
+    }
+
+    let summary = format!(
+        "{} error(s), {} warning(s)",
+        lints.errors.len(),
+        lints.warnings.len()
+    );
+    let summary = match (lints.errors.is_empty(), lints.warnings.is_empty()) {
+        (false, _) => summary.bright_red().bold(),
+        (true, false) => summary.bright_yellow().bold(),
+        (true, true) => summary.bright_green().bold(),
+    };
+    println!();
+    utils::print_ansi_boxed_lines(&[format!("{} {}", "Lint:".bold(), summary)]);
+}
@@ -2,9 +2,11 @@ pub mod config;
 mod extract;
 mod info;
 mod init;
+mod lint;
 mod pack;
 
 pub use extract::*;
 pub use info::*;
 pub use init::*;
+pub use lint::*;
 pub use pack::*;
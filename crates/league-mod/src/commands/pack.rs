@@ -1,13 +1,10 @@
 use crate::println_pad;
-use crate::{
-    errors::CliError,
-    utils::{validate_mod_name, validate_version_format},
-};
+use crate::{errors::CliError, utils::validate_mod_project};
 use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
 use ltk_fantome::{get_unsupported_layers, pack_to_fantome};
 use ltk_mod_project::ModProject;
-use ltk_modpkg::project::{self as modpkg_project, PackError};
+use ltk_modpkg::project::{self as modpkg_project, PackError, ProjectPacker};
 use miette::{miette, IntoDiagnostic, Result, WrapErr};
 use std::fs::File;
 use std::io::BufWriter;
@@ -27,14 +24,14 @@ pub struct PackModProjectArgs {
     pub format: PackFormat,
     #[allow(dead_code)]
     pub sign: bool,
+    pub follow_symlinks: bool,
 }
 
 pub fn pack_mod_project(args: PackModProjectArgs) -> Result<()> {
     let config_path = resolve_config_path(args.config_path.clone())?;
     let mod_project = load_config(&config_path)?;
 
-    validate_mod_name(&mod_project.name)?;
-    validate_version_format(&mod_project.version)?;
+    validate_mod_project(&mod_project)?;
 
     match args.format {
         PackFormat::Modpkg => pack_to_modpkg(args, config_path, mod_project),
@@ -80,7 +77,9 @@ fn pack_to_modpkg(
     let output_path = output_dir.join(&modpkg_file_name);
 
     // Use the shared packing logic from ltk_modpkg
-    modpkg_project::pack_from_project_with_config(project_root, &output_path, &mod_project)
+    ProjectPacker::with_mod_project(mod_project, project_root.to_owned())
+        .map(|packer| packer.with_follow_symlinks(args.follow_symlinks))
+        .and_then(|packer| packer.pack(&output_path))
         .map_err(|e| convert_pack_error(e, project_root))?;
 
     println_pad!(
@@ -99,10 +98,7 @@ fn convert_pack_error(err: PackError, project_root: &Utf8Path) -> miette::Report
         PackError::LayerDirMissing { layer, path } => {
             CliError::layer_directory_missing(layer, path.into_std_path_buf()).into()
         }
-        PackError::InvalidLayerName(name) => CliError::invalid_layer_name(name, None).into(),
-        PackError::InvalidBaseLayerPriority(priority) => {
-            CliError::invalid_base_layer_priority(priority).into()
-        }
+        PackError::InvalidProject(issues) => CliError::invalid_project(issues).into(),
         PackError::ConfigNotFound(_) => {
             CliError::config_not_found(project_root.as_std_path().to_owned()).into()
         }
@@ -209,7 +205,7 @@ fn resolve_config_path(config_path: Option<String>) -> Result<PathBuf> {
     }
 }
 
-fn resolve_correct_config_extension(project_dir: &Path) -> Result<PathBuf> {
+pub(super) fn resolve_correct_config_extension(project_dir: &Path) -> Result<PathBuf> {
     // JSON first, then TOML
     let config_extensions = ["json", "toml"];
 
@@ -223,40 +219,11 @@ fn resolve_correct_config_extension(project_dir: &Path) -> Result<PathBuf> {
     Err(CliError::config_not_found(project_dir.to_owned()).into())
 }
 
-fn load_config(config_path: &Path) -> Result<ModProject> {
-    let config_extension = config_path.extension().unwrap_or_default();
-
-    match config_extension.to_str() {
-        Some("json") => {
-            let file = File::open(config_path).into_diagnostic().with_context(|| {
-                format!("Failed to open config file: {}", config_path.display())
-            })?;
-            serde_json::from_reader(file)
-                .into_diagnostic()
-                .with_context(|| {
-                    format!(
-                        "Failed to parse JSON config file: {}",
-                        config_path.display()
-                    )
-                })
-        }
-        Some("toml") => {
-            let content = std::fs::read_to_string(config_path)
-                .into_diagnostic()
-                .with_context(|| {
-                    format!("Failed to read config file: {}", config_path.display())
-                })?;
-            toml::from_str(&content).into_diagnostic().with_context(|| {
-                format!(
-                    "Failed to parse TOML config file: {}",
-                    config_path.display()
-                )
-            })
-        }
-        _ => Err(miette!(
-            "Invalid config file extension, expected mod.config.json or mod.config.toml"
-        )),
-    }
+/// Load the project config at `config_path`, merging in its `mod.config.d/` fragments.
+pub(super) fn load_config(config_path: &Path) -> Result<ModProject> {
+    ltk_mod_project::load_config_file_with_includes(config_path)
+        .into_diagnostic()
+        .with_context(|| format!("Failed to load config file: {}", config_path.display()))
 }
 
 fn resolve_output_dir(output_dir: &str, config_path: &Utf8Path) -> Result<Utf8PathBuf> {
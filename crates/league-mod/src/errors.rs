@@ -13,17 +13,6 @@ pub enum CliError {
     )]
     ConfigNotFound { search_path: PathBuf },
 
-    #[error("Invalid layer name: {name}")]
-    #[diagnostic(
-        code(layer::invalid_name),
-        help("Layer names must be alphanumeric and contain no spaces or special characters")
-    )]
-    InvalidLayerName {
-        name: String,
-        #[label("invalid layer name")]
-        span: Option<SourceSpan>,
-    },
-
     #[error("Layer directory not found at {expected_path}")]
     #[diagnostic(
         code(layer::directory_missing),
@@ -37,7 +26,7 @@ pub enum CliError {
     #[error("Invalid mod name: {name}")]
     #[diagnostic(
         code(project::invalid_name),
-        help("Mod names must be alphanumeric and contain no spaces or special characters (You can set a display name later)")
+        help("Mod names can only contain letters, digits, '_' and '-' (You can set a display name later)")
     )]
     InvalidModName {
         name: String,
@@ -45,16 +34,12 @@ pub enum CliError {
         span: Option<SourceSpan>,
     },
 
-    #[error("Invalid version format: {version}")]
+    #[error("Invalid mod project: {}", issues.join("; "))]
     #[diagnostic(
-        code(project::invalid_version),
-        help("Version must follow semantic versioning (e.g., 1.0.0, 2.1.3-beta)")
+        code(project::invalid),
+        help("Fix the listed issues in your mod.config.json or mod.config.toml file")
     )]
-    InvalidVersion {
-        version: String,
-        #[label("invalid version")]
-        span: Option<SourceSpan>,
-    },
+    InvalidProject { issues: Vec<String> },
 
     #[error("Configuration file error")]
     #[diagnostic(
@@ -93,19 +78,19 @@ pub enum CliError {
         source: std::io::Error,
     },
 
-    #[error("Invalid base layer priority: {provided} (expected 0)")]
-    #[diagnostic(
-        code(layer::invalid_base_priority),
-        help("The 'base' layer must have priority 0")
-    )]
-    InvalidBaseLayerPriority { provided: i32 },
-
     #[error("WAD extraction failed: {message}")]
     #[diagnostic(
         code(fantome::wad_extraction_failed),
         help("The packed WAD file could not be extracted. The file may be corrupted or in an unsupported format.")
     )]
     WadExtractionFailed { message: String },
+
+    #[error("Not a valid Fantome archive: {message}")]
+    #[diagnostic(
+        code(fantome::invalid_archive),
+        help("Make sure the file is a .fantome/.zip mod containing META/info.json")
+    )]
+    InvalidFantomeArchive { message: String },
 }
 
 impl CliError {
@@ -113,10 +98,6 @@ impl CliError {
         Self::ConfigNotFound { search_path }
     }
 
-    pub fn invalid_layer_name(name: String, span: Option<SourceSpan>) -> Self {
-        Self::InvalidLayerName { name, span }
-    }
-
     pub fn layer_directory_missing(layer_name: String, expected_path: PathBuf) -> Self {
         Self::LayerDirectoryMissing {
             layer_name,
@@ -128,8 +109,8 @@ impl CliError {
         Self::InvalidModName { name, span }
     }
 
-    pub fn invalid_version(version: String, span: Option<SourceSpan>) -> Self {
-        Self::InvalidVersion { version, span }
+    pub fn invalid_project(issues: Vec<String>) -> Self {
+        Self::InvalidProject { issues }
     }
 
     #[allow(unused)]
@@ -149,9 +130,4 @@ impl CliError {
     pub fn directory_creation_failed(path: PathBuf, source: std::io::Error) -> Self {
         Self::DirectoryCreationFailed { path, source }
     }
-
-    #[allow(unused)]
-    pub fn invalid_base_layer_priority(provided: i32) -> Self {
-        Self::InvalidBaseLayerPriority { provided }
-    }
 }
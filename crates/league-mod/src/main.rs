@@ -2,8 +2,9 @@ use clap::builder::{styling::AnsiColor, Styles};
 use clap::ColorChoice;
 use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use commands::{
-    extract_mod_package, info_mod_package, init_mod_project, pack_mod_project,
-    ExtractModPackageArgs, InfoModPackageArgs, InitModProjectArgs, PackFormat, PackModProjectArgs,
+    extract_mod_package, info_mod_package, init_mod_project, lint_mod_project, pack_mod_project,
+    ExtractModPackageArgs, InfoModPackageArgs, InitModProjectArgs, LintModProjectArgs, PackFormat,
+    PackModProjectArgs,
 };
 use miette::Result;
 
@@ -51,6 +52,10 @@ pub enum Commands {
         /// Whether to sign the mod
         #[arg(long, default_value_t = true)]
         sign: bool,
+
+        /// Refuse symlinks under content/ when packing a modpkg instead of following them
+        #[arg(long)]
+        no_follow_symlinks: bool,
     },
     /// Show information about a mod package
     Info {
@@ -66,6 +71,15 @@ pub enum Commands {
         #[arg(short, long)]
         output_dir: Option<String>,
     },
+    /// Check a mod project for errors and common mistakes
+    Lint {
+        /// The mod project directory (defaults to the current directory)
+        project_dir: Option<String>,
+
+        /// The League of Legends Game directory (defaults to the configured league path)
+        #[arg(short, long)]
+        game_dir: Option<String>,
+    },
     /// Manage application configuration
     Config {
         #[command(subcommand)]
@@ -127,12 +141,14 @@ fn main() -> Result<()> {
             output_dir,
             format,
             sign,
+            no_follow_symlinks,
         } => pack_mod_project(PackModProjectArgs {
             config_path,
             file_name,
             output_dir,
             format,
             sign,
+            follow_symlinks: !no_follow_symlinks,
         }),
         Commands::Info { file_path } => info_mod_package(InfoModPackageArgs { file_path }),
         Commands::Extract {
@@ -142,6 +158,13 @@ fn main() -> Result<()> {
             file_path,
             output_dir,
         }),
+        Commands::Lint {
+            project_dir,
+            game_dir,
+        } => lint_mod_project(LintModProjectArgs {
+            project_dir,
+            game_dir,
+        }),
         Commands::Config { action } => match action {
             ConfigAction::Show => config_cmd::show_config(),
             ConfigAction::SetLeaguePath { path } => config_cmd::set_league_path(path),
@@ -14,6 +14,8 @@ pub struct AppConfig {
     pub league_path: Option<Utf8PathBuf>,
     /// Directory where WAD hashtables are stored.
     pub hashtable_dir: Option<Utf8PathBuf>,
+    /// Directory where overlay state, such as the game index cache, is stored.
+    pub state_dir: Option<Utf8PathBuf>,
 }
 
 impl Default for AppConfig {
@@ -21,6 +23,7 @@ impl Default for AppConfig {
         Self {
             league_path: None,
             hashtable_dir: default_hashtable_dir(),
+            state_dir: default_state_dir(),
         }
     }
 }
@@ -69,6 +72,7 @@ pub fn save_config(cfg: &AppConfig) -> io::Result<()> {
         let normalized_cfg = AppConfig {
             league_path: cfg.league_path.as_ref().map(normalize_path),
             hashtable_dir: cfg.hashtable_dir.as_ref().map(normalize_path),
+            state_dir: cfg.state_dir.as_ref().map(normalize_path),
         };
 
         let content = toml::to_string_pretty(&normalized_cfg).map_err(io::Error::other)?;
@@ -134,3 +138,14 @@ pub fn default_hashtable_dir() -> Option<Utf8PathBuf> {
     path.push("wad_hashtables");
     Utf8PathBuf::from_path_buf(path).ok()
 }
+
+/// Returns the default directory where overlay state is stored.
+/// Uses the user's Documents folder: Documents/LeagueToolkit/state
+pub fn default_state_dir() -> Option<Utf8PathBuf> {
+    let user_dirs = directories_next::UserDirs::new()?;
+    let doc_dir = user_dirs.document_dir()?;
+    let mut path = doc_dir.to_path_buf();
+    path.push("LeagueToolkit");
+    path.push("state");
+    Utf8PathBuf::from_path_buf(path).ok()
+}
@@ -2,4 +2,4 @@
 //!
 //! This module re-exports functionality from `ltk_mod_core` for backward compatibility.
 
-pub use ltk_mod_core::{auto_detect_league_path, is_valid_league_path};
+pub use ltk_mod_core::{auto_detect_league_path, is_riot_client_path, is_valid_league_path};
@@ -1,4 +1,5 @@
 use crate::errors::CliError;
+use ltk_mod_project::{ModProject, ValidationSeverity};
 use miette::Result;
 use regex::Regex;
 
@@ -16,25 +17,25 @@ macro_rules! println_pad {
     }};
 }
 
-pub fn is_valid_slug(name: impl AsRef<str>) -> bool {
-    Regex::new(r"^[[:word:]-]+$")
-        .unwrap()
-        .is_match(name.as_ref())
-}
-
 pub fn validate_mod_name(name: impl AsRef<str>) -> Result<()> {
     let name_str = name.as_ref();
-    if !is_valid_slug(name_str) {
+    if !ltk_mod_project::is_valid_slug(name_str) {
         return Err(CliError::invalid_mod_name(name_str.to_string(), None).into());
     }
 
     Ok(())
 }
 
-pub fn validate_version_format(version: impl AsRef<str>) -> Result<()> {
-    let version_str = version.as_ref();
-    if semver::Version::parse(version_str).is_err() {
-        return Err(CliError::invalid_version(version_str.to_string(), None).into());
+/// Fails with every error [`ModProject::validate`] reports for the project.
+pub fn validate_mod_project(mod_project: &ModProject) -> Result<()> {
+    let issues: Vec<String> = mod_project
+        .validate()
+        .into_iter()
+        .filter(|issue| issue.severity == ValidationSeverity::Error)
+        .map(|issue| issue.message)
+        .collect();
+    if !issues.is_empty() {
+        return Err(CliError::invalid_project(issues).into());
     }
 
     Ok(())
@@ -65,11 +66,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn validate_slug_valid() {
-        assert!(is_valid_slug("test"));
-        assert!(is_valid_slug("test-123"));
-        assert!(!is_valid_slug("test 123"));
-        assert!(!is_valid_slug("test!123"));
-        assert!(!is_valid_slug("Nice mod: ([test])@"));
+    fn validate_mod_name_valid() {
+        assert!(validate_mod_name("test").is_ok());
+        assert!(validate_mod_name("test-123").is_ok());
+        assert!(validate_mod_name("test 123").is_err());
+        assert!(validate_mod_name("test!123").is_err());
+        assert!(validate_mod_name("Nice mod: ([test])@").is_err());
+    }
+
+    #[test]
+    fn validate_mod_project_reports_every_error() {
+        let mod_project = ModProject {
+            name: "Old Rift".to_string(),
+            version: "1.0".to_string(),
+            layers: ltk_mod_project::default_layers(),
+            ..Default::default()
+        };
+        let err = validate_mod_project(&mod_project).unwrap_err().to_string();
+        assert!(err.contains("mod name"), "{err}");
+        assert!(err.contains("version"), "{err}");
     }
 }
@@ -17,6 +17,36 @@ pub enum FantomeExtractError {
     #[error("WAD error: {0}")]
     Wad(#[from] ltk_wad::WadError),
 
-    #[error("Missing info.json metadata file")]
+    #[error("Thumbnail error: {0}")]
+    Thumbnail(#[from] image::ImageError),
+
+    #[error("Modpkg builder error: {0}")]
+    ModpkgBuilder(#[from] ltk_modpkg::builder::ModpkgBuilderError),
+
+    #[error("Missing META/info.json metadata file")]
     MissingMetadata,
+
+    #[error("Invalid archive entry path: {path}")]
+    InvalidWadPath { path: String },
+
+    #[error("Invalid layer name: {name}")]
+    InvalidLayerName { name: String },
+}
+
+impl FantomeExtractError {
+    /// Returns `true` if the error means the input is not a valid Fantome archive,
+    /// as opposed to a failure reading or writing files on disk.
+    pub fn is_invalid_archive(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingMetadata
+                | Self::InvalidWadPath { .. }
+                | Self::InvalidLayerName { .. }
+                | Self::Json(_)
+                | Self::Zip(
+                    zip::result::ZipError::InvalidArchive(_)
+                        | zip::result::ZipError::UnsupportedArchive(_)
+                )
+        )
+    }
 }
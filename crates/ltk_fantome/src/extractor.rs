@@ -1,21 +1,25 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, Write};
 use std::path::Path;
 
 use camino::Utf8Path;
 use image::ImageFormat;
-use ltk_mod_project::{ModMap, ModProject, ModProjectAuthor, ModTag, default_layers};
+use ltk_mod_project::{ModDependency, ModMap, ModProject, ModProjectAuthor, ModTag};
 use ltk_wad::{HexPathResolver, Wad, WadExtractor};
 use zip::ZipArchive;
 
-use crate::FantomeInfo;
 use crate::error::FantomeExtractError;
 use crate::hashtable::WadHashtable;
+use crate::{FantomeInfo, FantomeLayerInfo, content_entry_layer};
 
 /// Result of extracting a Fantome package.
 pub struct FantomeExtractResult {
     /// The mod project configuration extracted from the Fantome package.
     pub mod_project: ModProject,
+    /// Per-layer metadata from the `Layers` map of `info.json`, keyed by layer
+    /// name. Empty for packages whose `info.json` predates it.
+    pub layers: HashMap<String, FantomeLayerInfo>,
 }
 
 /// Extractor for Fantome packages.
@@ -106,7 +110,8 @@ impl<R: Read + Seek> FantomeExtractor<R> {
     ///
     /// This will:
     /// 1. Validate the archive structure
-    /// 2. Extract WAD contents to content/base/
+    /// 2. Extract `WAD/` contents to content/base/ and `LAYERS/{layer}/`
+    ///    contents to content/{layer}/
     /// 3. Extract README.md if present
     /// 4. Extract thumbnail image if present
     /// 5. Create a mod.config.json file
@@ -119,6 +124,14 @@ impl<R: Read + Seek> FantomeExtractor<R> {
         self.validate()?;
 
         let info = self.read_metadata()?;
+        let content_layers: Vec<String> = self
+            .archive
+            .file_names()
+            .filter_map(content_entry_layer)
+            .map(|(layer, _)| layer.to_string())
+            .collect();
+        let project_layers = info.project_layers(content_layers.iter().map(String::as_str));
+        let layers = info.layers;
         let mod_project = ModProject {
             name: slug::slugify(&info.name),
             display_name: info.name,
@@ -129,33 +142,61 @@ impl<R: Read + Seek> FantomeExtractor<R> {
             tags: info.tags.into_iter().map(ModTag::from).collect(),
             champions: info.champions,
             maps: info.maps.into_iter().map(ModMap::from).collect(),
+            dependencies: info
+                .dependencies
+                .into_iter()
+                .map(|d| ModDependency {
+                    name: d.name,
+                    version_req: d.version_req,
+                })
+                .collect(),
             transformers: vec![],
-            layers: default_layers(),
+            compression: Default::default(),
+            layers: project_layers,
             thumbnail: None,
         };
 
+        if let Some(layer) = mod_project
+            .layers
+            .iter()
+            .find(|layer| !is_layer_dir_name(&layer.name))
+        {
+            return Err(FantomeExtractError::InvalidLayerName {
+                name: layer.name.clone(),
+            });
+        }
+
         if !output_dir.exists() {
             std::fs::create_dir_all(output_dir)?;
         }
 
+        // Every declared layer gets a content directory, even one that only
+        // carries string overrides, so the extracted project packs as-is.
+        for layer in &mod_project.layers {
+            std::fs::create_dir_all(output_dir.join("content").join(&layer.name))?;
+        }
+
         for i in 0..self.archive.len() {
             let mut file = self.archive.by_index(i)?;
             let file_name = file.name().to_string();
 
-            if file_name.starts_with("WAD/") {
-                let relative_path = file_name.strip_prefix("WAD/").unwrap();
+            // Reject entries that would escape the output directory (zip-slip)
+            if file.enclosed_name().is_none() {
+                return Err(FantomeExtractError::InvalidWadPath { path: file_name });
+            }
+
+            if let Some((layer, relative_path)) = content_entry_layer(&file_name) {
+                let layer_dir = output_dir.join("content").join(layer);
 
-                // Check if this is a packed WAD file (directly under WAD/, ends with .wad.client etc.)
+                // Check if this is a packed WAD file (directly under the layer, ends with .wad.client etc.)
                 if !file.is_dir() && !relative_path.contains('/') && is_wad_file_name(relative_path)
                 {
                     // Extract packed WAD file using WadExtractor
-                    let wad_output_dir =
-                        output_dir.join("content").join("base").join(relative_path);
+                    let wad_output_dir = layer_dir.join(relative_path);
                     extract_packed_wad(&mut file, &wad_output_dir, self.hashtable.as_ref())?;
                 } else {
-                    // Extract WAD folder content to content/base/
-                    let output_file_path =
-                        output_dir.join("content").join("base").join(relative_path);
+                    // Extract WAD folder content to content/{layer}/
+                    let output_file_path = layer_dir.join(relative_path);
 
                     if file.is_dir() {
                         std::fs::create_dir_all(&output_file_path)?;
@@ -202,12 +243,20 @@ impl<R: Read + Seek> FantomeExtractor<R> {
         let mut config_file = File::create(config_path)?;
         config_file.write_all(config_content.as_bytes())?;
 
-        Ok(FantomeExtractResult { mod_project })
+        Ok(FantomeExtractResult {
+            mod_project,
+            layers,
+        })
     }
 }
 
+/// Whether a layer name can be used as a directory under `content/`.
+fn is_layer_dir_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+}
+
 /// Check if a filename looks like a WAD file (ends with .wad.client or similar WAD extensions)
-fn is_wad_file_name(name: &str) -> bool {
+pub(crate) fn is_wad_file_name(name: &str) -> bool {
     name.ends_with(".wad.client") || name.ends_with(".wad") || name.ends_with(".wad.mobile")
 }
 
@@ -219,10 +268,8 @@ fn extract_thumbnail<R: Read>(
     let mut data = Vec::new();
     reader.read_to_end(&mut data)?;
 
-    let img = image::load_from_memory_with_format(&data, ImageFormat::Png)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-
-    img.save(output_path).map_err(std::io::Error::other)?;
+    let img = image::load_from_memory_with_format(&data, ImageFormat::Png)?;
+    img.save(output_path)?;
 
     Ok(())
 }
@@ -260,6 +307,7 @@ fn extract_packed_wad<R: Read>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ltk_mod_project::default_layers;
     use std::io::Cursor;
     use tempfile::tempdir;
     use zip::ZipWriter;
@@ -302,6 +350,8 @@ mod tests {
 
         assert_eq!(result.mod_project.display_name, "Test Mod");
         assert_eq!(result.mod_project.version, "1.0.0");
+        assert!(result.layers.is_empty());
+        assert_eq!(result.mod_project.layers, default_layers());
 
         // Check that mod.config.json was created
         assert!(temp_dir.path().join("mod.config.json").exists());
@@ -315,6 +365,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extract_string_overrides() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        let info = r#"{
+            "Name": "Test",
+            "Author": "Test",
+            "Version": "1.0.0",
+            "Description": "Test",
+            "Layers": {
+                "base": {
+                    "Name": "base",
+                    "Priority": 0,
+                    "StringOverrides": {
+                        "en_us": {
+                            "game_character_displayname_Aatrox": "Darkin",
+                            "game_character_title_Aatrox": "the Blade"
+                        }
+                    }
+                }
+            }
+        }"#;
+        zip.write_all(info.as_bytes()).unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let result = extractor.extract_to(temp_dir.path()).unwrap();
+
+        let expected = HashMap::from([(
+            "en_us".to_string(),
+            HashMap::from([
+                (
+                    "game_character_displayname_Aatrox".to_string(),
+                    "Darkin".to_string(),
+                ),
+                (
+                    "game_character_title_Aatrox".to_string(),
+                    "the Blade".to_string(),
+                ),
+            ]),
+        )]);
+        assert_eq!(result.layers.len(), 1);
+        assert_eq!(result.layers["base"].priority, 0);
+        assert_eq!(result.layers["base"].string_overrides, expected);
+        assert_eq!(result.mod_project.layers.len(), 1);
+        assert_eq!(result.mod_project.layers[0].string_overrides, expected);
+
+        // The overrides round-trip through the written mod.config.json
+        let config = ModProject::load(temp_dir.path()).unwrap();
+        assert_eq!(config.layers, result.mod_project.layers);
+    }
+
+    #[test]
+    fn test_extract_creates_declared_layer_dirs() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        let info = r#"{
+            "Name": "Test",
+            "Author": "Test",
+            "Version": "1.0.0",
+            "Description": "Test",
+            "Layers": {
+                "chroma": {
+                    "Name": "chroma",
+                    "Priority": 1,
+                    "StringOverrides": { "default": { "name": "Ruby" } }
+                }
+            }
+        }"#;
+        zip.write_all(info.as_bytes()).unwrap();
+        zip.start_file("LAYERS/extra/Aatrox.wad.client/data/extra.bin", options)
+            .unwrap();
+        zip.write_all(b"extra").unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let result = extractor.extract_to(temp_dir.path()).unwrap();
+
+        let layers: Vec<(&str, i32)> = result
+            .mod_project
+            .layers
+            .iter()
+            .map(|layer| (layer.name.as_str(), layer.priority))
+            .collect();
+        assert_eq!(layers, [("base", 0), ("chroma", 1), ("extra", 2)]);
+        for layer in ["base", "chroma", "extra"] {
+            assert!(
+                temp_dir.path().join("content").join(layer).is_dir(),
+                "{layer}"
+            );
+        }
+        assert_eq!(
+            std::fs::read(
+                temp_dir
+                    .path()
+                    .join("content/extra/Aatrox.wad.client/data/extra.bin")
+            )
+            .unwrap(),
+            b"extra"
+        );
+    }
+
+    #[test]
+    fn test_rejects_escaping_layer_name() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        let info = r#"{
+            "Name": "Test",
+            "Author": "Test",
+            "Version": "1.0.0",
+            "Description": "Test",
+            "Layers": { "evil": { "Name": "../evil", "Priority": 1 } }
+        }"#;
+        zip.write_all(info.as_bytes()).unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let err = extractor.extract_to(temp_dir.path()).err().unwrap();
+
+        assert!(matches!(
+            err,
+            FantomeExtractError::InvalidLayerName { ref name } if name == "../evil"
+        ));
+        assert!(err.is_invalid_archive());
+    }
+
     #[test]
     fn test_extract_raw_files() {
         let buffer = Vec::new();
@@ -357,4 +539,53 @@ mod tests {
         assert!(raw_file2.exists());
         assert_eq!(std::fs::read(&raw_file2).unwrap(), b"map data");
     }
+
+    #[test]
+    fn test_missing_info_json() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(
+            "WAD/test.wad.client/assets/test.bin",
+            SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.write_all(b"test content").unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let err = extractor.extract_to(temp_dir.path()).err().unwrap();
+
+        assert!(matches!(err, FantomeExtractError::MissingMetadata));
+        assert!(err.is_invalid_archive());
+    }
+
+    #[test]
+    fn test_rejects_escaping_entry_path() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        zip.write_all(
+            br#"{"Name": "Test", "Author": "Test", "Version": "1.0.0", "Description": "Test"}"#,
+        )
+        .unwrap();
+        zip.start_file("RAW/../../evil.bin", options).unwrap();
+        zip.write_all(b"evil").unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let err = extractor.extract_to(temp_dir.path()).err().unwrap();
+
+        assert!(matches!(
+            err,
+            FantomeExtractError::InvalidWadPath { ref path } if path == "RAW/../../evil.bin"
+        ));
+        assert!(err.is_invalid_archive());
+    }
+
+    #[test]
+    fn test_io_error_is_not_invalid_archive() {
+        let err = FantomeExtractError::Io(std::io::Error::other("disk full"));
+        assert!(!err.is_invalid_archive());
+    }
 }
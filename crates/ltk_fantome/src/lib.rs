@@ -1,20 +1,53 @@
 use eyre::Result;
 use image::ImageFormat;
+use image::codecs::png::PngEncoder;
 use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{File, read_dir};
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufReader, Read, Seek, Write};
+use std::path::{Path, PathBuf};
 use zip::{ZipWriter, write::SimpleFileOptions};
 
 pub mod error;
 mod extractor;
 mod hashtable;
+mod modpkg;
 
 pub use error::FantomeExtractError;
 pub use extractor::{FantomeExtractResult, FantomeExtractor};
 pub use hashtable::{WadHashtable, format_chunk_path_hash};
+pub use modpkg::{FantomeModpkgBuilder, from_modpkg, into_modpkg_builder};
+
+/// Directory holding the WAD content of non-base layers in a layered Fantome
+/// archive, as `LAYERS/{layer}/{name}.wad.client/{file}`.
+///
+/// The base layer stays under `WAD/`, so managers that only know the legacy
+/// format still load it and ignore the rest.
+pub const LAYERS_DIR: &str = "LAYERS";
+
+/// Largest thumbnail, in bytes, copied into a Fantome archive as is. Larger
+/// thumbnails are re-encoded, see [`MAX_IMAGE_DIMENSION`].
+pub const MAX_IMAGE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Largest width and height of a thumbnail re-encoded for a Fantome archive.
+/// Bigger images are scaled down to fit, keeping their aspect ratio.
+pub const MAX_IMAGE_DIMENSION: u32 = 1024;
+
+/// Split a content entry name into its layer and the path below the layer.
+///
+/// `WAD/{path}` belongs to the base layer and `LAYERS/{layer}/{path}` to
+/// `layer`. Returns `None` for every other entry.
+pub fn content_entry_layer(name: &str) -> Option<(&str, &str)> {
+    if let Some(path) = name.strip_prefix("WAD/") {
+        return Some(("base", path));
+    }
+    let rest = name.strip_prefix(LAYERS_DIR)?.strip_prefix('/')?;
+    match rest.split_once('/') {
+        Some((layer, path)) if !layer.is_empty() => Some((layer, path)),
+        _ => None,
+    }
+}
 
 /// Fantome metadata structure that goes into info.json
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,11 +69,28 @@ pub struct FantomeInfo {
     /// Maps this mod targets (e.g., "Summoner's Rift", "Howling Abyss").
     #[serde(rename = "Maps", default, skip_serializing_if = "Vec::is_empty")]
     pub maps: Vec<String>,
+    /// Other mods this mod requires (e.g., the base pack of an add-on).
+    #[serde(
+        rename = "Dependencies",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub dependencies: Vec<FantomeDependency>,
     /// Per-layer metadata including string overrides.
     #[serde(rename = "Layers", default, skip_serializing_if = "HashMap::is_empty")]
     pub layers: HashMap<String, FantomeLayerInfo>,
 }
 
+/// Another mod required by a Fantome mod, as listed in info.json.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FantomeDependency {
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// Semver requirement the required mod's version must match (e.g., "^1.2").
+    #[serde(rename = "VersionReq")]
+    pub version_req: String,
+}
+
 /// Per-layer metadata in a Fantome info.json.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FantomeLayerInfo {
@@ -65,6 +115,97 @@ pub struct FantomeLayerInfo {
     pub string_overrides: HashMap<String, HashMap<String, String>>,
 }
 
+impl FantomeInfo {
+    /// The layers of an archive with this `info.json`, sorted by priority then name.
+    ///
+    /// These are the layers listed in `Layers`, plus `base` when it is not
+    /// listed. A layer in `content_layers` (the layers seen under [`LAYERS_DIR`])
+    /// that `info.json` does not list is added above the listed ones, in name
+    /// order, so its content is not lost.
+    pub fn archive_layers<'a>(
+        &self,
+        content_layers: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<FantomeLayerInfo> {
+        let mut layers: Vec<FantomeLayerInfo> = self.layers.values().cloned().collect();
+        if !layers.iter().any(|layer| layer.name == "base") {
+            layers.push(FantomeLayerInfo {
+                name: "base".to_string(),
+                display_name: None,
+                priority: 0,
+                string_overrides: HashMap::new(),
+            });
+        }
+
+        let mut unlisted: Vec<&str> = content_layers
+            .into_iter()
+            .filter(|name| !layers.iter().any(|layer| layer.name == *name))
+            .collect();
+        unlisted.sort_unstable();
+        unlisted.dedup();
+        let top = layers.iter().map(|layer| layer.priority).max().unwrap_or(0);
+        for (priority, name) in (top + 1..).zip(unlisted) {
+            layers.push(FantomeLayerInfo {
+                name: name.to_string(),
+                display_name: None,
+                priority,
+                string_overrides: HashMap::new(),
+            });
+        }
+
+        layers.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.name.cmp(&b.name)));
+        layers
+    }
+
+    /// [`archive_layers`](Self::archive_layers) as mod project layers.
+    pub fn project_layers<'a>(
+        &self,
+        content_layers: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<ModProjectLayer> {
+        self.archive_layers(content_layers)
+            .into_iter()
+            .map(|layer| ModProjectLayer {
+                description: (layer.name == "base")
+                    .then(|| ModProjectLayer::base().description)
+                    .flatten(),
+                name: layer.name,
+                display_name: layer.display_name,
+                priority: layer.priority,
+                string_overrides: layer.string_overrides,
+            })
+            .collect()
+    }
+}
+
+/// Progress of [`pack_to_fantome_with_progress`], reported after each WAD
+/// file is written to the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FantomePackProgress {
+    /// Archive path of the file just written (e.g. `WAD/Aatrox.wad.client/data/skin.bin`).
+    pub file_name: String,
+    /// Number of files written so far, including this one.
+    pub files_written: usize,
+    /// Number of files found in the base layer before packing started.
+    pub total_files: usize,
+}
+
+/// Running file count of a pack, forwarded to the caller's callback.
+struct PackProgress<'a> {
+    files_written: usize,
+    total_files: usize,
+    callback: &'a mut dyn FnMut(FantomePackProgress),
+}
+
+impl PackProgress<'_> {
+    fn file_written(&mut self, file_name: String) {
+        self.files_written += 1;
+        (self.callback)(FantomePackProgress {
+            file_name,
+            files_written: self.files_written,
+            total_files: self.total_files,
+        });
+    }
+}
+
 /// Create a standard Fantome file name from a mod project.
 ///
 /// If `custom_name` is provided, it will be used (with `.fantome` extension added if missing).
@@ -86,47 +227,123 @@ pub fn create_file_name(mod_project: &ModProject, custom_name: Option<String>) -
 
 /// Get layers that are not supported by the Fantome format.
 ///
-/// Fantome only supports the base layer. This returns all non-base layers
-/// from the project, which can be used to warn users about data loss.
+/// Legacy Fantome only supports the base layer. This returns all non-base
+/// layers from the project, which can be used to warn users about data loss
+/// when packing with [`pack_to_fantome`] rather than
+/// [`pack_to_fantome_with_layers`].
 pub fn get_unsupported_layers(mod_project: &ModProject) -> Vec<&ModProjectLayer> {
     mod_project
         .layers
         .iter()
-        .filter(|layer| layer.name != "base")
+        .filter(|layer| !is_supported_layer(&layer.name))
         .collect()
 }
 
 /// Check if the mod project has layers that won't be included in Fantome format.
 pub fn has_unsupported_layers(mod_project: &ModProject) -> bool {
-    mod_project.layers.iter().any(|layer| layer.name != "base")
+    mod_project
+        .layers
+        .iter()
+        .any(|layer| !is_supported_layer(&layer.name))
+}
+
+/// Whether a layer's content can be stored in a Fantome archive.
+pub(crate) fn is_supported_layer(name: &str) -> bool {
+    name == "base"
 }
 
 /// Pack a mod project into a Fantome .zip format
+///
+/// Only the base layer is packed; see [`get_unsupported_layers`].
 pub fn pack_to_fantome<W: Write + std::io::Seek>(
     writer: W,
     mod_project: &ModProject,
     project_root: &Path,
+) -> Result<()> {
+    pack_to_fantome_with_progress(writer, mod_project, project_root, |_| {})
+}
+
+/// Pack a mod project into a Fantome .zip format, reporting each WAD file
+/// written.
+///
+/// The base layer is walked once up front to count its files, so every
+/// [`FantomePackProgress`] carries the total. Metadata files are not counted.
+pub fn pack_to_fantome_with_progress<W: Write + std::io::Seek>(
+    writer: W,
+    mod_project: &ModProject,
+    project_root: &Path,
+    mut progress: impl FnMut(FantomePackProgress),
 ) -> Result<()> {
     let mut zip = ZipWriter::new(writer);
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
         .unix_permissions(0o755);
 
+    let base_layer_path = base_layer_path(project_root)?;
+    let mut total_files = 0;
+    for wad_dir in layer_wad_dirs(&base_layer_path)? {
+        total_files += count_files(&wad_dir)?;
+    }
+    let mut progress = PackProgress {
+        files_written: 0,
+        total_files,
+        callback: &mut progress,
+    };
+
     // Pack base layer WAD files
-    pack_base_layer(&mut zip, project_root, &options)?;
+    pack_layer_wads(&mut zip, &base_layer_path, "WAD", &options, &mut progress)?;
 
     // Pack metadata
-    pack_metadata(&mut zip, mod_project, project_root, &options)?;
+    let layers = build_fantome_layers(mod_project);
+    pack_metadata(&mut zip, mod_project, layers, project_root, &options)?;
 
     zip.finish()?;
     Ok(())
 }
 
-fn pack_base_layer<W: Write + std::io::Seek>(
-    zip: &mut ZipWriter<W>,
+/// Pack a mod project with all of its layers into a Fantome .zip format.
+///
+/// The base layer is packed under `WAD/` as with [`pack_to_fantome`], and every
+/// other layer under [`LAYERS_DIR`]. `info.json` lists every layer in `Layers`
+/// with its priority and string overrides. A non-base layer without a content
+/// directory is listed but has no files.
+pub fn pack_to_fantome_with_layers<W: Write + std::io::Seek>(
+    writer: W,
+    mod_project: &ModProject,
     project_root: &Path,
-    options: &SimpleFileOptions,
 ) -> Result<()> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    let mut progress = PackProgress {
+        files_written: 0,
+        total_files: 0,
+        callback: &mut |_| {},
+    };
+    let base_layer_path = base_layer_path(project_root)?;
+    pack_layer_wads(&mut zip, &base_layer_path, "WAD", &options, &mut progress)?;
+    for layer in get_unsupported_layers(mod_project) {
+        let layer_path = project_root.join("content").join(&layer.name);
+        if layer_path.exists() {
+            let zip_prefix = format!("{}/{}", LAYERS_DIR, layer.name);
+            pack_layer_wads(&mut zip, &layer_path, &zip_prefix, &options, &mut progress)?;
+        }
+    }
+
+    let layers = mod_project
+        .layers
+        .iter()
+        .map(|layer| (layer.name.clone(), fantome_layer_info(layer)))
+        .collect();
+    pack_metadata(&mut zip, mod_project, layers, project_root, &options)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+fn base_layer_path(project_root: &Path) -> Result<PathBuf> {
     let base_layer_path = project_root.join("content").join("base");
 
     if !base_layer_path.exists() {
@@ -136,10 +353,14 @@ fn pack_base_layer<W: Write + std::io::Seek>(
         ));
     }
 
-    // Iterate through all .wad.client directories in the base layer
-    for entry in read_dir(&base_layer_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    Ok(base_layer_path)
+}
+
+/// The `.wad.client` directories of a layer.
+fn layer_wad_dirs(layer_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut wad_dirs = Vec::new();
+    for entry in read_dir(layer_path)? {
+        let path = entry?.path();
 
         if path.is_dir()
             && path
@@ -148,11 +369,43 @@ fn pack_base_layer<W: Write + std::io::Seek>(
                 .to_string_lossy()
                 .ends_with(".wad.client")
         {
-            let wad_name = path.file_name().unwrap().to_string_lossy();
-            pack_wad_directory(zip, &path, &format!("WAD/{}", wad_name), options)?;
+            wad_dirs.push(path);
         }
     }
 
+    Ok(wad_dirs)
+}
+
+fn count_files(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry.map_err(|e| eyre::eyre!("Failed to walk directory: {}", e))?;
+        if entry.path().is_file() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Pack every `.wad.client` directory of a layer as `{zip_prefix}/{wad name}/`.
+fn pack_layer_wads<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    layer_path: &Path,
+    zip_prefix: &str,
+    options: &SimpleFileOptions,
+    progress: &mut PackProgress,
+) -> Result<()> {
+    for path in layer_wad_dirs(layer_path)? {
+        let wad_name = path.file_name().unwrap().to_string_lossy();
+        pack_wad_directory(
+            zip,
+            &path,
+            &format!("{}/{}", zip_prefix, wad_name),
+            options,
+            progress,
+        )?;
+    }
+
     Ok(())
 }
 
@@ -161,6 +414,7 @@ fn pack_wad_directory<W: Write + std::io::Seek>(
     wad_dir: &Path,
     zip_prefix: &str,
     options: &SimpleFileOptions,
+    progress: &mut PackProgress,
 ) -> Result<()> {
     for entry in walkdir::WalkDir::new(wad_dir).into_iter() {
         let entry = entry.map_err(|e| eyre::eyre!("Failed to walk directory: {}", e))?;
@@ -174,9 +428,10 @@ fn pack_wad_directory<W: Write + std::io::Seek>(
                 relative_path.to_string_lossy().replace('\\', "/")
             );
 
-            zip.start_file(zip_path, *options)?;
+            zip.start_file(zip_path.as_str(), *options)?;
             let mut file = File::open(path)?;
             std::io::copy(&mut file, zip)?;
+            progress.file_written(zip_path);
         }
     }
 
@@ -186,12 +441,10 @@ fn pack_wad_directory<W: Write + std::io::Seek>(
 fn pack_metadata<W: Write + std::io::Seek>(
     zip: &mut ZipWriter<W>,
     mod_project: &ModProject,
+    layers: HashMap<String, FantomeLayerInfo>,
     project_root: &Path,
     options: &SimpleFileOptions,
 ) -> Result<()> {
-    // Build layers map with string overrides
-    let layers = build_fantome_layers(mod_project);
-
     // Create info.json
     let info = FantomeInfo {
         name: mod_project.display_name.clone(),
@@ -201,6 +454,14 @@ fn pack_metadata<W: Write + std::io::Seek>(
         tags: mod_project.tags.iter().map(|t| t.to_string()).collect(),
         champions: mod_project.champions.clone(),
         maps: mod_project.maps.iter().map(|m| m.to_string()).collect(),
+        dependencies: mod_project
+            .dependencies
+            .iter()
+            .map(|d| FantomeDependency {
+                name: d.name.clone(),
+                version_req: d.version_req.clone(),
+            })
+            .collect(),
         layers,
     };
 
@@ -226,18 +487,50 @@ fn pack_metadata<W: Write + std::io::Seek>(
     Ok(())
 }
 
+/// Write the thumbnail at `image_path` as `META/image.png`.
+///
+/// A PNG of at most [`MAX_IMAGE_SIZE`] bytes is copied through as is, once its
+/// header decodes. Any other image is decoded, scaled down to
+/// [`MAX_IMAGE_DIMENSION`] if it is bigger, and re-encoded straight into the
+/// archive.
 fn pack_image<W: Write + std::io::Seek>(
     zip: &mut ZipWriter<W>,
     image_path: &Path,
     options: &SimpleFileOptions,
 ) -> Result<()> {
-    let img = image::open(image_path)?;
-
-    let mut png_buffer = Vec::new();
-    img.write_to(&mut std::io::Cursor::new(&mut png_buffer), ImageFormat::Png)?;
+    pack_image_with_limit(zip, image_path, options, MAX_IMAGE_SIZE)
+}
 
-    zip.start_file("META/image.png", *options)?;
-    zip.write_all(&png_buffer)?;
+fn pack_image_with_limit<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    image_path: &Path,
+    options: &SimpleFileOptions,
+    max_size: u64,
+) -> Result<()> {
+    let mut file = File::open(image_path)?;
+    let mut signature = [0u8; 8];
+    let is_png = file.read_exact(&mut signature).is_ok()
+        && image::guess_format(&signature).ok() == Some(ImageFormat::Png);
+
+    if is_png && file.metadata()?.len() <= max_size {
+        file.rewind()?;
+        image::ImageReader::with_format(BufReader::new(&mut file), ImageFormat::Png)
+            .into_dimensions()?;
+        file.rewind()?;
+        zip.start_file("META/image.png", *options)?;
+        std::io::copy(&mut file, zip)?;
+    } else {
+        let mut img = image::open(image_path)?;
+        if img.width() > MAX_IMAGE_DIMENSION || img.height() > MAX_IMAGE_DIMENSION {
+            img = img.resize(
+                MAX_IMAGE_DIMENSION,
+                MAX_IMAGE_DIMENSION,
+                image::imageops::FilterType::Lanczos3,
+            );
+        }
+        zip.start_file("META/image.png", *options)?;
+        img.write_with_encoder(PngEncoder::new(&mut *zip))?;
+    }
 
     Ok(())
 }
@@ -247,20 +540,21 @@ fn build_fantome_layers(mod_project: &ModProject) -> HashMap<String, FantomeLaye
     for layer in &mod_project.layers {
         // Only include layers that have string overrides
         if !layer.string_overrides.is_empty() {
-            layers.insert(
-                layer.name.clone(),
-                FantomeLayerInfo {
-                    name: layer.name.clone(),
-                    display_name: layer.display_name.clone(),
-                    priority: layer.priority,
-                    string_overrides: layer.string_overrides.clone(),
-                },
-            );
+            layers.insert(layer.name.clone(), fantome_layer_info(layer));
         }
     }
     layers
 }
 
+fn fantome_layer_info(layer: &ModProjectLayer) -> FantomeLayerInfo {
+    FantomeLayerInfo {
+        name: layer.name.clone(),
+        display_name: layer.display_name.clone(),
+        priority: layer.priority,
+        string_overrides: layer.string_overrides.clone(),
+    }
+}
+
 fn format_authors(authors: &[ModProjectAuthor]) -> String {
     if authors.is_empty() {
         return "Unknown".to_string();
@@ -276,3 +570,225 @@ fn format_authors(authors: &[ModProjectAuthor]) -> String {
 
     author_names.join(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use zip::ZipArchive;
+
+    fn packed_image(image_path: &Path) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        pack_image(&mut zip, image_path, &SimpleFileOptions::default()).unwrap();
+        let mut archive = ZipArchive::new(zip.finish().unwrap()).unwrap();
+        let mut packed = Vec::new();
+        archive
+            .by_name("META/image.png")
+            .unwrap()
+            .read_to_end(&mut packed)
+            .unwrap();
+        packed
+    }
+
+    #[test]
+    fn test_pack_image_copies_png_verbatim() {
+        let dir = tempfile::tempdir().unwrap();
+        let png_path = dir.path().join("thumbnail.png");
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]))
+            .save(&png_path)
+            .unwrap();
+
+        assert_eq!(packed_image(&png_path), std::fs::read(&png_path).unwrap());
+    }
+
+    #[test]
+    fn test_pack_image_reencodes_oversized_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let png_path = dir.path().join("thumbnail.png");
+        image::RgbaImage::from_pixel(2 * MAX_IMAGE_DIMENSION, 4, image::Rgba([0, 255, 0, 255]))
+            .save(&png_path)
+            .unwrap();
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        pack_image_with_limit(&mut zip, &png_path, &SimpleFileOptions::default(), 16).unwrap();
+        let mut archive = ZipArchive::new(zip.finish().unwrap()).unwrap();
+        let mut packed = Vec::new();
+        archive
+            .by_name("META/image.png")
+            .unwrap()
+            .read_to_end(&mut packed)
+            .unwrap();
+
+        assert_ne!(packed, std::fs::read(&png_path).unwrap());
+        let decoded = image::load_from_memory_with_format(&packed, ImageFormat::Png).unwrap();
+        assert_eq!(
+            (decoded.width(), decoded.height()),
+            (MAX_IMAGE_DIMENSION, 2)
+        );
+    }
+
+    #[test]
+    fn test_pack_image_rejects_corrupt_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let png_path = dir.path().join("thumbnail.png");
+        std::fs::write(&png_path, b"\x89PNG\r\n\x1a\nnot a png").unwrap();
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        assert!(pack_image(&mut zip, &png_path, &SimpleFileOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_pack_image_converts_webp_to_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let webp_path = dir.path().join("thumbnail.webp");
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 255, 255]))
+            .save(&webp_path)
+            .unwrap();
+
+        let packed = packed_image(&webp_path);
+        assert_eq!(image::guess_format(&packed).unwrap(), ImageFormat::Png);
+        let decoded = image::load_from_memory_with_format(&packed, ImageFormat::Png).unwrap();
+        assert_eq!(
+            decoded.to_rgba8().get_pixel(0, 0),
+            &image::Rgba([0, 0, 255, 255])
+        );
+    }
+
+    #[test]
+    fn test_pack_to_fantome_with_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for (layer, file) in [("base", "skin.bin"), ("chroma", "chroma.bin")] {
+            let wad_dir = root
+                .join("content")
+                .join(layer)
+                .join("Aatrox.wad.client/data");
+            std::fs::create_dir_all(&wad_dir).unwrap();
+            std::fs::write(wad_dir.join(file), layer).unwrap();
+        }
+
+        let mut chroma = ModProjectLayer {
+            name: "chroma".to_string(),
+            display_name: Some("Chroma".to_string()),
+            priority: 10,
+            description: None,
+            string_overrides: HashMap::new(),
+        };
+        chroma.string_overrides.insert(
+            "default".to_string(),
+            HashMap::from([("name".to_string(), "Ruby Aatrox".to_string())]),
+        );
+        let mod_project = ModProject {
+            name: "layered".to_string(),
+            display_name: "Layered".to_string(),
+            version: "1.0.0".to_string(),
+            layers: vec![ModProjectLayer::base(), chroma],
+            ..Default::default()
+        };
+
+        let mut buffer = Cursor::new(Vec::new());
+        pack_to_fantome_with_layers(&mut buffer, &mod_project, root).unwrap();
+        let buffer = buffer.into_inner();
+
+        // Extracting the archive restores both layers' content and declarations
+        let extracted = tempfile::tempdir().unwrap();
+        let result = FantomeExtractor::new(Cursor::new(buffer.clone()))
+            .unwrap()
+            .extract_to(extracted.path())
+            .unwrap();
+        let content = extracted.path().join("content");
+        assert_eq!(
+            std::fs::read_to_string(content.join("base/Aatrox.wad.client/data/skin.bin")).unwrap(),
+            "base"
+        );
+        assert_eq!(
+            std::fs::read_to_string(content.join("chroma/Aatrox.wad.client/data/chroma.bin"))
+                .unwrap(),
+            "chroma"
+        );
+        assert!(
+            !content
+                .join("base/Aatrox.wad.client/data/chroma.bin")
+                .exists()
+        );
+
+        let layers = &result.mod_project.layers;
+        assert_eq!(
+            layers.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+            ["base", "chroma"]
+        );
+        assert_eq!(layers[1].priority, 10);
+        assert_eq!(layers[1].display_name.as_deref(), Some("Chroma"));
+        assert_eq!(layers[1].string_overrides["default"]["name"], "Ruby Aatrox");
+
+        // Converting to a modpkg keeps the chroma content in its layer
+        let mut modpkg = Cursor::new(Vec::new());
+        into_modpkg_builder(Cursor::new(buffer))
+            .unwrap()
+            .build_to_writer(&mut modpkg)
+            .unwrap();
+        modpkg.set_position(0);
+        let mut modpkg = ltk_modpkg::Modpkg::mount_from_reader(modpkg).unwrap();
+        assert_eq!(
+            &*modpkg
+                .load_chunk_decompressed_by_path("data/chroma.bin", Some("chroma"))
+                .unwrap(),
+            b"chroma"
+        );
+        assert!(!modpkg.has_chunk("data/chroma.bin", Some("base")));
+        let metadata = modpkg.load_metadata().unwrap();
+        let chroma = metadata.layers.iter().find(|l| l.name == "chroma").unwrap();
+        assert_eq!(chroma.priority, 10);
+
+        // The legacy packer leaves the chroma layer out
+        let mut legacy = Cursor::new(Vec::new());
+        pack_to_fantome(&mut legacy, &mod_project, root).unwrap();
+        let legacy = ZipArchive::new(Cursor::new(legacy.into_inner())).unwrap();
+        assert!(!legacy.file_names().any(|name| name.starts_with(LAYERS_DIR)));
+    }
+
+    #[test]
+    fn test_pack_to_fantome_with_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "Aatrox.wad.client/data/skin.bin",
+            "Aatrox.wad.client/assets/skin.tex",
+            "Map11.wad.client/data/map.bin",
+        ] {
+            let path = root.join("content/base").join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, file).unwrap();
+        }
+        let mod_project = ModProject {
+            name: "progress".to_string(),
+            display_name: "Progress".to_string(),
+            version: "1.0.0".to_string(),
+            layers: vec![ModProjectLayer::base()],
+            ..Default::default()
+        };
+
+        let mut updates = Vec::new();
+        let mut buffer = Cursor::new(Vec::new());
+        pack_to_fantome_with_progress(&mut buffer, &mod_project, root, |progress| {
+            updates.push(progress)
+        })
+        .unwrap();
+        let archive = ZipArchive::new(Cursor::new(buffer.into_inner())).unwrap();
+
+        let mut packed: Vec<String> = archive
+            .file_names()
+            .filter(|name| name.starts_with("WAD/"))
+            .map(str::to_string)
+            .collect();
+        packed.sort();
+        assert_eq!(updates.len(), packed.len());
+        for (index, update) in updates.iter().enumerate() {
+            assert_eq!(update.files_written, index + 1);
+            assert_eq!(update.total_files, packed.len());
+        }
+        let mut reported: Vec<String> = updates.into_iter().map(|u| u.file_name).collect();
+        reported.sort();
+        assert_eq!(reported, packed);
+    }
+}
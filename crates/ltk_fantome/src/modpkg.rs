@@ -0,0 +1,629 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek, Write};
+
+use image::ImageFormat;
+use ltk_modpkg::builder::{
+    ModpkgBuilder, ModpkgBuilderError, ModpkgChunkBuilder, ModpkgLayerBuilder,
+};
+use ltk_modpkg::{
+    Modpkg, ModpkgAuthor, ModpkgCompression, ModpkgDependency, ModpkgLayerMetadata, ModpkgMetadata,
+    README_CHUNK_PATH, THUMBNAIL_CHUNK_PATH, hash_layer_name,
+};
+use ltk_wad::Wad;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::error::FantomeExtractError;
+use crate::extractor::is_wad_file_name;
+use crate::{
+    FantomeDependency, FantomeInfo, FantomeLayerInfo, content_entry_layer, format_chunk_path_hash,
+    is_supported_layer,
+};
+
+/// A [`ModpkgBuilder`] populated from a Fantome archive, together with the chunk data
+/// it needs to be written.
+///
+/// [`ModpkgBuilder`] only describes chunks; their bytes are supplied when the package
+/// is written. This type keeps the bytes read from the Fantome archive alongside the
+/// builder so the conversion can complete without touching the filesystem.
+pub struct FantomeModpkgBuilder {
+    /// The configured modpkg builder (metadata, layers, chunks, README, thumbnail).
+    pub builder: ModpkgBuilder,
+    /// Archive entries that could not be represented in a modpkg and were skipped
+    /// (e.g. `RAW/` overrides, which the modpkg format does not support).
+    pub skipped_entries: Vec<String>,
+    chunk_data: HashMap<(u64, u64), Vec<u8>>,
+}
+
+impl FantomeModpkgBuilder {
+    /// Get the uncompressed data collected for a chunk, if any.
+    pub fn chunk_data(&self, chunk: &ModpkgChunkBuilder) -> Option<&[u8]> {
+        self.chunk_data.get(&chunk.key()).map(Vec::as_slice)
+    }
+
+    /// Write the modpkg to `writer` using the collected chunk data.
+    pub fn build_to_writer<W: Write + Seek>(
+        self,
+        writer: &mut W,
+    ) -> Result<(), ModpkgBuilderError> {
+        let chunk_data = self.chunk_data;
+        self.builder.build_to_writer(writer, |chunk, cursor| {
+            let data = chunk_data.get(&chunk.key()).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Missing data for chunk: {}", chunk.path),
+                )
+            })?;
+            cursor.write_all(data)?;
+            Ok(())
+        })
+    }
+}
+
+/// Read a Fantome archive directly into a modpkg builder, without extracting to disk.
+///
+/// WAD folder entries and packed WAD files become chunks associated with their WAD,
+/// in the base layer for `WAD/` and in `{layer}` for `LAYERS/{layer}/`.
+/// `META/info.json`, `META/README.md` and `META/image.png` become the modpkg
+/// metadata, README and (WebP) thumbnail. `RAW/` entries are reported in
+/// [`FantomeModpkgBuilder::skipped_entries`].
+///
+/// Combine with [`FantomeModpkgBuilder::build_to_writer`] for a fully in-memory
+/// Fantome to modpkg conversion.
+pub fn into_modpkg_builder<R: Read + Seek>(
+    reader: R,
+) -> Result<FantomeModpkgBuilder, FantomeExtractError> {
+    let mut archive = ZipArchive::new(reader)?;
+
+    let mut info: Option<FantomeInfo> = None;
+    let mut readme: Option<String> = None;
+    let mut thumbnail: Option<Vec<u8>> = None;
+    let mut chunks: Vec<(ModpkgChunkBuilder, Vec<u8>)> = Vec::new();
+    let mut skipped_entries = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let file_name = file.name().to_string();
+
+        if file.enclosed_name().is_none() {
+            return Err(FantomeExtractError::InvalidWadPath { path: file_name });
+        }
+        if file.is_dir() {
+            continue;
+        }
+
+        let lower = file_name.to_lowercase();
+        if let Some((layer, relative_path)) = content_entry_layer(&file_name) {
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+
+            match relative_path.split_once('/') {
+                None if is_wad_file_name(relative_path) => {
+                    collect_packed_wad_chunks(layer, relative_path, data, &mut chunks)?;
+                }
+                Some((wad_name, chunk_path)) if !chunk_path.is_empty() => {
+                    let chunk = chunk_builder_for(chunk_path)?
+                        .with_layer(layer)
+                        .with_wad(wad_name);
+                    chunks.push((chunk, data));
+                }
+                _ => return Err(FantomeExtractError::InvalidWadPath { path: file_name }),
+            }
+        } else if lower == "meta/info.json" {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            let content = content.trim_start_matches('\u{feff}').trim();
+            if !content.is_empty() {
+                info = Some(serde_json::from_str(content)?);
+            }
+        } else if lower == "meta/readme.md" {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            readme = Some(content);
+        } else if lower == "meta/image.png" {
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            thumbnail = Some(png_to_webp(&data)?);
+        } else {
+            skipped_entries.push(file_name);
+        }
+    }
+
+    let info = info.ok_or(FantomeExtractError::MissingMetadata)?;
+    let layers = info.archive_layers(chunks.iter().map(|(chunk, _)| chunk.layer.as_str()));
+
+    let mut builder = ModpkgBuilder::default();
+    for layer in &layers {
+        builder =
+            builder.with_layer(ModpkgLayerBuilder::new(&layer.name).with_priority(layer.priority));
+    }
+    builder = builder.with_metadata(build_metadata(&info, &layers))?;
+
+    let mut chunk_data = HashMap::with_capacity(chunks.len());
+    for (chunk, data) in chunks {
+        chunk_data.insert(chunk.key(), data);
+        builder = builder.with_chunk(chunk);
+    }
+
+    if let Some(readme) = &readme {
+        builder = builder.with_readme(readme)?;
+    }
+    if let Some(thumbnail) = thumbnail {
+        builder = builder.with_thumbnail(thumbnail)?;
+    }
+
+    Ok(FantomeModpkgBuilder {
+        builder,
+        skipped_entries,
+        chunk_data,
+    })
+}
+
+/// Create a chunk builder, keeping hex-hash file names as raw path hashes.
+fn chunk_builder_for(chunk_path: &str) -> Result<ModpkgChunkBuilder, ModpkgBuilderError> {
+    let file_name = chunk_path.rsplit('/').next().unwrap_or(chunk_path);
+    let stem = file_name.split('.').next().unwrap_or(file_name);
+
+    let chunk = if ltk_modpkg::utils::is_hex_chunk_name(stem) {
+        ModpkgChunkBuilder::new().with_hashed_chunk_name(chunk_path)?
+    } else {
+        ModpkgChunkBuilder::new().with_path(chunk_path)?
+    };
+
+    Ok(chunk.with_compression(ModpkgCompression::Zstd))
+}
+
+/// Decompress every chunk of a packed WAD into hex-named chunk builders.
+fn collect_packed_wad_chunks(
+    layer: &str,
+    wad_name: &str,
+    wad_data: Vec<u8>,
+    chunks: &mut Vec<(ModpkgChunkBuilder, Vec<u8>)>,
+) -> Result<(), FantomeExtractError> {
+    let mut wad = Wad::mount(Cursor::new(wad_data))?;
+    let wad_chunks: Vec<_> = wad.chunks().iter().copied().collect();
+
+    for wad_chunk in wad_chunks {
+        let data = wad.load_chunk_decompressed(&wad_chunk)?.to_vec();
+        let chunk = chunk_builder_for(&format!("{:016x}.bin", wad_chunk.path_hash))?
+            .with_layer(layer)
+            .with_wad(wad_name);
+        chunks.push((chunk, data));
+    }
+
+    Ok(())
+}
+
+/// Write the base layer of a modpkg as a Fantome archive.
+///
+/// Base-layer chunks are written under `WAD/<wad name>/`, chunks without a known
+/// path under their hex path hash. The metadata becomes `META/info.json`, and the
+/// README and thumbnail become `META/README.md` and `META/image.png`.
+///
+/// Only the base layer is written, as by [`pack_to_fantome`](crate::pack_to_fantome),
+/// so the archive loads in managers that only know the legacy format. Content
+/// in other layers is dropped and those layers are not listed in `info.json`,
+/// as are chunks not associated with a WAD. Returns a warning for each layer
+/// and chunk left out.
+///
+/// The reverse conversion is [`into_modpkg_builder`]. There is no
+/// `ltk_modpkg::from_fantome`: `ltk_fantome` depends on `ltk_modpkg`, so
+/// `ltk_modpkg` cannot depend back on it without a dependency cycle.
+pub fn from_modpkg<R: Read + Seek, W: Write + Seek>(
+    modpkg: &mut Modpkg<R>,
+    writer: W,
+) -> eyre::Result<Vec<String>> {
+    let metadata = modpkg.load_metadata()?;
+    let mut warnings: Vec<String> = modpkg
+        .layer_indices
+        .iter()
+        .filter_map(|layer_hash| modpkg.layers.get(layer_hash))
+        .filter(|layer| !is_supported_layer(&layer.name))
+        .map(|layer| {
+            format!(
+                "Layer '{}' is not supported by Fantome and was not included",
+                layer.name
+            )
+        })
+        .collect();
+
+    let base_hash = hash_layer_name("base");
+    let mut files = Vec::new();
+    for chunk in modpkg.iter_chunks() {
+        if !chunk.layer.is_some_and(is_supported_layer) {
+            continue;
+        }
+        let path = chunk
+            .path
+            .map(str::to_string)
+            .unwrap_or_else(|| format_chunk_path_hash(chunk.path_hash));
+        match modpkg.chunk_wad_name(chunk.path_hash, base_hash) {
+            Some(wad) => files.push((format!("WAD/{}/{}", wad, path), chunk.path_hash)),
+            None => warnings.push(format!(
+                "Chunk '{}' is not associated with a WAD and was not included",
+                path
+            )),
+        }
+    }
+    files.sort();
+
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    for (zip_path, path_hash) in files {
+        let data = modpkg.load_chunk_decompressed_by_hash(path_hash, base_hash)?;
+        zip.start_file(zip_path, options)?;
+        zip.write_all(&data)?;
+    }
+
+    let info = FantomeInfo {
+        name: metadata.display_name.clone(),
+        author: format_authors(&metadata.authors),
+        version: metadata.version.to_string(),
+        description: metadata.description.clone().unwrap_or_default(),
+        tags: metadata.tags.clone(),
+        champions: metadata.champions.clone(),
+        maps: metadata.maps.clone(),
+        dependencies: metadata
+            .dependencies
+            .iter()
+            .map(|d| FantomeDependency {
+                name: d.name.clone(),
+                version_req: d.version_req.clone(),
+            })
+            .collect(),
+        layers: metadata
+            .layers
+            .iter()
+            .filter(|layer| is_supported_layer(&layer.name) && !layer.string_overrides.is_empty())
+            .map(|layer| {
+                let info = FantomeLayerInfo {
+                    name: layer.name.clone(),
+                    display_name: layer.display_name.clone(),
+                    priority: layer.priority,
+                    string_overrides: layer.string_overrides.clone(),
+                };
+                (layer.name.clone(), info)
+            })
+            .collect(),
+    };
+    zip.start_file("META/info.json", options)?;
+    zip.write_all(&serde_json::to_string_pretty(&info)?.into_bytes())?;
+
+    if modpkg.has_chunk(README_CHUNK_PATH, None) {
+        zip.start_file("META/README.md", options)?;
+        zip.write_all(&modpkg.load_readme()?)?;
+    }
+    if modpkg.has_chunk(THUMBNAIL_CHUNK_PATH, None) {
+        let thumbnail = image::load_from_memory(&modpkg.load_thumbnail()?)?;
+        let mut png = Vec::new();
+        thumbnail.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+        zip.start_file("META/image.png", options)?;
+        zip.write_all(&png)?;
+    }
+
+    zip.finish()?;
+    Ok(warnings)
+}
+
+fn format_authors(authors: &[ModpkgAuthor]) -> String {
+    if authors.is_empty() {
+        return "Unknown".to_string();
+    }
+    authors
+        .iter()
+        .map(|author| author.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn png_to_webp(data: &[u8]) -> Result<Vec<u8>, FantomeExtractError> {
+    let img = image::load_from_memory_with_format(data, ImageFormat::Png)?;
+    let mut webp = Vec::new();
+    img.write_to(&mut Cursor::new(&mut webp), ImageFormat::WebP)?;
+    Ok(webp)
+}
+
+fn build_metadata(info: &FantomeInfo, layers: &[FantomeLayerInfo]) -> ModpkgMetadata {
+    let layers = layers
+        .iter()
+        .map(|layer| ModpkgLayerMetadata {
+            name: layer.name.clone(),
+            display_name: layer.display_name.clone(),
+            priority: layer.priority,
+            description: None,
+            string_overrides: layer.string_overrides.clone(),
+        })
+        .collect();
+
+    ModpkgMetadata {
+        name: slug::slugify(&info.name),
+        display_name: info.name.clone(),
+        description: Some(info.description.clone()),
+        version: parse_version_lenient(&info.version),
+        authors: vec![ModpkgAuthor::new(info.author.clone(), None)],
+        tags: info.tags.clone(),
+        champions: info.champions.clone(),
+        maps: info.maps.clone(),
+        dependencies: info
+            .dependencies
+            .iter()
+            .map(|d| ModpkgDependency {
+                name: d.name.clone(),
+                version_req: d.version_req.clone(),
+            })
+            .collect(),
+        layers,
+        ..ModpkgMetadata::default()
+    }
+}
+
+/// Parse a Fantome version string, padding loose forms like `1` or `1.2` to semver.
+///
+/// Falls back to `0.0.0` when the version cannot be interpreted.
+fn parse_version_lenient(version: &str) -> semver::Version {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    if let Ok(v) = semver::Version::parse(version) {
+        return v;
+    }
+
+    let mut parts = version
+        .split('.')
+        .map(|p| p.trim().parse::<u64>().ok())
+        .chain(std::iter::repeat(Some(0)));
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Some(major)), Some(Some(minor)), Some(Some(patch)))
+            if version.split('.').count() <= 3 =>
+        {
+            semver::Version::new(major, minor, patch)
+        }
+        _ => semver::Version::new(0, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ltk_modpkg::{Modpkg, hash_layer_name};
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    fn create_test_fantome() -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("META/info.json", options).unwrap();
+        zip.write_all(
+            br#"{"Name": "Test Mod", "Author": "Tester", "Version": "1.2", "Description": "d"}"#,
+        )
+        .unwrap();
+        zip.start_file("META/README.md", options).unwrap();
+        zip.write_all(b"# Readme").unwrap();
+
+        zip.start_file("WAD/Aatrox.wad.client/assets/test.bin", options)
+            .unwrap();
+        zip.write_all(b"named chunk").unwrap();
+        zip.start_file("WAD/Aatrox.wad.client/0123456789abcdef.bin", options)
+            .unwrap();
+        zip.write_all(b"hashed chunk").unwrap();
+        zip.start_file("RAW/assets/raw.bin", options).unwrap();
+        zip.write_all(b"raw").unwrap();
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_fantome_to_modpkg_in_memory() {
+        let converted = into_modpkg_builder(Cursor::new(create_test_fantome())).unwrap();
+        assert_eq!(converted.skipped_entries, vec!["RAW/assets/raw.bin"]);
+
+        let mut output = Cursor::new(Vec::new());
+        converted.build_to_writer(&mut output).unwrap();
+
+        output.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(output).unwrap();
+
+        let metadata = modpkg.load_metadata().unwrap();
+        assert_eq!(metadata.name, "test-mod");
+        assert_eq!(metadata.display_name, "Test Mod");
+        assert_eq!(metadata.version, semver::Version::new(1, 2, 0));
+        assert_eq!(modpkg.load_readme().unwrap(), b"# Readme");
+
+        let named = modpkg
+            .load_chunk_decompressed_by_path("assets/test.bin", Some("base"))
+            .unwrap();
+        assert_eq!(&named[..], b"named chunk");
+        let hashed = modpkg
+            .load_chunk_decompressed_by_hash(0x0123456789abcdef, hash_layer_name("base"))
+            .unwrap();
+        assert_eq!(&hashed[..], b"hashed chunk");
+        assert!(modpkg.wad_index("aatrox.wad.client").is_some());
+    }
+
+    fn read_zip_entries(data: Vec<u8>) -> HashMap<String, Vec<u8>> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut file = archive.by_index(i).unwrap();
+                let mut contents = Vec::new();
+                file.read_to_end(&mut contents).unwrap();
+                (file.name().to_string(), contents)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fantome_round_trips_through_modpkg() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        zip.write_all(
+            br#"{"Name": "Test Mod", "Author": "Tester", "Version": "1.2.0", "Description": "d"}"#,
+        )
+        .unwrap();
+        zip.start_file("META/README.md", options).unwrap();
+        zip.write_all(b"# Readme").unwrap();
+        zip.start_file("WAD/aatrox.wad.client/assets/test.bin", options)
+            .unwrap();
+        zip.write_all(b"named chunk").unwrap();
+        zip.start_file("WAD/aatrox.wad.client/0123456789abcdef", options)
+            .unwrap();
+        zip.write_all(b"hashed chunk").unwrap();
+        let original = zip.finish().unwrap().into_inner();
+
+        let mut modpkg_data = Cursor::new(Vec::new());
+        into_modpkg_builder(Cursor::new(original.clone()))
+            .unwrap()
+            .build_to_writer(&mut modpkg_data)
+            .unwrap();
+        modpkg_data.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(modpkg_data).unwrap();
+
+        let mut fantome = Cursor::new(Vec::new());
+        let warnings = from_modpkg(&mut modpkg, &mut fantome).unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        let original = read_zip_entries(original);
+        let mut round_tripped = read_zip_entries(fantome.into_inner());
+        let info: FantomeInfo =
+            serde_json::from_slice(&round_tripped.remove("META/info.json").unwrap()).unwrap();
+        assert_eq!(
+            (
+                info.name.as_str(),
+                info.author.as_str(),
+                info.version.as_str()
+            ),
+            ("Test Mod", "Tester", "1.2.0")
+        );
+        assert_eq!(info.description, "d");
+        for (name, contents) in &round_tripped {
+            assert_eq!(original.get(name), Some(contents), "{name}");
+        }
+        assert_eq!(round_tripped.len(), original.len() - 1);
+    }
+
+    #[test]
+    fn test_modpkg_round_trips_through_fantome() {
+        let mut original = Cursor::new(Vec::new());
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(1))
+            .with_metadata(ModpkgMetadata {
+                name: "add-on".to_string(),
+                display_name: "Add-on".to_string(),
+                dependencies: vec![ModpkgDependency {
+                    name: "old-summoners-rift".to_string(),
+                    version_req: "^1.2".to_string(),
+                }],
+                layers: ["base", "chroma"]
+                    .into_iter()
+                    .map(|name| ModpkgLayerMetadata {
+                        name: name.to_string(),
+                        display_name: None,
+                        priority: 0,
+                        description: None,
+                        string_overrides: HashMap::from([(
+                            "default".to_string(),
+                            HashMap::from([("Name".to_string(), name.to_string())]),
+                        )]),
+                    })
+                    .collect(),
+                ..ModpkgMetadata::default()
+            })
+            .unwrap()
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_wad("Aatrox.wad.client"),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/chroma.bin")
+                    .unwrap()
+                    .with_layer("chroma")
+                    .with_wad("Aatrox.wad.client"),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/loose.bin")
+                    .unwrap(),
+            )
+            .build_to_writer(&mut original, |chunk, cursor| {
+                cursor.write_all(chunk.path.as_bytes())?;
+                Ok(())
+            })
+            .unwrap();
+        original.set_position(0);
+        let mut original = Modpkg::mount_from_reader(original).unwrap();
+
+        let mut fantome = Cursor::new(Vec::new());
+        let warnings = from_modpkg(&mut original, &mut fantome).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                "Layer 'chroma' is not supported by Fantome and was not included",
+                "Chunk 'data/loose.bin' is not associated with a WAD and was not included",
+            ]
+        );
+
+        let fantome = fantome.into_inner();
+        let info: FantomeInfo =
+            serde_json::from_slice(&read_zip_entries(fantome.clone())["META/info.json"]).unwrap();
+        assert_eq!(info.layers.keys().collect::<Vec<_>>(), vec!["base"]);
+
+        let mut converted = Cursor::new(Vec::new());
+        into_modpkg_builder(Cursor::new(fantome))
+            .unwrap()
+            .build_to_writer(&mut converted)
+            .unwrap();
+        converted.set_position(0);
+        let mut converted = Modpkg::mount_from_reader(converted).unwrap();
+
+        assert_eq!(
+            &*converted
+                .load_chunk_decompressed_by_path("data/skin.bin", Some("base"))
+                .unwrap(),
+            b"data/skin.bin"
+        );
+        let skin = *converted.get_chunk("data/skin.bin", Some("base")).unwrap();
+        assert_eq!(
+            converted.wad_name_for_index(skin.wad_index),
+            Some("aatrox.wad.client")
+        );
+        assert!(!converted.has_chunk("data/chroma.bin", Some("chroma")));
+        assert!(!converted.has_chunk("data/loose.bin", Some("base")));
+        assert_eq!(
+            converted.load_metadata().unwrap().dependencies,
+            vec![ModpkgDependency {
+                name: "old-summoners-rift".to_string(),
+                version_req: "^1.2".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_missing_info_json() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("WAD/Aatrox.wad.client/a.bin", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"a").unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let err = into_modpkg_builder(Cursor::new(buffer)).err().unwrap();
+        assert!(matches!(err, FantomeExtractError::MissingMetadata));
+    }
+
+    #[test]
+    fn test_parse_version_lenient() {
+        assert_eq!(
+            parse_version_lenient("1.0.0"),
+            semver::Version::new(1, 0, 0)
+        );
+        assert_eq!(parse_version_lenient("v2"), semver::Version::new(2, 0, 0));
+        assert_eq!(parse_version_lenient("1.3"), semver::Version::new(1, 3, 0));
+        assert_eq!(parse_version_lenient("beta"), semver::Version::new(0, 0, 0));
+    }
+}
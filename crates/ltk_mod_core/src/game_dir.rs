@@ -0,0 +1,105 @@
+//! Normalization of user-supplied League paths to the `Game/` directory.
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+/// Errors returned by [`resolve_game_dir`].
+#[derive(Debug, thiserror::Error)]
+pub enum GameDirError {
+    #[error("no League of Legends Game directory (containing DATA/FINAL) found at '{0}'")]
+    NotFound(Utf8PathBuf),
+
+    #[error(
+        "'{0}' looks like the Riot Client, point to the League of Legends Game directory instead"
+    )]
+    RiotClient(Utf8PathBuf),
+}
+
+/// Resolve a League path to its `Game/` directory.
+///
+/// Accepts any of:
+/// - the `Game/` directory itself (contains `DATA/FINAL`),
+/// - the install root (contains `Game/DATA/FINAL`),
+/// - a file inside `Game/`, such as the `League of Legends.exe` path returned by
+///   [`auto_detect_league_path`](crate::auto_detect_league_path).
+///
+/// The `Game/` directory is the one overlay builders expect: the directory that
+/// `DATA/FINAL` is joined onto.
+pub fn resolve_game_dir(path: &Utf8Path) -> Result<Utf8PathBuf, GameDirError> {
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+
+    if has_data_final(dir) {
+        return Ok(dir.to_path_buf());
+    }
+
+    let nested = dir.join("Game");
+    if has_data_final(&nested) {
+        return Ok(nested);
+    }
+
+    if crate::is_riot_client_path(path) {
+        return Err(GameDirError::RiotClient(path.to_path_buf()));
+    }
+    Err(GameDirError::NotFound(path.to_path_buf()))
+}
+
+fn has_data_final(dir: &Utf8Path) -> bool {
+    dir.join("DATA").join("FINAL").is_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install_root() -> (tempfile::TempDir, Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        std::fs::create_dir_all(root.join("Game/DATA/FINAL")).unwrap();
+        std::fs::write(root.join("Game/League of Legends.exe"), b"").unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn accepts_game_dir_install_root_and_exe() {
+        let (_dir, root) = install_root();
+        let game = root.join("Game");
+
+        assert_eq!(resolve_game_dir(&game).unwrap(), game);
+        assert_eq!(resolve_game_dir(&root).unwrap(), game);
+        assert_eq!(
+            resolve_game_dir(&game.join("League of Legends.exe")).unwrap(),
+            game
+        );
+    }
+
+    #[test]
+    fn rejects_unrelated_directory() {
+        let (_dir, root) = install_root();
+        let data = root.join("Game/DATA");
+
+        assert!(matches!(
+            resolve_game_dir(&data),
+            Err(GameDirError::NotFound(p)) if p == data
+        ));
+    }
+
+    #[test]
+    fn reports_riot_client_install() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let client = root.join("Riot Client");
+        std::fs::create_dir_all(&client).unwrap();
+        let exe = client.join("RiotClientServices.exe");
+        std::fs::write(&exe, b"").unwrap();
+
+        for path in [&root, &client, &exe] {
+            assert!(
+                matches!(resolve_game_dir(path), Err(GameDirError::RiotClient(p)) if p == *path),
+                "{path}"
+            );
+        }
+    }
+}
@@ -15,6 +15,21 @@ pub fn is_valid_league_path(path: &Utf8Path) -> bool {
     false
 }
 
+/// Executable at the root of a Riot Client install.
+const RIOT_CLIENT_EXE: &str = "RiotClientServices.exe";
+
+/// Whether `path` points at the Riot Client install instead of League of Legends.
+///
+/// Matches `RiotClientServices.exe` itself, a directory containing it, or a
+/// directory with a `Riot Client` subfolder containing it. Used to give a
+/// specific error for this common misconfiguration.
+pub fn is_riot_client_path(path: &Utf8Path) -> bool {
+    if path.is_file() {
+        return path.file_name() == Some(RIOT_CLIENT_EXE);
+    }
+    path.join(RIOT_CLIENT_EXE).is_file() || path.join("Riot Client").join(RIOT_CLIENT_EXE).is_file()
+}
+
 /// Get all available drives using sysinfo (cross-platform).
 fn get_available_drives() -> Vec<String> {
     let disks = Disks::new_with_refreshed_list();
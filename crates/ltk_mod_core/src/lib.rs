@@ -3,6 +3,8 @@
 //! This crate provides common functionality used by both the `league-mod` CLI
 //! and the `ltk-manager` GUI application.
 
+mod game_dir;
 mod league_path;
 
-pub use league_path::{auto_detect_league_path, is_valid_league_path};
+pub use game_dir::{resolve_game_dir, GameDirError};
+pub use league_path::{auto_detect_league_path, is_riot_client_path, is_valid_league_path};
@@ -0,0 +1,319 @@
+//! Bundled champion roster for normalizing [`ModProject::champions`](crate::ModProject::champions).
+//!
+//! Mod authors spell champions in several ways: the display name ("Wukong"),
+//! the game's internal name ("MonkeyKing"), or a community shorthand ("J4").
+//! [`canonical_champion`] maps all of them to one [`ChampionId`] so filters and
+//! conflict checks compare like with like.
+
+use std::fmt;
+
+/// A champion from the bundled roster, identified by the game's internal name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChampionId(&'static str);
+
+impl ChampionId {
+    /// The internal name used in game asset paths, e.g. `MonkeyKing`.
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// The name shown in game, e.g. `Wukong`.
+    pub fn display_name(&self) -> &'static str {
+        ROSTER
+            .iter()
+            .find(|entry| entry.internal == self.0)
+            .map(|entry| entry.display)
+            .unwrap_or(self.0)
+    }
+
+    /// Every champion in the bundled roster, in alphabetical internal-name order.
+    pub fn all() -> impl Iterator<Item = ChampionId> {
+        ROSTER.iter().map(|entry| ChampionId(entry.internal))
+    }
+}
+
+impl fmt::Display for ChampionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Resolve a champion name, internal name or known alias to its [`ChampionId`].
+///
+/// Matching ignores case and anything that is not a letter or digit, so
+/// `"Kai'Sa"`, `"kaisa"` and `"KAI SA"` are all the same champion. Returns
+/// `None` for names not in the bundled roster.
+pub fn canonical_champion(name: &str) -> Option<ChampionId> {
+    let key = normalize(name);
+    if key.is_empty() {
+        return None;
+    }
+
+    ROSTER
+        .iter()
+        .find(|entry| {
+            normalize(entry.internal) == key
+                || normalize(entry.display) == key
+                || entry.aliases.iter().any(|alias| normalize(alias) == key)
+        })
+        .map(|entry| ChampionId(entry.internal))
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+struct RosterEntry {
+    internal: &'static str,
+    display: &'static str,
+    aliases: &'static [&'static str],
+}
+
+const fn champ(
+    internal: &'static str,
+    display: &'static str,
+    aliases: &'static [&'static str],
+) -> RosterEntry {
+    RosterEntry {
+        internal,
+        display,
+        aliases,
+    }
+}
+
+#[rustfmt::skip]
+const ROSTER: &[RosterEntry] = &[
+    champ("Aatrox", "Aatrox", &[]),
+    champ("Ahri", "Ahri", &[]),
+    champ("Akali", "Akali", &[]),
+    champ("Akshan", "Akshan", &[]),
+    champ("Alistar", "Alistar", &[]),
+    champ("Ambessa", "Ambessa", &[]),
+    champ("Amumu", "Amumu", &[]),
+    champ("Anivia", "Anivia", &[]),
+    champ("Annie", "Annie", &[]),
+    champ("Aphelios", "Aphelios", &[]),
+    champ("Ashe", "Ashe", &[]),
+    champ("AurelionSol", "Aurelion Sol", &["ASol"]),
+    champ("Aurora", "Aurora", &[]),
+    champ("Azir", "Azir", &[]),
+    champ("Bard", "Bard", &[]),
+    champ("Belveth", "Bel'Veth", &[]),
+    champ("Blitzcrank", "Blitzcrank", &["Blitz"]),
+    champ("Brand", "Brand", &[]),
+    champ("Braum", "Braum", &[]),
+    champ("Briar", "Briar", &[]),
+    champ("Caitlyn", "Caitlyn", &["Cait"]),
+    champ("Camille", "Camille", &[]),
+    champ("Cassiopeia", "Cassiopeia", &["Cass"]),
+    champ("Chogath", "Cho'Gath", &["Cho"]),
+    champ("Corki", "Corki", &[]),
+    champ("Darius", "Darius", &[]),
+    champ("Diana", "Diana", &[]),
+    champ("DrMundo", "Dr. Mundo", &["Mundo"]),
+    champ("Draven", "Draven", &[]),
+    champ("Ekko", "Ekko", &[]),
+    champ("Elise", "Elise", &[]),
+    champ("Evelynn", "Evelynn", &["Eve"]),
+    champ("Ezreal", "Ezreal", &["Ez"]),
+    champ("Fiddlesticks", "Fiddlesticks", &["Fiddle", "FiddleSticks"]),
+    champ("Fiora", "Fiora", &[]),
+    champ("Fizz", "Fizz", &[]),
+    champ("Galio", "Galio", &[]),
+    champ("Gangplank", "Gangplank", &["GP"]),
+    champ("Garen", "Garen", &[]),
+    champ("Gnar", "Gnar", &[]),
+    champ("Gragas", "Gragas", &[]),
+    champ("Graves", "Graves", &[]),
+    champ("Gwen", "Gwen", &[]),
+    champ("Hecarim", "Hecarim", &[]),
+    champ("Heimerdinger", "Heimerdinger", &["Heimer", "Donger"]),
+    champ("Hwei", "Hwei", &[]),
+    champ("Illaoi", "Illaoi", &[]),
+    champ("Irelia", "Irelia", &[]),
+    champ("Ivern", "Ivern", &[]),
+    champ("Janna", "Janna", &[]),
+    champ("JarvanIV", "Jarvan IV", &["Jarvan", "J4"]),
+    champ("Jax", "Jax", &[]),
+    champ("Jayce", "Jayce", &[]),
+    champ("Jhin", "Jhin", &[]),
+    champ("Jinx", "Jinx", &[]),
+    champ("KSante", "K'Sante", &[]),
+    champ("Kaisa", "Kai'Sa", &[]),
+    champ("Kalista", "Kalista", &[]),
+    champ("Karma", "Karma", &[]),
+    champ("Karthus", "Karthus", &[]),
+    champ("Kassadin", "Kassadin", &["Kass"]),
+    champ("Katarina", "Katarina", &["Kat"]),
+    champ("Kayle", "Kayle", &[]),
+    champ("Kayn", "Kayn", &[]),
+    champ("Kennen", "Kennen", &[]),
+    champ("Khazix", "Kha'Zix", &["Kha"]),
+    champ("Kindred", "Kindred", &[]),
+    champ("Kled", "Kled", &[]),
+    champ("KogMaw", "Kog'Maw", &["Kog"]),
+    champ("Leblanc", "LeBlanc", &["LB"]),
+    champ("LeeSin", "Lee Sin", &["Lee"]),
+    champ("Leona", "Leona", &[]),
+    champ("Lillia", "Lillia", &[]),
+    champ("Lissandra", "Lissandra", &["Liss"]),
+    champ("Lucian", "Lucian", &[]),
+    champ("Lulu", "Lulu", &[]),
+    champ("Lux", "Lux", &[]),
+    champ("Malphite", "Malphite", &[]),
+    champ("Malzahar", "Malzahar", &["Malz"]),
+    champ("Maokai", "Maokai", &[]),
+    champ("MasterYi", "Master Yi", &["Yi"]),
+    champ("Mel", "Mel", &[]),
+    champ("Milio", "Milio", &[]),
+    champ("MissFortune", "Miss Fortune", &["MF"]),
+    champ("MonkeyKing", "Wukong", &[]),
+    champ("Mordekaiser", "Mordekaiser", &["Morde"]),
+    champ("Morgana", "Morgana", &["Morg"]),
+    champ("Naafiri", "Naafiri", &[]),
+    champ("Nami", "Nami", &[]),
+    champ("Nasus", "Nasus", &[]),
+    champ("Nautilus", "Nautilus", &["Naut"]),
+    champ("Neeko", "Neeko", &[]),
+    champ("Nidalee", "Nidalee", &["Nid"]),
+    champ("Nilah", "Nilah", &[]),
+    champ("Nocturne", "Nocturne", &["Noc"]),
+    champ("Nunu", "Nunu & Willump", &["Nunu and Willump", "Willump"]),
+    champ("Olaf", "Olaf", &[]),
+    champ("Orianna", "Orianna", &["Ori"]),
+    champ("Ornn", "Ornn", &[]),
+    champ("Pantheon", "Pantheon", &["Panth"]),
+    champ("Poppy", "Poppy", &[]),
+    champ("Pyke", "Pyke", &[]),
+    champ("Qiyana", "Qiyana", &[]),
+    champ("Quinn", "Quinn", &[]),
+    champ("Rakan", "Rakan", &[]),
+    champ("Rammus", "Rammus", &[]),
+    champ("RekSai", "Rek'Sai", &[]),
+    champ("Rell", "Rell", &[]),
+    champ("Renata", "Renata Glasc", &[]),
+    champ("Renekton", "Renekton", &["Renek"]),
+    champ("Rengar", "Rengar", &[]),
+    champ("Riven", "Riven", &[]),
+    champ("Rumble", "Rumble", &[]),
+    champ("Ryze", "Ryze", &[]),
+    champ("Samira", "Samira", &[]),
+    champ("Sejuani", "Sejuani", &["Sej"]),
+    champ("Senna", "Senna", &[]),
+    champ("Seraphine", "Seraphine", &[]),
+    champ("Sett", "Sett", &[]),
+    champ("Shaco", "Shaco", &[]),
+    champ("Shen", "Shen", &[]),
+    champ("Shyvana", "Shyvana", &["Shyv"]),
+    champ("Singed", "Singed", &[]),
+    champ("Sion", "Sion", &[]),
+    champ("Sivir", "Sivir", &[]),
+    champ("Skarner", "Skarner", &[]),
+    champ("Smolder", "Smolder", &[]),
+    champ("Sona", "Sona", &[]),
+    champ("Soraka", "Soraka", &["Raka"]),
+    champ("Swain", "Swain", &[]),
+    champ("Sylas", "Sylas", &[]),
+    champ("Syndra", "Syndra", &[]),
+    champ("TahmKench", "Tahm Kench", &["Tahm"]),
+    champ("Taliyah", "Taliyah", &[]),
+    champ("Talon", "Talon", &[]),
+    champ("Taric", "Taric", &[]),
+    champ("Teemo", "Teemo", &[]),
+    champ("Thresh", "Thresh", &[]),
+    champ("Tristana", "Tristana", &["Trist"]),
+    champ("Trundle", "Trundle", &[]),
+    champ("Tryndamere", "Tryndamere", &["Trynd"]),
+    champ("TwistedFate", "Twisted Fate", &["TF"]),
+    champ("Twitch", "Twitch", &[]),
+    champ("Udyr", "Udyr", &[]),
+    champ("Urgot", "Urgot", &[]),
+    champ("Varus", "Varus", &[]),
+    champ("Vayne", "Vayne", &[]),
+    champ("Veigar", "Veigar", &[]),
+    champ("Velkoz", "Vel'Koz", &[]),
+    champ("Vex", "Vex", &[]),
+    champ("Vi", "Vi", &[]),
+    champ("Viego", "Viego", &[]),
+    champ("Viktor", "Viktor", &[]),
+    champ("Vladimir", "Vladimir", &["Vlad"]),
+    champ("Volibear", "Volibear", &["Voli"]),
+    champ("Warwick", "Warwick", &["WW"]),
+    champ("Xayah", "Xayah", &[]),
+    champ("Xerath", "Xerath", &[]),
+    champ("XinZhao", "Xin Zhao", &["Xin"]),
+    champ("Yasuo", "Yasuo", &[]),
+    champ("Yone", "Yone", &[]),
+    champ("Yorick", "Yorick", &[]),
+    champ("Yunara", "Yunara", &[]),
+    champ("Yuumi", "Yuumi", &[]),
+    champ("Zac", "Zac", &[]),
+    champ("Zed", "Zed", &[]),
+    champ("Zeri", "Zeri", &[]),
+    champ("Ziggs", "Ziggs", &[]),
+    champ("Zilean", "Zilean", &[]),
+    champ("Zoe", "Zoe", &[]),
+    champ("Zyra", "Zyra", &[]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn resolves_display_names_internal_names_and_aliases() {
+        let cases = [
+            ("Wukong", "MonkeyKing"),
+            ("monkeyking", "MonkeyKing"),
+            ("Nunu", "Nunu"),
+            ("Nunu & Willump", "Nunu"),
+            ("nunu and willump", "Nunu"),
+            ("Kai'Sa", "Kaisa"),
+            ("KAISA", "Kaisa"),
+            ("Dr. Mundo", "DrMundo"),
+            ("mundo", "DrMundo"),
+            ("J4", "JarvanIV"),
+            ("Jarvan IV", "JarvanIV"),
+            ("Renata Glasc", "Renata"),
+            ("MF", "MissFortune"),
+            ("Cho'Gath", "Chogath"),
+            ("Bel'Veth", "Belveth"),
+        ];
+        for (name, internal) in cases {
+            assert_eq!(
+                canonical_champion(name).map(|id| id.as_str()),
+                Some(internal),
+                "{name}"
+            );
+        }
+
+        assert_eq!(canonical_champion("Not A Champion"), None);
+        assert_eq!(canonical_champion("  '. "), None);
+        assert_eq!(
+            canonical_champion("wukong").unwrap().display_name(),
+            "Wukong"
+        );
+    }
+
+    #[test]
+    fn roster_keys_are_unambiguous() {
+        let mut seen = HashSet::new();
+        for entry in ROSTER {
+            let mut keys: HashSet<String> =
+                HashSet::from([normalize(entry.internal), normalize(entry.display)]);
+            keys.extend(entry.aliases.iter().map(|alias| normalize(alias)));
+            for key in keys {
+                assert!(
+                    seen.insert(key.clone()),
+                    "'{key}' maps to several champions"
+                );
+            }
+        }
+        assert!(ChampionId::all().count() > 160);
+        assert!(ROSTER.windows(2).all(|w| w[0].internal < w[1].internal));
+    }
+}
@@ -0,0 +1,127 @@
+//! Requirements of a mod on other mods.
+
+use crate::ModProject;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+/// Another mod a project requires, such as the base pack of an add-on.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ModDependency {
+    /// The name of the required mod
+    ///
+    /// Example: `old-summoners-rift`
+    pub name: String,
+
+    /// The versions of the required mod that satisfy the dependency, as a
+    /// semver requirement
+    ///
+    /// Example: `^1.2`
+    pub version_req: String,
+}
+
+impl ModDependency {
+    /// Whether a mod named `name` at `version` satisfies the dependency.
+    ///
+    /// Always `false` if `version_req` is not a valid semver requirement.
+    pub fn is_satisfied_by(&self, name: &str, version: &Version) -> bool {
+        name == self.name
+            && VersionReq::parse(&self.version_req).is_ok_and(|req| req.matches(version))
+    }
+}
+
+impl ModProject {
+    /// The dependencies that none of the `installed` mods, given as name and
+    /// version, satisfy.
+    ///
+    /// A dependency whose requirement does not parse is never satisfied.
+    pub fn unmet_dependencies(&self, installed: &[(&str, &Version)]) -> Vec<&ModDependency> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| {
+                !installed
+                    .iter()
+                    .any(|(name, version)| dependency.is_satisfied_by(name, version))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_with_dependency(version_req: &str) -> ModProject {
+        let mut project: ModProject = serde_json::from_str(
+            r#"{
+                "name": "rift-addon",
+                "display_name": "Rift Add-on",
+                "version": "1.0.0",
+                "description": "",
+                "authors": []
+            }"#,
+        )
+        .unwrap();
+        project.dependencies.push(ModDependency {
+            name: "old-summoners-rift".to_string(),
+            version_req: version_req.to_string(),
+        });
+        project
+    }
+
+    #[test]
+    fn test_dependency_satisfied() {
+        let project = project_with_dependency("^1.2");
+        let version = Version::new(1, 4, 0);
+        assert!(project
+            .unmet_dependencies(&[
+                ("other", &Version::new(2, 0, 0)),
+                ("old-summoners-rift", &version)
+            ])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dependency_missing() {
+        let project = project_with_dependency("^1.2");
+        let version = Version::new(1, 4, 0);
+        assert_eq!(
+            project.unmet_dependencies(&[("other", &version)]),
+            vec![&project.dependencies[0]]
+        );
+        assert_eq!(project.unmet_dependencies(&[]).len(), 1);
+    }
+
+    #[test]
+    fn test_dependency_version_mismatch() {
+        let project = project_with_dependency("^1.2");
+        let version = Version::new(1, 1, 0);
+        assert_eq!(
+            project.unmet_dependencies(&[("old-summoners-rift", &version)]),
+            vec![&project.dependencies[0]]
+        );
+
+        let invalid = project_with_dependency("not a requirement");
+        let version = Version::new(1, 4, 0);
+        assert_eq!(
+            invalid
+                .unmet_dependencies(&[("old-summoners-rift", &version)])
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_dependencies_default_empty() {
+        let project = project_with_dependency("^1.2");
+        let json = serde_json::to_string(&project).unwrap();
+        assert!(
+            json.contains(r#""dependencies":[{"name":"old-summoners-rift","version_req":"^1.2"}]"#)
+        );
+
+        let mut without = project.clone();
+        without.dependencies.clear();
+        assert!(!serde_json::to_string(&without)
+            .unwrap()
+            .contains("dependencies"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Splitting a project config across `mod.config.d/*.json` fragments.
+//!
+//! Large mods can keep the main `mod.config.json` (or `.toml`) small and move
+//! layer definitions and string overrides into fragment files. Fragments are
+//! merged onto the main config in file name order, so `10-chromas.json` is
+//! applied before `20-strings.json`.
+
+use crate::{ModProject, ModProjectError, ModProjectLayer};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Directory next to the main config that holds config fragments.
+pub const CONFIG_FRAGMENT_DIR: &str = "mod.config.d";
+
+/// A config fragment from `mod.config.d/`.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct ConfigFragment {
+    /// Layers to add, or to extend when a layer with the same name exists
+    #[serde(default)]
+    pub layers: Vec<LayerFragment>,
+}
+
+/// A layer declared in a [`ConfigFragment`].
+///
+/// Fields left out keep the values of an existing layer with the same name.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct LayerFragment {
+    /// The name of the layer to add or extend
+    pub name: String,
+
+    /// Required for a new layer; must match the existing priority otherwise
+    #[serde(default)]
+    pub priority: Option<i32>,
+
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// String overrides merged per locale; a later fragment replaces a field
+    /// set by an earlier one
+    #[serde(default)]
+    pub string_overrides: HashMap<String, HashMap<String, String>>,
+}
+
+/// Load a mod project from `project_dir`, merging in any config fragments.
+///
+/// The main config is read as by [`ModProject::load`]. Then every
+/// `mod.config.d/*.json` file is applied in file name order. A fragment layer
+/// whose name is new is appended and must declare a priority that no other
+/// layer has; one whose name already exists extends that layer and must not
+/// change its priority.
+pub fn load_config_with_includes(project_dir: &Path) -> Result<ModProject, ModProjectError> {
+    apply_fragments(ModProject::load(project_dir)?, project_dir)
+}
+
+/// Load the mod project at `config_path`, merging in the config fragments next to it.
+///
+/// Like [`load_config_with_includes`], for callers given a config file rather
+/// than a project directory.
+pub fn load_config_file_with_includes(config_path: &Path) -> Result<ModProject, ModProjectError> {
+    let project_dir = config_path.parent().unwrap_or(Path::new(""));
+    apply_fragments(ModProject::load_from_file(config_path)?, project_dir)
+}
+
+fn apply_fragments(
+    mut project: ModProject,
+    project_dir: &Path,
+) -> Result<ModProject, ModProjectError> {
+    for path in fragment_paths(project_dir)? {
+        let contents = std::fs::read_to_string(&path)?;
+        let fragment: ConfigFragment =
+            serde_json::from_str(&contents).map_err(|source| ModProjectError::Fragment {
+                path: path.clone(),
+                source,
+            })?;
+        merge_fragment(&mut project, fragment, &path)?;
+    }
+    Ok(project)
+}
+
+/// The `.json` files in the fragment directory, sorted by file name.
+fn fragment_paths(project_dir: &Path) -> Result<Vec<PathBuf>, ModProjectError> {
+    let dir = project_dir.join(CONFIG_FRAGMENT_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn merge_fragment(
+    project: &mut ModProject,
+    fragment: ConfigFragment,
+    path: &Path,
+) -> Result<(), ModProjectError> {
+    for layer in fragment.layers {
+        let existing = project.layers.iter_mut().find(|l| l.name == layer.name);
+        let target = match (existing, layer.priority) {
+            (Some(existing), Some(priority)) if existing.priority != priority => {
+                return Err(ModProjectError::LayerPriorityMismatch {
+                    path: path.to_owned(),
+                    layer: layer.name,
+                    existing: existing.priority,
+                    declared: priority,
+                });
+            }
+            (Some(existing), _) => existing,
+            (None, Some(priority)) => {
+                if let Some(other) = project.layers.iter().find(|l| l.priority == priority) {
+                    return Err(ModProjectError::DuplicateLayerPriority {
+                        path: path.to_owned(),
+                        layer: layer.name,
+                        other: other.name.clone(),
+                        priority,
+                    });
+                }
+                project.layers.push(ModProjectLayer {
+                    name: layer.name.clone(),
+                    display_name: None,
+                    priority,
+                    description: None,
+                    string_overrides: HashMap::new(),
+                });
+                project.layers.last_mut().unwrap()
+            }
+            (None, None) => {
+                return Err(ModProjectError::MissingLayerPriority {
+                    path: path.to_owned(),
+                    layer: layer.name,
+                });
+            }
+        };
+
+        if layer.display_name.is_some() {
+            target.display_name = layer.display_name;
+        }
+        if layer.description.is_some() {
+            target.description = layer.description;
+        }
+        for (locale, fields) in layer.string_overrides {
+            target
+                .string_overrides
+                .entry(locale)
+                .or_default()
+                .extend(fields);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_project(dir: &Path, fragments: &[(&str, &str)]) {
+        std::fs::write(
+            dir.join("mod.config.json"),
+            r#"{
+                "name": "big_mod",
+                "display_name": "Big Mod",
+                "version": "1.0.0",
+                "description": "",
+                "authors": [],
+                "layers": [
+                    {
+                        "name": "base",
+                        "priority": 0,
+                        "string_overrides": { "en_us": { "a": "main", "b": "main" } }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let fragment_dir = dir.join(CONFIG_FRAGMENT_DIR);
+        std::fs::create_dir_all(&fragment_dir).unwrap();
+        for (name, contents) in fragments {
+            std::fs::write(fragment_dir.join(name), contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn merges_fragments_in_file_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[
+                (
+                    "20-strings.json",
+                    r#"{ "layers": [
+                        { "name": "base", "string_overrides": { "en_us": { "b": "20" } } },
+                        { "name": "chromas", "string_overrides": { "en_us": { "c": "20" } } }
+                    ] }"#,
+                ),
+                (
+                    "10-chromas.json",
+                    r#"{ "layers": [
+                        { "name": "chromas", "priority": 5, "display_name": "Chromas",
+                          "string_overrides": { "en_us": { "c": "10" } } }
+                    ] }"#,
+                ),
+                ("notes.txt", "not a fragment"),
+            ],
+        );
+
+        let project = load_config_with_includes(dir.path()).unwrap();
+        assert_eq!(project.layers.len(), 2);
+
+        let base = &project.layers[0];
+        assert_eq!(base.string_overrides["en_us"]["a"], "main");
+        assert_eq!(base.string_overrides["en_us"]["b"], "20");
+
+        let chromas = &project.layers[1];
+        assert_eq!(chromas.name, "chromas");
+        assert_eq!(chromas.priority, 5);
+        assert_eq!(chromas.display_name.as_deref(), Some("Chromas"));
+        assert_eq!(chromas.string_overrides["en_us"]["c"], "20");
+    }
+
+    #[test]
+    fn no_fragment_dir_loads_the_main_config() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(dir.path(), &[]);
+        std::fs::remove_dir(dir.path().join(CONFIG_FRAGMENT_DIR)).unwrap();
+
+        let project = load_config_with_includes(dir.path()).unwrap();
+        assert_eq!(project, ModProject::load(dir.path()).unwrap());
+    }
+
+    #[test]
+    fn rejects_inconsistent_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[(
+                "base.json",
+                r#"{ "layers": [ { "name": "base", "priority": 3 } ] }"#,
+            )],
+        );
+        assert!(matches!(
+            load_config_with_includes(dir.path()),
+            Err(ModProjectError::LayerPriorityMismatch {
+                existing: 0,
+                declared: 3,
+                ..
+            })
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[("new.json", r#"{ "layers": [ { "name": "extra" } ] }"#)],
+        );
+        assert!(matches!(
+            load_config_with_includes(dir.path()),
+            Err(ModProjectError::MissingLayerPriority { layer, .. }) if layer == "extra"
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[(
+                "new.json",
+                r#"{ "layers": [ { "name": "extra", "priority": 0 } ] }"#,
+            )],
+        );
+        assert!(matches!(
+            load_config_with_includes(dir.path()),
+            Err(ModProjectError::DuplicateLayerPriority { layer, other, priority: 0, .. })
+                if layer == "extra" && other == "base"
+        ));
+    }
+
+    #[test]
+    fn config_file_variant_reads_fragments_next_to_it() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[(
+                "10-chromas.json",
+                r#"{ "layers": [ { "name": "chromas", "priority": 5 } ] }"#,
+            )],
+        );
+
+        let project = load_config_file_with_includes(&dir.path().join("mod.config.json")).unwrap();
+        assert_eq!(project, load_config_with_includes(dir.path()).unwrap());
+        assert_eq!(project.layers.len(), 2);
+    }
+}
@@ -3,6 +3,19 @@ use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
 
+mod champions;
+mod dependencies;
+mod includes;
+mod validate;
+
+pub use champions::{canonical_champion, ChampionId};
+pub use dependencies::ModDependency;
+pub use includes::{
+    load_config_file_with_includes, load_config_with_includes, ConfigFragment, LayerFragment,
+    CONFIG_FRAGMENT_DIR,
+};
+pub use validate::{is_valid_layer_name, is_valid_slug, ValidationIssue, ValidationSeverity};
+
 fn serde_fmt<T: Serialize>(value: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let json = serde_json::to_string(value).map_err(|_| fmt::Error)?;
     let s: String = serde_json::from_str(&json).map_err(|_| fmt::Error)?;
@@ -106,10 +119,38 @@ pub enum ModProjectError {
 
     #[error("Unsupported config file extension: {0}")]
     UnsupportedExtension(String),
+
+    #[error("Failed to parse config fragment {path}: {source}")]
+    Fragment {
+        path: std::path::PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Config fragment {path} gives layer '{layer}' priority {declared}, but it already has priority {existing}")]
+    LayerPriorityMismatch {
+        path: std::path::PathBuf,
+        layer: String,
+        existing: i32,
+        declared: i32,
+    },
+
+    #[error("Config fragment {path} gives layer '{layer}' priority {priority}, which layer '{other}' already has")]
+    DuplicateLayerPriority {
+        path: std::path::PathBuf,
+        layer: String,
+        other: String,
+        priority: i32,
+    },
+
+    #[error("Config fragment {path} adds layer '{layer}' without a priority")]
+    MissingLayerPriority {
+        path: std::path::PathBuf,
+        layer: String,
+    },
 }
 
 /// Describes a mod project configuration file
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 pub struct ModProject {
     /// The name of the mod
     /// Must not contain spaces or special characters except for underscores and hyphens
@@ -151,11 +192,24 @@ pub struct ModProject {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub maps: Vec<ModMap>,
 
+    /// Other mods this mod requires, such as the base pack of an add-on
+    /// Not enforced when packing; mod managers use it to refuse enabling a mod
+    /// whose dependencies are missing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<ModDependency>,
+
     /// File transformers to be applied during the build process
     /// Optional field - if not provided, no transformers will be applied
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub transformers: Vec<FileTransformer>,
 
+    /// Compression to use for override files whose path matches a glob pattern
+    /// Keys are patterns relative to the WAD root (e.g. `**/*.bnk`); when several
+    /// patterns match, the longest one wins
+    /// If empty, the overlay builder picks compression from each file's type
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub compression: HashMap<String, CompressionHint>,
+
     /// Layers of the mod project
     /// Layers are loaded in order of priority (highest priority last)
     /// If not specified, a default "base" layer with priority 0 is assumed
@@ -206,6 +260,56 @@ impl ModProject {
             other => Err(ModProjectError::UnsupportedExtension(other.to_string())),
         }
     }
+
+    /// Summarize each layer for display, sorted by priority (then name), lowest first.
+    pub fn layer_summaries(&self) -> Vec<LayerSummary> {
+        let mut summaries: Vec<LayerSummary> = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let mut locales: Vec<String> = layer.string_overrides.keys().cloned().collect();
+                locales.sort();
+                LayerSummary {
+                    name: layer.name.clone(),
+                    priority: layer.priority,
+                    description: layer.description.clone(),
+                    override_field_count: layer.string_overrides.values().map(HashMap::len).sum(),
+                    locales,
+                }
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.name.cmp(&b.name)));
+        summaries
+    }
+}
+
+/// At-a-glance information about a [`ModProjectLayer`], see [`ModProject::layer_summaries`]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct LayerSummary {
+    /// The name of the layer
+    pub name: String,
+
+    /// The priority of the layer
+    pub priority: i32,
+
+    /// Optional description of the layer
+    pub description: Option<String>,
+
+    /// Number of string override fields, summed across all locales
+    pub override_field_count: usize,
+
+    /// Locales the layer has string overrides for, sorted
+    pub locales: Vec<String>,
+}
+
+/// Compression to apply to override files matched by [`ModProject::compression`]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionHint {
+    /// Store the file uncompressed
+    None,
+    /// Compress the file with Zstandard
+    Zstd,
 }
 
 /// Represents a layer in a mod project
@@ -324,12 +428,17 @@ mod tests {
             tags: vec![ModTag::Known(WellKnownModTag::MapSkin)],
             champions: vec![],
             maps: vec![ModMap::Known(WellKnownMap::SummonersRift)],
+            dependencies: vec![],
             transformers: vec![FileTransformer {
                 name: "tex-converter".to_string(),
                 patterns: vec!["**/*.dds".to_string(), "**/*.png".to_string()],
                 files: vec![],
                 options: None,
             }],
+            compression: HashMap::from([
+                ("**/*.bnk".to_string(), CompressionHint::None),
+                ("**/*.tex".to_string(), CompressionHint::Zstd),
+            ]),
             layers: vec![
                 ModProjectLayer {
                     name: "base".to_string(),
@@ -350,6 +459,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_layer_summaries() {
+        let mut project = create_example_project();
+        project.layers[1].string_overrides = HashMap::from([
+            (
+                "ko_kr".to_string(),
+                HashMap::from([("a".to_string(), "1".to_string())]),
+            ),
+            (
+                "en_us".to_string(),
+                HashMap::from([
+                    ("a".to_string(), "1".to_string()),
+                    ("b".to_string(), "2".to_string()),
+                ]),
+            ),
+        ]);
+        project.layers.reverse();
+
+        let summaries = project.layer_summaries();
+
+        assert_eq!(
+            summaries,
+            vec![
+                LayerSummary {
+                    name: "base".to_string(),
+                    priority: 0,
+                    description: Some("Base layer of the mod".to_string()),
+                    override_field_count: 0,
+                    locales: vec![],
+                },
+                LayerSummary {
+                    name: "chroma1".to_string(),
+                    priority: 20,
+                    description: Some("Chroma 1".to_string()),
+                    override_field_count: 3,
+                    locales: vec!["en_us".to_string(), "ko_kr".to_string()],
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_json_parsing() {
         let project: ModProject =
@@ -0,0 +1,292 @@
+//! Checks of a mod project's configuration shared by the CLI and the manager.
+
+use crate::ModProject;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValidationSeverity {
+    /// The project cannot be packed as configured.
+    Error,
+    /// The project can be packed, but the configuration is likely a mistake.
+    Warning,
+}
+
+/// A problem found by [`ModProject::validate`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: ValidationSeverity,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(message: String) -> Self {
+        Self {
+            severity: ValidationSeverity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: ValidationSeverity::Warning,
+            message,
+        }
+    }
+}
+
+/// Whether `name` is a valid mod name: non-empty, with only ASCII letters,
+/// digits, `_` and `-`.
+///
+/// Layer names follow the stricter [`is_valid_layer_name`].
+pub fn is_valid_slug(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Whether `name` is a valid layer name: non-empty, with only lowercase ASCII
+/// letters, digits and `-`, and not starting or ending with `-`.
+pub fn is_valid_layer_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+}
+
+impl ModProject {
+    /// Check the project's configuration, returning every issue found.
+    ///
+    /// Checks that the name is a slug, the version is valid semver, the
+    /// project has authors, dependency requirements are valid semver
+    /// requirements, and the layers include `base` at priority 0, have
+    /// unique names valid per [`is_valid_layer_name`] and don't share priorities. An empty
+    /// result means the project is valid.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if !is_valid_slug(&self.name) {
+            issues.push(ValidationIssue::error(format!(
+                "Invalid mod name '{}': only letters, digits, '_' and '-' are allowed",
+                self.name
+            )));
+        }
+        if let Err(e) = semver::Version::parse(&self.version) {
+            issues.push(ValidationIssue::error(format!(
+                "Invalid version '{}': {}",
+                self.version, e
+            )));
+        }
+        if self.authors.is_empty() {
+            issues.push(ValidationIssue::warning(
+                "The project has no authors".to_string(),
+            ));
+        }
+
+        for dependency in &self.dependencies {
+            if let Err(e) = semver::VersionReq::parse(&dependency.version_req) {
+                issues.push(ValidationIssue::error(format!(
+                    "Invalid version requirement '{}' for dependency '{}': {}",
+                    dependency.version_req, dependency.name, e
+                )));
+            }
+        }
+
+        match self.layers.iter().find(|layer| layer.name == "base") {
+            None => issues.push(ValidationIssue::error(
+                "The project has no 'base' layer".to_string(),
+            )),
+            Some(base) if base.priority != 0 => issues.push(ValidationIssue::error(format!(
+                "The 'base' layer must have priority 0, got {}",
+                base.priority
+            ))),
+            Some(_) => {}
+        }
+        let mut names: HashMap<&str, usize> = HashMap::new();
+        let mut priorities: HashMap<i32, &str> = HashMap::new();
+        for layer in &self.layers {
+            let count = names.entry(layer.name.as_str()).or_default();
+            *count += 1;
+            if *count == 2 {
+                issues.push(ValidationIssue::error(format!(
+                    "Layer '{}' is declared more than once",
+                    layer.name
+                )));
+            }
+            if *count > 1 {
+                continue;
+            }
+            if !is_valid_layer_name(&layer.name) {
+                issues.push(ValidationIssue::error(format!(
+                    "Invalid layer name '{}': only lowercase letters, digits and '-' are allowed, \
+                     not at the start or end",
+                    layer.name
+                )));
+            }
+            match priorities.get(&layer.priority) {
+                Some(other) => issues.push(ValidationIssue::warning(format!(
+                    "Layers '{}' and '{}' share priority {}, so they are ordered by name",
+                    other, layer.name, layer.priority
+                ))),
+                None => {
+                    priorities.insert(layer.priority, &layer.name);
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ModProjectAuthor, ModProjectLayer};
+
+    fn valid_project() -> ModProject {
+        ModProject {
+            name: "old-summoners-rift".to_string(),
+            display_name: "Old Summoners Rift".to_string(),
+            version: "1.0.0".to_string(),
+            authors: vec![ModProjectAuthor::Name("TheKillerey".to_string())],
+            layers: vec![ModProjectLayer::base(), layer("chroma", 10)],
+            ..Default::default()
+        }
+    }
+
+    fn layer(name: &str, priority: i32) -> ModProjectLayer {
+        ModProjectLayer {
+            name: name.to_string(),
+            display_name: None,
+            priority,
+            description: None,
+            string_overrides: HashMap::new(),
+        }
+    }
+
+    fn severities(project: &ModProject) -> Vec<ValidationSeverity> {
+        project
+            .validate()
+            .into_iter()
+            .map(|issue| issue.severity)
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_project() {
+        assert_eq!(valid_project().validate(), vec![]);
+    }
+
+    #[test]
+    fn test_invalid_name() {
+        let mut project = valid_project();
+        project.name = "Old Summoner's Rift".to_string();
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("mod name"));
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        let mut project = valid_project();
+        project.version = "1.0".to_string();
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("version"));
+    }
+
+    #[test]
+    fn test_invalid_dependency_requirement() {
+        let mut project = valid_project();
+        project.dependencies.push(crate::ModDependency {
+            name: "base-pack".to_string(),
+            version_req: "one point two".to_string(),
+        });
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("'base-pack'"));
+    }
+
+    #[test]
+    fn test_missing_authors() {
+        let mut project = valid_project();
+        project.authors.clear();
+        assert_eq!(severities(&project), vec![ValidationSeverity::Warning]);
+    }
+
+    #[test]
+    fn test_duplicate_layer_names() {
+        let mut project = valid_project();
+        project.layers.push(layer("chroma", 20));
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("'chroma'"));
+    }
+
+    #[test]
+    fn test_missing_base_layer() {
+        let mut project = valid_project();
+        project.layers.remove(0);
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("'base'"));
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("test"));
+        assert!(is_valid_slug("test-123"));
+        assert!(is_valid_slug("Old_Rift"));
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("test 123"));
+        assert!(!is_valid_slug("test!123"));
+        assert!(!is_valid_slug("Nice mod: ([test])@"));
+    }
+
+    #[test]
+    fn test_is_valid_layer_name() {
+        assert!(is_valid_layer_name("base"));
+        assert!(is_valid_layer_name("my-layer"));
+        assert!(is_valid_layer_name("layer123"));
+        assert!(!is_valid_layer_name(""));
+        assert!(!is_valid_layer_name("-invalid"));
+        assert!(!is_valid_layer_name("invalid-"));
+        assert!(!is_valid_layer_name("UPPERCASE"));
+        assert!(!is_valid_layer_name("has spaces"));
+        assert!(!is_valid_layer_name("old_rift"));
+    }
+
+    #[test]
+    fn test_invalid_layer_name() {
+        let mut project = valid_project();
+        project.layers.push(layer("High Res", 20));
+        project.layers.push(layer("UPPERCASE", 30));
+        project.layers.push(layer("-chroma", 40));
+        project.layers.push(layer("old_rift", 50));
+        let issues = project.validate();
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error; 4]);
+        assert!(issues[0].message.contains("'High Res'"));
+        assert!(issues[1].message.contains("'UPPERCASE'"));
+        assert!(issues[2].message.contains("'-chroma'"));
+        assert!(issues[3].message.contains("'old_rift'"));
+    }
+
+    #[test]
+    fn test_base_layer_priority() {
+        let mut project = valid_project();
+        project.layers[0].priority = 5;
+        assert_eq!(severities(&project), vec![ValidationSeverity::Error]);
+        assert!(project.validate()[0].message.contains("priority 0"));
+    }
+
+    #[test]
+    fn test_colliding_layer_priorities() {
+        let mut project = valid_project();
+        project.layers.push(layer("extra", 10));
+        assert_eq!(severities(&project), vec![ValidationSeverity::Warning]);
+        assert_eq!(
+            project.validate()[0].message,
+            "Layers 'chroma' and 'extra' share priority 10, so they are ordered by name"
+        );
+    }
+}
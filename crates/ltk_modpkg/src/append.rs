@@ -0,0 +1,230 @@
+//! Adding chunks to an existing package.
+//!
+//! The layer, path and WAD tables and the chunk TOC come before the chunk data,
+//! so a new chunk cannot be appended in place: its path and TOC entry would
+//! overwrite the start of the data. Growing the tables would mean moving all of
+//! the data anyway.
+//!
+//! The package is therefore rewritten instead. This is still cheap:
+//! [`Modpkg::append_builder`] seeds a builder from the package, and
+//! [`ModpkgBuilder::build_appended_to_writer`] copies the chunks that were
+//! already there as stored. It does not decompress or recompress them, so
+//! adding one texture to a large package costs a sequential copy of its data.
+//! The rewritten package is not signed unless a signer is set on the builder,
+//! since any signature the source had does not cover the new contents.
+
+use std::io::{Read, Seek};
+
+use crate::{
+    builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder},
+    chunk::{NO_LAYER_HASH, NO_WAD_INDEX},
+    error::ModpkgError,
+    hash_chunk_name, Modpkg, CHANGELOG_CHUNK_PATH, METADATA_CHUNK_PATH, METADATA_FOLDER_NAME,
+    README_CHUNK_PATH, THUMBNAIL_CHUNK_PATH,
+};
+
+impl<TSource: Read + Seek> Modpkg<TSource> {
+    /// Create a builder holding this package's layers, metadata, readme,
+    /// changelog, thumbnail and chunks, to add or replace chunks with.
+    ///
+    /// Build it with [`ModpkgBuilder::build_appended_to_writer`], passing this
+    /// package as the source, so the chunks it already holds are copied as
+    /// stored. Meta chunks other than the metadata, readme, changelog and
+    /// thumbnail are not carried over. Fails with
+    /// [`ModpkgError::InvalidMetaChunk`] if the readme or changelog is not
+    /// valid UTF-8.
+    pub fn append_builder(&mut self) -> Result<ModpkgBuilder, ModpkgError> {
+        let metadata_compression = self
+            .chunks
+            .get(&(hash_chunk_name(METADATA_CHUNK_PATH), NO_LAYER_HASH))
+            .map(|chunk| chunk.compression)
+            .unwrap_or_default();
+        let mut builder = ModpkgBuilder::default()
+            .with_metadata_compression(metadata_compression)
+            .with_metadata(self.load_metadata()?)
+            .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+
+        if self.has_meta_chunk(README_CHUNK_PATH) {
+            let readme = String::from_utf8(self.load_readme()?)
+                .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+            builder = builder
+                .with_readme(&readme)
+                .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+        }
+        if self.has_meta_chunk(CHANGELOG_CHUNK_PATH) {
+            let changelog = String::from_utf8(self.load_changelog()?)
+                .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+            builder = builder
+                .with_changelog(&changelog)
+                .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+        }
+        if self.has_meta_chunk(THUMBNAIL_CHUNK_PATH) {
+            builder = builder
+                .with_thumbnail(self.load_thumbnail()?)
+                .map_err(|_| ModpkgError::InvalidMetaChunk)?;
+        }
+
+        for layer_hash in &self.layer_indices {
+            if let Some(layer) = self.layers.get(layer_hash) {
+                builder = builder
+                    .with_layer(ModpkgLayerBuilder::new(&layer.name).with_priority(layer.priority));
+            }
+        }
+
+        for chunk in self.content_chunk_builders()? {
+            builder = builder.with_chunk(chunk);
+        }
+
+        Ok(builder)
+    }
+
+    /// Builders for the package's content chunks, each keeping its TOC entry.
+    pub(crate) fn content_chunk_builders(&self) -> Result<Vec<ModpkgChunkBuilder>, ModpkgError> {
+        let meta_prefix = format!("{}/", METADATA_FOLDER_NAME);
+        let mut builders = Vec::with_capacity(self.chunks.len());
+        for chunk in self.chunks.values() {
+            let path = self
+                .chunk_path_indices
+                .get(chunk.path_index as usize)
+                .and_then(|path_hash| self.chunk_paths.get(path_hash))
+                .ok_or(ModpkgError::MissingChunk(chunk.path_hash))?;
+            if path.starts_with(&meta_prefix) {
+                continue;
+            }
+            let layer = chunk
+                .layer()
+                .and_then(|index| self.layer_indices.get(index as usize))
+                .and_then(|layer_hash| self.layers.get(layer_hash))
+                .map(|layer| layer.name.clone())
+                .unwrap_or_default();
+            let wad = match chunk.wad_index {
+                NO_WAD_INDEX => None,
+                index => self.wad_name_for_index(index),
+            };
+            builders.push(ModpkgChunkBuilder::from_existing(
+                *chunk,
+                path.clone(),
+                layer,
+                wad.unwrap_or_default().to_string(),
+            ));
+        }
+        Ok(builders)
+    }
+
+    fn has_meta_chunk(&self, path: &str) -> bool {
+        self.chunks
+            .contains_key(&(hash_chunk_name(path), NO_LAYER_HASH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ModpkgCompression;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn test_append_chunk_to_existing_package() {
+        let mut cursor = Cursor::new(Vec::new());
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(2))
+            .with_readme("# Skin")
+            .unwrap()
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_wad("Aatrox.wad.client")
+                    .with_compression(ModpkgCompression::Zstd),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/chroma.bin")
+                    .unwrap()
+                    .with_layer("chroma")
+                    .with_compression(ModpkgCompression::Zstd),
+            )
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                cursor.write_all(chunk.path.repeat(32).as_bytes())?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        let mut original = Modpkg::mount_from_reader(cursor).unwrap();
+
+        let builder = original.append_builder().unwrap().with_chunk(
+            ModpkgChunkBuilder::new()
+                .with_path("assets/new.tex")
+                .unwrap()
+                .with_wad("Aatrox.wad.client")
+                .with_compression(ModpkgCompression::Zstd),
+        );
+        let mut appended = Cursor::new(Vec::new());
+        builder
+            .build_appended_to_writer(&mut original, &mut appended, |chunk, cursor| {
+                assert_eq!(chunk.path, "assets/new.tex", "only new chunks are provided");
+                cursor.write_all(b"new texture")?;
+                Ok(())
+            })
+            .unwrap();
+        appended.set_position(0);
+        let mut appended = Modpkg::mount_from_reader(appended).unwrap();
+
+        for (path, layer) in [("data/skin.bin", "base"), ("data/chroma.bin", "chroma")] {
+            assert_eq!(
+                appended
+                    .load_chunk_decompressed_by_path(path, Some(layer))
+                    .unwrap(),
+                original
+                    .load_chunk_decompressed_by_path(path, Some(layer))
+                    .unwrap()
+            );
+            assert_eq!(
+                appended.get_chunk(path, Some(layer)).unwrap().compression,
+                ModpkgCompression::Zstd
+            );
+        }
+        assert_eq!(
+            &*appended
+                .load_chunk_decompressed_by_path("assets/new.tex", Some("base"))
+                .unwrap(),
+            b"new texture"
+        );
+        let skin = *appended.get_chunk("data/skin.bin", Some("base")).unwrap();
+        assert_eq!(
+            appended.wad_name_for_index(skin.wad_index),
+            Some("aatrox.wad.client")
+        );
+        assert_eq!(appended.layer_index("chroma"), Some(1));
+        assert_eq!(appended.load_readme().unwrap(), b"# Skin");
+        assert_eq!(
+            appended.load_metadata().unwrap(),
+            original.load_metadata().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_append_rejects_invalid_utf8_readme() {
+        let mut cursor = Cursor::new(Vec::new());
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_readme("# Skin")
+            .unwrap()
+            .build_to_writer(&mut cursor, |_, _| Ok(()))
+            .unwrap();
+        // The readme is stored uncompressed, so it can be corrupted in place.
+        let mut bytes = cursor.into_inner();
+        let start = bytes
+            .windows(6)
+            .position(|window| window == b"# Skin")
+            .unwrap();
+        bytes[start] = 0xFF;
+        let mut original = Modpkg::mount_from_reader(Cursor::new(bytes)).unwrap();
+
+        assert!(matches!(
+            original.append_builder(),
+            Err(ModpkgError::InvalidMetaChunk)
+        ));
+    }
+}
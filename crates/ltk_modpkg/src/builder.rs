@@ -3,18 +3,28 @@ use byteorder::{WriteBytesExt, LE};
 use std::collections::{HashMap, HashSet};
 use std::io::{self, BufWriter, Cursor, Seek, SeekFrom, Write};
 use std::path::Path;
+use std::sync::Arc;
 use xxhash_rust::xxh3::xxh3_64;
 
+use ltk_pki::pki::sign::{SignerError, SignerKeys};
+use sha2::{Digest, Sha512};
+
 use crate::{
     chunk::{ModpkgChunk, NO_LAYER_HASH, NO_LAYER_INDEX, NO_WAD_INDEX},
-    metadata::{ModpkgMetadata, METADATA_CHUNK_PATH},
+    metadata::{ModpkgLayerMetadata, ModpkgMetadata, METADATA_CHUNK_PATH},
+    signature::{package_digest, sign_digest, HEADER_SIZE},
     thumbnail::THUMBNAIL_CHUNK_PATH,
-    ModpkgCompression,
+    Modpkg, ModpkgCompression,
 };
 use crate::{
-    hash_chunk_name, hash_layer_name, hash_wad_name, utils, BASE_LAYER_NAME, README_CHUNK_PATH,
+    hash_chunk_name, hash_layer_name, hash_wad_name, utils, BASE_LAYER_NAME, CHANGELOG_CHUNK_PATH,
+    README_CHUNK_PATH,
 };
 
+/// Zstd level used for chunks that do not set one with
+/// [`ModpkgChunkBuilder::with_compression_level`].
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ModpkgBuilderError {
     #[error("io error")]
@@ -37,28 +47,59 @@ pub enum ModpkgBuilderError {
 
     #[error("invalid chunk name: {0}")]
     InvalidChunkName(String),
+
+    #[error("metadata layer not declared in header: {0}")]
+    MetadataLayerNotInHeader(String),
+
+    #[error("duplicate metadata layer: {0}")]
+    DuplicateMetadataLayer(String),
+
+    #[error("zstd compression level {0} is out of range ({1:?})")]
+    InvalidCompressionLevel(i32, std::ops::RangeInclusive<i32>),
+
+    #[error("signing error: {0}")]
+    Signing(#[from] SignerError),
+
+    #[error("failed to encode signature: {0}")]
+    SignatureEncode(der::Error),
+
+    #[error("signature is {actual} bytes, but {reserved} bytes were reserved for it")]
+    SignatureSizeMismatch { reserved: usize, actual: usize },
+
+    #[error("chunk {path} already exists in layer '{layer}'")]
+    ChunkConflict { path: String, layer: String },
 }
 
+/// Reads the stored bytes of a chunk from the package a builder was seeded from.
+type CopyExistingChunk<'a> = dyn FnMut(&ModpkgChunk) -> Result<Box<[u8]>, ModpkgBuilderError> + 'a;
+
 /// Provides an interface to build a Modpkg file.
 #[derive(Debug, Clone)]
 pub struct ModpkgBuilder {
     pub readme: Option<String>,
+    pub changelog: Option<String>,
     pub thumbnail: Option<Vec<u8>>,
     pub metadata: ModpkgMetadata,
+    pub metadata_compression: ModpkgCompression,
     pub chunks: HashMap<(u64, u64), ModpkgChunkBuilder>,
     pub meta_chunks: HashMap<(u64, u64), ModpkgChunkBuilder>,
     pub layers: Vec<ModpkgLayerBuilder>,
+    /// Keys used to sign the package, unsigned when `None`.
+    pub signer: Option<SignerKeys>,
 }
 
 impl Default for ModpkgBuilder {
     fn default() -> Self {
         let mut builder = Self {
             readme: None,
+            changelog: None,
             thumbnail: None,
             metadata: ModpkgMetadata::default(),
+            metadata_compression: ModpkgCompression::None,
             chunks: HashMap::new(),
             meta_chunks: HashMap::new(),
             layers: Vec::new(),
+            signer: None,
         };
 
         builder = builder.with_metadata(ModpkgMetadata::default()).unwrap();
@@ -71,8 +112,17 @@ pub struct ModpkgChunkBuilder {
     path_hash: u64,
     pub path: String,
     pub compression: ModpkgCompression,
+    /// Zstd level for the chunk's data, [`DEFAULT_ZSTD_LEVEL`] when `None`.
+    pub compression_level: Option<i32>,
     pub layer: String,
     pub wad: String,
+    pub toc_weight: i32,
+    /// TOC entry of the chunk in the package this builder was seeded from by
+    /// [`Modpkg::append_builder`](crate::Modpkg::append_builder).
+    pub(crate) existing: Option<ModpkgChunk>,
+    /// Data merged in from another package by [`ModpkgBuilder::merge_from`],
+    /// used instead of asking the data provider.
+    pub(crate) data: Option<Arc<[u8]>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -96,8 +146,21 @@ impl ModpkgBuilder {
         self
     }
 
+    /// Sign the package with `signer`.
+    ///
+    /// `signer` must hold exactly one private key along with its certificate;
+    /// the rest of its certificates and CRLs are embedded in the signature so
+    /// the chain can be verified. See [`Modpkg::verify_signature`](crate::Modpkg::verify_signature).
+    pub fn with_signature(mut self, signer: SignerKeys) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
     /// Build the Modpkg file and write it to the given writer.
     ///
+    /// The output is reproducible: it depends only on the builder's contents and
+    /// the chunk data, never on insertion order or the time of the build.
+    ///
     /// * `writer` - The writer to write the Modpkg file to.
     /// * `provide_chunk_data` - A function that provides the raw data for each chunk.
     pub fn build_to_writer<
@@ -108,7 +171,45 @@ impl ModpkgBuilder {
         writer: &mut TWriter,
         provide_chunk_data: TChunkDataProvider,
     ) -> Result<(), ModpkgBuilderError> {
-        let mut writer = BufWriter::new(writer);
+        self.build(writer, provide_chunk_data, None)
+    }
+
+    /// Build a builder seeded by [`Modpkg::append_builder`] from `source`.
+    ///
+    /// Chunks taken over from `source` are copied as stored, without being
+    /// decompressed or recompressed; `provide_chunk_data` is only called for the
+    /// chunks added or replaced since. `source` must be the package the builder
+    /// was seeded from, and `writer` must not write to the same file.
+    ///
+    /// [`Modpkg::append_builder`]: crate::Modpkg::append_builder
+    pub fn build_appended_to_writer<
+        TSource: io::Read + io::Seek,
+        TWriter: io::Write + io::Seek,
+        TChunkDataProvider: Fn(&ModpkgChunkBuilder, &mut Cursor<Vec<u8>>) -> Result<(), ModpkgBuilderError>,
+    >(
+        self,
+        source: &mut Modpkg<TSource>,
+        writer: &mut TWriter,
+        provide_chunk_data: TChunkDataProvider,
+    ) -> Result<(), ModpkgBuilderError> {
+        let mut copy_existing = |chunk: &ModpkgChunk| -> Result<Box<[u8]>, ModpkgBuilderError> {
+            Ok(source.decoder().load_chunk_raw(chunk)?)
+        };
+        self.build(writer, provide_chunk_data, Some(&mut copy_existing))
+    }
+
+    fn build<
+        TWriter: io::Write + io::Seek,
+        TChunkDataProvider: Fn(&ModpkgChunkBuilder, &mut Cursor<Vec<u8>>) -> Result<(), ModpkgBuilderError>,
+    >(
+        mut self,
+        writer: &mut TWriter,
+        provide_chunk_data: TChunkDataProvider,
+        copy_existing: Option<&mut CopyExistingChunk>,
+    ) -> Result<(), ModpkgBuilderError> {
+        let mut writer = BufWriter::new(DigestWriter::new(writer));
+
+        Self::sync_metadata_layers(&self.layers, &mut self.metadata)?;
 
         // Collect all unique paths, layers, and wads
         let (chunk_paths, chunk_path_indices) = self.collect_unique_paths();
@@ -119,35 +220,76 @@ impl ModpkgBuilder {
 
         let total_chunks = self.chunks.len() + self.meta_chunks.len();
 
-        Self::write_header(&mut writer, total_chunks)?;
-        Self::write_layers(&mut writer, &self.layers)?;
-        Self::write_chunk_paths(&mut writer, &chunk_paths)?;
-        Self::write_wads(&mut writer, &wads)?;
-        Self::write_alignment(&mut writer)?;
+        // A signature's size depends only on the signer and the digest length,
+        // so a trial signature tells how much space to reserve for the real one.
+        let signature_size = match &self.signer {
+            Some(signer) => sign_digest(signer, &[0; 64])?.len(),
+            None => 0,
+        };
+
+        let mut header = Vec::new();
+        Self::write_header(&mut header, total_chunks, signature_size)?;
+        writer.write_all(&header)?;
+        writer.write_all(&vec![0; signature_size])?;
+
+        let mut tables = Vec::new();
+        Self::write_layers(&mut tables, &self.layers)?;
+        Self::write_chunk_paths(&mut tables, &chunk_paths)?;
+        Self::write_wads(&mut tables, &wads)?;
+        let tables_end = HEADER_SIZE + (signature_size + tables.len()) as u64;
+        tables.resize(tables.len() + ((8 - tables_end % 8) % 8) as usize, 0);
+        writer.write_all(&tables)?;
 
         // Reserve space for chunk TOC
         let chunk_toc_offset = writer.stream_position()?;
         writer.write_all(&vec![0; total_chunks * ModpkgChunk::size_of()])?;
 
+        if self.signer.is_some() {
+            writer.flush()?;
+            writer.get_mut().start_digest();
+        }
+
         let layer_index_map = Self::build_layer_index_map(&self.layers);
 
         let all_chunks = self.process_all_chunks(
             &mut writer,
             provide_chunk_data,
+            copy_existing,
             &chunk_path_indices,
             &layer_index_map,
             &wad_indices,
         )?;
 
+        writer.flush()?;
+        let data_digest = writer.get_mut().finish_digest();
+
         // Go back and write the actual chunk TOC
-        Self::write_chunk_toc(&mut writer, chunk_toc_offset, &all_chunks)?;
+        let mut toc = Vec::new();
+        Self::write_chunk_toc(&mut Cursor::new(&mut toc), 0, &all_chunks)?;
+        writer.seek(SeekFrom::Start(chunk_toc_offset))?;
+        writer.write_all(&toc)?;
+
+        if let (Some(signer), Some(data_digest)) = (&self.signer, data_digest) {
+            tables.append(&mut toc);
+            let signature = sign_digest(signer, &package_digest(&header, &tables, &data_digest))?;
+            if signature.len() != signature_size {
+                return Err(ModpkgBuilderError::SignatureSizeMismatch {
+                    reserved: signature_size,
+                    actual: signature.len(),
+                });
+            }
+            writer.seek(SeekFrom::Start(HEADER_SIZE))?;
+            writer.write_all(&signature)?;
+        }
 
+        writer.flush()?;
         Ok(())
     }
 
     fn write_header<W: io::Write>(
         writer: &mut W,
         total_chunks: usize,
+        signature_size: usize,
     ) -> Result<(), ModpkgBuilderError> {
         // Write magic header
         writer.write_all(b"_modpkg_")?;
@@ -156,13 +298,9 @@ impl ModpkgBuilder {
         writer.write_u32::<LE>(1)?;
 
         // Write signature size and chunk count
-        writer.write_u32::<LE>(0)?; // Placeholder for signature size
+        writer.write_u32::<LE>(signature_size as u32)?;
         writer.write_u32::<LE>(total_chunks as u32)?;
 
-        // Write signature (empty for now)
-        let signature = Vec::new();
-        writer.write_all(&signature)?;
-
         Ok(())
     }
 
@@ -204,15 +342,6 @@ impl ModpkgBuilder {
         Ok(())
     }
 
-    fn write_alignment<W: io::Write + io::Seek>(writer: &mut W) -> Result<(), ModpkgBuilderError> {
-        let current_pos = writer.stream_position()?;
-        let padding = (8 - (current_pos % 8)) % 8;
-        for _ in 0..padding {
-            writer.write_all(&[0])?;
-        }
-        Ok(())
-    }
-
     fn build_layer_index_map(layers: &[ModpkgLayerBuilder]) -> HashMap<u64, u32> {
         let mut layer_index_map = HashMap::new();
         for (idx, layer) in layers.iter().enumerate() {
@@ -228,6 +357,7 @@ impl ModpkgBuilder {
         &self,
         writer: &mut BufWriter<TWriter>,
         provide_chunk_data: TChunkDataProvider,
+        copy_existing: Option<&mut CopyExistingChunk>,
         chunk_path_indices: &HashMap<u64, u32>,
         layer_index_map: &HashMap<u64, u32>,
         wad_indices: &HashMap<u64, u32>,
@@ -238,6 +368,7 @@ impl ModpkgBuilder {
             &regular_chunks,
             writer,
             provide_chunk_data,
+            copy_existing,
             chunk_path_indices,
             layer_index_map,
             wad_indices,
@@ -248,10 +379,12 @@ impl ModpkgBuilder {
         Ok(meta_chunks)
     }
 
-    /// Collect all non-meta chunks, sorted by WAD name then layer.
+    /// Collect all non-meta chunks, sorted by TOC weight (highest first), then
+    /// WAD name, then layer.
     ///
     /// This groups related chunks physically in the file,
     /// enabling more sequential I/O when reading all overrides for a WAD.
+    /// Chunks with equal keys keep the order of [`ordered_chunks`](Self::ordered_chunks).
     fn collect_regular_chunks(&self, meta_chunks: &[ModpkgChunk]) -> Vec<&ModpkgChunkBuilder> {
         let meta_path_hashes = meta_chunks
             .iter()
@@ -259,12 +392,16 @@ impl ModpkgBuilder {
             .collect::<HashSet<_>>();
 
         let mut regular_chunks: Vec<_> = self
-            .chunks
-            .values()
-            .chain(self.meta_chunks.values())
+            .ordered_chunks()
+            .into_iter()
             .filter(|chunk| !meta_path_hashes.contains(&chunk.path_hash))
             .collect();
-        regular_chunks.sort_by(|a, b| a.wad.cmp(&b.wad).then(a.layer.cmp(&b.layer)));
+        regular_chunks.sort_by(|a, b| {
+            b.toc_weight
+                .cmp(&a.toc_weight)
+                .then(a.wad.cmp(&b.wad))
+                .then(a.layer.cmp(&b.layer))
+        });
 
         regular_chunks
     }
@@ -289,8 +426,12 @@ impl ModpkgBuilder {
         let mut meta_chunks = Vec::new();
 
         // Metadata
-        let metadata_chunk =
-            Self::process_metadata_chunk(&self.metadata, writer, chunk_path_indices)?;
+        let metadata_chunk = Self::process_metadata_chunk(
+            &self.metadata,
+            self.metadata_compression,
+            writer,
+            chunk_path_indices,
+        )?;
         meta_chunks.push(metadata_chunk);
 
         // Thumbnail
@@ -315,23 +456,47 @@ impl ModpkgBuilder {
             meta_chunks.push(readme_chunk);
         }
 
+        // CHANGELOG
+        if let Some(changelog_content) = self.changelog.as_ref() {
+            let changelog_chunk = Self::write_meta_chunk(
+                hash_chunk_name(CHANGELOG_CHUNK_PATH),
+                changelog_content.as_bytes(),
+                writer,
+                chunk_path_indices,
+            )?;
+            meta_chunks.push(changelog_chunk);
+        }
+
         Ok(meta_chunks)
     }
 
     fn process_metadata_chunk<TWriter: io::Write + io::Seek>(
         metadata: &ModpkgMetadata,
+        compression: ModpkgCompression,
         writer: &mut BufWriter<TWriter>,
         chunk_path_indices: &HashMap<u64, u32>,
     ) -> Result<ModpkgChunk, ModpkgBuilderError> {
         let mut metadata_bytes = Vec::new();
         metadata.write(&mut metadata_bytes)?;
 
-        Self::write_meta_chunk(
-            hash_chunk_name(METADATA_CHUNK_PATH),
-            &metadata_bytes,
-            writer,
-            chunk_path_indices,
-        )
+        let (stored, compression) =
+            Self::compress_chunk_data(&metadata_bytes, compression, DEFAULT_ZSTD_LEVEL)?;
+        let path_hash = hash_chunk_name(METADATA_CHUNK_PATH);
+        let data_offset = writer.stream_position()?;
+        writer.write_all(&stored)?;
+
+        Ok(ModpkgChunk {
+            path_hash,
+            data_offset,
+            compression,
+            compressed_size: stored.len() as u64,
+            uncompressed_size: metadata_bytes.len() as u64,
+            compressed_checksum: xxh3_64(&stored),
+            uncompressed_checksum: xxh3_64(&metadata_bytes),
+            path_index: *chunk_path_indices.get(&path_hash).unwrap_or(&0),
+            layer_index: NO_LAYER_INDEX,
+            wad_index: NO_WAD_INDEX,
+        })
     }
 
     fn write_meta_chunk<TWriter: io::Write + io::Seek>(
@@ -369,27 +534,62 @@ impl ModpkgBuilder {
     fn compress_chunk_data(
         data: &[u8],
         compression: ModpkgCompression,
+        level: i32,
     ) -> Result<(Vec<u8>, ModpkgCompression), ModpkgBuilderError> {
         match compression {
             ModpkgCompression::None => Ok((data.to_vec(), ModpkgCompression::None)),
             ModpkgCompression::Zstd => {
-                let compressed = zstd::bulk::compress(data, 3)?;
-
-                if (compressed.len() as u64) * 100
-                    >= (data.len() as u64) * Self::MAX_COMPRESSED_SIZE_PERCENT
-                {
-                    Ok((data.to_vec(), ModpkgCompression::None))
-                } else {
-                    Ok((compressed, ModpkgCompression::Zstd))
+                let levels = zstd::compression_level_range();
+                if !levels.contains(&level) {
+                    return Err(ModpkgBuilderError::InvalidCompressionLevel(level, levels));
                 }
+                let compressed = zstd::bulk::compress(data, level)?;
+                Ok(Self::keep_if_smaller(data, compressed, compression))
+            }
+            ModpkgCompression::Lz4 => {
+                let compressed = lz4_flex::block::compress(data);
+                Ok(Self::keep_if_smaller(data, compressed, compression))
             }
         }
     }
 
+    /// Use `compressed` unless it saves too little over `data` to be worth it,
+    /// in which case the chunk is stored uncompressed.
+    fn keep_if_smaller(
+        data: &[u8],
+        compressed: Vec<u8>,
+        compression: ModpkgCompression,
+    ) -> (Vec<u8>, ModpkgCompression) {
+        if (compressed.len() as u64) * 100
+            >= (data.len() as u64) * Self::MAX_COMPRESSED_SIZE_PERCENT
+        {
+            (data.to_vec(), ModpkgCompression::None)
+        } else {
+            (compressed, compression)
+        }
+    }
+
+    /// All chunk builders, regular chunks first, each sorted by path hash then
+    /// layer hash.
+    ///
+    /// Every table and data section is derived from this order rather than from
+    /// the chunk maps' iteration order, so building the same chunks twice
+    /// produces byte-identical output.
+    fn ordered_chunks(&self) -> Vec<&ModpkgChunkBuilder> {
+        let mut chunks: Vec<(bool, &ModpkgChunkBuilder)> = self
+            .chunks
+            .values()
+            .map(|chunk| (false, chunk))
+            .chain(self.meta_chunks.values().map(|chunk| (true, chunk)))
+            .collect();
+        chunks.sort_by_key(|(is_meta, chunk)| (*is_meta, chunk.key()));
+        chunks.into_iter().map(|(_, chunk)| chunk).collect()
+    }
+
     fn collect_unique_layers(&self) -> (Vec<String>, HashMap<u64, u32>) {
         let mut layers = Vec::new();
         let mut layer_indices = HashMap::new();
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             // Skip empty layer names (they represent chunks with no layer)
             if chunk.layer.is_empty() {
                 continue;
@@ -410,7 +610,7 @@ impl ModpkgBuilder {
         let mut path_indices = HashMap::new();
 
         // Collect paths from both regular chunks and meta chunks
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             path_indices.entry(chunk.path_hash).or_insert_with(|| {
                 let index = paths.len();
                 paths.push(chunk.path.clone());
@@ -424,7 +624,7 @@ impl ModpkgBuilder {
     fn collect_unique_wads(&self) -> (Vec<String>, HashMap<u64, u32>) {
         let mut wads = Vec::new();
         let mut wad_indices = HashMap::new();
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             // Skip empty wad names (they represent chunks with no wad)
             if chunk.wad.is_empty() {
                 continue;
@@ -466,6 +666,47 @@ impl ModpkgBuilder {
         Ok(())
     }
 
+    /// Bring `metadata.layers` in line with the header layers.
+    ///
+    /// Header layers without a metadata entry get a default one, and metadata
+    /// priorities are overwritten with the header's. A metadata layer that the
+    /// header doesn't declare (or one listed twice) is an error, since its
+    /// description and string overrides would be unreachable after packing.
+    fn sync_metadata_layers(
+        defined_layers: &[ModpkgLayerBuilder],
+        metadata: &mut ModpkgMetadata,
+    ) -> Result<(), ModpkgBuilderError> {
+        let mut seen = HashSet::new();
+        for meta_layer in &mut metadata.layers {
+            if !seen.insert(meta_layer.name.clone()) {
+                return Err(ModpkgBuilderError::DuplicateMetadataLayer(
+                    meta_layer.name.clone(),
+                ));
+            }
+            let header_layer = defined_layers
+                .iter()
+                .find(|l| l.name == meta_layer.name)
+                .ok_or_else(|| {
+                    ModpkgBuilderError::MetadataLayerNotInHeader(meta_layer.name.clone())
+                })?;
+            meta_layer.priority = header_layer.priority;
+        }
+
+        for layer in defined_layers {
+            if !seen.contains(&layer.name) {
+                metadata.layers.push(ModpkgLayerMetadata {
+                    name: layer.name.clone(),
+                    display_name: None,
+                    priority: layer.priority,
+                    description: None,
+                    string_overrides: HashMap::new(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     fn process_chunks<
         TWriter: io::Write + io::Seek,
         TChunkDataProvider: Fn(&ModpkgChunkBuilder, &mut Cursor<Vec<u8>>) -> Result<(), ModpkgBuilderError>,
@@ -473,6 +714,7 @@ impl ModpkgBuilder {
         chunks: &[&ModpkgChunkBuilder],
         writer: &mut BufWriter<TWriter>,
         provide_chunk_data: TChunkDataProvider,
+        mut copy_existing: Option<&mut CopyExistingChunk>,
         chunk_path_indices: &HashMap<u64, u32>,
         layer_indices: &HashMap<u64, u32>,
         wad_indices: &HashMap<u64, u32>,
@@ -487,22 +729,49 @@ impl ModpkgBuilder {
             HashMap::new();
 
         for chunk_builder in chunks {
-            let mut data_writer = Cursor::new(Vec::new());
-            provide_chunk_data(chunk_builder, &mut data_writer)?;
-
-            let uncompressed_data = data_writer.get_ref();
-            let uncompressed_size = uncompressed_data.len();
-            let uncompressed_checksum = xxh3_64(uncompressed_data);
+            // Chunks taken over from the source package are copied as stored.
+            let existing = chunk_builder.existing.filter(|_| copy_existing.is_some());
+            let (uncompressed_data, uncompressed_size, uncompressed_checksum) = match existing {
+                Some(existing) => (
+                    None,
+                    existing.uncompressed_size as usize,
+                    existing.uncompressed_checksum,
+                ),
+                None => {
+                    let data = match &chunk_builder.data {
+                        Some(data) => data.to_vec(),
+                        None => {
+                            let mut data_writer = Cursor::new(Vec::new());
+                            provide_chunk_data(chunk_builder, &mut data_writer)?;
+                            data_writer.into_inner()
+                        }
+                    };
+                    let (size, checksum) = (data.len(), xxh3_64(&data));
+                    (Some(data), size, checksum)
+                }
+            };
 
             let content_key = (uncompressed_checksum, uncompressed_size as u64);
             let (data_offset, compressed_size, compressed_checksum, compression) =
                 match written_by_content.get(&content_key) {
                     Some(&existing) => existing,
                     None => {
-                        let (compressed_data, compression) = Self::compress_chunk_data(
-                            uncompressed_data,
-                            chunk_builder.compression,
-                        )?;
+                        let (compressed_data, compression) = match (existing, &uncompressed_data) {
+                            (Some(existing), _) => {
+                                let copy = copy_existing
+                                    .as_deref_mut()
+                                    .expect("existing chunks are only kept with a source");
+                                (copy(&existing)?.into_vec(), existing.compression)
+                            }
+                            (None, Some(data)) => Self::compress_chunk_data(
+                                data,
+                                chunk_builder.compression,
+                                chunk_builder
+                                    .compression_level
+                                    .unwrap_or(DEFAULT_ZSTD_LEVEL),
+                            )?,
+                            (None, None) => unreachable!("new chunks always have data"),
+                        };
 
                         let compressed_size = compressed_data.len() as u64;
                         let compressed_checksum = xxh3_64(&compressed_data);
@@ -568,8 +837,32 @@ impl ModpkgChunkBuilder {
             path_hash: 0,
             path: String::new(),
             compression: ModpkgCompression::None,
+            compression_level: None,
             layer: Self::DEFAULT_LAYER.to_string(),
             wad: String::new(),
+            toc_weight: 0,
+            existing: None,
+            data: None,
+        }
+    }
+
+    /// A builder that keeps `chunk`, stored in the package it was read from.
+    pub(crate) fn from_existing(
+        chunk: ModpkgChunk,
+        path: String,
+        layer: String,
+        wad: String,
+    ) -> Self {
+        Self {
+            path_hash: chunk.path_hash,
+            path,
+            compression: chunk.compression,
+            compression_level: None,
+            layer,
+            wad,
+            toc_weight: 0,
+            existing: Some(chunk),
+            data: None,
         }
     }
 
@@ -625,6 +918,18 @@ impl ModpkgChunkBuilder {
         self
     }
 
+    /// Set the Zstd level used when the chunk is compressed with
+    /// [`ModpkgCompression::Zstd`], [`DEFAULT_ZSTD_LEVEL`] by default.
+    ///
+    /// Higher levels (up to 22) give smaller packages for release builds; level 1
+    /// is the fastest for iterating. A level outside
+    /// [`zstd::compression_level_range`] fails the build with
+    /// [`ModpkgBuilderError::InvalidCompressionLevel`].
+    pub fn with_compression_level(mut self, level: i32) -> Self {
+        self.compression_level = Some(level);
+        self
+    }
+
     pub fn with_layer(mut self, layer: &str) -> Self {
         self.layer = layer.to_string();
         self
@@ -638,6 +943,19 @@ impl ModpkgChunkBuilder {
         self
     }
 
+    /// Set the TOC weight for this chunk (default `0`).
+    ///
+    /// Chunks with a higher weight are written earlier in both the TOC and the
+    /// data section, ahead of the usual WAD/layer grouping. Giving frequently
+    /// read chunks a positive weight clusters them near the start of the file,
+    /// so loaders that only touch those chunks read one contiguous region
+    /// instead of seeking across the package. Meta chunks (metadata, README,
+    /// thumbnail) always come first regardless of weight.
+    pub fn with_toc_weight(mut self, weight: i32) -> Self {
+        self.toc_weight = weight;
+        self
+    }
+
     pub fn path_hash(&self) -> u64 {
         self.path_hash
     }
@@ -674,6 +992,17 @@ impl ModpkgBuilder {
         Ok(self)
     }
 
+    /// Request a compression type for the metadata chunk (default `None`).
+    ///
+    /// Metadata is usually a few hundred bytes and is best left uncompressed.
+    /// Packages with many layers or large localized string overrides can opt
+    /// into Zstd here; as with content chunks, it is only used when it actually
+    /// shrinks the data. Readers decompress the chunk according to its TOC entry.
+    pub fn with_metadata_compression(mut self, compression: ModpkgCompression) -> Self {
+        self.metadata_compression = compression;
+        self
+    }
+
     /// Set the readme for the builder.
     pub fn with_readme(mut self, readme: &str) -> Result<Self, ModpkgBuilderError> {
         self.readme = Some(readme.to_string());
@@ -688,6 +1017,20 @@ impl ModpkgBuilder {
         Ok(self)
     }
 
+    /// Set the changelog for the builder.
+    pub fn with_changelog(mut self, changelog: &str) -> Result<Self, ModpkgBuilderError> {
+        self.changelog = Some(changelog.to_string());
+        let changelog_chunk = ModpkgChunkBuilder::new()
+            .with_path(CHANGELOG_CHUNK_PATH)?
+            .with_compression(ModpkgCompression::None)
+            .with_layer("");
+
+        let key = changelog_chunk.key();
+        self.meta_chunks.insert(key, changelog_chunk);
+
+        Ok(self)
+    }
+
     /// Set the thumbnail for the builder.
     pub fn with_thumbnail(mut self, thumbnail: Vec<u8>) -> Result<Self, ModpkgBuilderError> {
         self.thumbnail = Some(thumbnail);
@@ -729,6 +1072,52 @@ impl ModpkgLayerBuilder {
     }
 }
 
+/// Writer that can hash the bytes passed through it, used to digest the chunk
+/// data of a signed package as it is written.
+struct DigestWriter<W> {
+    inner: W,
+    digest: Option<Sha512>,
+}
+
+impl<W> DigestWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            digest: None,
+        }
+    }
+
+    /// Hash every byte written from now on. Writes must be sequential until
+    /// [`finish_digest`](Self::finish_digest).
+    fn start_digest(&mut self) {
+        self.digest = Some(Sha512::new());
+    }
+
+    fn finish_digest(&mut self) -> Option<Vec<u8>> {
+        self.digest.take().map(|digest| digest.finalize().to_vec())
+    }
+}
+
+impl<W: io::Write> io::Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(digest) = &mut self.digest {
+            digest.update(&buf[..written]);
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: io::Seek> io::Seek for DigestWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Modpkg, ModpkgLayer};
@@ -737,6 +1126,74 @@ mod tests {
 
     use std::io::Cursor;
 
+    #[test]
+    fn test_compression_level() {
+        let data: Vec<u8> = (0..64 * 1024u32)
+            .map(|i| (i % 251) as u8 ^ (i / 1024) as u8)
+            .collect();
+        let build = |level: i32| {
+            let mut cursor = Cursor::new(Vec::new());
+            ModpkgBuilder::default()
+                .with_layer(ModpkgLayerBuilder::base())
+                .with_chunk(
+                    ModpkgChunkBuilder::new()
+                        .with_path("data/big.bin")
+                        .unwrap()
+                        .with_compression(ModpkgCompression::Zstd)
+                        .with_compression_level(level),
+                )
+                .build_to_writer(&mut cursor, |_, cursor| {
+                    cursor.write_all(&data)?;
+                    Ok(())
+                })
+                .map(|_| cursor.into_inner())
+        };
+        let compressed_size = |bytes: Vec<u8>| {
+            let modpkg = Modpkg::mount_from_reader(Cursor::new(bytes)).unwrap();
+            let chunk = *modpkg.get_chunk("data/big.bin", Some("base")).unwrap();
+            assert_eq!(chunk.compression, ModpkgCompression::Zstd);
+            chunk.compressed_size
+        };
+
+        let fast = compressed_size(build(1).unwrap());
+        let max = compressed_size(build(19).unwrap());
+        assert!(max < fast, "level 19 ({max}) should beat level 1 ({fast})");
+
+        assert!(matches!(
+            build(100),
+            Err(ModpkgBuilderError::InvalidCompressionLevel(100, _))
+        ));
+    }
+
+    #[test]
+    fn test_lz4_chunk_round_trip() {
+        let data = b"lz4 round trip ".repeat(64);
+        let mut cursor = Cursor::new(Vec::new());
+
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/sound.wem")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::Lz4),
+            )
+            .build_to_writer(&mut cursor, |_, cursor| {
+                cursor.write_all(&data)?;
+                Ok(())
+            })
+            .expect("Failed to build Modpkg");
+
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+        let chunk = *modpkg.get_chunk("data/sound.wem", Some("base")).unwrap();
+        assert_eq!(chunk.compression, ModpkgCompression::Lz4);
+        assert!(chunk.compressed_size < chunk.uncompressed_size);
+
+        let decompressed = modpkg.load_chunk_decompressed(&chunk).unwrap();
+        assert_eq!(&*decompressed, &data[..]);
+    }
+
     #[test]
     fn test_modpkg_builder() {
         let scratch = Vec::new();
@@ -893,6 +1350,53 @@ mod tests {
         assert_eq!(&loaded[..], &noise[..]);
     }
 
+    #[test]
+    fn identical_chunks_across_layers_are_stored_once() {
+        let build = |chroma_data: Vec<u8>| {
+            let mut cursor = Cursor::new(Vec::new());
+            ModpkgBuilder::default()
+                .with_layer(ModpkgLayerBuilder::base())
+                .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(1))
+                .with_chunk(
+                    ModpkgChunkBuilder::new()
+                        .with_path("skin.tex")
+                        .unwrap()
+                        .with_layer("base"),
+                )
+                .with_chunk(
+                    ModpkgChunkBuilder::new()
+                        .with_path("skin.tex")
+                        .unwrap()
+                        .with_layer("chroma"),
+                )
+                .build_to_writer(&mut cursor, |chunk, cursor| {
+                    if chunk.layer == "base" {
+                        cursor.write_all(&[0xAB; 10_000])?;
+                    } else {
+                        cursor.write_all(&chroma_data)?;
+                    }
+                    Ok(())
+                })
+                .expect("Failed to build Modpkg");
+            cursor.into_inner()
+        };
+
+        let shared = build(vec![0xAB; 10_000]);
+        let distinct = build(vec![0xCD; 10_000]);
+        assert_eq!(shared.len() + 10_000, distinct.len());
+
+        let mut modpkg = Modpkg::mount_from_reader(Cursor::new(shared)).unwrap();
+        let base = *modpkg.get_chunk("skin.tex", Some("base")).unwrap();
+        let chroma = *modpkg.get_chunk("skin.tex", Some("chroma")).unwrap();
+        assert_eq!(base.data_offset, chroma.data_offset);
+        for layer in ["base", "chroma"] {
+            let loaded = modpkg
+                .load_chunk_decompressed_by_path("skin.tex", Some(layer))
+                .unwrap();
+            assert_eq!(&loaded[..], &[0xAB; 10_000][..]);
+        }
+    }
+
     #[test]
     fn identical_chunk_content_is_stored_once() {
         let scratch = Vec::new();
@@ -1041,4 +1545,210 @@ mod tests {
             "chunk should be retrievable with normalized path hash"
         );
     }
+
+    fn layer_metadata(name: &str, priority: i32) -> ModpkgLayerMetadata {
+        ModpkgLayerMetadata {
+            name: name.to_string(),
+            display_name: None,
+            priority,
+            description: Some(format!("{name} layer")),
+            string_overrides: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_syncs_metadata_layers_with_header() {
+        let metadata = ModpkgMetadata {
+            layers: vec![layer_metadata("chroma", 0)],
+            ..ModpkgMetadata::default()
+        };
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(3))
+            .with_metadata(metadata)
+            .unwrap();
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |_chunk, _cursor| Ok(()))
+            .unwrap();
+
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+        let layers = modpkg.load_metadata().unwrap().layers;
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].name, "chroma");
+        assert_eq!(layers[0].priority, 3);
+        assert_eq!(layers[0].description.as_deref(), Some("chroma layer"));
+        assert_eq!(layers[1].name, "base");
+        assert_eq!(layers[1].description, None);
+    }
+
+    #[test]
+    fn test_build_rejects_metadata_layer_missing_from_header() {
+        let metadata = ModpkgMetadata {
+            layers: vec![layer_metadata("base", 0), layer_metadata("ghost", 1)],
+            ..ModpkgMetadata::default()
+        };
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_metadata(metadata)
+            .unwrap();
+
+        let result =
+            builder.build_to_writer(&mut Cursor::new(Vec::new()), |_chunk, _cursor| Ok(()));
+        assert!(matches!(
+            result,
+            Err(ModpkgBuilderError::MetadataLayerNotInHeader(name)) if name == "ghost"
+        ));
+    }
+
+    #[test]
+    fn test_build_rejects_duplicate_metadata_layers() {
+        let metadata = ModpkgMetadata {
+            layers: vec![layer_metadata("base", 0), layer_metadata("base", 0)],
+            ..ModpkgMetadata::default()
+        };
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_metadata(metadata)
+            .unwrap();
+
+        let result =
+            builder.build_to_writer(&mut Cursor::new(Vec::new()), |_chunk, _cursor| Ok(()));
+        assert!(matches!(
+            result,
+            Err(ModpkgBuilderError::DuplicateMetadataLayer(name)) if name == "base"
+        ));
+    }
+
+    #[test]
+    fn test_toc_weight_orders_chunks() {
+        let chunk = |path: &str, wad: &str, weight: i32| {
+            ModpkgChunkBuilder::new()
+                .with_path(path)
+                .unwrap()
+                .with_layer("base")
+                .with_wad(wad)
+                .with_toc_weight(weight)
+        };
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(chunk("cold.bin", "a.wad.client", 0))
+            .with_chunk(chunk("warm.bin", "z.wad.client", 1))
+            .with_chunk(chunk("hot.bin", "z.wad.client", 10))
+            .with_chunk(chunk("sink.bin", "a.wad.client", -5));
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                cursor.write_all(chunk.path.as_bytes())?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        let modpkg = Modpkg::mount_from_reader(&mut cursor).unwrap();
+
+        let mut by_offset: Vec<_> = modpkg
+            .chunks
+            .values()
+            .filter(|c| c.layer_index != NO_LAYER_INDEX)
+            .collect();
+        by_offset.sort_by_key(|c| c.data_offset);
+        let paths: Vec<&str> = by_offset
+            .iter()
+            .map(|c| modpkg.chunk_paths[&c.path_hash].as_str())
+            .collect();
+        assert_eq!(paths, vec!["hot.bin", "warm.bin", "cold.bin", "sink.bin"]);
+    }
+
+    #[test]
+    fn test_build_is_reproducible() {
+        let build = || {
+            let mut layer = layer_metadata("chroma", 10);
+            layer.string_overrides = (0..8)
+                .map(|locale| {
+                    let fields = (0..8)
+                        .map(|field| (format!("field_{field}"), format!("value {locale}")))
+                        .collect();
+                    (format!("locale_{locale}"), fields)
+                })
+                .collect();
+            let metadata = ModpkgMetadata {
+                layers: vec![layer_metadata("base", 0), layer],
+                ..ModpkgMetadata::default()
+            };
+
+            let mut builder = ModpkgBuilder::default()
+                .with_layer(ModpkgLayerBuilder::base())
+                .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(10))
+                .with_metadata(metadata)
+                .unwrap()
+                .with_readme("# Readme")
+                .unwrap();
+            for i in 0..64 {
+                builder = builder.with_chunk(
+                    ModpkgChunkBuilder::new()
+                        .with_path(&format!("data/file_{i}.bin"))
+                        .unwrap()
+                        .with_layer(if i % 3 == 0 { "chroma" } else { "base" })
+                        .with_wad(&format!("wad_{}.wad.client", i % 5))
+                        .with_compression(ModpkgCompression::Zstd),
+                );
+            }
+
+            let mut cursor = Cursor::new(Vec::new());
+            builder
+                .build_to_writer(&mut cursor, |chunk, cursor| {
+                    cursor.write_all(chunk.path.repeat(32).as_bytes())?;
+                    Ok(())
+                })
+                .unwrap();
+            cursor.into_inner()
+        };
+
+        assert_eq!(build(), build());
+    }
+
+    #[test]
+    fn test_metadata_compression_round_trips() {
+        let metadata = ModpkgMetadata {
+            description: Some("localized ".repeat(500)),
+            ..ModpkgMetadata::default()
+        };
+        let build = |compression| {
+            let mut cursor = Cursor::new(Vec::new());
+            ModpkgBuilder::default()
+                .with_layer(ModpkgLayerBuilder::base())
+                .with_metadata(metadata.clone())
+                .unwrap()
+                .with_metadata_compression(compression)
+                .build_to_writer(&mut cursor, |_, _| Ok(()))
+                .unwrap();
+            cursor.set_position(0);
+            Modpkg::mount_from_reader(cursor).unwrap()
+        };
+
+        let mut plain = build(ModpkgCompression::None);
+        let mut packed = build(ModpkgCompression::Zstd);
+        let chunk = |modpkg: &Modpkg<Cursor<Vec<u8>>>| {
+            *modpkg
+                .chunks
+                .get(&(hash_chunk_name(METADATA_CHUNK_PATH), NO_LAYER_HASH))
+                .unwrap()
+        };
+
+        assert_eq!(chunk(&plain).compression, ModpkgCompression::None);
+        assert_eq!(chunk(&packed).compression, ModpkgCompression::Zstd);
+        assert!(chunk(&packed).compressed_size < chunk(&plain).compressed_size);
+        assert_eq!(
+            packed.load_metadata().unwrap(),
+            plain.load_metadata().unwrap()
+        );
+        assert_eq!(
+            packed.load_metadata().unwrap().description,
+            metadata.description
+        );
+    }
 }
@@ -0,0 +1,51 @@
+use std::io::{Read, Seek};
+
+use crate::{
+    chunk::{NO_LAYER_INDEX, NO_WAD_INDEX},
+    error::ModpkgError,
+    Modpkg,
+};
+
+/// The path to the CHANGELOG.md chunk.
+pub const CHANGELOG_CHUNK_PATH: &str = "_meta_/changelog.md";
+
+impl<TSource: Read + Seek> Modpkg<TSource> {
+    /// Load the CHANGELOG.md chunk from the mod package.
+    pub fn load_changelog(&mut self) -> Result<Vec<u8>, ModpkgError> {
+        let chunk = *self.get_chunk(CHANGELOG_CHUNK_PATH, None)?;
+
+        if chunk.layer_index != NO_LAYER_INDEX || chunk.wad_index != NO_WAD_INDEX {
+            return Err(ModpkgError::InvalidMetaChunk);
+        }
+
+        let data = self.load_chunk_decompressed(&chunk)?;
+
+        Ok(data.into_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ModpkgBuilder, ModpkgLayerBuilder};
+    use std::io::Cursor;
+
+    #[test]
+    fn changelog_round_trips() {
+        let mut buffer = Cursor::new(Vec::new());
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_changelog("## 1.0.0\n- Initial release")
+            .unwrap()
+            .build_to_writer(&mut buffer, |_, _| Ok(()))
+            .unwrap();
+
+        buffer.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(buffer).unwrap();
+        assert_eq!(
+            modpkg.load_changelog().unwrap(),
+            b"## 1.0.0\n- Initial release"
+        );
+        assert!(modpkg.load_readme().is_err());
+    }
+}
@@ -28,6 +28,7 @@ where
         match chunk.compression {
             ModpkgCompression::None => self.load_chunk_raw(chunk),
             ModpkgCompression::Zstd => self.decode_zstd_chunk(chunk),
+            ModpkgCompression::Lz4 => self.decode_lz4_chunk(chunk),
         }
     }
 
@@ -39,6 +40,15 @@ where
 
         Ok(data.into_boxed_slice())
     }
+
+    fn decode_lz4_chunk(&mut self, chunk: &ModpkgChunk) -> Result<Box<[u8]>, ModpkgError> {
+        let compressed = self.load_chunk_raw(chunk)?;
+
+        let data = lz4_flex::block::decompress(&compressed, chunk.uncompressed_size as usize)
+            .map_err(|e| ModpkgError::Io(std::io::Error::other(e)))?;
+
+        Ok(data.into_boxed_slice())
+    }
 }
 
 #[cfg(test)]
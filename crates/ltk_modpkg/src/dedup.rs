@@ -0,0 +1,207 @@
+//! Read-only analysis of content duplicated across a library of mod packages.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{chunk::NO_LAYER_INDEX, error::ModpkgError, Modpkg};
+
+/// Summary of how much chunk content is repeated across a set of `.modpkg` files.
+///
+/// Chunk content is identified by its uncompressed checksum and size. Content that
+/// appears several times inside a single package is already stored once by the
+/// builder, so only repetition *across* packages counts as duplication here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DedupReport {
+    /// Number of packages successfully mounted and analyzed.
+    pub packages_scanned: usize,
+    /// Packages that could not be mounted, with the reason.
+    pub unreadable: Vec<(Utf8PathBuf, String)>,
+    /// Number of distinct chunk contents across the library.
+    pub unique_contents: usize,
+    /// Number of distinct chunk contents present in more than one package.
+    pub shared_contents: usize,
+    /// Bytes currently used by chunk data across all packages (as stored, i.e. compressed).
+    pub stored_bytes: u64,
+    /// Bytes that would be used if each distinct content were stored exactly once.
+    pub deduplicated_bytes: u64,
+}
+
+impl DedupReport {
+    /// Bytes that a shared content-addressed store would save.
+    pub fn savings_bytes(&self) -> u64 {
+        self.stored_bytes - self.deduplicated_bytes
+    }
+
+    /// Fraction of stored bytes that are duplicates, in `0.0..=1.0`.
+    pub fn savings_ratio(&self) -> f64 {
+        if self.stored_bytes == 0 {
+            0.0
+        } else {
+            self.savings_bytes() as f64 / self.stored_bytes as f64
+        }
+    }
+}
+
+/// Per-content accumulator: how many packages hold it and the smallest stored size.
+#[derive(Default)]
+struct ContentUsage {
+    packages: usize,
+    stored_bytes: u64,
+    min_stored_size: u64,
+}
+
+/// Analyze every `.modpkg` file under `storage_dir` (recursively) for cross-package
+/// duplicate chunk content.
+///
+/// Only chunk tables are read; no chunk data is loaded. Meta chunks (`_meta_/`) are
+/// ignored. Packages that fail to mount are listed in [`DedupReport::unreadable`]
+/// instead of aborting the analysis.
+pub fn analyze_dedup(storage_dir: &Utf8Path) -> Result<DedupReport, ModpkgError> {
+    let mut package_paths = Vec::new();
+    collect_modpkg_paths(storage_dir, &mut package_paths)?;
+    package_paths.sort();
+
+    let mut report = DedupReport::default();
+    let mut usage: HashMap<(u64, u64), ContentUsage> = HashMap::new();
+
+    for path in package_paths {
+        let modpkg = match File::open(&path)
+            .map_err(ModpkgError::from)
+            .and_then(|file| Modpkg::mount_from_reader(BufReader::new(file)))
+        {
+            Ok(modpkg) => modpkg,
+            Err(e) => {
+                report.unreadable.push((path, e.to_string()));
+                continue;
+            }
+        };
+        report.packages_scanned += 1;
+
+        // Identical content within one package is stored once, so record the
+        // smallest stored size per content key for this package.
+        let mut package_contents: HashMap<(u64, u64), u64> = HashMap::new();
+        for chunk in modpkg.chunks.values() {
+            if chunk.layer_index == NO_LAYER_INDEX {
+                continue;
+            }
+            let key = (chunk.uncompressed_checksum, chunk.uncompressed_size);
+            package_contents
+                .entry(key)
+                .and_modify(|size| *size = (*size).min(chunk.compressed_size))
+                .or_insert(chunk.compressed_size);
+        }
+
+        for (key, size) in package_contents {
+            let entry = usage.entry(key).or_insert_with(|| ContentUsage {
+                min_stored_size: size,
+                ..Default::default()
+            });
+            entry.packages += 1;
+            entry.stored_bytes += size;
+            entry.min_stored_size = entry.min_stored_size.min(size);
+        }
+    }
+
+    for content in usage.values() {
+        report.unique_contents += 1;
+        if content.packages > 1 {
+            report.shared_contents += 1;
+        }
+        report.stored_bytes += content.stored_bytes;
+        report.deduplicated_bytes += content.min_stored_size;
+    }
+
+    Ok(report)
+}
+
+fn collect_modpkg_paths(dir: &Utf8Path, out: &mut Vec<Utf8PathBuf>) -> Result<(), ModpkgError> {
+    for entry in dir.read_dir_utf8()? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_modpkg_paths(path, out)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("modpkg"))
+        {
+            out.push(path.to_owned());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use std::io::{Cursor, Write};
+
+    fn write_package(path: &Utf8Path, chunks: &[(&str, &[u8])]) {
+        let mut builder = ModpkgBuilder::default().with_layer(ModpkgLayerBuilder::base());
+        for (chunk_path, _) in chunks {
+            builder = builder.with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path(chunk_path)
+                    .unwrap()
+                    .with_layer("base"),
+            );
+        }
+
+        let data: HashMap<String, Vec<u8>> = chunks
+            .iter()
+            .map(|(p, d)| (p.to_string(), d.to_vec()))
+            .collect();
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |chunk, out| {
+                out.write_all(&data[&chunk.path])?;
+                Ok(())
+            })
+            .unwrap();
+        std::fs::write(path, cursor.into_inner()).unwrap();
+    }
+
+    #[test]
+    fn reports_content_shared_across_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+
+        let shared: &[u8] = &[7u8; 64];
+        write_package(
+            &root.join("a.modpkg"),
+            &[("shared.bin", shared), ("only_a.bin", b"aaaa")],
+        );
+        write_package(
+            &root.join("nested/b.modpkg"),
+            &[("other/name.bin", shared), ("only_b.bin", b"bbbbbb")],
+        );
+        std::fs::write(root.join("broken.modpkg"), b"not a modpkg").unwrap();
+        std::fs::write(root.join("ignored.txt"), b"ignored").unwrap();
+
+        let report = analyze_dedup(root).unwrap();
+
+        assert_eq!(report.packages_scanned, 2);
+        assert_eq!(report.unreadable.len(), 1);
+        assert_eq!(report.unique_contents, 3);
+        assert_eq!(report.shared_contents, 1);
+        assert!(report.savings_bytes() > 0);
+        assert_eq!(
+            report.stored_bytes - report.deduplicated_bytes,
+            report.savings_bytes()
+        );
+    }
+
+    #[test]
+    fn empty_library_has_no_savings() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+
+        let report = analyze_dedup(root).unwrap();
+        assert_eq!(report, DedupReport::default());
+        assert_eq!(report.savings_ratio(), 0.0);
+    }
+}
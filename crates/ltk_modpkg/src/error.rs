@@ -19,6 +19,8 @@ pub enum ModpkgError {
     MissingMetadata,
     #[error("Missing base layer")]
     MissingBaseLayer,
+    #[error("Layer not found: {0}")]
+    LayerNotFound(String),
     #[error("Invalid modpkg compression type: {0}")]
     InvalidCompressionType(u8),
     #[error(
@@ -41,6 +43,17 @@ pub enum ModpkgError {
     MissingChunk(u64),
     #[error("Invalid meta chunk: must not belong to any layer or wad")]
     InvalidMetaChunk,
+    #[error("Not a meta chunk path (expected `_meta_/...`): {0}")]
+    NotMetaChunkPath(String),
+
+    #[error("Package is not signed")]
+    Unsigned,
+    #[error("Failed to decode package signature: {0}")]
+    SignatureDecode(der::Error),
+    #[error("Package signature is not trusted: {0}")]
+    SignatureVerify(#[from] ltk_pki::pki::verify::VerifyError),
+    #[error("Package contents do not match its signature")]
+    SignatureMismatch,
 
     #[error("Msgpack decode error: {0}")]
     MsgpackDecode(#[from] rmp_serde::decode::Error),
@@ -5,7 +5,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::{chunk::ModpkgChunk, error::ModpkgError, Modpkg};
+use crate::{chunk::ModpkgChunk, error::ModpkgError, hash_layer_name, Modpkg};
+
+/// Summary of an extraction run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractStats {
+    /// Number of chunk files written to disk.
+    pub files_written: usize,
+}
 
 /// Extractor for ModPkg archives.
 ///
@@ -24,6 +31,11 @@ impl<'modpkg, TSource: Read + Seek> ModpkgExtractor<'modpkg, TSource> {
     /// Extract all chunks from the ModPkg to the specified output directory.
     ///
     /// Chunks will be organized by layer, with each layer having its own subdirectory.
+    /// A chunk packed with a WAD association is placed under a directory named
+    /// after its WAD (e.g. `base/Aatrox.wad.client/data/...`), matching the
+    /// layout the project packer reads, so extracting and repacking keeps the
+    /// WAD grouping. Chunks without a WAD association are written directly
+    /// under the layer directory.
     pub fn extract_all(&mut self, output_dir: impl AsRef<Path>) -> Result<(), ModpkgError> {
         let output_dir = output_dir.as_ref();
 
@@ -49,13 +61,73 @@ impl<'modpkg, TSource: Read + Seek> ModpkgExtractor<'modpkg, TSource> {
             fs::create_dir_all(&layer_dir)?;
 
             for chunk in chunks {
-                self.extract_chunk(&chunk, &layer_dir)?;
+                self.extract_layer_chunk(&chunk, layer_hash, &layer_dir)?;
             }
         }
 
         Ok(())
     }
 
+    /// Extract only the chunks of a single layer to the specified output directory.
+    ///
+    /// Unlike [`extract_all`](Self::extract_all), files are written directly under
+    /// `output_dir` rather than a `<layer>/` subdirectory; WAD grouping is kept the
+    /// same way. Meta chunks (`_meta_/`) are never extracted.
+    ///
+    /// Returns [`ModpkgError::LayerNotFound`] if the package has no such layer.
+    pub fn extract_layer(
+        &mut self,
+        layer_name: &str,
+        output_dir: impl AsRef<Path>,
+    ) -> Result<ExtractStats, ModpkgError> {
+        let output_dir = output_dir.as_ref();
+
+        let layer_hash = hash_layer_name(layer_name);
+        if !self.modpkg.layers.contains_key(&layer_hash) {
+            return Err(ModpkgError::LayerNotFound(layer_name.to_string()));
+        }
+
+        let chunks: Vec<ModpkgChunk> = self
+            .modpkg
+            .chunks
+            .iter()
+            .filter(|((_, chunk_layer), _)| *chunk_layer == layer_hash)
+            .filter(|((path_hash, _), _)| {
+                !self
+                    .modpkg
+                    .chunk_paths
+                    .get(path_hash)
+                    .is_some_and(|path| path.starts_with("_meta_/"))
+            })
+            .map(|(_, chunk)| *chunk)
+            .collect();
+
+        fs::create_dir_all(output_dir)?;
+
+        let mut stats = ExtractStats::default();
+        for chunk in chunks {
+            self.extract_layer_chunk(&chunk, layer_hash, output_dir)?;
+            stats.files_written += 1;
+        }
+
+        Ok(stats)
+    }
+
+    /// Extract a chunk of the given layer into `layer_dir`, nested under its WAD
+    /// directory when it has one.
+    fn extract_layer_chunk(
+        &mut self,
+        chunk: &ModpkgChunk,
+        layer_hash: u64,
+        layer_dir: &Path,
+    ) -> Result<PathBuf, ModpkgError> {
+        let chunk_dir = match self.modpkg.chunk_wad_name(chunk.path_hash, layer_hash) {
+            Some(wad_name) => layer_dir.join(wad_name),
+            None => layer_dir.to_path_buf(),
+        };
+        self.extract_chunk(chunk, chunk_dir)
+    }
+
     /// Extract a specific chunk to the specified directory.
     pub fn extract_chunk(
         &mut self,
@@ -224,4 +296,104 @@ mod tests {
         assert_eq!(extracted_base_data, base_data);
         assert_eq!(extracted_custom_data, custom_data);
     }
+
+    #[test]
+    fn test_extract_preserves_wad_grouping() {
+        let mut cursor = Cursor::new(Vec::new());
+
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None)
+                    .with_wad("Aatrox.wad.client"),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("readme.txt")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None),
+            );
+
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                cursor.write_all(chunk.path.as_bytes())?;
+                Ok(())
+            })
+            .expect("Failed to build Modpkg");
+
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path();
+        ModpkgExtractor::new(&mut modpkg)
+            .extract_all(output_dir)
+            .unwrap();
+
+        let wad_file = output_dir
+            .join("base")
+            .join("aatrox.wad.client")
+            .join("data/skin.bin");
+        assert_eq!(fs::read(wad_file).unwrap(), b"data/skin.bin");
+        assert!(!output_dir.join("base").join("data/skin.bin").exists());
+
+        let raw_file = output_dir.join("base").join("readme.txt");
+        assert_eq!(fs::read(raw_file).unwrap(), b"readme.txt");
+    }
+
+    #[test]
+    fn test_extract_single_layer() {
+        let mut cursor = Cursor::new(Vec::new());
+
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(1))
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None)
+                    .with_wad("Aatrox.wad.client"),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/chroma.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None)
+                    .with_layer("chroma")
+                    .with_wad("Aatrox.wad.client"),
+            );
+
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                cursor.write_all(chunk.path.as_bytes())?;
+                Ok(())
+            })
+            .expect("Failed to build Modpkg");
+
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+
+        let temp_dir = tempdir().unwrap();
+        let output_dir = temp_dir.path();
+        let mut extractor = ModpkgExtractor::new(&mut modpkg);
+        let stats = extractor.extract_layer("base", output_dir).unwrap();
+        assert_eq!(stats.files_written, 1);
+
+        let wad_dir = output_dir.join("aatrox.wad.client");
+        assert_eq!(
+            fs::read(wad_dir.join("data/skin.bin")).unwrap(),
+            b"data/skin.bin"
+        );
+        assert!(!wad_dir.join("data/chroma.bin").exists());
+        assert!(!output_dir.join("_meta_").exists());
+
+        assert!(matches!(
+            extractor.extract_layer("missing", output_dir),
+            Err(ModpkgError::LayerNotFound(name)) if name == "missing"
+        ));
+    }
 }
@@ -1,5 +1,5 @@
 use binrw::binrw;
-use chunk::{ModpkgChunk, NO_LAYER_HASH};
+use chunk::{ModpkgChunk, NO_LAYER_HASH, NO_WAD_INDEX};
 use error::ModpkgError;
 use std::{
     collections::HashMap,
@@ -8,24 +8,33 @@ use std::{
     path::Path,
 };
 
+mod append;
 pub mod builder;
+mod changelog;
 mod chunk;
 mod decoder;
+mod dedup;
 pub mod error;
 mod extractor;
 mod license;
+mod merge;
+mod meta;
 mod metadata;
 mod read;
 mod readme;
+mod signature;
 mod thumbnail;
 pub mod utils;
 
 #[cfg(feature = "project")]
 pub mod project;
 
+pub use changelog::*;
 pub use decoder::ModpkgDecoder;
-pub use extractor::ModpkgExtractor;
+pub use dedup::{analyze_dedup, DedupReport};
+pub use extractor::{ExtractStats, ModpkgExtractor};
 pub use license::*;
+pub use merge::MergeConflict;
 pub use metadata::*;
 pub use readme::*;
 pub use thumbnail::*;
@@ -40,9 +49,26 @@ pub type BatchChunkEntry = (u64, u64, Box<[u8]>);
 /// The name of the metadata folder inside the mod package.
 pub const METADATA_FOLDER_NAME: &str = "_meta_";
 
+/// A chunk of a mod package with its path and layer resolved, as yielded by
+/// [`Modpkg::iter_chunks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkInfo<'a> {
+    pub path_hash: u64,
+    /// The chunk's path, or `None` if the path table has no entry for it.
+    pub path: Option<&'a str>,
+    /// The name of the chunk's layer, or `None` for chunks outside any layer
+    /// (such as meta chunks).
+    pub layer: Option<&'a str>,
+    pub uncompressed_size: u64,
+    pub compressed_size: u64,
+    pub compression: ModpkgCompression,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Modpkg<TSource: Read + Seek> {
     signature: Vec<u8>,
+    /// Offset of the chunk data, right after the chunk TOC.
+    chunk_data_offset: u64,
 
     pub layer_indices: Vec<u64>,
     pub layers: HashMap<u64, ModpkgLayer>,
@@ -91,6 +117,7 @@ pub enum ModpkgCompression {
     #[default]
     None = 0,
     Zstd = 1,
+    Lz4 = 2,
 }
 
 impl<TSource: Read + Seek> Modpkg<TSource> {
@@ -230,6 +257,18 @@ impl<TSource: Read + Seek> Modpkg<TSource> {
         self.wads.get(wad_hash).map(|s| s.as_str())
     }
 
+    /// Get the target WAD name recorded for a chunk.
+    ///
+    /// Returns `None` if the chunk does not exist or was packed without a WAD
+    /// association.
+    pub fn chunk_wad_name(&self, path_hash: u64, layer_hash: u64) -> Option<&str> {
+        let chunk = self.chunks.get(&(path_hash, layer_hash))?;
+        if chunk.wad_index == NO_WAD_INDEX {
+            return None;
+        }
+        self.wad_name_for_index(chunk.wad_index)
+    }
+
     /// Get the chunk keys for a given (wad_index, layer_index) pair.
     ///
     /// Returns an empty slice if no chunks match.
@@ -240,6 +279,35 @@ impl<TSource: Read + Seek> Modpkg<TSource> {
             .unwrap_or(&[])
     }
 
+    /// Iterate over every chunk with its path and layer name resolved, in no
+    /// particular order.
+    ///
+    /// Only the tables read at mount time are consulted; no chunk data is read.
+    pub fn iter_chunks(&self) -> impl Iterator<Item = ChunkInfo<'_>> {
+        self.chunks.values().map(|chunk| {
+            // Look the path up by index: a hex-named chunk's path hash is the
+            // parsed hex value, not the hash of its stored path string.
+            let path = self
+                .chunk_path_indices
+                .get(chunk.path_index as usize)
+                .and_then(|path_hash| self.chunk_paths.get(path_hash))
+                .map(String::as_str);
+            let layer = chunk
+                .layer()
+                .and_then(|index| self.layer_indices.get(index as usize))
+                .and_then(|layer_hash| self.layers.get(layer_hash))
+                .map(|layer| layer.name.as_str());
+            ChunkInfo {
+                path_hash: chunk.path_hash,
+                path,
+                layer,
+                uncompressed_size: chunk.uncompressed_size,
+                compressed_size: chunk.compressed_size,
+                compression: chunk.compression,
+            }
+        })
+    }
+
     /// Load and decompress multiple chunks in offset-sorted order for better I/O performance.
     ///
     /// Returns `(path_hash, layer_hash, data)` tuples in arbitrary order.
@@ -274,6 +342,7 @@ impl Display for ModpkgCompression {
             match self {
                 ModpkgCompression::None => "none",
                 ModpkgCompression::Zstd => "zstd",
+                ModpkgCompression::Lz4 => "lz4",
             }
         )
     }
@@ -286,6 +355,7 @@ impl TryFrom<u8> for ModpkgCompression {
         Ok(match value {
             0 => ModpkgCompression::None,
             1 => ModpkgCompression::Zstd,
+            2 => ModpkgCompression::Lz4,
             _ => return Err(ModpkgError::InvalidCompressionType(value)),
         })
     }
@@ -451,4 +521,61 @@ mod tests {
 
         assert!(modpkg.get_chunk("nonexistent", Some(layer_name)).is_err());
     }
+
+    #[test]
+    fn test_iter_chunks() {
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(1))
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/a.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::Zstd),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/b.bin")
+                    .unwrap()
+                    .with_layer("chroma")
+                    .with_compression(ModpkgCompression::None),
+            );
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                // Distinct contents, so the two chunks are not deduplicated.
+                cursor.write_all(&[chunk.path_hash() as u8; 64])?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+
+        // Drop one path so its chunk is only known by hash.
+        modpkg.chunk_paths.remove(&hash_chunk_name("data/b.bin"));
+
+        // The builder also writes the metadata chunk, which has no layer.
+        let (meta, mut chunks): (Vec<ChunkInfo>, Vec<ChunkInfo>) = modpkg
+            .iter_chunks()
+            .partition(|chunk| chunk.layer.is_none());
+        assert_eq!(meta.len(), 1);
+        assert_eq!(meta[0].path, Some(METADATA_CHUNK_PATH));
+        chunks.sort_by_key(|chunk| chunk.layer);
+        assert_eq!(chunks.len(), 2);
+
+        assert_eq!(chunks[0].path_hash, hash_chunk_name("data/a.bin"));
+        assert_eq!(chunks[0].path, Some("data/a.bin"));
+        assert_eq!(chunks[0].layer, Some("base"));
+        assert_eq!(chunks[0].uncompressed_size, 64);
+        assert_eq!(chunks[0].compression, ModpkgCompression::Zstd);
+        assert!(chunks[0].compressed_size < 64);
+
+        assert_eq!(chunks[1].path_hash, hash_chunk_name("data/b.bin"));
+        assert_eq!(chunks[1].path, None);
+        assert_eq!(chunks[1].layer, Some("chroma"));
+        assert_eq!(chunks[1].uncompressed_size, 64);
+        assert_eq!(chunks[1].compressed_size, 64);
+        assert_eq!(chunks[1].compression, ModpkgCompression::None);
+    }
 }
@@ -0,0 +1,205 @@
+//! Merging the content of one package into a builder.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+use crate::{
+    builder::{ModpkgBuilder, ModpkgBuilderError, ModpkgLayerBuilder},
+    Modpkg,
+};
+
+/// What [`ModpkgBuilder::merge_from`] does with a chunk whose path and layer
+/// are already in the builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Keep the builder's chunk.
+    Skip,
+    /// Replace the builder's chunk with the merged one.
+    Overwrite,
+    /// Fail with [`ModpkgBuilderError::ChunkConflict`] without merging anything.
+    Error,
+}
+
+impl ModpkgBuilder {
+    /// Merge the content chunks of `other` into the builder.
+    ///
+    /// Every content chunk is decompressed and added with its path, layer, WAD
+    /// and compression; layers the builder lacks are added with `other`'s
+    /// priority. `other`'s metadata, readme, changelog and thumbnail are ignored.
+    /// The merged data is held in memory until the builder is built.
+    pub fn merge_from<TSource: Read + Seek>(
+        &mut self,
+        other: &mut Modpkg<TSource>,
+        on_conflict: MergeConflict,
+    ) -> Result<(), ModpkgBuilderError> {
+        let mut incoming = other.content_chunk_builders()?;
+        incoming.sort_by(|a, b| (&a.layer, &a.path).cmp(&(&b.layer, &b.path)));
+
+        if on_conflict == MergeConflict::Error {
+            if let Some(chunk) = incoming
+                .iter()
+                .find(|chunk| self.chunks.contains_key(&chunk.key()))
+            {
+                return Err(ModpkgBuilderError::ChunkConflict {
+                    path: chunk.path.clone(),
+                    layer: chunk.layer.clone(),
+                });
+            }
+        }
+
+        let priorities: HashMap<&str, i32> = other
+            .layers
+            .values()
+            .map(|layer| (layer.name.as_str(), layer.priority))
+            .collect();
+        let mut new_layers = Vec::new();
+        for chunk in &incoming {
+            let layer = chunk.layer.as_str();
+            if layer.is_empty()
+                || self.layers.iter().any(|l| l.name == layer)
+                || new_layers
+                    .iter()
+                    .any(|l: &ModpkgLayerBuilder| l.name == layer)
+            {
+                continue;
+            }
+            new_layers.push(
+                ModpkgLayerBuilder::new(layer)
+                    .with_priority(priorities.get(layer).copied().unwrap_or_default()),
+            );
+        }
+
+        let mut merged = Vec::with_capacity(incoming.len());
+        for mut chunk in incoming {
+            if on_conflict == MergeConflict::Skip && self.chunks.contains_key(&chunk.key()) {
+                continue;
+            }
+            let existing = chunk
+                .existing
+                .take()
+                .expect("content chunk builders keep their TOC entry");
+            chunk.data = Some(Arc::from(
+                other.decoder().load_chunk_decompressed(&existing)?,
+            ));
+            merged.push(chunk);
+        }
+
+        self.layers.extend(new_layers);
+        for chunk in merged {
+            self.chunks.insert(chunk.key(), chunk);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::ModpkgChunkBuilder;
+    use crate::ModpkgCompression;
+    use std::io::{Cursor, Write};
+
+    fn single_chunk_package(
+        path: &str,
+        layer: &str,
+        data: &'static [u8],
+    ) -> Modpkg<Cursor<Vec<u8>>> {
+        let mut builder = ModpkgBuilder::default().with_layer(ModpkgLayerBuilder::base());
+        if layer != "base" {
+            builder = builder.with_layer(ModpkgLayerBuilder::new(layer).with_priority(5));
+        }
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path(path)
+                    .unwrap()
+                    .with_layer(layer)
+                    .with_wad("Aatrox.wad.client")
+                    .with_compression(ModpkgCompression::Zstd),
+            )
+            .build_to_writer(&mut cursor, |_, cursor| {
+                cursor.write_all(data)?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        Modpkg::mount_from_reader(cursor).unwrap()
+    }
+
+    fn build(builder: ModpkgBuilder) -> Modpkg<Cursor<Vec<u8>>> {
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |chunk, _| {
+                panic!("merged chunk {} asked the provider for data", chunk.path)
+            })
+            .unwrap();
+        cursor.set_position(0);
+        Modpkg::mount_from_reader(cursor).unwrap()
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let mut first = single_chunk_package("data/a.bin", "base", b"first");
+        let mut second = single_chunk_package("data/b.bin", "chroma", b"second");
+
+        let mut builder = ModpkgBuilder::default().with_layer(ModpkgLayerBuilder::base());
+        builder
+            .merge_from(&mut first, MergeConflict::Error)
+            .unwrap();
+        builder
+            .merge_from(&mut second, MergeConflict::Error)
+            .unwrap();
+        let mut merged = build(builder.clone());
+
+        assert_eq!(
+            &*merged
+                .load_chunk_decompressed_by_path("data/a.bin", Some("base"))
+                .unwrap(),
+            b"first"
+        );
+        assert_eq!(
+            &*merged
+                .load_chunk_decompressed_by_path("data/b.bin", Some("chroma"))
+                .unwrap(),
+            b"second"
+        );
+        let chroma = merged
+            .layers
+            .get(&crate::hash_layer_name("chroma"))
+            .unwrap();
+        assert_eq!(chroma.priority, 5);
+        let b = *merged.get_chunk("data/b.bin", Some("chroma")).unwrap();
+        assert_eq!(
+            merged.wad_name_for_index(b.wad_index),
+            Some("aatrox.wad.client")
+        );
+
+        let mut clash = single_chunk_package("data/a.bin", "base", b"clash");
+        assert!(matches!(
+            builder.clone().merge_from(&mut clash, MergeConflict::Error),
+            Err(ModpkgBuilderError::ChunkConflict { path, layer })
+                if path == "data/a.bin" && layer == "base"
+        ));
+
+        let mut skipped = builder.clone();
+        skipped.merge_from(&mut clash, MergeConflict::Skip).unwrap();
+        assert_eq!(
+            &*build(skipped)
+                .load_chunk_decompressed_by_path("data/a.bin", Some("base"))
+                .unwrap(),
+            b"first"
+        );
+
+        builder
+            .merge_from(&mut clash, MergeConflict::Overwrite)
+            .unwrap();
+        assert_eq!(
+            &*build(builder)
+                .load_chunk_decompressed_by_path("data/a.bin", Some("base"))
+                .unwrap(),
+            b"clash"
+        );
+    }
+}
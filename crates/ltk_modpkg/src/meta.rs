@@ -0,0 +1,102 @@
+use std::io::{Read, Seek};
+
+use crate::{
+    chunk::{ModpkgChunk, NO_LAYER_INDEX, NO_WAD_INDEX},
+    error::ModpkgError,
+    Modpkg, METADATA_FOLDER_NAME,
+};
+
+impl<TSource: Read + Seek> Modpkg<TSource> {
+    /// List the meta chunks (`_meta_/...`) in the mod package with their paths, sorted by path.
+    ///
+    /// This includes the metadata, readme and thumbnail chunks as well as any other
+    /// meta files a newer packer may have written.
+    pub fn meta_chunks(&self) -> Vec<(&str, &ModpkgChunk)> {
+        let mut meta_chunks: Vec<(&str, &ModpkgChunk)> = self
+            .chunks
+            .values()
+            .filter(|chunk| chunk.layer_index == NO_LAYER_INDEX)
+            .filter_map(|chunk| {
+                let path = self.chunk_paths.get(&chunk.path_hash)?;
+                is_meta_path(path).then_some((path.as_str(), chunk))
+            })
+            .collect();
+        meta_chunks.sort_by(|a, b| a.0.cmp(b.0));
+        meta_chunks
+    }
+
+    /// Load and decompress a meta chunk by its path (e.g. `_meta_/readme.md`).
+    pub fn load_meta_chunk(&mut self, path: &str) -> Result<Box<[u8]>, ModpkgError> {
+        if !is_meta_path(path) {
+            return Err(ModpkgError::NotMetaChunkPath(path.to_string()));
+        }
+
+        let chunk = *self.get_chunk(path, None)?;
+
+        if chunk.layer_index != NO_LAYER_INDEX || chunk.wad_index != NO_WAD_INDEX {
+            return Err(ModpkgError::InvalidMetaChunk);
+        }
+
+        self.load_chunk_decompressed(&chunk)
+    }
+}
+
+fn is_meta_path(path: &str) -> bool {
+    path.strip_prefix(METADATA_FOLDER_NAME)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use crate::{METADATA_CHUNK_PATH, README_CHUNK_PATH};
+    use std::io::{Cursor, Write};
+
+    fn mount_with_readme() -> Modpkg<Cursor<Vec<u8>>> {
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_readme("# Hello")
+            .unwrap()
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/_meta_/skin0.bin")
+                    .unwrap()
+                    .with_layer("base"),
+            );
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |_chunk, out| {
+                out.write_all(b"data")?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        Modpkg::mount_from_reader(cursor).unwrap()
+    }
+
+    #[test]
+    fn meta_chunks_lists_only_meta_folder() {
+        let modpkg = mount_with_readme();
+        let paths: Vec<&str> = modpkg.meta_chunks().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(paths, vec![METADATA_CHUNK_PATH, README_CHUNK_PATH]);
+    }
+
+    #[test]
+    fn load_meta_chunk_reads_meta_and_rejects_other_paths() {
+        let mut modpkg = mount_with_readme();
+        assert_eq!(
+            &*modpkg.load_meta_chunk(README_CHUNK_PATH).unwrap(),
+            b"# Hello"
+        );
+        assert!(matches!(
+            modpkg.load_meta_chunk("data/_meta_/skin0.bin"),
+            Err(ModpkgError::NotMetaChunkPath(_))
+        ));
+        assert!(matches!(
+            modpkg.load_meta_chunk("_meta_/license.txt"),
+            Err(ModpkgError::MissingChunk(_))
+        ));
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Cursor, Read, Seek, Write};
 
 /// The path to the info.msgpack chunk.
@@ -72,6 +72,16 @@ impl DistributorInfo {
     }
 }
 
+/// Another mod that a mod package requires.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+pub struct ModpkgDependency {
+    /// The name of the required mod (e.g., "old-summoners-rift").
+    pub name: String,
+    /// The semver requirement the required mod's version must match (e.g., "^1.2").
+    pub version_req: String,
+}
+
 /// Per-layer metadata that can be stored inside the mod package metadata.
 ///
 /// Added in schema version 2: the `string_overrides` field allows mods to
@@ -119,8 +129,13 @@ pub struct ModpkgLayerMetadata {
     ///
     /// Only the overrides are stored — not the full stringtable — so the
     /// mod stays compatible across game patches.
-    /// Empty maps are omitted during serialization.
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    /// Empty maps are omitted during serialization, and keys are written in
+    /// sorted order so the encoded metadata is reproducible.
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_sorted_string_overrides"
+    )]
     #[cfg_attr(
         test,
         proptest(strategy = "proptest::collection::hash_map(\
@@ -132,6 +147,17 @@ pub struct ModpkgLayerMetadata {
     pub string_overrides: HashMap<String, HashMap<String, String>>,
 }
 
+fn serialize_sorted_string_overrides<S: serde::Serializer>(
+    overrides: &HashMap<String, HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sorted: BTreeMap<&String, BTreeMap<&String, &String>> = overrides
+        .iter()
+        .map(|(locale, fields)| (locale, fields.iter().collect()))
+        .collect();
+    sorted.serialize(serializer)
+}
+
 /// The metadata of a mod package.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -180,6 +206,30 @@ pub struct ModpkgMetadata {
     )]
     pub maps: Vec<String>,
 
+    /// Compression for override files whose path matches a glob pattern, keyed
+    /// by pattern relative to the WAD root (e.g. `**/*.bnk`).
+    ///
+    /// Values are `none` or `zstd`, as in the project's `mod.config.json`.
+    /// Empty when the overlay builder should pick compression from each file's type.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[cfg_attr(
+        test,
+        proptest(
+            strategy = "proptest::collection::btree_map(\"[a-z*/]{1,10}\\\\.[a-z]{2,4}\", \"(none|zstd)\", 0..3)"
+        )
+    )]
+    pub compression: BTreeMap<String, String>,
+
+    /// Other mods this mod requires, such as the base pack of an add-on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(
+        test,
+        proptest(
+            strategy = "proptest::collection::vec(proptest::prelude::any::<ModpkgDependency>(), 0..3)"
+        )
+    )]
+    pub dependencies: Vec<ModpkgDependency>,
+
     /// This is purely informational and does not affect how the modpkg loader
     /// resolves layers; the canonical source of truth for layer priority is
     /// still the modpkg header.
@@ -207,6 +257,8 @@ impl Default for ModpkgMetadata {
             tags: Vec::new(),
             champions: Vec::new(),
             maps: Vec::new(),
+            compression: BTreeMap::new(),
+            dependencies: Vec::new(),
             layers: Vec::new(),
         }
     }
@@ -369,6 +421,8 @@ mod tests {
             tags: vec![],
             champions: vec![],
             maps: vec![],
+            compression: BTreeMap::new(),
+            dependencies: vec![],
             layers: vec![],
         };
         let mut cursor = Cursor::new(Vec::new());
@@ -404,6 +458,8 @@ mod tests {
             tags: vec![],
             champions: vec![],
             maps: vec![],
+            compression: BTreeMap::new(),
+            dependencies: vec![],
             layers: vec![],
         };
 
@@ -491,6 +547,8 @@ mod tests {
             tags: vec![],
             champions: vec![],
             maps: vec![],
+            compression: BTreeMap::new(),
+            dependencies: vec![],
             layers: vec![ModpkgLayerMetadata {
                 name: "base".to_string(),
                 display_name: None,
@@ -526,6 +584,8 @@ mod tests {
             tags: vec![],
             champions: vec![],
             maps: vec![],
+            compression: BTreeMap::new(),
+            dependencies: vec![],
             layers: vec![
                 ModpkgLayerMetadata {
                     name: "base".to_string(),
@@ -20,8 +20,11 @@ pub mod thumbnail;
 #[cfg(test)]
 mod tests;
 
-pub use packer::ProjectPacker;
-pub use thumbnail::{load_thumbnail, MAX_THUMBNAIL_SIZE};
+pub use packer::{
+    validate_layer_dirs, validate_project, validate_project_config, validate_project_thumbnail,
+    ProjectPacker,
+};
+pub use thumbnail::{load_thumbnail, validate_thumbnail, MAX_THUMBNAIL_SIZE};
 
 use crate::builder::ModpkgBuilderError;
 use camino::Utf8Path;
@@ -51,15 +54,29 @@ pub enum PackError {
     #[error("Layer directory missing: {layer} at {path}")]
     LayerDirMissing { layer: String, path: Utf8PathBuf },
 
+    /// The project has [`ModProject::validate`] errors, listed in order.
+    #[error("Invalid project: {}", .0.join("; "))]
+    InvalidProject(Vec<String>),
+
+    #[deprecated(note = "layer names are checked by `ModProject::validate`, see `InvalidProject`")]
     #[error("Invalid layer name: {0}")]
     InvalidLayerName(String),
 
+    #[deprecated(
+        note = "the base layer priority is checked by `ModProject::validate`, see `InvalidProject`"
+    )]
     #[error("Base layer must have priority 0, got: {0}")]
     InvalidBaseLayerPriority(i32),
 
     #[error("Failed to process thumbnail: {0}")]
     ThumbnailError(String),
 
+    #[error("Configured thumbnail not found: {0}")]
+    ThumbnailMissing(Utf8PathBuf),
+
+    #[error("Configured thumbnail is outside the project directory: {0}")]
+    ThumbnailOutsideProject(Utf8PathBuf),
+
     #[error("Invalid version format: {0}")]
     InvalidVersion(String),
 
@@ -68,6 +85,9 @@ pub enum PackError {
 
     #[error("Invalid UTF-8 path: {0}")]
     InvalidUtf8Path(String),
+
+    #[error("Symlink in content directory (symlink following is disabled): {0}")]
+    SymlinkNotFollowed(Utf8PathBuf),
 }
 
 /// Result of a successful pack operation.
@@ -1,16 +1,19 @@
 //! [`ProjectPacker`] — scans a mod project directory and builds a `.modpkg` archive.
 
-use super::thumbnail::load_thumbnail;
+use super::thumbnail::{load_thumbnail, validate_thumbnail};
 use super::PackError;
 use crate::{
     builder::{ModpkgBuilder, ModpkgBuilderError, ModpkgChunkBuilder, ModpkgLayerBuilder},
     metadata::CURRENT_SCHEMA_VERSION,
     utils::hash_layer_name,
-    ModpkgCompression, ModpkgLayerMetadata, ModpkgMetadata,
+    ModpkgCompression, ModpkgDependency, ModpkgLayerMetadata, ModpkgMetadata,
 };
 use camino::{Utf8Path, Utf8PathBuf};
-use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer, ModProjectLicense};
-use std::collections::HashMap;
+use ltk_mod_project::{
+    CompressionHint, ModProject, ModProjectAuthor, ModProjectLayer, ModProjectLicense,
+    ValidationSeverity,
+};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, BufWriter, Read, Seek, Write};
 
@@ -21,8 +24,8 @@ type ChunkFileMap = HashMap<(u64, u64), Utf8PathBuf>;
 
 /// Packs a mod project directory into a `.modpkg` archive.
 ///
-/// The packer validates the project structure, scans the content directory for
-/// files, and collects all the information needed to build the archive. Call
+/// The packer validates the project structure on construction and scans the
+/// content directory for files when packing. Call
 /// [`pack`](Self::pack) to write to a file or
 /// [`pack_to_writer`](Self::pack_to_writer) to write to an arbitrary output.
 ///
@@ -47,7 +50,16 @@ pub struct ProjectPacker {
     project_root: Utf8PathBuf,
     chunks: Vec<ChunkEntry>,
     readme: Option<String>,
+    changelog: Option<String>,
     thumbnail: Option<Vec<u8>>,
+    follow_symlinks: bool,
+}
+
+/// What a content entry is, after the symlink policy has been applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryKind {
+    File,
+    Dir,
 }
 
 /// An individual content file collected during the project scan.
@@ -67,9 +79,9 @@ impl ProjectPacker {
     /// Create a new packer by loading the mod project config from a directory.
     ///
     /// Looks for `mod.config.json` or `mod.config.toml` in `project_root`,
-    /// validates the project, and scans all layer directories for content.
+    /// merges in any `mod.config.d/` fragments, and validates the project. Layer directories are scanned when packing.
     pub fn new(project_root: Utf8PathBuf) -> Result<Self, PackError> {
-        let mod_project = ModProject::load(project_root.as_std_path())
+        let mod_project = ltk_mod_project::load_config_with_includes(project_root.as_std_path())
             .map_err(|e| PackError::ConfigError(e.to_string()))?;
 
         Self::with_mod_project(mod_project, project_root)
@@ -91,15 +103,30 @@ impl ProjectPacker {
             project_root,
             chunks: Vec::new(),
             readme: None,
+            changelog: None,
             thumbnail: None,
+            follow_symlinks: true,
         };
 
-        packer.scan_layers()?;
         packer.scan_meta_files()?;
 
         Ok(packer)
     }
 
+    /// Follow symlinks found under `content/` (default `true`).
+    ///
+    /// When enabled, symlinked files and directories are packed as if their
+    /// targets lived at the link's location, which lets projects share assets
+    /// through links or git submodules; a link pointing back up the tree is
+    /// skipped. Pass `false` to refuse symlinks instead: packing then fails with
+    /// [`PackError::SymlinkNotFollowed`] on the first one found. Either way,
+    /// `.git` entries (such as the file git places in a submodule checkout) are
+    /// never packed.
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
     /// Pack to a file on disk, creating parent directories if needed.
     ///
     /// Returns [`PackResult`] with the output path on success.
@@ -187,11 +214,13 @@ impl ProjectPacker {
             let entry = entry?;
             let entry_path = utf8_path_from(entry.path())?;
 
-            if entry_path.is_dir() {
-                self.scan_directory(&layer_dir, &entry_path, layer)?;
-            } else if entry_path.is_file() {
-                let rel_path = strip_prefix(&entry_path, &layer_dir)?;
-                self.push_chunk(rel_path, layer, None, entry_path);
+            match self.classify_entry(&entry_path)? {
+                Some(EntryKind::Dir) => self.scan_directory(&layer_dir, &entry_path, layer)?,
+                Some(EntryKind::File) => {
+                    let rel_path = strip_prefix(&entry_path, &layer_dir)?;
+                    self.push_chunk(rel_path, layer, None, entry_path);
+                }
+                None => {}
             }
         }
 
@@ -216,12 +245,11 @@ impl ProjectPacker {
         // so the directory name is preserved in the chunk path.
         let strip_base = if is_wad { dir_path } else { layer_dir };
 
-        let pattern = dir_path.join("**/*");
-        for file in glob::glob(pattern.as_str())?
-            .filter_map(Result::ok)
-            .filter(|e| e.is_file())
-        {
-            let file_path = utf8_path_from(file)?;
+        let mut files = Vec::new();
+        self.collect_files(dir_path, &mut HashSet::new(), &mut files)?;
+        files.sort();
+
+        for file_path in files {
             let rel_path = strip_prefix(&file_path, strip_base)?;
             self.push_chunk(rel_path, layer, wad_name.clone(), file_path);
         }
@@ -229,6 +257,58 @@ impl ProjectPacker {
         Ok(())
     }
 
+    /// Recursively collect the files under `dir`.
+    ///
+    /// `ancestors` holds the canonical paths of the directories being walked,
+    /// so a symlink pointing back up the tree is skipped instead of recursing
+    /// forever.
+    fn collect_files(
+        &self,
+        dir: &Utf8Path,
+        ancestors: &mut HashSet<std::path::PathBuf>,
+        files: &mut Vec<Utf8PathBuf>,
+    ) -> Result<(), PackError> {
+        let canonical = fs::canonicalize(dir)?;
+        if !ancestors.insert(canonical.clone()) {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(dir.as_std_path())? {
+            let entry_path = utf8_path_from(entry?.path())?;
+            match self.classify_entry(&entry_path)? {
+                Some(EntryKind::Dir) => self.collect_files(&entry_path, ancestors, files)?,
+                Some(EntryKind::File) => files.push(entry_path),
+                None => {}
+            }
+        }
+
+        ancestors.remove(&canonical);
+        Ok(())
+    }
+
+    /// Decide how to treat a content entry, applying the symlink policy.
+    ///
+    /// Returns `None` for entries that are skipped: `.git` files and
+    /// directories, dangling symlinks and special files.
+    fn classify_entry(&self, path: &Utf8Path) -> Result<Option<EntryKind>, PackError> {
+        if path.file_name() == Some(".git") {
+            return Ok(None);
+        }
+
+        let is_symlink = fs::symlink_metadata(path)?.file_type().is_symlink();
+        if is_symlink && !self.follow_symlinks {
+            return Err(PackError::SymlinkNotFollowed(path.to_owned()));
+        }
+
+        Ok(if path.is_dir() {
+            Some(EntryKind::Dir)
+        } else if path.is_file() {
+            Some(EntryKind::File)
+        } else {
+            None
+        })
+    }
+
     fn push_chunk(
         &mut self,
         rel_path: String,
@@ -250,6 +330,11 @@ impl ProjectPacker {
             self.readme = Some(fs::read_to_string(&readme_path)?);
         }
 
+        let changelog_path = self.project_root.join("CHANGELOG.md");
+        if changelog_path.exists() {
+            self.changelog = Some(fs::read_to_string(&changelog_path)?);
+        }
+
         let thumbnail_path = self
             .mod_project
             .thumbnail
@@ -268,7 +353,9 @@ impl ProjectPacker {
 
     /// Consume the packer and produce a configured `ModpkgBuilder` plus a map
     /// from chunk keys to source file paths.
-    fn into_builder(self) -> Result<(ModpkgBuilder, ChunkFileMap), PackError> {
+    fn into_builder(mut self) -> Result<(ModpkgBuilder, ChunkFileMap), PackError> {
+        self.scan_layers()?;
+
         let mut builder = ModpkgBuilder::default().with_layer(ModpkgLayerBuilder::base());
 
         // Layers
@@ -309,6 +396,11 @@ impl ProjectPacker {
         if let Some(readme) = &self.readme {
             builder = builder.with_readme(readme).map_err(PackError::Builder)?;
         }
+        if let Some(changelog) = &self.changelog {
+            builder = builder
+                .with_changelog(changelog)
+                .map_err(PackError::Builder)?;
+        }
         if let Some(thumbnail) = self.thumbnail {
             builder = builder
                 .with_thumbnail(thumbnail)
@@ -349,6 +441,21 @@ impl ProjectPacker {
                 .iter()
                 .map(|m| m.to_string())
                 .collect(),
+            compression: self
+                .mod_project
+                .compression
+                .iter()
+                .map(|(pattern, hint)| (pattern.clone(), convert_compression_hint(*hint)))
+                .collect(),
+            dependencies: self
+                .mod_project
+                .dependencies
+                .iter()
+                .map(|d| ModpkgDependency {
+                    name: d.name.clone(),
+                    version_req: d.version_req.clone(),
+                })
+                .collect(),
             layers: build_layer_metadata(&self.mod_project),
         })
     }
@@ -358,14 +465,40 @@ impl ProjectPacker {
 // Validation
 // ---------------------------------------------------------------------------
 
-fn validate_project(mod_project: &ModProject, project_root: &Utf8Path) -> Result<(), PackError> {
+/// Check a project the way [`ProjectPacker`] does before packing.
+///
+/// Runs [`validate_project_config`], [`validate_layer_dirs`] and
+/// [`validate_project_thumbnail`] in that order, returning the first error.
+pub fn validate_project(
+    mod_project: &ModProject,
+    project_root: &Utf8Path,
+) -> Result<(), PackError> {
+    validate_project_config(mod_project)?;
+    validate_layer_dirs(mod_project, project_root)?;
+    validate_project_thumbnail(mod_project, project_root)
+}
+
+/// Check that the project has no [`ModProject::validate`] errors.
+pub fn validate_project_config(mod_project: &ModProject) -> Result<(), PackError> {
+    let errors: Vec<String> = mod_project
+        .validate()
+        .into_iter()
+        .filter(|issue| issue.severity == ValidationSeverity::Error)
+        .map(|issue| issue.message)
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(PackError::InvalidProject(errors))
+    }
+}
+
+/// Check that every layer has a `content/<layer>` directory.
+pub fn validate_layer_dirs(
+    mod_project: &ModProject,
+    project_root: &Utf8Path,
+) -> Result<(), PackError> {
     for layer in &mod_project.layers {
-        if !is_valid_slug(&layer.name) {
-            return Err(PackError::InvalidLayerName(layer.name.clone()));
-        }
-        if layer.name == "base" && layer.priority != 0 {
-            return Err(PackError::InvalidBaseLayerPriority(layer.priority));
-        }
         let layer_dir = project_root.join("content").join(&layer.name);
         if !layer_dir.exists() {
             return Err(PackError::LayerDirMissing {
@@ -377,13 +510,34 @@ fn validate_project(mod_project: &ModProject, project_root: &Utf8Path) -> Result
     Ok(())
 }
 
-/// Check if a string is a valid slug (lowercase alphanumeric with hyphens).
-pub(super) fn is_valid_slug(s: &str) -> bool {
-    !s.is_empty()
-        && s.chars()
-            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
-        && !s.starts_with('-')
-        && !s.ends_with('-')
+/// Check that a configured thumbnail exists inside the project and decodes.
+pub fn validate_project_thumbnail(
+    mod_project: &ModProject,
+    project_root: &Utf8Path,
+) -> Result<(), PackError> {
+    match &mod_project.thumbnail {
+        Some(thumbnail) => validate_configured_thumbnail(project_root, thumbnail),
+        None => Ok(()),
+    }
+}
+
+/// Check that the configured thumbnail exists inside the project and decodes.
+fn validate_configured_thumbnail(
+    project_root: &Utf8Path,
+    thumbnail: &str,
+) -> Result<(), PackError> {
+    let thumbnail_path = project_root.join(thumbnail);
+    if !thumbnail_path.is_file() {
+        return Err(PackError::ThumbnailMissing(thumbnail_path));
+    }
+
+    let canonical_root = project_root.canonicalize_utf8()?;
+    let canonical_thumbnail = thumbnail_path.canonicalize_utf8()?;
+    if !canonical_thumbnail.starts_with(&canonical_root) {
+        return Err(PackError::ThumbnailOutsideProject(thumbnail_path));
+    }
+
+    validate_thumbnail(&thumbnail_path)
 }
 
 // ---------------------------------------------------------------------------
@@ -416,6 +570,14 @@ fn convert_license(license: Option<&ModProjectLicense>) -> crate::ModpkgLicense
     }
 }
 
+fn convert_compression_hint(hint: CompressionHint) -> String {
+    match hint {
+        CompressionHint::None => "none",
+        CompressionHint::Zstd => "zstd",
+    }
+    .to_string()
+}
+
 fn build_layer_metadata(mod_project: &ModProject) -> Vec<ModpkgLayerMetadata> {
     let mut layers = Vec::new();
 
@@ -1,9 +1,12 @@
-use super::packer::{is_valid_slug, requested_compression};
+use super::packer::requested_compression;
 use super::*;
-use crate::{Modpkg, ModpkgCompression};
+use crate::{Modpkg, ModpkgCompression, ModpkgDependency};
 use camino::{Utf8Path, Utf8PathBuf};
-use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer, ModProjectLicense};
-use std::collections::HashMap;
+use ltk_mod_project::{
+    CompressionHint, ModDependency, ModProject, ModProjectAuthor, ModProjectLayer,
+    ModProjectLicense,
+};
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{self, File};
 use std::io::Cursor;
 
@@ -26,8 +29,9 @@ fn new_rejects_invalid_layer_slug() {
 
     let err = ProjectPacker::with_mod_project(project, root.clone()).unwrap_err();
     assert!(
-        matches!(err, PackError::InvalidLayerName(ref n) if n == "UPPERCASE"),
-        "Expected InvalidLayerName, got: {err}"
+        matches!(err, PackError::InvalidProject(ref errors)
+            if errors.iter().any(|e| e.contains("'UPPERCASE'"))),
+        "Expected InvalidProject, got: {err}"
     );
 }
 
@@ -48,8 +52,9 @@ fn new_rejects_base_layer_with_wrong_priority() {
 
     let err = ProjectPacker::with_mod_project(project, root.clone()).unwrap_err();
     assert!(
-        matches!(err, PackError::InvalidBaseLayerPriority(5)),
-        "Expected InvalidBaseLayerPriority(5), got: {err}"
+        matches!(err, PackError::InvalidProject(ref errors)
+            if errors.iter().any(|e| e.contains("priority 0, got 5"))),
+        "Expected InvalidProject, got: {err}"
     );
 }
 
@@ -81,6 +86,72 @@ fn new_rejects_missing_layer_directory() {
 
 // -- packing tests ---------------------------------------------------------
 
+#[test]
+fn new_rejects_missing_configured_thumbnail() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = utf8_tempdir(&tmp);
+    fs::create_dir_all(root.join("content/base")).unwrap();
+
+    let mut project = test_mod_project(vec![ModProjectLayer::base()]);
+    project.thumbnail = Some("images/thumb.png".to_string());
+
+    let err = ProjectPacker::with_mod_project(project, root.clone()).unwrap_err();
+    assert!(
+        matches!(err, PackError::ThumbnailMissing(ref p) if p.ends_with("images/thumb.png")),
+        "Expected ThumbnailMissing, got: {err}"
+    );
+}
+
+#[test]
+fn new_rejects_corrupt_thumbnail() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = utf8_tempdir(&tmp);
+    fs::create_dir_all(root.join("content/base")).unwrap();
+    fs::write(root.join("thumb.png"), b"definitely not a png").unwrap();
+
+    let mut project = test_mod_project(vec![ModProjectLayer::base()]);
+    project.thumbnail = Some("thumb.png".to_string());
+
+    let err = ProjectPacker::with_mod_project(project, root.clone()).unwrap_err();
+    assert!(
+        matches!(err, PackError::ThumbnailError(_)),
+        "Expected ThumbnailError, got: {err}"
+    );
+}
+
+#[test]
+fn new_rejects_thumbnail_outside_project() {
+    let tmp = tempfile::tempdir().unwrap();
+    let base = utf8_tempdir(&tmp);
+    let root = base.join("project");
+    fs::create_dir_all(root.join("content/base")).unwrap();
+    fs::write(base.join("outside.png"), b"irrelevant").unwrap();
+
+    let mut project = test_mod_project(vec![ModProjectLayer::base()]);
+    project.thumbnail = Some("../outside.png".to_string());
+
+    let err = ProjectPacker::with_mod_project(project, root.clone()).unwrap_err();
+    assert!(
+        matches!(err, PackError::ThumbnailOutsideProject(_)),
+        "Expected ThumbnailOutsideProject, got: {err}"
+    );
+}
+
+#[test]
+fn new_accepts_valid_configured_thumbnail() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = utf8_tempdir(&tmp);
+    fs::create_dir_all(root.join("content/base")).unwrap();
+    image::RgbaImage::new(4, 4)
+        .save(root.join("thumb.png").as_std_path())
+        .unwrap();
+
+    let mut project = test_mod_project(vec![ModProjectLayer::base()]);
+    project.thumbnail = Some("thumb.png".to_string());
+
+    ProjectPacker::with_mod_project(project, root).unwrap();
+}
+
 #[test]
 fn new_loads_config_from_directory() {
     let tmp = tempfile::tempdir().unwrap();
@@ -105,6 +176,39 @@ fn new_loads_config_from_directory() {
     assert_eq!(modpkg.wads.len(), 1);
 }
 
+#[test]
+fn new_merges_config_fragments() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = utf8_tempdir(&tmp);
+
+    let config = r#"{
+        "name": "fragment-test",
+        "display_name": "Fragment Test",
+        "version": "1.0.0",
+        "description": "",
+        "authors": [],
+        "layers": [{"name": "base", "priority": 0}]
+    }"#;
+    fs::write(root.join("mod.config.json"), config).unwrap();
+    fs::create_dir_all(root.join("mod.config.d")).unwrap();
+    fs::write(
+        root.join("mod.config.d/chroma.json"),
+        r#"{ "layers": [{ "name": "chroma", "priority": 10 }] }"#,
+    )
+    .unwrap();
+    create_content_file(&root, "base", "X.wad.client/f.bin", b"base");
+    create_content_file(&root, "chroma", "X.wad.client/f.bin", b"chroma");
+
+    let output = root.join("build/out.modpkg");
+    ProjectPacker::new(root).unwrap().pack(&output).unwrap();
+
+    let modpkg = mount_modpkg(&output);
+    assert!(modpkg
+        .layers
+        .values()
+        .any(|layer| layer.name == "chroma" && layer.priority == 10));
+}
+
 #[test]
 fn new_returns_error_for_missing_config() {
     let tmp = tempfile::tempdir().unwrap();
@@ -254,12 +358,17 @@ fn pack_preserves_metadata() {
         description: "A cool mod".to_string(),
         authors: vec![ModProjectAuthor::Name("Alice".to_string())],
         license: Some(ModProjectLicense::Spdx("MIT".to_string())),
-        tags: vec![],
         champions: vec!["Graves".to_string()],
-        maps: vec![],
-        thumbnail: None,
+        compression: HashMap::from([
+            ("**/*.bnk".to_string(), CompressionHint::None),
+            ("**/*.tex".to_string(), CompressionHint::Zstd),
+        ]),
+        dependencies: vec![ModDependency {
+            name: "old-summoners-rift".to_string(),
+            version_req: "^1.2".to_string(),
+        }],
         layers: vec![ModProjectLayer::base()],
-        transformers: vec![],
+        ..Default::default()
     };
 
     let mut buffer = Cursor::new(Vec::new());
@@ -279,6 +388,20 @@ fn pack_preserves_metadata() {
     assert_eq!(meta.authors.len(), 1);
     assert_eq!(meta.authors[0].name, "Alice");
     assert_eq!(meta.champions, vec!["Graves"]);
+    assert_eq!(
+        meta.compression,
+        BTreeMap::from([
+            ("**/*.bnk".to_string(), "none".to_string()),
+            ("**/*.tex".to_string(), "zstd".to_string()),
+        ])
+    );
+    assert_eq!(
+        meta.dependencies,
+        vec![ModpkgDependency {
+            name: "old-summoners-rift".to_string(),
+            version_req: "^1.2".to_string(),
+        }]
+    );
 }
 
 // -- utility tests ---------------------------------------------------------
@@ -298,18 +421,6 @@ fn test_create_file_name() {
     );
 }
 
-#[test]
-fn test_is_valid_slug() {
-    assert!(is_valid_slug("base"));
-    assert!(is_valid_slug("my-layer"));
-    assert!(is_valid_slug("layer123"));
-    assert!(!is_valid_slug(""));
-    assert!(!is_valid_slug("-invalid"));
-    assert!(!is_valid_slug("invalid-"));
-    assert!(!is_valid_slug("UPPERCASE"));
-    assert!(!is_valid_slug("has spaces"));
-}
-
 #[test]
 fn test_requested_compression() {
     // Wwise audio containers are never compressed
@@ -323,6 +434,57 @@ fn test_requested_compression() {
     assert_eq!(requested_compression(None), ModpkgCompression::Zstd);
 }
 
+#[cfg(unix)]
+#[test]
+fn pack_follows_symlinked_content_unless_disabled() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = utf8_tempdir(&tmp);
+
+    // A "submodule" checkout outside content/, linked into the WAD directory.
+    let shared = root.join("shared");
+    fs::create_dir_all(shared.join("data")).unwrap();
+    fs::write(shared.join("data/skin0.bin"), b"shared").unwrap();
+    fs::write(shared.join(".git"), b"gitdir: ../.git/modules/shared").unwrap();
+
+    create_content_file(&root, "base", "Graves.wad.client/assets/tex.dds", b"dds");
+    std::os::unix::fs::symlink(&shared, root.join("content/base/Graves.wad.client/shared"))
+        .unwrap();
+    // A link back up the tree must not be walked forever.
+    std::os::unix::fs::symlink(
+        root.join("content/base/Graves.wad.client"),
+        root.join("content/base/Graves.wad.client/assets/loop"),
+    )
+    .unwrap();
+
+    let project = test_mod_project(vec![ModProjectLayer::base()]);
+
+    let err = ProjectPacker::with_mod_project(project.clone(), root.clone())
+        .unwrap()
+        .with_follow_symlinks(false)
+        .pack(&root.join("build/strict.modpkg"))
+        .unwrap_err();
+    assert!(
+        matches!(err, PackError::SymlinkNotFollowed(ref path) if path.ends_with("shared")),
+        "Expected SymlinkNotFollowed, got: {err}"
+    );
+
+    let output = root.join("build/out.modpkg");
+    ProjectPacker::with_mod_project(project, root.clone())
+        .unwrap()
+        .pack(&output)
+        .unwrap();
+
+    let modpkg = mount_modpkg(&output);
+    let mut paths: Vec<&str> = modpkg
+        .chunk_paths
+        .values()
+        .filter(|p| !p.starts_with("_meta_/"))
+        .map(String::as_str)
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec!["assets/tex.dds", "shared/data/skin0.bin"]);
+}
+
 // -- test helpers ----------------------------------------------------------
 
 fn test_mod_project(layers: Vec<ModProjectLayer>) -> ModProject {
@@ -330,15 +492,8 @@ fn test_mod_project(layers: Vec<ModProjectLayer>) -> ModProject {
         name: "test-mod".to_string(),
         display_name: "Test Mod".to_string(),
         version: "1.0.0".to_string(),
-        description: String::new(),
-        authors: vec![],
-        license: None,
-        tags: vec![],
-        champions: vec![],
-        maps: vec![],
-        thumbnail: None,
         layers,
-        transformers: vec![],
+        ..Default::default()
     }
 }
 
@@ -53,6 +53,22 @@ pub fn load_thumbnail(path: &Utf8Path) -> Result<Vec<u8>, PackError> {
     Ok(buffer.into_inner())
 }
 
+/// Check that a thumbnail file decodes as an image in a supported format.
+///
+/// This does not enforce [`MAX_THUMBNAIL_SIZE`] or convert the image; it only
+/// catches corrupt or unsupported files before packing.
+pub fn validate_thumbnail(path: &Utf8Path) -> Result<(), PackError> {
+    image::ImageReader::open(path)
+        .map_err(PackError::Io)?
+        .with_guessed_format()
+        .map_err(PackError::Io)?
+        .decode()
+        .map_err(|e| {
+            PackError::ThumbnailError(format!("Failed to decode thumbnail '{}': {}", path, e))
+        })?;
+    Ok(())
+}
+
 fn convert_gif_to_webp(path: &Utf8Path) -> Result<Vec<u8>, PackError> {
     let file = File::open(path).map_err(PackError::Io)?;
     let reader = BufReader::new(file);
@@ -61,10 +61,12 @@ impl<TSource: Read + Seek> Modpkg<TSource> {
             chunks.insert(key, chunk);
         }
 
+        let chunk_data_offset = reader.stream_position()?;
         drop(reader);
 
         Ok(Self {
             signature,
+            chunk_data_offset,
             layer_indices,
             layers,
             chunk_path_indices,
@@ -0,0 +1,206 @@
+//! Package signatures.
+//!
+//! A signed package stores a DER-encoded CMS `ContentInfo` in the signature area
+//! of its header. The signed content is a SHA-512 digest of every byte of the
+//! package except the signature itself:
+//!
+//! ```text
+//! SHA-512( header (magic, version, signature size, chunk count)
+//!        | layer, path and WAD tables | chunk TOC
+//!        | SHA-512(chunk data) )
+//! ```
+//!
+//! The chunk data is hashed on its own so the builder can stream it while the
+//! chunk TOC is still being filled in.
+
+use cms::{content_info::ContentInfo, signed_data::SignedData};
+use der::{Decode, Encode};
+use ltk_pki::pki::{sign::SignerKeys, verify::VerifyContext};
+use sha2::{Digest, Sha512};
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::{builder::ModpkgBuilderError, error::ModpkgError, Modpkg};
+
+/// Size of the fixed header fields preceding the signature.
+pub(crate) const HEADER_SIZE: u64 = 20;
+
+/// Combine the package regions into the digest that gets signed.
+pub(crate) fn package_digest(header: &[u8], tables_and_toc: &[u8], data_digest: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+    hasher.update(header);
+    hasher.update(tables_and_toc);
+    hasher.update(data_digest);
+    hasher.finalize().to_vec()
+}
+
+/// Sign `digest`, returning the DER-encoded `ContentInfo`.
+pub(crate) fn sign_digest(
+    signer: &SignerKeys,
+    digest: &[u8],
+) -> Result<Vec<u8>, ModpkgBuilderError> {
+    signer
+        .sign(digest)?
+        .to_der()
+        .map_err(ModpkgBuilderError::SignatureEncode)
+}
+
+impl<TSource: Read + Seek> Modpkg<TSource> {
+    /// Whether the package carries a signature.
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+
+    /// Decode the package's CMS signed data, or `None` if it is unsigned.
+    ///
+    /// Feed it to [`VerifyContextBuilder::add_certs_from_signed_data`] and
+    /// [`add_crls_from_signed_data`] so the certificates the package was signed
+    /// with are known to the context passed to [`verify_signature`].
+    ///
+    /// [`VerifyContextBuilder::add_certs_from_signed_data`]: ltk_pki::pki::verify::VerifyContextBuilder::add_certs_from_signed_data
+    /// [`add_crls_from_signed_data`]: ltk_pki::pki::verify::VerifyContextBuilder::add_crls_from_signed_data
+    /// [`verify_signature`]: Self::verify_signature
+    pub fn signed_data(&self) -> Result<Option<SignedData>, ModpkgError> {
+        if !self.is_signed() {
+            return Ok(None);
+        }
+        let content_info =
+            ContentInfo::from_der(&self.signature).map_err(ModpkgError::SignatureDecode)?;
+        let signed_data = content_info
+            .content
+            .decode_as::<SignedData>()
+            .map_err(ModpkgError::SignatureDecode)?;
+        Ok(Some(signed_data))
+    }
+
+    /// Check the package's signature against `ctx` and the package contents.
+    ///
+    /// Returns [`ModpkgError::Unsigned`] for unsigned packages and
+    /// [`ModpkgError::SignatureMismatch`] when the package was modified after
+    /// signing. The whole package is read to compute its digest.
+    pub fn verify_signature(&mut self, ctx: &VerifyContext) -> Result<(), ModpkgError> {
+        let signed_data = self.signed_data()?.ok_or(ModpkgError::Unsigned)?;
+        ctx.verify_signed_data(&signed_data, None)?;
+
+        let signed_digest = signed_data
+            .encap_content_info
+            .econtent
+            .as_ref()
+            .map(|content| content.value())
+            .ok_or(ModpkgError::SignatureMismatch)?;
+
+        if signed_digest != self.compute_digest()? {
+            return Err(ModpkgError::SignatureMismatch);
+        }
+        Ok(())
+    }
+
+    fn compute_digest(&mut self) -> Result<Vec<u8>, ModpkgError> {
+        let tables_offset = HEADER_SIZE + self.signature.len() as u64;
+        let source = &mut self.source;
+
+        let mut header = vec![0; HEADER_SIZE as usize];
+        source.seek(SeekFrom::Start(0))?;
+        source.read_exact(&mut header)?;
+
+        let mut tables_and_toc = vec![0; (self.chunk_data_offset - tables_offset) as usize];
+        source.seek(SeekFrom::Start(tables_offset))?;
+        source.read_exact(&mut tables_and_toc)?;
+
+        let mut data_hasher = Sha512::new();
+        io::copy(source, &mut data_hasher)?;
+
+        Ok(package_digest(
+            &header,
+            &tables_and_toc,
+            &data_hasher.finalize(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use crate::ModpkgCompression;
+    use ltk_pki::pki::verify::VerifyContextBuilder;
+    use std::io::{Cursor, Write};
+
+    /// Inside the validity window of the test certificates and CRLs.
+    const TEST_TIME: u64 = 1752525266;
+
+    fn build(signer: Option<SignerKeys>) -> Vec<u8> {
+        let mut builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::Zstd),
+            );
+        if let Some(signer) = signer {
+            builder = builder.with_signature(signer);
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |_, cursor| {
+                cursor.write_all(&[0x42; 4096])?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.into_inner()
+    }
+
+    fn test_signer() -> SignerKeys {
+        SignerKeys::from_pem_str(include_str!("../test-data/signing/signer.pem")).unwrap()
+    }
+
+    fn verify(bytes: Vec<u8>) -> Result<(), ModpkgError> {
+        let mut modpkg = Modpkg::mount_from_reader(Cursor::new(bytes))?;
+        let signed_data = modpkg.signed_data()?.ok_or(ModpkgError::Unsigned)?;
+
+        let mut ctx = VerifyContextBuilder::default();
+        ctx.with_unix_time_sec(TEST_TIME)
+            .add_anchor_der(include_bytes!("../test-data/signing/root.der"))
+            .unwrap();
+        ctx.add_certs_from_signed_data(&signed_data).unwrap();
+        ctx.add_crls_from_signed_data(&signed_data).unwrap();
+
+        modpkg.verify_signature(&ctx.finalize())
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let bytes = build(Some(test_signer()));
+
+        let modpkg = Modpkg::mount_from_reader(Cursor::new(bytes.clone())).unwrap();
+        assert!(modpkg.is_signed());
+        assert!(modpkg.has_chunk("data/skin.bin", Some("base")));
+
+        verify(bytes).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_package_fails_verification() {
+        let mut bytes = build(Some(test_signer()));
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        assert!(matches!(verify(bytes), Err(ModpkgError::SignatureMismatch)));
+    }
+
+    #[test]
+    fn test_unsigned_package() {
+        let bytes = build(None);
+
+        let mut modpkg = Modpkg::mount_from_reader(Cursor::new(bytes.clone())).unwrap();
+        assert!(!modpkg.is_signed());
+        assert!(modpkg.signed_data().unwrap().is_none());
+
+        let ctx = VerifyContextBuilder::default();
+        assert!(matches!(
+            modpkg.verify_signature(&ctx.finalize()),
+            Err(ModpkgError::Unsigned)
+        ));
+    }
+}
@@ -43,6 +43,27 @@ pub fn hash_wad_name(name: &str) -> u64 {
     xxh3::xxh3_64(name.to_lowercase().as_bytes())
 }
 
+/// Format a byte count for display, e.g. `"1.2 MiB"`.
+///
+/// Uses binary units (1 KiB = 1024 bytes) with one decimal place. Counts under
+/// 1 KiB are printed as whole bytes (`"512 B"`). A value that would round up to
+/// 1024 of a unit is shown in the next unit instead, so `1048575` is `"1.0 MiB"`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while (value * 10.0).round() >= 10240.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -86,4 +107,17 @@ mod tests {
         assert_eq!(hash_chunk_name(&forward), hash_chunk_name(&back));
         assert_eq!(hash_chunk_name(&forward), hash_chunk_name(&mixed));
     }
+
+    #[test]
+    fn format_size_boundaries() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(1024 * 1024 - 1), "1.0 MiB");
+        assert_eq!(format_size(1024 * 1024), "1.0 MiB");
+        assert_eq!(format_size(1_258_291), "1.2 MiB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GiB");
+        assert_eq!(format_size(u64::MAX), "16.0 EiB");
+    }
 }
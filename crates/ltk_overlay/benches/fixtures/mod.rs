@@ -0,0 +1,199 @@
+//! Synthetic game directories and mods for the overlay benches.
+//!
+//! Everything is generated deterministically from the given sizes so runs are
+//! comparable across machines and commits without shipping binary fixtures.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ltk_overlay::{EnabledMod, FsModContent};
+use ltk_wad::{WadBuilder, WadChunkBuilder, WadChunkCompression};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+/// Shape of a synthetic game directory.
+#[derive(Debug, Clone, Copy)]
+pub struct GameShape {
+    pub wads: usize,
+    pub chunks_per_wad: usize,
+    pub chunk_size: usize,
+}
+
+/// Shape of a synthetic mod set.
+#[derive(Debug, Clone, Copy)]
+pub struct ModShape {
+    pub mods: usize,
+    pub overrides_per_mod: usize,
+    /// Overrides per mod that introduce assets absent from the game.
+    pub new_assets_per_mod: usize,
+}
+
+/// A generated game directory under a temp root.
+pub struct SyntheticGame {
+    _root: tempfile::TempDir,
+    pub game_dir: Utf8PathBuf,
+    pub shape: GameShape,
+}
+
+impl SyntheticGame {
+    pub fn generate(shape: GameShape) -> Self {
+        let root = tempfile::tempdir().expect("create temp dir");
+        let game_dir = Utf8Path::from_path(root.path())
+            .expect("UTF-8 temp dir")
+            .join("Game");
+        let champions_dir = game_dir.join("DATA/FINAL/Champions");
+        fs::create_dir_all(&champions_dir).expect("create DATA/FINAL");
+
+        for wad in 0..shape.wads {
+            write_wad(
+                &champions_dir.join(wad_file_name(wad)),
+                wad,
+                shape.chunks_per_wad,
+                shape.chunk_size,
+            );
+        }
+
+        Self {
+            _root: root,
+            game_dir,
+            shape,
+        }
+    }
+
+    /// Absolute path of the `index`-th generated WAD.
+    pub fn wad_path(&self, index: usize) -> Utf8PathBuf {
+        self.game_dir
+            .join("DATA/FINAL/Champions")
+            .join(wad_file_name(index))
+    }
+}
+
+/// A generated set of mod project directories.
+pub struct SyntheticMods {
+    _root: tempfile::TempDir,
+    pub mod_dirs: Vec<Utf8PathBuf>,
+}
+
+impl SyntheticMods {
+    /// Generate mods whose overrides pick game chunks with a fixed-seed PRNG,
+    /// so different mods overlap on some paths the way real mod sets do.
+    pub fn generate(game: &SyntheticGame, shape: ModShape) -> Self {
+        let root = tempfile::tempdir().expect("create temp dir");
+        let root_path = Utf8Path::from_path(root.path()).expect("UTF-8 temp dir");
+        let mut rng = Lcg(0x5eed);
+
+        let mut mod_dirs = Vec::with_capacity(shape.mods);
+        for m in 0..shape.mods {
+            let mod_dir = root_path.join(format!("mod-{m}"));
+            write_mod_config(&mod_dir, m);
+
+            for _ in 0..shape.overrides_per_mod {
+                let wad = rng.below(game.shape.wads);
+                let chunk = rng.below(game.shape.chunks_per_wad);
+                write_override(
+                    &mod_dir,
+                    wad,
+                    &chunk_path(wad, chunk),
+                    m,
+                    game.shape.chunk_size,
+                );
+            }
+            for n in 0..shape.new_assets_per_mod {
+                let wad = rng.below(game.shape.wads);
+                let path = format!("data/mod{m}/new_{n}.bin");
+                write_override(&mod_dir, wad, &path, m, game.shape.chunk_size);
+            }
+
+            mod_dirs.push(mod_dir);
+        }
+
+        Self {
+            _root: root,
+            mod_dirs,
+        }
+    }
+
+    /// Fresh `EnabledMod` handles for every generated mod.
+    pub fn enabled_mods(&self) -> Vec<EnabledMod> {
+        self.mod_dirs
+            .iter()
+            .enumerate()
+            .map(|(i, dir)| EnabledMod {
+                id: format!("mod-{i}"),
+                content: Box::new(FsModContent::new(dir.clone())),
+                enabled_layers: None,
+            })
+            .collect()
+    }
+}
+
+pub fn wad_file_name(index: usize) -> String {
+    format!("Champion{index}.wad.client")
+}
+
+pub fn chunk_path(wad: usize, chunk: usize) -> String {
+    format!("data/characters/champion{wad}/asset_{chunk}.bin")
+}
+
+/// Deterministic, mildly compressible payload.
+pub fn payload(seed: usize, size: usize) -> Vec<u8> {
+    (0..size)
+        .map(|i| ((i / 16) as u8).wrapping_mul(31) ^ seed as u8)
+        .collect()
+}
+
+fn write_wad(path: &Utf8Path, wad: usize, chunks: usize, chunk_size: usize) {
+    let mut builder = WadBuilder::default();
+    for chunk in 0..chunks {
+        builder = builder.with_chunk(
+            WadChunkBuilder::default()
+                .with_path(chunk_path(wad, chunk))
+                .with_force_compression(WadChunkCompression::Zstd),
+        );
+    }
+
+    let data = payload(wad, chunk_size);
+    let mut writer = BufWriter::new(File::create(path).expect("create WAD"));
+    builder
+        .build_to_writer(&mut writer, |_hash, out| {
+            out.write_all(&data)?;
+            Ok(())
+        })
+        .expect("build synthetic WAD");
+}
+
+fn write_mod_config(mod_dir: &Utf8Path, index: usize) {
+    fs::create_dir_all(mod_dir).expect("create mod dir");
+    let project = ltk_mod_project::ModProject {
+        name: format!("bench-mod-{index}"),
+        display_name: format!("Bench Mod {index}"),
+        version: "1.0.0".to_string(),
+        layers: ltk_mod_project::default_layers(),
+        ..Default::default()
+    };
+    fs::write(
+        mod_dir.join("mod.config.json"),
+        serde_json::to_string_pretty(&project).expect("serialize mod config"),
+    )
+    .expect("write mod config");
+}
+
+fn write_override(mod_dir: &Utf8Path, wad: usize, rel_path: &str, seed: usize, size: usize) {
+    let path = mod_dir
+        .join("content/base")
+        .join(wad_file_name(wad))
+        .join(rel_path);
+    fs::create_dir_all(path.parent().expect("override has parent")).expect("create dirs");
+    fs::write(&path, payload(seed + 1000, size)).expect("write override");
+}
+
+/// Minimal linear congruential generator; good enough for fixture selection.
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n
+    }
+}
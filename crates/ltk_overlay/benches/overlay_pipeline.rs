@@ -0,0 +1,126 @@
+//! Criterion benches for the overlay build pipeline stages.
+//!
+//! Run with `cargo bench -p ltk_overlay --features bench-internals`.
+//! Fixtures are generated once per bench group into temp directories.
+
+mod fixtures;
+
+use camino::Utf8PathBuf;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use fixtures::{chunk_path, payload, GameShape, ModShape, SyntheticGame, SyntheticMods};
+use ltk_modpkg::utils::hash_chunk_name;
+use ltk_overlay::builder::bench_support::{collect_all_overrides, distribute_overrides};
+use ltk_overlay::wad_builder::build_patched_wad;
+use ltk_overlay::{GameIndex, OverlayBuilder};
+use std::collections::{HashMap, HashSet};
+
+const GAME: GameShape = GameShape {
+    wads: 32,
+    chunks_per_wad: 256,
+    chunk_size: 4 * 1024,
+};
+
+const MODS: ModShape = ModShape {
+    mods: 16,
+    overrides_per_mod: 128,
+    new_assets_per_mod: 8,
+};
+
+fn overlay_builder(game: &SyntheticGame, state: &tempfile::TempDir) -> OverlayBuilder {
+    let state_dir = Utf8PathBuf::from_path_buf(state.path().to_path_buf()).expect("UTF-8 path");
+    OverlayBuilder::new(game.game_dir.clone(), state_dir.join("overlay"), state_dir)
+}
+
+fn bench_game_index(c: &mut Criterion) {
+    let game = SyntheticGame::generate(GAME);
+
+    let mut group = c.benchmark_group("game_index");
+    group.throughput(Throughput::Elements(
+        (GAME.wads * GAME.chunks_per_wad) as u64,
+    ));
+    group.bench_function("build", |b| {
+        b.iter(|| GameIndex::build(&game.game_dir).expect("build game index"))
+    });
+    group.finish();
+}
+
+fn bench_collect_and_distribute(c: &mut Criterion) {
+    let game = SyntheticGame::generate(GAME);
+    let mods = SyntheticMods::generate(&game, MODS);
+    let game_index = GameIndex::build(&game.game_dir).expect("build game index");
+    let state = tempfile::tempdir().expect("create state dir");
+
+    let mut group = c.benchmark_group("overrides");
+    group.throughput(Throughput::Elements(
+        (MODS.mods * (MODS.overrides_per_mod + MODS.new_assets_per_mod)) as u64,
+    ));
+
+    group.bench_function("collect_all_overrides", |b| {
+        b.iter_batched(
+            || {
+                // Cold pass 1: drop the metadata cache left by the previous iteration.
+                let _ = std::fs::remove_file(state.path().join("override_meta.bin"));
+                let mut builder = overlay_builder(&game, &state);
+                builder.set_enabled_mods(mods.enabled_mods());
+                builder
+            },
+            |mut builder| collect_all_overrides(&mut builder, &game_index).expect("collect"),
+            BatchSize::PerIteration,
+        )
+    });
+
+    let _ = std::fs::remove_file(state.path().join("override_meta.bin"));
+    let mut builder = overlay_builder(&game, &state);
+    builder.set_enabled_mods(mods.enabled_mods());
+    let collected = collect_all_overrides(&mut builder, &game_index).expect("collect");
+
+    group.bench_function("distribute_overrides", |b| {
+        b.iter(|| distribute_overrides(&builder, &collected, &game_index))
+    });
+    group.finish();
+}
+
+fn bench_build_patched_wad(c: &mut Criterion) {
+    let game = SyntheticGame::generate(GAME);
+    let src = game.wad_path(0);
+    let out = tempfile::tempdir().expect("create output dir");
+    let dst =
+        Utf8PathBuf::from_path_buf(out.path().join("patched.wad.client")).expect("UTF-8 path");
+
+    // Replace every fourth chunk and add a handful of new entries.
+    let mut overrides: HashMap<u64, Vec<u8>> = (0..GAME.chunks_per_wad)
+        .step_by(4)
+        .map(|chunk| {
+            (
+                hash_chunk_name(&chunk_path(0, chunk)),
+                payload(chunk, GAME.chunk_size),
+            )
+        })
+        .collect();
+    for n in 0..16 {
+        overrides.insert(
+            hash_chunk_name(&format!("data/new/asset_{n}.bin")),
+            payload(n, GAME.chunk_size),
+        );
+    }
+    let override_hashes: HashSet<u64> = overrides.keys().copied().collect();
+
+    let mut group = c.benchmark_group("wad_builder");
+    group.throughput(Throughput::Elements(GAME.chunks_per_wad as u64));
+    group.bench_function("build_patched_wad", |b| {
+        b.iter(|| {
+            build_patched_wad(&src, &dst, &override_hashes, |hash| {
+                Ok(overrides[&hash].as_slice())
+            })
+            .expect("build patched WAD")
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = bench_game_index, bench_collect_and_distribute, bench_build_patched_wad
+}
+criterion_main!(benches);
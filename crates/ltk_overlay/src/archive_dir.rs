@@ -0,0 +1,259 @@
+//! One-shot overlay build from a directory of mod archives.
+//!
+//! A convenience facade over [`OverlayBuilder`] for scripts and tests that just
+//! want "every archive in this folder, applied in a stable order".
+
+use crate::builder::{EnabledMod, OverlayBuildResult, OverlayBuilder};
+use crate::error::{Error, Result};
+use crate::fantome_content::FantomeContent;
+use crate::modpkg_content::ModpkgContent;
+use camino::{Utf8Path, Utf8PathBuf};
+use ltk_modpkg::Modpkg;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Build an overlay from every `.modpkg` and `.fantome` file in `archives_dir`.
+///
+/// Archives are taken from the top level of `archives_dir` only and applied in
+/// filename order, so the first file (alphabetically) has the highest priority.
+/// Each mod's ID is its file name. Other files are ignored.
+///
+/// Overlay state (`overlay.json`, `game_index.bin`) is stored in `overlay_root`
+/// itself, so repeated calls with the same arguments rebuild incrementally.
+pub fn build_from_archive_dir(
+    game_dir: &Utf8Path,
+    overlay_root: &Utf8Path,
+    archives_dir: &Utf8Path,
+) -> Result<OverlayBuildResult> {
+    let mods = open_archive_dir(archives_dir)?;
+
+    let mut builder = OverlayBuilder::new(
+        game_dir.to_path_buf(),
+        overlay_root.to_path_buf(),
+        overlay_root.to_path_buf(),
+    );
+    builder.set_enabled_mods(mods);
+    builder.build()
+}
+
+/// Open every supported archive in `archives_dir`, sorted by file name.
+fn open_archive_dir(archives_dir: &Utf8Path) -> Result<Vec<EnabledMod>> {
+    let mut paths: Vec<Utf8PathBuf> = Vec::new();
+    for entry in archives_dir.read_dir_utf8()? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && archive_kind(entry.path()).is_some() {
+            paths.push(entry.into_path());
+        }
+    }
+    paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+    paths.iter().map(|path| open_archive(path)).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Modpkg,
+    Fantome,
+}
+
+fn archive_kind(path: &Utf8Path) -> Option<ArchiveKind> {
+    let ext = path.extension()?;
+    if ext.eq_ignore_ascii_case("modpkg") {
+        Some(ArchiveKind::Modpkg)
+    } else if ext.eq_ignore_ascii_case("fantome") {
+        Some(ArchiveKind::Fantome)
+    } else {
+        None
+    }
+}
+
+fn open_archive(path: &Utf8Path) -> Result<EnabledMod> {
+    let id = path.file_name().unwrap_or(path.as_str()).to_string();
+    let reader = BufReader::new(File::open(path.as_std_path())?);
+
+    let content: Box<dyn crate::ModContentProvider> = match archive_kind(path) {
+        Some(ArchiveKind::Modpkg) => {
+            let modpkg = Modpkg::mount_from_reader(reader)
+                .map_err(|e| Error::Other(format!("Failed to mount modpkg '{}': {}", path, e)))?;
+            Box::new(ModpkgContent::new(modpkg).with_archive_path(path.to_path_buf()))
+        }
+        Some(ArchiveKind::Fantome) => {
+            Box::new(FantomeContent::new(reader)?.with_archive_path(path.to_path_buf()))
+        }
+        None => return Err(Error::Other(format!("Unsupported archive: {}", path))),
+    };
+
+    Ok(EnabledMod {
+        id,
+        content,
+        enabled_layers: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::RebuildReason;
+    use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+    use ltk_modpkg::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use std::io::Write;
+
+    const CHUNK_PATH: &str = "data/characters/test/skin0.bin";
+
+    /// Write a game with a single WAD and return its `Game` directory.
+    fn write_game(root: &Utf8Path) -> Utf8PathBuf {
+        let spec = FakeGameSpec::new().with_wad(
+            FakeWad::new("Champions/Test.wad.client").with_path(CHUNK_PATH, b"original".to_vec()),
+        );
+        make_fake_game_dir(root, &spec).unwrap()
+    }
+
+    fn write_modpkg(path: &Utf8Path, data: &'static [u8]) {
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path(CHUNK_PATH)
+                    .unwrap()
+                    .with_layer("base")
+                    .with_wad("Test.wad.client"),
+            );
+        let mut file = File::create(path).unwrap();
+        builder
+            .build_to_writer(&mut file, |_chunk, out| {
+                out.write_all(data)?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn write_fantome(path: &Utf8Path) {
+        let info = serde_json::to_vec(&ltk_fantome::FantomeInfo {
+            name: "Fantome Mod".to_string(),
+            author: "Author".to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            tags: Vec::new(),
+            champions: Vec::new(),
+            maps: Vec::new(),
+            dependencies: Vec::new(),
+            layers: std::collections::HashMap::new(),
+        })
+        .unwrap();
+
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("META/info.json", options).unwrap();
+        zip.write_all(&info).unwrap();
+        zip.start_file(format!("WAD/Test.wad.client/{CHUNK_PATH}"), options)
+            .unwrap();
+        zip.write_all(b"fantome").unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn open_archive_dir_sorts_by_file_name_and_skips_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        write_modpkg(&root.join("b.modpkg"), b"b");
+        write_fantome(&root.join("a.FANTOME"));
+        write_modpkg(&root.join("c.modpkg"), b"c");
+        std::fs::write(root.join("notes.txt"), b"ignored").unwrap();
+        std::fs::create_dir(root.join("d.modpkg")).unwrap();
+
+        let ids: Vec<String> = open_archive_dir(root)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(ids, vec!["a.FANTOME", "b.modpkg", "c.modpkg"]);
+    }
+
+    #[test]
+    fn build_from_archive_dir_applies_first_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let overlay_root = root.join("overlay");
+        let archives = root.join("mods");
+        std::fs::create_dir_all(&archives).unwrap();
+
+        let game_dir = write_game(root);
+        write_modpkg(&archives.join("a.modpkg"), b"winner");
+        write_fantome(&archives.join("b.fantome"));
+
+        let result = build_from_archive_dir(&game_dir, &overlay_root, &archives).unwrap();
+        assert_eq!(result.wads_built.len(), 1);
+
+        let bytes = std::fs::read(&result.wads_built[0]).unwrap();
+        let mut wad = ltk_wad::Wad::mount(std::io::Cursor::new(bytes)).unwrap();
+        let chunk = *wad
+            .chunks()
+            .get(ltk_modpkg::utils::hash_chunk_name(CHUNK_PATH))
+            .unwrap();
+        assert_eq!(&*wad.load_chunk_decompressed(&chunk).unwrap(), b"winner");
+    }
+
+    #[test]
+    fn build_from_archive_dir_accepts_install_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let archives = root.join("mods");
+        std::fs::create_dir_all(&archives).unwrap();
+
+        write_game(root);
+        write_modpkg(&archives.join("a.modpkg"), b"modded");
+
+        let result = build_from_archive_dir(root, &root.join("overlay"), &archives).unwrap();
+        assert_eq!(result.wads_built.len(), 1);
+        assert!(result.wads_built[0].ends_with("DATA/FINAL/Champions/Test.wad.client"));
+    }
+
+    #[test]
+    fn built_overlay_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let overlay_root = root.join("overlay");
+        let archives = root.join("mods");
+        std::fs::create_dir_all(&archives).unwrap();
+
+        let game_dir = write_game(root);
+        write_modpkg(&archives.join("a.modpkg"), b"modded");
+        build_from_archive_dir(&game_dir, &overlay_root, &archives).unwrap();
+
+        let report = crate::validate(&overlay_root).unwrap();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.wads_checked, 1);
+    }
+
+    #[test]
+    fn repeated_builds_report_rebuild_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let overlay_root = root.join("overlay");
+        let archives = root.join("mods");
+        std::fs::create_dir_all(&archives).unwrap();
+
+        let game_dir = write_game(root);
+        write_modpkg(&archives.join("a.modpkg"), b"modded");
+
+        let build = || build_from_archive_dir(&game_dir, &overlay_root, &archives).unwrap();
+        assert_eq!(
+            build().rebuild_reason,
+            RebuildReason::FullRebuildNoPreviousState
+        );
+        assert_eq!(build().rebuild_reason, RebuildReason::ExactMatchSkipped);
+
+        let state_path = overlay_root.join("overlay.json");
+        let mut state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+        state["version"] = 0.into();
+        std::fs::write(&state_path, state.to_string()).unwrap();
+        assert_eq!(
+            build().rebuild_reason,
+            RebuildReason::FullRebuildVersionBump
+        );
+
+        std::fs::remove_file(archives.join("a.modpkg")).unwrap();
+        assert_eq!(build().rebuild_reason, RebuildReason::EmptyModList);
+    }
+}
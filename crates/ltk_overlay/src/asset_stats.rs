@@ -0,0 +1,292 @@
+//! Asset composition of a mod package or a built overlay, by file extension.
+//!
+//! Useful for analytics and for spotting wrong-format assets, e.g. a mod that
+//! ships `.dds` textures where the game expects `.tex`.
+
+use crate::backup::collect_overlay_wad_paths;
+use crate::error::{Error, Result};
+use crate::state::load_output_extension;
+use crate::utils::parse_hex_chunk_name;
+use camino::Utf8Path;
+use ltk_file::{LeagueFileKind, MAX_MAGIC_SIZE};
+use ltk_modpkg::{Modpkg, METADATA_FOLDER_NAME};
+use ltk_wad::{decompress_raw, Wad, WadChunk, WadChunkCompression};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+
+/// Key used for chunks whose type could not be determined.
+pub const UNKNOWN_EXTENSION: &str = "unknown";
+
+/// How many chunks share an extension and how large they are in total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ExtensionStats {
+    pub count: usize,
+    /// Sum of the chunks' uncompressed sizes.
+    pub total_bytes: u64,
+}
+
+/// Lowercased extension (without the dot) -> stats.
+pub type ExtensionReport = BTreeMap<String, ExtensionStats>;
+
+fn record(report: &mut ExtensionReport, extension: &str, size: u64) {
+    let stats = report.entry(extension.to_string()).or_default();
+    stats.count += 1;
+    stats.total_bytes += size;
+}
+
+fn sniffed_extension(data: &[u8]) -> &'static str {
+    LeagueFileKind::identify_from_bytes(data)
+        .extension()
+        .unwrap_or(UNKNOWN_EXTENSION)
+}
+
+/// The first [`MAX_MAGIC_SIZE`] bytes of `chunk`'s uncompressed data, enough
+/// to identify its type.
+///
+/// Stored and Zstd chunks are only read and decompressed that far. Other
+/// compressions are decompressed in full.
+fn chunk_prefix<R: BufRead + Seek>(source: &mut R, chunk: &WadChunk) -> Result<Vec<u8>> {
+    source.seek(SeekFrom::Start(chunk.data_offset as u64))?;
+    let mut raw = source.take(chunk.compressed_size as u64);
+
+    let mut prefix = Vec::with_capacity(MAX_MAGIC_SIZE);
+    match chunk.compression_type {
+        WadChunkCompression::None => {
+            raw.take(MAX_MAGIC_SIZE as u64).read_to_end(&mut prefix)?;
+        }
+        WadChunkCompression::Zstd => {
+            zstd::stream::read::Decoder::with_buffer(raw)?
+                .take(MAX_MAGIC_SIZE as u64)
+                .read_to_end(&mut prefix)?;
+        }
+        compression => {
+            let mut compressed = Vec::with_capacity(chunk.compressed_size);
+            raw.read_to_end(&mut compressed)?;
+            let data = decompress_raw(&compressed, compression, chunk.uncompressed_size)?;
+            prefix.extend_from_slice(&data[..data.len().min(MAX_MAGIC_SIZE)]);
+        }
+    }
+    Ok(prefix)
+}
+
+/// Tally the content chunks of a mod package by extension.
+///
+/// Extensions come from each chunk's resolved path. Chunks stored under a
+/// hex-hash name (or with no extension) are decompressed and identified by
+/// their magic bytes instead. Meta chunks (`_meta_/`) are not counted, and a
+/// chunk present in several layers is counted once per layer.
+pub fn modpkg_extension_stats<TSource: Read + Seek>(
+    modpkg: &mut Modpkg<TSource>,
+) -> Result<ExtensionReport> {
+    let meta_prefix = format!("{}/", METADATA_FOLDER_NAME);
+    let mut report = ExtensionReport::new();
+
+    let chunks: Vec<_> = modpkg.chunks.values().copied().collect();
+    for chunk in chunks {
+        // Look the path up by index: a hex-named chunk's path hash is the parsed
+        // hex value, not the hash of its stored path string.
+        let Some(path) = modpkg
+            .chunk_path_indices
+            .get(chunk.path_index as usize)
+            .and_then(|path_key| modpkg.chunk_paths.get(path_key))
+            .cloned()
+        else {
+            continue;
+        };
+        if path.starts_with(&meta_prefix) {
+            continue;
+        }
+
+        let path = Utf8Path::new(&path);
+        let named_extension = path
+            .extension()
+            .filter(|_| parse_hex_chunk_name(path).is_none())
+            .map(str::to_ascii_lowercase);
+
+        let extension = match named_extension {
+            Some(extension) => extension,
+            None => {
+                let data = modpkg
+                    .load_chunk_decompressed(&chunk)
+                    .map_err(|e| Error::Other(format!("Failed to load chunk '{}': {}", path, e)))?;
+                sniffed_extension(&data).to_string()
+            }
+        };
+        record(&mut report, &extension, chunk.uncompressed_size);
+    }
+
+    Ok(report)
+}
+
+/// Tally every chunk of every WAD in a built overlay by extension.
+///
+/// Overlay WADs store only path hashes, so each chunk is identified by its
+/// magic bytes, decompressing no more of it than needed. Note that patched WADs hold the game's own
+/// chunks as well as the mods' overrides, so this describes the whole overlay.
+///
+/// The overlay WADs' extension is read from the `overlay.json` in
+/// `overlay_root`; see [`overlay_extension_stats_with_state_dir`] for overlays
+/// built with a separate state directory.
+pub fn overlay_extension_stats(overlay_root: &Utf8Path) -> Result<ExtensionReport> {
+    overlay_extension_stats_with_state_dir(overlay_root, overlay_root)
+}
+
+/// Like [`overlay_extension_stats`], reading `overlay.json` from `state_dir`.
+pub fn overlay_extension_stats_with_state_dir(
+    overlay_root: &Utf8Path,
+    state_dir: &Utf8Path,
+) -> Result<ExtensionReport> {
+    let output_extension = load_output_extension(state_dir)?;
+    let wad_paths = collect_overlay_wad_paths(overlay_root, &output_extension)?;
+
+    let per_wad: Vec<ExtensionReport> = wad_paths
+        .par_iter()
+        .map(|path| {
+            let file = File::open(path.as_std_path())?;
+            let (mut source, chunks) = Wad::mount(BufReader::new(file))?.into_parts();
+
+            let mut report = ExtensionReport::new();
+            for chunk in &chunks {
+                let prefix = chunk_prefix(&mut source, chunk)?;
+                record(
+                    &mut report,
+                    sniffed_extension(&prefix),
+                    chunk.uncompressed_size as u64,
+                );
+            }
+            Ok(report)
+        })
+        .collect::<Result<_>>()?;
+
+    let mut report = ExtensionReport::new();
+    for wad_report in per_wad {
+        for (extension, stats) in wad_report {
+            let total = report.entry(extension).or_default();
+            total.count += stats.count;
+            total.total_bytes += stats.total_bytes;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+    use ltk_modpkg::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use std::io::{Cursor, Write};
+
+    /// Minimal payloads `LeagueFileKind::identify_from_bytes` recognizes.
+    const TEX: &[u8] = b"TEX\0 texture";
+    const BIN: &[u8] = b"PROP property bin";
+
+    #[test]
+    fn modpkg_stats_use_paths_and_sniff_hex_names() {
+        let chunk = |path: &str| {
+            ModpkgChunkBuilder::new()
+                .with_path(path)
+                .unwrap()
+                .with_layer("base")
+        };
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_readme("# readme")
+            .unwrap()
+            .with_chunk(chunk("assets/a.DDS"))
+            .with_chunk(chunk("assets/b.dds"))
+            .with_chunk(chunk("data/skin0.bin"))
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_hashed_chunk_name("0123456789abcdef.dds")
+                    .unwrap()
+                    .with_layer("base"),
+            );
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |chunk, out| {
+                let data: &[u8] = match chunk.path.as_str() {
+                    "0123456789abcdef.dds" => TEX,
+                    "data/skin0.bin" => BIN,
+                    _ => b"dds!",
+                };
+                out.write_all(data)?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+
+        let report = modpkg_extension_stats(&mut modpkg).unwrap();
+        let summary: Vec<(&str, usize, u64)> = report
+            .iter()
+            .map(|(ext, s)| (ext.as_str(), s.count, s.total_bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("bin", 1, BIN.len() as u64),
+                ("dds", 2, 8),
+                ("tex", 1, TEX.len() as u64),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlay_stats_sniff_every_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let spec = FakeGameSpec::new()
+            .with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_chunk(1, TEX.to_vec())
+                    .with_chunk(2, b"????".to_vec()),
+            )
+            .with_wad(FakeWad::new("Champions/B.wad.client").with_chunk(3, TEX.to_vec()));
+        let game_dir = make_fake_game_dir(root, &spec).unwrap();
+
+        let report = overlay_extension_stats(&game_dir).unwrap();
+        assert_eq!(
+            report["tex"],
+            ExtensionStats {
+                count: 2,
+                total_bytes: 2 * TEX.len() as u64,
+            }
+        );
+        assert_eq!(report[UNKNOWN_EXTENSION].count, 1);
+    }
+
+    #[test]
+    fn chunk_prefix_decompresses_only_the_magic() {
+        use ltk_wad::{WadBuilder, WadChunkBuilder};
+
+        let payload: Vec<u8> = BIN.iter().copied().cycle().take(1 << 20).collect();
+        let mut cursor = Cursor::new(Vec::new());
+        WadBuilder::default()
+            .with_chunk(
+                WadChunkBuilder::default()
+                    .with_path("data/zstd.bin")
+                    .with_force_compression(WadChunkCompression::Zstd),
+            )
+            .with_chunk(
+                WadChunkBuilder::default()
+                    .with_path("data/stored.bin")
+                    .with_force_compression(WadChunkCompression::None),
+            )
+            .build_to_writer(&mut cursor, |_, out| {
+                out.write_all(&payload)?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+
+        let (mut source, chunks) = Wad::mount(cursor).unwrap().into_parts();
+        for chunk in &chunks {
+            let prefix = chunk_prefix(&mut source, chunk).unwrap();
+            assert_eq!(prefix, &payload[..MAX_MAGIC_SIZE]);
+            assert_eq!(sniffed_extension(&prefix), "bin");
+        }
+    }
+}
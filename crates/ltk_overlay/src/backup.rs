@@ -0,0 +1,172 @@
+//! Backups of replaced overlay WADs, for rolling back a bad build.
+//!
+//! With [`OverlayBuilder::with_backup`](crate::OverlayBuilder::with_backup)
+//! enabled, a build moves every overlay WAD it is about to overwrite or delete
+//! into `overlay_root/.backup/<relpath>` and records the WADs it creates from
+//! scratch. [`rollback`] restores that previous overlay. Only the backup of the
+//! most recent build that changed the overlay is kept.
+
+use crate::builder::STAGING_DIR_NAME;
+use crate::error::{Error, Result};
+use crate::game_index::collect_paths_with_suffix_sorted;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// Directory inside the overlay root that holds the previous build's WADs.
+pub const BACKUP_DIR_NAME: &str = ".backup";
+
+/// Manifest stored inside [`BACKUP_DIR_NAME`].
+const MANIFEST_FILE_NAME: &str = "backup.json";
+
+/// Left in the overlay root by [`rollback`] so the next build knows the
+/// persisted overlay state no longer describes the WADs on disk.
+const ROLLED_BACK_MARKER: &str = ".rolled-back";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BackupManifest {
+    /// WADs (relative to the overlay root) the build created with no previous
+    /// version to back up. Rolling back deletes them.
+    added: Vec<String>,
+}
+
+/// Backup of the WADs one build replaces.
+///
+/// The previous backup is pruned the first time a WAD is preserved, so a build
+/// that leaves every WAD alone keeps the older backup.
+pub(crate) struct WadBackup {
+    overlay_root: Utf8PathBuf,
+    manifest: BackupManifest,
+    started: bool,
+}
+
+impl WadBackup {
+    pub(crate) fn new(overlay_root: &Utf8Path) -> Self {
+        Self {
+            overlay_root: overlay_root.to_path_buf(),
+            manifest: BackupManifest::default(),
+            started: false,
+        }
+    }
+
+    /// Move the overlay WAD at `rel_path` into the backup, or record it as
+    /// added when there is none, before the build overwrites or deletes it.
+    ///
+    /// The manifest is kept up to date as WADs are preserved, so a build that
+    /// fails partway through can still be rolled back.
+    pub(crate) fn preserve(&mut self, rel_path: &Utf8Path) -> Result<()> {
+        let backup_dir = self.overlay_root.join(BACKUP_DIR_NAME);
+        if !self.started {
+            if backup_dir.as_std_path().exists() {
+                std::fs::remove_dir_all(backup_dir.as_std_path())?;
+            }
+            std::fs::create_dir_all(backup_dir.as_std_path())?;
+            self.started = true;
+            self.save_manifest()?;
+        }
+
+        let current = self.overlay_root.join(rel_path);
+        if !current.as_std_path().is_file() {
+            self.manifest
+                .added
+                .push(rel_path.as_str().replace('\\', "/"));
+            return self.save_manifest();
+        }
+
+        let backed_up = backup_dir.join(rel_path);
+        if let Some(parent) = backed_up.parent() {
+            std::fs::create_dir_all(parent.as_std_path())?;
+        }
+        tracing::debug!("Backing up overlay WAD: {}", rel_path);
+        std::fs::rename(current.as_std_path(), backed_up.as_std_path())?;
+        Ok(())
+    }
+
+    fn save_manifest(&self) -> Result<()> {
+        let manifest_path = self
+            .overlay_root
+            .join(BACKUP_DIR_NAME)
+            .join(MANIFEST_FILE_NAME);
+        std::fs::write(
+            manifest_path.as_std_path(),
+            serde_json::to_vec_pretty(&self.manifest)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// Restore the overlay WADs replaced by the most recent build.
+///
+/// WADs that build created are deleted and the backed-up WADs are moved back
+/// into place; the backup is consumed. The next build then rebuilds the overlay
+/// in full, since the persisted overlay state describes the undone build.
+///
+/// Returns [`Error::NoBackup`] if the overlay has no backup.
+pub fn rollback(overlay_root: &Utf8Path) -> Result<()> {
+    let backup_dir = overlay_root.join(BACKUP_DIR_NAME);
+    let manifest_path = backup_dir.join(MANIFEST_FILE_NAME);
+    if !manifest_path.as_std_path().is_file() {
+        return Err(Error::NoBackup(overlay_root.to_path_buf()));
+    }
+    let manifest: BackupManifest =
+        serde_json::from_slice(&std::fs::read(manifest_path.as_std_path())?)?;
+
+    for rel_path in &manifest.added {
+        let added = overlay_root.join(rel_path);
+        if added.as_std_path().exists() {
+            tracing::info!("Rollback: removing {}", rel_path);
+            std::fs::remove_file(added.as_std_path())?;
+        }
+    }
+
+    // Every file but the manifest is a backed-up WAD, whatever its extension
+    for backed_up in collect_paths_with_suffix_sorted(&backup_dir, "")?
+        .into_iter()
+        .filter(|path| *path != manifest_path)
+    {
+        let rel_path = backed_up
+            .strip_prefix(&backup_dir)
+            .expect("backup WADs live under the backup dir");
+        let restored = overlay_root.join(rel_path);
+        if let Some(parent) = restored.parent() {
+            std::fs::create_dir_all(parent.as_std_path())?;
+        }
+        tracing::info!("Rollback: restoring {}", rel_path);
+        std::fs::rename(backed_up.as_std_path(), restored.as_std_path())?;
+    }
+
+    std::fs::remove_dir_all(backup_dir.as_std_path())?;
+    std::fs::write(overlay_root.join(ROLLED_BACK_MARKER).as_std_path(), b"")?;
+    Ok(())
+}
+
+/// Whether [`rollback`] ran since the last build.
+pub(crate) fn has_rollback_marker(overlay_root: &Utf8Path) -> bool {
+    overlay_root.join(ROLLED_BACK_MARKER).as_std_path().exists()
+}
+
+/// Remove the marker left by [`rollback`], returning whether it was present.
+pub(crate) fn take_rollback_marker(overlay_root: &Utf8Path) -> Result<bool> {
+    let marker = overlay_root.join(ROLLED_BACK_MARKER);
+    if !marker.as_std_path().exists() {
+        return Ok(false);
+    }
+    std::fs::remove_file(marker.as_std_path())?;
+    Ok(true)
+}
+
+/// All overlay WADs written with `output_extension` (see
+/// [`OverlayBuilder::with_output_extension`](crate::OverlayBuilder::with_output_extension)),
+/// excluding the backup and any staged build, sorted.
+pub(crate) fn collect_overlay_wad_paths(
+    overlay_root: &Utf8Path,
+    output_extension: &str,
+) -> Result<Vec<Utf8PathBuf>> {
+    let backup_dir = overlay_root.join(BACKUP_DIR_NAME);
+    let staging_dir = overlay_root.join(STAGING_DIR_NAME);
+    Ok(
+        collect_paths_with_suffix_sorted(overlay_root, output_extension)?
+            .into_iter()
+            .filter(|path| !path.starts_with(&backup_dir) && !path.starts_with(&staging_dir))
+            .collect(),
+    )
+}
@@ -0,0 +1,44 @@
+//! Thin public wrappers around internal build stages, for the criterion benches.
+//!
+//! Only compiled with the `bench-internals` feature. Not part of the stable API.
+
+use super::*;
+
+/// Pass 1 output, kept opaque so [`OverrideMeta`] stays crate-private.
+pub struct CollectedOverrides {
+    meta: HashMap<u64, OverrideMeta>,
+}
+
+impl CollectedOverrides {
+    /// Number of unique override path hashes collected.
+    pub fn len(&self) -> usize {
+        self.meta.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.meta.is_empty()
+    }
+}
+
+/// Run pass 1 (override metadata collection) for the builder's enabled mods.
+///
+/// Honors the persistent metadata cache in `state_dir`; delete
+/// `override_meta.bin` beforehand to measure a cold collection.
+pub fn collect_all_overrides(
+    builder: &mut OverlayBuilder,
+    game_index: &GameIndex,
+) -> Result<CollectedOverrides> {
+    let collection = builder.collect_all_override_metadata(game_index)?;
+    Ok(CollectedOverrides {
+        meta: collection.all_meta,
+    })
+}
+
+/// Route collected overrides to game WADs (relative WAD path -> path hashes).
+pub fn distribute_overrides(
+    builder: &OverlayBuilder,
+    overrides: &CollectedOverrides,
+    game_index: &GameIndex,
+) -> BTreeMap<Utf8PathBuf, HashSet<u64>> {
+    builder.distribute_override_hashes(&overrides.meta, game_index)
+}
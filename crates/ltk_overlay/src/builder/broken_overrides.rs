@@ -0,0 +1,143 @@
+//! Detection of override files that hold no real asset data.
+//!
+//! A failed download leaves a zero-byte file, and a repository cloned without
+//! `git lfs pull` leaves a small text pointer in place of every large asset.
+//! Both hash like any other file, so without this check they would be injected
+//! and replace the game's asset with garbage.
+
+use super::*;
+
+/// First line of every git LFS pointer file.
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/";
+
+/// LFS pointers are three short lines of text; anything larger is real content.
+const MAX_LFS_POINTER_SIZE: usize = 1024;
+
+/// Why a [`BrokenOverride`] was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BrokenOverrideKind {
+    /// The file is zero bytes long.
+    Empty,
+    /// The file is a git LFS pointer instead of the asset it points to.
+    LfsPointer,
+}
+
+/// An override file that was skipped because it holds no asset data.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenOverride {
+    /// Mod identifier (matches [`EnabledMod::id`]).
+    pub mod_id: String,
+    /// Layer and WAD directory the file was read from; `None` for RAW overrides.
+    pub source_wad: Option<(String, String)>,
+    /// Path the override was read from, relative to its WAD directory (or the
+    /// content directory for RAW overrides).
+    pub rel_path: Utf8PathBuf,
+    /// What is wrong with the file.
+    pub kind: BrokenOverrideKind,
+}
+
+/// A problem found during a build that did not stop it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildWarning {
+    /// An override file was skipped instead of being injected.
+    BrokenOverride(BrokenOverride),
+    /// A mod was built although its signature is missing or does not verify
+    /// (see [`SignaturePolicy::Warn`](super::SignaturePolicy::Warn)).
+    UntrustedMod(super::ModSignature),
+}
+
+impl std::fmt::Display for BuildWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildWarning::BrokenOverride(broken) => {
+                let reason = match broken.kind {
+                    BrokenOverrideKind::Empty => "is empty",
+                    BrokenOverrideKind::LfsPointer => "is a git LFS pointer, not the asset",
+                };
+                match &broken.source_wad {
+                    Some((layer, wad_name)) => write!(
+                        f,
+                        "mod '{}' override '{}' (layer '{}', WAD '{}') {}; skipped",
+                        broken.mod_id, broken.rel_path, layer, wad_name, reason
+                    ),
+                    None => write!(
+                        f,
+                        "mod '{}' override '{}' {}; skipped",
+                        broken.mod_id, broken.rel_path, reason
+                    ),
+                }
+            }
+            BuildWarning::UntrustedMod(signature) => write!(
+                f,
+                "mod '{}' is untrusted ({})",
+                signature.mod_id, signature.status
+            ),
+        }
+    }
+}
+
+/// Check whether `bytes` can be an asset at all.
+pub(crate) fn detect_broken_override(bytes: &[u8]) -> Option<BrokenOverrideKind> {
+    if bytes.is_empty() {
+        Some(BrokenOverrideKind::Empty)
+    } else if bytes.len() <= MAX_LFS_POINTER_SIZE && bytes.starts_with(LFS_POINTER_PREFIX) {
+        Some(BrokenOverrideKind::LfsPointer)
+    } else {
+        None
+    }
+}
+
+/// Remove the broken files from `files`, recording each one in `broken`.
+///
+/// `source_wad` is the `(layer, WAD directory)` the files were read from, or
+/// `None` for RAW overrides.
+pub(crate) fn take_broken_overrides(
+    mod_id: &str,
+    source_wad: Option<(&str, &str)>,
+    files: Vec<(Utf8PathBuf, Vec<u8>)>,
+    broken: &mut Vec<BrokenOverride>,
+) -> Vec<(Utf8PathBuf, Vec<u8>)> {
+    files
+        .into_iter()
+        .filter(|(rel_path, bytes)| {
+            let Some(kind) = detect_broken_override(bytes) else {
+                return true;
+            };
+            let broken_override = BrokenOverride {
+                mod_id: mod_id.to_string(),
+                source_wad: source_wad.map(|(l, w)| (l.to_string(), w.to_string())),
+                rel_path: rel_path.clone(),
+                kind,
+            };
+            tracing::warn!("{}", BuildWarning::BrokenOverride(broken_override.clone()));
+            broken.push(broken_override);
+            false
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_empty_files_and_lfs_pointers() {
+        let pointer = b"version https://git-lfs.github.com/spec/v1\n\
+            oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+            size 12345\n";
+        assert_eq!(detect_broken_override(b""), Some(BrokenOverrideKind::Empty));
+        assert_eq!(
+            detect_broken_override(pointer),
+            Some(BrokenOverrideKind::LfsPointer)
+        );
+        assert_eq!(detect_broken_override(b"PROP\x02\x00\x00\x00"), None);
+
+        // A real asset that happens to start like a pointer is too large to be one.
+        let mut large = pointer.to_vec();
+        large.resize(MAX_LFS_POINTER_SIZE + 1, b' ');
+        assert_eq!(detect_broken_override(&large), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! Per-mod compression hints from [`ModProject::compression`].
+//!
+//! By default the WAD patcher picks each override's compression from its file
+//! type. A mod can pin it instead by mapping glob patterns to a
+//! [`CompressionHint`]. Patterns are matched case-insensitively against the
+//! override's source path: the path inside the WAD directory for layer
+//! overrides, or the asset path for RAW overrides.
+
+use super::*;
+use glob::{MatchOptions, Pattern};
+use ltk_mod_project::{CompressionHint, ModProject};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A mod's compiled compression hints, most specific (longest) pattern first.
+pub(crate) struct CompressionRules {
+    rules: Vec<(Pattern, WadChunkCompression)>,
+}
+
+impl CompressionRules {
+    /// Compile the hints declared in `project`. Invalid patterns are skipped with a warning.
+    pub(crate) fn from_project(mod_id: &str, project: &ModProject) -> Self {
+        let mut hints: Vec<(&String, CompressionHint)> = project
+            .compression
+            .iter()
+            .map(|(pattern, hint)| (pattern, *hint))
+            .collect();
+        hints.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(b.0)));
+
+        let rules = hints
+            .into_iter()
+            .filter_map(|(pattern, hint)| match Pattern::new(pattern) {
+                Ok(compiled) => Some((compiled, wad_compression(hint))),
+                Err(e) => {
+                    tracing::warn!(
+                        "Mod='{}' ignoring invalid compression pattern '{}': {}",
+                        mod_id,
+                        pattern,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// The compression pinned for an override read from `rel_path`, if any.
+    pub(crate) fn resolve(&self, rel_path: &Utf8Path) -> Option<WadChunkCompression> {
+        let path = rel_path.as_str().replace('\\', "/");
+        self.rules
+            .iter()
+            .find(|(pattern, _)| pattern.matches_with(&path, MATCH_OPTIONS))
+            .map(|(_, compression)| *compression)
+    }
+
+    /// Set [`OverrideMeta::compression`] on every entry of a mod's metadata.
+    pub(crate) fn apply(&self, mod_meta: &mut HashMap<u64, OverrideMeta>) {
+        for meta in mod_meta.values_mut() {
+            meta.compression = self.resolve(meta.source.rel_path());
+        }
+    }
+}
+
+fn wad_compression(hint: CompressionHint) -> WadChunkCompression {
+    match hint {
+        CompressionHint::None => WadChunkCompression::None,
+        CompressionHint::Zstd => WadChunkCompression::Zstd,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(hints: &[(&str, CompressionHint)]) -> CompressionRules {
+        let mut project = crate::testing::mock_project("mock");
+        project.compression = hints
+            .iter()
+            .map(|(pattern, hint)| (pattern.to_string(), *hint))
+            .collect();
+        CompressionRules::from_project("mock", &project)
+    }
+
+    #[test]
+    fn matches_globs_against_source_path() {
+        let rules = rules(&[
+            ("**/*.bnk", CompressionHint::None),
+            ("**/*.tex", CompressionHint::Zstd),
+        ]);
+
+        assert_eq!(
+            rules.resolve(Utf8Path::new("assets/sounds/wwise2016/vo/aatrox.BNK")),
+            Some(WadChunkCompression::None)
+        );
+        assert_eq!(
+            rules.resolve(Utf8Path::new("skin.tex")),
+            Some(WadChunkCompression::Zstd)
+        );
+        assert_eq!(rules.resolve(Utf8Path::new("data/skin0.bin")), None);
+    }
+
+    #[test]
+    fn longest_pattern_wins_and_invalid_patterns_are_skipped() {
+        let rules = rules(&[
+            ("**/*.wpk", CompressionHint::None),
+            ("assets/music/**/*.wpk", CompressionHint::Zstd),
+            ("[", CompressionHint::Zstd),
+        ]);
+
+        assert_eq!(
+            rules.resolve(Utf8Path::new("assets/music/login/theme.wpk")),
+            Some(WadChunkCompression::Zstd)
+        );
+        assert_eq!(
+            rules.resolve(Utf8Path::new("assets/sounds/vo.wpk")),
+            Some(WadChunkCompression::None)
+        );
+    }
+}
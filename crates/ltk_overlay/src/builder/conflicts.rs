@@ -0,0 +1,265 @@
+//! Cross-mod conflict detection.
+//!
+//! A conflict is a chunk path hash that more than one enabled mod overrides.
+//! Detection is purely diagnostic: it runs on the per-mod pass 1 metadata
+//! before the merge and never changes which override wins. The winner itself
+//! is picked by [`OverlayBuilder::override_rank`], shared with the merge.
+
+use super::hex_names::is_hex_named;
+use super::*;
+
+/// Sort key of a contribution to a chunk; the greatest one wins.
+pub(crate) type OverrideRank = (i32, isize);
+
+impl OverlayBuilder {
+    /// Rank of an override from the mod at `mod_index`, from a layer with
+    /// `layer_priority`, under the configured [`ConflictStrategy`].
+    pub(crate) fn override_rank(&self, mod_index: usize, layer_priority: i32) -> OverrideRank {
+        let layer_priority = match self.conflict_strategy {
+            ConflictStrategy::HighestPriority => layer_priority,
+            ConflictStrategy::FirstWins | ConflictStrategy::LastWins => 0,
+        };
+        let position = match self.priority_order {
+            PriorityOrder::FrontWins => -(mod_index as isize),
+            PriorityOrder::BackWins => mod_index as isize,
+        };
+        (layer_priority, position)
+    }
+
+    /// Layer priorities by name for each enabled mod, or empty maps when the
+    /// conflict strategy does not compare layers.
+    pub(crate) fn layer_priorities(&mut self) -> Vec<HashMap<String, i32>> {
+        if self.conflict_strategy != ConflictStrategy::HighestPriority {
+            return vec![HashMap::new(); self.enabled_mods.len()];
+        }
+        self.enabled_mods
+            .iter_mut()
+            .map(|enabled_mod| match enabled_mod.content.mod_project() {
+                Ok(project) => project
+                    .layers
+                    .into_iter()
+                    .map(|l| (l.name, l.priority))
+                    .collect(),
+                Err(_) => HashMap::new(),
+            })
+            .collect()
+    }
+
+    /// Find every path hash overridden by two or more mods.
+    ///
+    /// `per_mod_results` MUST be parallel to `self.enabled_mods`. Conflicts are
+    /// classified by comparing each contribution's content hash against the
+    /// winner's, and returned sorted by path hash.
+    pub(crate) fn detect_conflicts(
+        &mut self,
+        per_mod_results: &[HashMap<u64, OverrideMeta>],
+        game_index: &GameIndex,
+    ) -> Vec<Conflict> {
+        let mut contributors: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (mod_index, mod_meta) in per_mod_results.iter().enumerate() {
+            for &path_hash in mod_meta.keys() {
+                contributors.entry(path_hash).or_default().push(mod_index);
+            }
+        }
+        contributors.retain(|_, mods| mods.len() > 1);
+        if contributors.is_empty() {
+            return Vec::new();
+        }
+
+        // Display names and layer priorities, only for mods involved in a conflict.
+        let mut projects: HashMap<usize, (String, HashMap<String, i32>)> = HashMap::new();
+        for &mod_index in contributors.values().flatten() {
+            projects.entry(mod_index).or_insert_with(|| {
+                let enabled_mod = &mut self.enabled_mods[mod_index];
+                match enabled_mod.content.mod_project() {
+                    Ok(project) => (
+                        project.display_name,
+                        project
+                            .layers
+                            .into_iter()
+                            .map(|l| (l.name, l.priority))
+                            .collect(),
+                    ),
+                    Err(_) => (enabled_mod.id.clone(), HashMap::new()),
+                }
+            });
+        }
+
+        let mut conflicts: Vec<Conflict> = contributors
+            .into_iter()
+            .map(|(path_hash, mod_indices)| {
+                let contributing_mods: Vec<ModContribution> = mod_indices
+                    .iter()
+                    .map(|&mod_index| {
+                        let meta = &per_mod_results[mod_index][&path_hash];
+                        let (mod_name, priorities) = &projects[&mod_index];
+                        let layer = meta.source.layer().to_string();
+                        ModContribution {
+                            mod_id: self.enabled_mods[mod_index].id.clone(),
+                            mod_name: mod_name.clone(),
+                            priority: priorities.get(&layer).copied().unwrap_or(0),
+                            layer,
+                            install_order: mod_index,
+                            content_hash: meta.content_hash,
+                        }
+                    })
+                    .collect();
+                let winner_index = contributing_mods
+                    .iter()
+                    .max_by_key(|c| self.override_rank(c.install_order, c.priority))
+                    .map(|c| c.install_order)
+                    .expect("conflicts have at least two contributors");
+                let winner_meta = &per_mod_results[winner_index][&path_hash];
+
+                let kind = if contributing_mods
+                    .iter()
+                    .all(|c| c.content_hash == winner_meta.content_hash)
+                {
+                    ConflictKind::Redundant
+                } else {
+                    ConflictKind::Divergent
+                };
+
+                Conflict {
+                    path_hash,
+                    path: conflict_path(path_hash, winner_meta, game_index),
+                    contributing_mods,
+                    winner: self.enabled_mods[winner_index].id.clone(),
+                    kind,
+                }
+            })
+            .collect();
+
+        conflicts.sort_unstable_by_key(|c| c.path_hash);
+        conflicts
+    }
+}
+
+/// The winner's file path, or the hashtable path when the file is hex-named.
+fn conflict_path(path_hash: u64, winner_meta: &OverrideMeta, game_index: &GameIndex) -> String {
+    match game_index.resolve_hash(path_hash) {
+        Some(path) if is_hex_named(path_hash, winner_meta) => path.to_string(),
+        _ => winner_meta.source.rel_path().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_project, StubContent};
+    use ltk_mod_project::{ModProject, ModProjectLayer};
+
+    fn meta(mod_id: &str, layer: &str, content_hash: u64) -> OverrideMeta {
+        OverrideMeta {
+            content_hash,
+            uncompressed_size: 4,
+            source: OverrideSource::LayerWad {
+                mod_id: mod_id.to_string(),
+                layer: layer.to_string(),
+                wad_name: "Test.wad.client".to_string(),
+                rel_path: Utf8PathBuf::from("data/shared.bin"),
+            },
+            fallback_wad: None,
+            linked_bins: Vec::new(),
+            compression: None,
+        }
+    }
+
+    fn builder_with(ids: &[&'static str], order: PriorityOrder) -> OverlayBuilder {
+        let mut builder = OverlayBuilder::new(
+            Utf8PathBuf::from("/game"),
+            Utf8PathBuf::from("/profile/overlay"),
+            Utf8PathBuf::from("/profile"),
+        )
+        .with_priority_order(order);
+        builder.set_enabled_mods(
+            ids.iter()
+                .map(|id| EnabledMod {
+                    id: id.to_string(),
+                    content: Box::new(StubContent::new(ModProject {
+                        display_name: id.to_uppercase(),
+                        layers: vec![
+                            ModProjectLayer::base(),
+                            ModProjectLayer {
+                                name: "extra".to_string(),
+                                display_name: None,
+                                priority: 7,
+                                description: None,
+                                string_overrides: HashMap::new(),
+                            },
+                        ],
+                        ..mock_project(id)
+                    })),
+                    enabled_layers: None,
+                })
+                .collect(),
+        );
+        builder
+    }
+
+    #[test]
+    fn classifies_divergent_and_redundant_conflicts() {
+        let mut builder = builder_with(&["a", "b", "c"], PriorityOrder::FrontWins);
+        let per_mod = vec![
+            HashMap::from([(1, meta("a", "base", 10)), (2, meta("a", "base", 20))]),
+            HashMap::from([(1, meta("b", "extra", 11)), (2, meta("b", "base", 20))]),
+            HashMap::from([(3, meta("c", "base", 30))]),
+        ];
+
+        let conflicts = builder.detect_conflicts(&per_mod, &GameIndex::new());
+        assert_eq!(conflicts.len(), 2);
+
+        let divergent = &conflicts[0];
+        assert_eq!(divergent.path_hash, 1);
+        assert_eq!(divergent.kind, ConflictKind::Divergent);
+        assert_eq!(divergent.winner, "a");
+        assert_eq!(divergent.path, "data/shared.bin");
+        let loser = &divergent.contributing_mods[1];
+        assert_eq!((loser.mod_id.as_str(), loser.mod_name.as_str()), ("b", "B"));
+        assert_eq!((loser.layer.as_str(), loser.priority), ("extra", 7));
+        assert_eq!(loser.install_order, 1);
+
+        assert_eq!(conflicts[1].path_hash, 2);
+        assert_eq!(conflicts[1].kind, ConflictKind::Redundant);
+    }
+
+    #[test]
+    fn winner_follows_priority_order() {
+        let mut builder = builder_with(&["a", "b"], PriorityOrder::BackWins);
+        let per_mod = vec![
+            HashMap::from([(1, meta("a", "base", 10))]),
+            HashMap::from([(1, meta("b", "base", 11))]),
+        ];
+
+        let conflicts = builder.detect_conflicts(&per_mod, &GameIndex::new());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].winner, "b");
+    }
+
+    #[test]
+    fn hex_named_conflicts_use_the_hashtable_path() {
+        let mut builder = builder_with(&["a", "b"], PriorityOrder::FrontWins);
+        let hex_meta = |mod_id: &str| {
+            let mut meta = meta(mod_id, "base", 10);
+            if let OverrideSource::LayerWad { rel_path, .. } = &mut meta.source {
+                *rel_path = Utf8PathBuf::from("data/00000000000000ab.bin");
+            }
+            meta
+        };
+        let per_mod = vec![
+            HashMap::from([(0xab, hex_meta("a")), (1, meta("a", "base", 10))]),
+            HashMap::from([(0xab, hex_meta("b")), (1, meta("b", "base", 10))]),
+        ];
+        let mut game_index = GameIndex::new();
+        game_index
+            .hashtable
+            .insert(0xab, "data/characters/a/a.bin".to_string());
+        game_index
+            .hashtable
+            .insert(1, "data/elsewhere.bin".to_string());
+
+        let conflicts = builder.detect_conflicts(&per_mod, &game_index);
+        let paths: Vec<&str> = conflicts.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["data/shared.bin", "data/characters/a/a.bin"]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Structured build events, for consumers without a `tracing` subscriber.
+//!
+//! Each [`BuildEvent`] mirrors one of the builder's key log lines. Register a
+//! sink with [`OverlayBuilder::with_event_sink`] to show a detailed build log
+//! in a UI or to assert on what a build did in tests.
+
+use super::*;
+
+/// One step of an overlay build, delivered to the sink registered with
+/// [`OverlayBuilder::with_event_sink`].
+///
+/// WAD paths are relative to the game directory (e.g.
+/// `DATA/FINAL/Champions/Aatrox.wad.client`), which is also their path
+/// relative to the overlay root.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BuildEvent {
+    /// The build strategy was chosen.
+    #[serde(rename_all = "camelCase")]
+    StrategyChosen { reason: RebuildReason },
+    /// A mod's overrides were collected, from its files or the metadata cache.
+    #[serde(rename_all = "camelCase")]
+    ModCollected {
+        mod_id: String,
+        override_count: usize,
+        from_cache: bool,
+    },
+    /// A WAD directory in a mod was matched to a game WAD. `game_wad` is
+    /// `None` when nothing matched and the overrides are routed by hash only.
+    ///
+    /// Only sent when the mod's files are read, not on a metadata cache hit.
+    #[serde(rename_all = "camelCase")]
+    WadResolved {
+        mod_id: String,
+        wad_name: String,
+        game_wad: Option<Utf8PathBuf>,
+        /// Whether the match came from chunk overlap rather than the WAD name.
+        by_overlap: bool,
+    },
+    /// Overrides were read from one of a mod's WAD directories, or from its RAW
+    /// content when `wad_name` is `None`.
+    ///
+    /// Only sent when the mod's files are read, not on a metadata cache hit.
+    #[serde(rename_all = "camelCase")]
+    OverridesAdded {
+        mod_id: String,
+        layer: Option<String>,
+        wad_name: Option<String>,
+        count: usize,
+    },
+    /// An override was dropped before distribution.
+    #[serde(rename_all = "camelCase")]
+    OverrideFiltered {
+        mod_id: String,
+        path_hash: u64,
+        reason: FilterReason,
+    },
+    /// A WAD was left out of the overlay because it is blocked.
+    #[serde(rename_all = "camelCase")]
+    WadBlocked { wad: Utf8PathBuf },
+    /// An overlay WAD was written.
+    #[serde(rename_all = "camelCase")]
+    WadPatched {
+        wad: Utf8PathBuf,
+        override_count: usize,
+    },
+    /// An overlay WAD from the previous build was kept as is.
+    #[serde(rename_all = "camelCase")]
+    WadReused { wad: Utf8PathBuf },
+    /// An overlay WAD no longer needed was removed.
+    #[serde(rename_all = "camelCase")]
+    StaleWadRemoved { wad: Utf8PathBuf },
+}
+
+/// Why a [`BuildEvent::OverrideFiltered`] override was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterReason {
+    /// The override targets a `.wad.SubChunkTOC`, which mods must not replace.
+    SubChunkToc,
+    /// The override is identical to the game's copy.
+    UnchangedFromGame,
+}
+
+pub(crate) type EventSink = Arc<dyn Fn(BuildEvent) + Send + Sync>;
+
+/// Send `event` to `sink`, if there is one.
+pub(crate) fn emit_event(sink: Option<&EventSink>, event: BuildEvent) {
+    if let Some(sink) = sink {
+        sink(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
+    use std::sync::Mutex;
+
+    #[test]
+    fn sink_receives_the_build_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/a.bin", b"a".to_vec())
+                    .with_path("data/b.bin", b"b".to_vec()),
+            ),
+        )
+        .unwrap();
+        let mod_dir = root.join("mods/skin");
+        write_fs_mod(
+            &mod_dir,
+            &mock_project("skin"),
+            &[
+                ("base/A.wad.client/data/a.bin", b"patched"),
+                ("base/A.wad.client/data/b.bin", b"b"),
+            ],
+        )
+        .unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf())
+            .with_event_sink(move |event| sink.lock().unwrap().push(event));
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(mod_dir)),
+            enabled_layers: None,
+        }]);
+        builder.build().unwrap();
+
+        let wad = Utf8PathBuf::from("DATA/FINAL/Champions/A.wad.client");
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                BuildEvent::StrategyChosen {
+                    reason: RebuildReason::FullRebuildNoPreviousState
+                },
+                BuildEvent::WadResolved {
+                    mod_id: "skin".to_string(),
+                    wad_name: "A.wad.client".to_string(),
+                    game_wad: Some(wad.clone()),
+                    by_overlap: false,
+                },
+                BuildEvent::OverridesAdded {
+                    mod_id: "skin".to_string(),
+                    layer: Some(BASE_LAYER_NAME.to_string()),
+                    wad_name: Some("A.wad.client".to_string()),
+                    count: 2,
+                },
+                BuildEvent::ModCollected {
+                    mod_id: "skin".to_string(),
+                    override_count: 2,
+                    from_cache: false,
+                },
+                BuildEvent::OverrideFiltered {
+                    mod_id: "skin".to_string(),
+                    path_hash: ltk_modpkg::utils::hash_chunk_name("data/b.bin"),
+                    reason: FilterReason::UnchangedFromGame,
+                },
+                BuildEvent::WadPatched {
+                    wad,
+                    override_count: 1,
+                },
+            ]
+        );
+    }
+}
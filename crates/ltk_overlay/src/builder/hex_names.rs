@@ -0,0 +1,155 @@
+//! Strict check for hex-named overrides that match no game chunk.
+//!
+//! A file whose stem is exactly 16 hex digits is taken as a literal path hash
+//! (see [`resolve_chunk_hash`](crate::utils::resolve_chunk_hash)). Extractors
+//! only emit such names for chunks that already exist in the game, so a hex name
+//! whose hash is in no game WAD usually means a real asset was coincidentally
+//! named like a hash and is being inserted under the wrong path.
+
+use super::*;
+use crate::utils::parse_hex_chunk_name;
+
+/// A hex-named override whose hash is not present in any game WAD.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SuspectHexOverride {
+    /// Mod identifier (matches [`EnabledMod::id`]).
+    pub mod_id: String,
+    /// Path the override was read from, relative to its WAD directory (or the
+    /// content directory for RAW overrides).
+    pub rel_path: Utf8PathBuf,
+    /// The hash parsed from the file name.
+    pub path_hash: u64,
+}
+
+/// Whether `meta` was read from a file named after its own path hash.
+pub(crate) fn is_hex_named(path_hash: u64, meta: &OverrideMeta) -> bool {
+    parse_hex_chunk_name(meta.source.rel_path()) == Some(path_hash)
+}
+
+/// Collect hex-named overrides from each mod whose hash no game WAD contains.
+///
+/// Overrides with a [`fallback_wad`](OverrideMeta::fallback_wad) are declared
+/// additions to that WAD and are not reported.
+///
+/// `per_mod_results` is parallel to the enabled mod list; results keep that
+/// order and are sorted by path within each mod.
+pub(crate) fn find_suspect_hex_overrides(
+    per_mod_results: &[HashMap<u64, OverrideMeta>],
+    game_index: &GameIndex,
+) -> Vec<SuspectHexOverride> {
+    let mut suspects = Vec::new();
+
+    for mod_meta in per_mod_results {
+        let mut mod_suspects: Vec<SuspectHexOverride> = mod_meta
+            .iter()
+            .filter(|(&path_hash, meta)| {
+                meta.fallback_wad.is_none()
+                    && is_hex_named(path_hash, meta)
+                    && game_index.find_wads_with_hash(path_hash).is_none()
+            })
+            .map(|(&path_hash, meta)| SuspectHexOverride {
+                mod_id: meta.source.mod_id().to_string(),
+                rel_path: meta.source.rel_path().to_path_buf(),
+                path_hash,
+            })
+            .collect();
+        mod_suspects.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+        for suspect in &mod_suspects {
+            tracing::warn!(
+                "Mod='{}' override '{}' is named like a chunk hash ({:016x}) that no game \
+                 WAD contains; the file may be misnamed",
+                suspect.mod_id,
+                suspect.rel_path,
+                suspect.path_hash
+            );
+        }
+        suspects.append(&mut mod_suspects);
+    }
+
+    suspects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(mod_id: &str, rel_path: &str) -> OverrideMeta {
+        OverrideMeta {
+            content_hash: 0,
+            uncompressed_size: 4,
+            source: OverrideSource::LayerWad {
+                mod_id: mod_id.to_string(),
+                layer: "base".to_string(),
+                wad_name: "Test.wad.client".to_string(),
+                rel_path: Utf8PathBuf::from(rel_path),
+            },
+            fallback_wad: None,
+            linked_bins: Vec::new(),
+            compression: None,
+        }
+    }
+
+    #[test]
+    fn flags_only_hex_names_missing_from_the_game() {
+        let game_index = GameIndex {
+            wad_index: HashMap::new(),
+            hash_index: HashMap::from([(
+                0x1111,
+                vec![Utf8PathBuf::from("DATA/FINAL/Test.wad.client")],
+            )]),
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+        let named_hash = ltk_modpkg::utils::hash_chunk_name("data/absent.bin");
+        let per_mod = vec![
+            HashMap::from([
+                (0x1111, meta("a", "0000000000001111.bin")),
+                (0x2222, meta("a", "0000000000002222.dds")),
+                (named_hash, meta("a", "data/absent.bin")),
+            ]),
+            HashMap::from([(0x3333, meta("b", "0000000000003333.tex"))]),
+        ];
+
+        let suspects = find_suspect_hex_overrides(&per_mod, &game_index);
+        let found: Vec<(&str, u64)> = suspects
+            .iter()
+            .map(|s| (s.mod_id.as_str(), s.path_hash))
+            .collect();
+        assert_eq!(found, vec![("a", 0x2222), ("b", 0x3333)]);
+        assert_eq!(suspects[0].rel_path, "0000000000002222.dds");
+    }
+
+    #[test]
+    fn skips_hex_names_declared_as_additions() {
+        let game_index = GameIndex {
+            wad_index: HashMap::new(),
+            hash_index: HashMap::new(),
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+        let mut declared = meta("a", "0000000000002222.dds");
+        declared.fallback_wad = Some(Utf8PathBuf::from("DATA/FINAL/Test.wad.client"));
+        let per_mod = vec![HashMap::from([
+            (0x2222, declared),
+            (0x3333, meta("a", "0000000000003333.dds")),
+        ])];
+
+        let suspects = find_suspect_hex_overrides(&per_mod, &game_index);
+        let found: Vec<u64> = suspects.iter().map(|s| s.path_hash).collect();
+        assert_eq!(found, vec![0x3333]);
+    }
+
+    #[test]
+    fn hex_name_must_match_the_override_hash() {
+        assert!(is_hex_named(0x2222, &meta("a", "0000000000002222.dds")));
+        assert!(!is_hex_named(0x3333, &meta("a", "0000000000002222.dds")));
+        assert!(!is_hex_named(0x2222, &meta("a", "data/skin0.bin")));
+    }
+}
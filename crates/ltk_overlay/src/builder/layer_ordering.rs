@@ -0,0 +1,231 @@
+//! Layer-level ordering diagnostics across multiple mods.
+//!
+//! Within a mod, layers are applied in `priority` order with the layer name as a
+//! tiebreak. Across mods, two layers that share a priority and touch the same
+//! WAD have no meaningful relative order — which one wins is decided only by
+//! install order and name. This module surfaces those cases so users can
+//! disambiguate them explicitly.
+
+use super::*;
+
+/// A mod layer referenced by a [`LayerOrderingIssue`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LayerRef {
+    /// Mod identifier (matches [`EnabledMod::id`]).
+    pub mod_id: String,
+    /// Layer name within the mod.
+    pub layer: String,
+}
+
+/// Two layers from different mods with equal priority that touch the same WADs.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LayerOrderingIssue {
+    /// The priority value both layers share.
+    pub priority: i32,
+    /// The layer from the mod earlier in the enabled list.
+    pub first: LayerRef,
+    /// The layer from the mod later in the enabled list.
+    pub second: LayerRef,
+    /// WAD names (lowercased) that both layers provide overrides for. Sorted.
+    pub overlapping_wads: Vec<String>,
+}
+
+/// One active layer's footprint, gathered from its content provider.
+struct LayerFootprint {
+    mod_index: usize,
+    layer: LayerRef,
+    priority: i32,
+    wads: BTreeSet<String>,
+}
+
+impl OverlayBuilder {
+    /// Report equal-priority layers from different mods that touch overlapping WADs.
+    ///
+    /// This is a layer-level diagnostic that complements chunk-level conflict
+    /// detection: it reads only each mod's project and layer WAD listings, not any
+    /// override bytes. Layers excluded by [`EnabledMod::enabled_layers`] are
+    /// ignored. Mods whose content cannot be read are skipped with a warning.
+    ///
+    /// Issues are ordered by the enabled-list position of their first mod.
+    pub fn analyze_layer_ordering(&mut self) -> Vec<LayerOrderingIssue> {
+        let mut footprints: Vec<LayerFootprint> = Vec::new();
+
+        for (mod_index, enabled_mod) in self.enabled_mods.iter_mut().enumerate() {
+            match collect_layer_footprints(mod_index, enabled_mod) {
+                Ok(mut layers) => footprints.append(&mut layers),
+                Err(e) => tracing::warn!(
+                    "Skipping mod '{}' in layer ordering analysis: {}",
+                    enabled_mod.id,
+                    e
+                ),
+            }
+        }
+
+        let mut issues = Vec::new();
+        for (i, a) in footprints.iter().enumerate() {
+            for b in &footprints[i + 1..] {
+                if a.mod_index == b.mod_index || a.priority != b.priority {
+                    continue;
+                }
+
+                let overlapping_wads: Vec<String> = a.wads.intersection(&b.wads).cloned().collect();
+                if overlapping_wads.is_empty() {
+                    continue;
+                }
+
+                issues.push(LayerOrderingIssue {
+                    priority: a.priority,
+                    first: a.layer.clone(),
+                    second: b.layer.clone(),
+                    overlapping_wads,
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+fn collect_layer_footprints(
+    mod_index: usize,
+    enabled_mod: &mut EnabledMod,
+) -> Result<Vec<LayerFootprint>> {
+    let project = enabled_mod.content.mod_project()?;
+    let mut footprints = Vec::new();
+
+    for layer in &project.layers {
+        if !enabled_mod.is_layer_active(&layer.name) {
+            continue;
+        }
+
+        let wads: BTreeSet<String> = enabled_mod
+            .content
+            .list_layer_wads(&layer.name)?
+            .into_iter()
+            .map(|w| w.to_ascii_lowercase())
+            .collect();
+        if wads.is_empty() {
+            continue;
+        }
+
+        footprints.push(LayerFootprint {
+            mod_index,
+            layer: LayerRef {
+                mod_id: enabled_mod.id.clone(),
+                layer: layer.name.clone(),
+            },
+            priority: layer.priority,
+            wads,
+        });
+    }
+
+    Ok(footprints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_project, StubContent};
+    use ltk_mod_project::{ModProject, ModProjectLayer};
+
+    /// A mod exposing a fixed set of `(layer, priority, wads)`.
+    fn mock_mod(id: &str, layers: Vec<(&'static str, i32, Vec<&'static str>)>) -> EnabledMod {
+        let mut content = StubContent::new(ModProject {
+            layers: layers
+                .iter()
+                .map(|(name, priority, _)| ModProjectLayer {
+                    name: name.to_string(),
+                    display_name: None,
+                    priority: *priority,
+                    description: None,
+                    string_overrides: HashMap::new(),
+                })
+                .collect(),
+            ..mock_project("mock")
+        });
+        for (name, _, wads) in &layers {
+            content = content.with_layer_wads(name, wads);
+        }
+        EnabledMod {
+            id: id.to_string(),
+            content: Box::new(content),
+            enabled_layers: None,
+        }
+    }
+
+    fn builder_with(mods: Vec<EnabledMod>) -> OverlayBuilder {
+        let mut builder = OverlayBuilder::new(
+            Utf8PathBuf::from("/game"),
+            Utf8PathBuf::from("/profile/overlay"),
+            Utf8PathBuf::from("/profile"),
+        );
+        builder.set_enabled_mods(mods);
+        builder
+    }
+
+    #[test]
+    fn reports_equal_priority_layers_sharing_wads() {
+        let mut builder = builder_with(vec![
+            mock_mod(
+                "a",
+                vec![
+                    ("base", 0, vec!["Aatrox.wad.client"]),
+                    ("chroma", 5, vec!["Aatrox.wad.client", "Map11.wad.client"]),
+                ],
+            ),
+            mock_mod(
+                "b",
+                vec![
+                    ("base", 0, vec!["Ahri.wad.client"]),
+                    ("vfx", 5, vec!["aatrox.wad.client"]),
+                ],
+            ),
+        ]);
+
+        let issues = builder.analyze_layer_ordering();
+        assert_eq!(
+            issues,
+            vec![LayerOrderingIssue {
+                priority: 5,
+                first: LayerRef {
+                    mod_id: "a".to_string(),
+                    layer: "chroma".to_string(),
+                },
+                second: LayerRef {
+                    mod_id: "b".to_string(),
+                    layer: "vfx".to_string(),
+                },
+                overlapping_wads: vec!["aatrox.wad.client".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_same_mod_and_different_priorities() {
+        let mut builder = builder_with(vec![
+            mock_mod(
+                "a",
+                vec![
+                    ("base", 0, vec!["Aatrox.wad.client"]),
+                    ("extra", 0, vec!["Aatrox.wad.client"]),
+                ],
+            ),
+            mock_mod("b", vec![("base", 1, vec!["Aatrox.wad.client"])]),
+        ]);
+
+        assert!(builder.analyze_layer_ordering().is_empty());
+    }
+
+    #[test]
+    fn skips_inactive_layers() {
+        let mut a = mock_mod(
+            "a",
+            vec![("base", 0, vec![]), ("extra", 3, vec!["Aatrox.wad.client"])],
+        );
+        a.enabled_layers = Some(HashSet::new());
+        let b = mock_mod("b", vec![("extra", 3, vec!["Aatrox.wad.client"])]);
+
+        let mut builder = builder_with(vec![a, b]);
+        assert!(builder.analyze_layer_ordering().is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! Content checks for a single mod against the game, without building anything.
+//!
+//! Surfaces the overrides a build would silently drop or misplace, so authors
+//! can fix them before shipping.
+
+use super::hex_names::find_suspect_hex_overrides;
+use super::*;
+use crate::healthcheck::{find_layer_shadowing, ShadowedOverride};
+
+/// An override flagged by [`OverlayBuilder::lint_single_mod`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LintedOverride {
+    /// Path the override was read from, relative to its WAD directory (or the
+    /// content directory for RAW overrides).
+    pub rel_path: Utf8PathBuf,
+    /// The override's chunk path hash.
+    pub path_hash: u64,
+}
+
+/// What [`OverlayBuilder::lint_single_mod`] found in one mod.
+///
+/// Each list is sorted by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModLintReport {
+    /// Overrides byte-identical to the game's copy. Builds skip them.
+    pub lazy_overrides: Vec<LintedOverride>,
+    /// Overrides of a WAD's `.SubChunkTOC`. Builds always strip them, since
+    /// replacing one corrupts the game.
+    pub subchunktoc_overrides: Vec<LintedOverride>,
+    /// Hex-named overrides whose hash no game WAD contains.
+    pub suspect_hex_overrides: Vec<SuspectHexOverride>,
+    /// Chunks overridden by more than one of the mod's layers (see
+    /// [`find_layer_shadowing`]). Sorted by WAD, then path.
+    #[serde(default)]
+    pub layer_shadowing: Vec<ShadowedOverride>,
+}
+
+impl ModLintReport {
+    /// Whether nothing was flagged.
+    pub fn is_clean(&self) -> bool {
+        self.lazy_overrides.is_empty()
+            && self.subchunktoc_overrides.is_empty()
+            && self.suspect_hex_overrides.is_empty()
+            && self.layer_shadowing.is_empty()
+    }
+}
+
+impl OverlayBuilder {
+    /// Check a single mod's overrides against the game.
+    ///
+    /// Loads (or builds) the [`GameIndex`] the same way as
+    /// [`analyze_single_mod`](Self::analyze_single_mod), collects the mod's
+    /// override metadata and reports lazy overrides, SubChunkTOC overrides,
+    /// suspect hex-named overrides and chunks shadowed across the mod's layers.
+    /// Nothing is written besides the index cache.
+    pub fn lint_single_mod(
+        game_dir: &Utf8Path,
+        state_dir: &Utf8Path,
+        enabled_mod: &mut EnabledMod,
+    ) -> Result<ModLintReport> {
+        let game_dir = &ltk_mod_core::resolve_game_dir(game_dir)?;
+
+        std::fs::create_dir_all(state_dir.as_std_path())?;
+        let cache_path = state_dir.join("game_index.bin");
+        let game_index = GameIndex::load_or_build(game_dir, &cache_path)?;
+
+        let mod_meta = metadata::collect_single_mod_metadata(
+            enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )?;
+
+        let blocked = game_index.subchunktoc_blocked();
+        let hashes: HashSet<u64> = mod_meta.keys().copied().collect();
+        let game_hashes = game_index.compute_content_hashes_batch(game_dir, &hashes);
+
+        let flagged = |keep: &dyn Fn(u64, &OverrideMeta) -> bool| {
+            let mut flagged: Vec<LintedOverride> = mod_meta
+                .iter()
+                .filter(|(&path_hash, meta)| keep(path_hash, meta))
+                .map(|(&path_hash, meta)| LintedOverride {
+                    rel_path: meta.source.rel_path().to_path_buf(),
+                    path_hash,
+                })
+                .collect();
+            flagged.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+            flagged
+        };
+
+        Ok(ModLintReport {
+            lazy_overrides: flagged(&|path_hash, meta| {
+                game_hashes.get(&path_hash) == Some(&meta.content_hash)
+            }),
+            subchunktoc_overrides: flagged(&|path_hash, _| blocked.contains(&path_hash)),
+            suspect_hex_overrides: find_suspect_hex_overrides(
+                std::slice::from_ref(&mod_meta),
+                &game_index,
+            ),
+            layer_shadowing: find_layer_shadowing(enabled_mod)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
+
+    #[test]
+    fn flags_lazy_subchunktoc_and_shadowed_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/same.bin", b"same".to_vec())
+                    .with_path("data/changed.bin", b"old".to_vec()),
+            ),
+        )
+        .unwrap();
+
+        let mod_dir = root.join("mod");
+        let mut project = mock_project("mock");
+        project.layers.push(ltk_mod_project::ModProjectLayer {
+            name: "chroma".to_string(),
+            display_name: None,
+            priority: 10,
+            description: None,
+            string_overrides: HashMap::new(),
+        });
+        write_fs_mod(
+            &mod_dir,
+            &project,
+            &[
+                ("base/A.wad.client/data/same.bin", b"same"),
+                ("base/A.wad.client/data/changed.bin", b"new"),
+                ("chroma/A.wad.client/data/changed.bin", b"chroma"),
+                ("base/A.wad.client/0123456789abcdef.bin", b"hex"),
+                (
+                    "base/A.wad.client/DATA/FINAL/Champions/A.wad.SubChunkTOC",
+                    b"toc",
+                ),
+            ],
+        )
+        .unwrap();
+
+        let mut enabled_mod = EnabledMod {
+            id: "mock".to_string(),
+            content: Box::new(FsModContent::new(mod_dir)),
+            enabled_layers: None,
+        };
+        let report =
+            OverlayBuilder::lint_single_mod(&game_dir, &root.join("state"), &mut enabled_mod)
+                .unwrap();
+
+        let paths = |flagged: &[LintedOverride]| -> Vec<String> {
+            flagged.iter().map(|o| o.rel_path.to_string()).collect()
+        };
+        assert_eq!(paths(&report.lazy_overrides), vec!["data/same.bin"]);
+        assert_eq!(
+            paths(&report.subchunktoc_overrides),
+            vec!["DATA/FINAL/Champions/A.wad.SubChunkTOC"]
+        );
+        // The hex-named file sits in a game WAD's directory, so it is a
+        // declared addition to that WAD rather than a misnamed asset.
+        assert!(report.suspect_hex_overrides.is_empty());
+        assert_eq!(report.layer_shadowing.len(), 1);
+        assert_eq!(report.layer_shadowing[0].rel_path, "data/changed.bin");
+        assert_eq!(report.layer_shadowing[0].layers, vec!["base", "chroma"]);
+        assert!(!report.layer_shadowing[0].identical);
+        assert!(!report.is_clean());
+    }
+}
@@ -3,29 +3,86 @@
 //! Collects lightweight metadata (hashes, sizes, source locations) from all
 //! enabled mods. Uses a persistent metadata cache to skip unchanged mods entirely.
 
+use super::broken_overrides::{take_broken_overrides, BrokenOverride};
+use super::compression_hints::CompressionRules;
+use super::hex_names::find_suspect_hex_overrides;
 use super::*;
 use crate::meta_cache::{CachedModMeta, OverrideMetaCache};
 use crate::utils::resolve_chunk_hash;
+use ltk_mod_project::ModProjectLayer;
 use rayon::prelude::*;
+use std::sync::Mutex;
 use xxhash_rust::xxh3::xxh3_64;
 
+/// The layers of a mod in the order their overrides are applied, lowest
+/// priority first, so a later layer's override replaces an earlier one's.
+///
+/// Formats without layer support only contribute their base layer.
+pub(crate) fn layers_in_order(
+    mut layers: Vec<ModProjectLayer>,
+    format_version: ContentFormatVersion,
+) -> Vec<ModProjectLayer> {
+    if !format_version.supports_layers() {
+        layers.retain(|layer| layer.name == BASE_LAYER_NAME);
+    }
+    layers.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.name.cmp(&b.name)));
+    layers
+}
+
 /// Collect override metadata from a single mod (pass 1).
 ///
-/// Reads all override files, computes their hashes and sizes, records source
-/// locations for pass 2 re-reading, then drops the bytes. Returns lightweight
-/// `OverrideMeta` entries instead of raw bytes.
+/// See [`collect_single_mod`]; the broken override files it skips are dropped
+/// without being reported.
 pub(crate) fn collect_single_mod_metadata(
     enabled_mod: &mut EnabledMod,
     game_index: &GameIndex,
     game_dir: &Utf8Path,
+    transform: Option<&OverrideTransform>,
+    retry: &RetryPolicy,
+    events: Option<&EventSink>,
 ) -> Result<HashMap<u64, OverrideMeta>> {
-    tracing::info!("Processing mod id={}", enabled_mod.id);
+    collect_single_mod(enabled_mod, game_index, game_dir, transform, retry, events)
+        .map(|(mod_meta, _)| mod_meta)
+}
+
+/// Collect override metadata from a single mod (pass 1).
+///
+/// Reads all override files, computes their hashes and sizes, records source
+/// locations for pass 2 re-reading, then drops the bytes. Returns lightweight
+/// `OverrideMeta` entries instead of raw bytes, along with the override files
+/// skipped because they are empty or git LFS pointers.
+///
+/// When `transform` is set, it is applied to each override's bytes before
+/// hashing so the recorded metadata describes what will actually be written.
+/// Each WAD's override read is retried according to `retry`, and WAD
+/// resolution and added overrides are reported to `events`.
+fn collect_single_mod(
+    enabled_mod: &mut EnabledMod,
+    game_index: &GameIndex,
+    game_dir: &Utf8Path,
+    transform: Option<&OverrideTransform>,
+    retry: &RetryPolicy,
+    events: Option<&EventSink>,
+) -> Result<(HashMap<u64, OverrideMeta>, Vec<BrokenOverride>)> {
+    let format_version = enabled_mod.content.format_version();
+    tracing::info!(
+        "Processing mod id={} format={}",
+        enabled_mod.id,
+        format_version
+    );
+    if format_version.is_deprecated() {
+        tracing::warn!(
+            "Mod={} uses the deprecated {} format; consider repackaging it as a modpkg",
+            enabled_mod.id,
+            format_version
+        );
+    }
 
     let project = enabled_mod.content.mod_project()?;
-    let mut layers = project.layers.clone();
-    layers.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.name.cmp(&b.name)));
+    let layers = layers_in_order(project.layers.clone(), format_version);
 
     let mut mod_meta: HashMap<u64, OverrideMeta> = HashMap::new();
+    let mut broken = Vec::new();
 
     for layer in &layers {
         if !enabled_mod.is_layer_active(&layer.name) {
@@ -51,15 +108,30 @@ pub(crate) fn collect_single_mod_metadata(
 
         for wad_name in &wad_names {
             let before = mod_meta.len();
-            let override_files = enabled_mod
-                .content
-                .read_wad_overrides(&layer.name, wad_name)?;
+            let override_files = retry.run(
+                &format_args!(
+                    "mod={} layer='{}' WAD='{}'",
+                    enabled_mod.id, layer.name, wad_name
+                ),
+                || {
+                    enabled_mod
+                        .content
+                        .read_wad_overrides(&layer.name, wad_name)
+                },
+            )?;
+            let override_files = take_broken_overrides(
+                &enabled_mod.id,
+                Some((&layer.name, wad_name)),
+                override_files,
+                &mut broken,
+            );
 
             // Pre-compute path hashes so we can use them for overlap detection
             let entries: Vec<(Utf8PathBuf, u64, u64, usize, Vec<String>)> = override_files
                 .into_iter()
                 .map(|(rel_path, bytes)| {
                     let path_hash = resolve_chunk_hash(&rel_path, &bytes)?;
+                    let bytes = apply_override_transform(transform, path_hash, bytes);
                     let content_hash = xxh3_64(&bytes);
                     let uncompressed_size = bytes.len();
                     let linked_bins =
@@ -74,7 +146,10 @@ pub(crate) fn collect_single_mod_metadata(
                 })
                 .collect::<Result<_>>()?;
 
-            let fallback_wad = match game_index.find_wad(wad_name) {
+            // Locale WADs may be copied into several folders; prefer the canonical one.
+            let hint = crate::game_index::wad_locale(wad_name)
+                .map(|_| Utf8Path::new(crate::game_index::LOCALIZED_WAD_DIR));
+            let fallback_wad = match game_index.find_wad_with_hint(wad_name, hint) {
                 Ok(original_wad_path) => {
                     let relative_game_path = original_wad_path
                         .strip_prefix(game_dir)
@@ -87,6 +162,15 @@ pub(crate) fn collect_single_mod_metadata(
                         original_wad_path,
                         relative_game_path
                     );
+                    emit_event(
+                        events,
+                        BuildEvent::WadResolved {
+                            mod_id: enabled_mod.id.clone(),
+                            wad_name: wad_name.clone(),
+                            game_wad: Some(relative_game_path.clone()),
+                            by_overlap: false,
+                        },
+                    );
                     Some(relative_game_path)
                 }
                 Err(Error::WadNotFound(_)) => {
@@ -94,7 +178,17 @@ pub(crate) fn collect_single_mod_metadata(
                     // the game WAD with the most matching chunk hashes (same
                     // approach as cslol-manager's find_by_overlap).
                     let path_hashes: Vec<u64> = entries.iter().map(|(_, h, _, _, _)| *h).collect();
-                    match game_index.find_best_matching_wad(&path_hashes) {
+                    let best_wad = game_index.find_best_matching_wad(&path_hashes);
+                    emit_event(
+                        events,
+                        BuildEvent::WadResolved {
+                            mod_id: enabled_mod.id.clone(),
+                            wad_name: wad_name.clone(),
+                            game_wad: best_wad.clone(),
+                            by_overlap: best_wad.is_some(),
+                        },
+                    );
+                    match best_wad {
                         Some(best_wad) => {
                             tracing::info!(
                                 "Mod='{}' WAD '{}' not found in game; \
@@ -133,6 +227,7 @@ pub(crate) fn collect_single_mod_metadata(
                         },
                         fallback_wad: fallback_wad.clone(),
                         linked_bins,
+                        compression: None,
                     },
                 );
             }
@@ -143,16 +238,31 @@ pub(crate) fn collect_single_mod_metadata(
                 after.saturating_sub(before),
                 after
             );
+            emit_event(
+                events,
+                BuildEvent::OverridesAdded {
+                    mod_id: enabled_mod.id.clone(),
+                    layer: Some(layer.name.clone()),
+                    wad_name: Some(wad_name.clone()),
+                    count: after.saturating_sub(before),
+                },
+            );
         }
     }
 
     // Process RAW overrides — files identified by game asset path
     // that get routed to correct WADs via hash matching in distribute_override_hashes()
-    let raw_overrides = enabled_mod.content.read_raw_overrides()?;
+    let raw_overrides = take_broken_overrides(
+        &enabled_mod.id,
+        None,
+        enabled_mod.content.read_raw_overrides()?,
+        &mut broken,
+    );
     if !raw_overrides.is_empty() {
         let before = mod_meta.len();
         for (rel_path, bytes) in raw_overrides {
             let path_hash = resolve_chunk_hash(&rel_path, &bytes)?;
+            let bytes = apply_override_transform(transform, path_hash, bytes);
             let content_hash = xxh3_64(&bytes);
             let uncompressed_size = bytes.len();
             let linked_bins = crate::linked_bins::parse_linked_bins(&bytes).unwrap_or_default();
@@ -167,16 +277,33 @@ pub(crate) fn collect_single_mod_metadata(
                     },
                     fallback_wad: None,
                     linked_bins,
+                    compression: None,
                 },
             );
         }
-        tracing::info!(
-            "Mod={} RAW overrides added={}",
-            enabled_mod.id,
-            mod_meta.len().saturating_sub(before)
+        let added = mod_meta.len().saturating_sub(before);
+        tracing::info!("Mod={} RAW overrides added={}", enabled_mod.id, added);
+        emit_event(
+            events,
+            BuildEvent::OverridesAdded {
+                mod_id: enabled_mod.id.clone(),
+                layer: None,
+                wad_name: None,
+                count: added,
+            },
         );
     }
 
+    // New assets from providers without a WAD folder structure (RAW files, modpkg
+    // chunks packed without a WAD) have no target yet; their own path usually
+    // names the WAD they belong in. Overrides of existing game chunks are routed
+    // by the hash index and need no guess.
+    for (&path_hash, meta) in mod_meta.iter_mut() {
+        if meta.fallback_wad.is_none() && game_index.find_wads_with_hash(path_hash).is_none() {
+            meta.fallback_wad = game_index.guess_target_wad(meta.source.rel_path().as_str());
+        }
+    }
+
     // Route any overrides that still have no fallback target — e.g. RAW files introducing
     // brand-new assets, or WAD-layer overrides whose own chunks didn't overlap any game WAD
     // — to the game WAD that the majority of THIS mod's chunks map to. Without this they
@@ -203,7 +330,9 @@ pub(crate) fn collect_single_mod_metadata(
         }
     }
 
-    Ok(mod_meta)
+    CompressionRules::from_project(&enabled_mod.id, &project).apply(&mut mod_meta);
+
+    Ok((mod_meta, broken))
 }
 
 /// Filter out override metadata that should not be included in the overlay.
@@ -212,18 +341,36 @@ pub(crate) fn collect_single_mod_metadata(
 /// 1. SubChunkTOC entries — always stripped to prevent game corruption.
 /// 2. Lazy overrides — mod files identical to game originals, detected by
 ///    comparing pre-computed content hashes against game originals.
+///
+/// Every dropped override is reported to `events`.
 pub(crate) fn filter_override_metadata(
     all_meta: &mut HashMap<u64, OverrideMeta>,
     game_index: &GameIndex,
     game_dir: &Utf8Path,
+    events: Option<&EventSink>,
 ) {
+    let filtered = |path_hash: u64, meta: &OverrideMeta, reason: FilterReason| {
+        emit_event(
+            events,
+            BuildEvent::OverrideFiltered {
+                mod_id: meta.source.mod_id().to_string(),
+                path_hash,
+                reason,
+            },
+        );
+    };
+
     // Filter out SubChunkTOC entries
     let blocked = game_index.subchunktoc_blocked();
     let before_filter = all_meta.len();
-    all_meta.retain(|path_hash, _| {
-        let dominated = blocked.contains(path_hash);
+    all_meta.retain(|&path_hash, meta| {
+        let dominated = blocked.contains(&path_hash);
         if dominated {
-            tracing::debug!("Filtered SubChunkTOC override: {:016x}", path_hash);
+            tracing::debug!(
+                "Filtered SubChunkTOC override: {}",
+                game_index.describe_hash(path_hash)
+            );
+            filtered(path_hash, meta, FilterReason::SubChunkToc);
         }
         !dominated
     });
@@ -244,7 +391,11 @@ pub(crate) fn filter_override_metadata(
     all_meta.retain(|&path_hash, meta| {
         if let Some(&original_hash) = content_hashes.get(&path_hash) {
             if meta.content_hash == original_hash {
-                tracing::debug!("Filtered lazy override: {:016x}", path_hash);
+                tracing::debug!(
+                    "Filtered lazy override: {}",
+                    game_index.describe_hash(path_hash)
+                );
+                filtered(path_hash, meta, FilterReason::UnchangedFromGame);
                 return false;
             }
         }
@@ -259,15 +410,62 @@ pub(crate) fn filter_override_metadata(
     }
 }
 
-/// Try the metadata cache for a single mod; on miss, collect fresh metadata and
-/// update the cache.
+/// One mod's pass 1 output, see [`collect_or_cache_mod_metadata`].
+struct CollectedMod {
+    mod_meta: HashMap<u64, OverrideMeta>,
+    /// Override files skipped because they hold no asset data.
+    broken: Vec<BrokenOverride>,
+    /// On a cache miss with a known fingerprint, the entry to store in the
+    /// cache for the next build.
+    cache_entry: Option<CachedModMeta>,
+}
+
+/// Try the metadata cache for a single mod; on miss, collect fresh metadata.
+///
+/// Skipped broken override files are cached along with the metadata, so they
+/// are reported on every build. The cache is only read here so that
+/// mods can be collected in parallel.
+///
+/// The cache is bypassed entirely when an override transform is registered,
+/// since the transform's output is not covered by the mod's content fingerprint.
+#[allow(clippy::too_many_arguments)]
 fn collect_or_cache_mod_metadata(
     enabled_mod: &mut EnabledMod,
     fingerprint: Option<u64>,
-    meta_cache: &mut OverrideMetaCache,
+    meta_cache: &OverrideMetaCache,
     game_index: &GameIndex,
     game_dir: &Utf8Path,
-) -> Result<HashMap<u64, OverrideMeta>> {
+    transform: Option<&OverrideTransform>,
+    retry: &RetryPolicy,
+    events: Option<&EventSink>,
+) -> Result<CollectedMod> {
+    let collected =
+        |enabled_mod: &EnabledMod, mod_meta: &HashMap<u64, OverrideMeta>, from_cache| {
+            emit_event(
+                events,
+                BuildEvent::ModCollected {
+                    mod_id: enabled_mod.id.clone(),
+                    override_count: mod_meta.len(),
+                    from_cache,
+                },
+            );
+        };
+
+    if transform.is_some() {
+        tracing::info!(
+            "Mod={} override transform registered, bypassing metadata cache",
+            enabled_mod.id
+        );
+        let (mod_meta, broken) =
+            collect_single_mod(enabled_mod, game_index, game_dir, transform, retry, events)?;
+        collected(enabled_mod, &mod_meta, false);
+        return Ok(CollectedMod {
+            mod_meta,
+            broken,
+            cache_entry: None,
+        });
+    }
+
     // Cache hit — reconstruct from cached data without reading any files.
     if let Some(fp) = fingerprint {
         if let Some(cached) = meta_cache.get_mod_meta(&enabled_mod.id, fp) {
@@ -277,21 +475,52 @@ fn collect_or_cache_mod_metadata(
                 fp,
                 cached.overrides.len()
             );
-            return Ok(cached.reconstruct(&enabled_mod.id));
+            // Compression hints live in the project config, which the content
+            // fingerprint does not necessarily cover, so re-derive them.
+            let mut mod_meta = cached.reconstruct(&enabled_mod.id);
+            let project = enabled_mod.content.mod_project()?;
+            CompressionRules::from_project(&enabled_mod.id, &project).apply(&mut mod_meta);
+            collected(enabled_mod, &mod_meta, true);
+            return Ok(CollectedMod {
+                mod_meta,
+                broken: cached.broken_overrides.clone(),
+                cache_entry: None,
+            });
         }
     }
 
     // Cache miss — collect fresh metadata from mod content.
     tracing::info!("Mod={} cache miss, reading files", enabled_mod.id);
-    let mod_meta = collect_single_mod_metadata(enabled_mod, game_index, game_dir)?;
+    let (mod_meta, broken) =
+        collect_single_mod(enabled_mod, game_index, game_dir, None, retry, events)?;
+    collected(enabled_mod, &mod_meta, false);
 
     // Persist to cache for next build.
-    if let Some(fp) = fingerprint {
-        let cache_entry = CachedModMeta::from_override_meta(fp, &mod_meta);
-        meta_cache.set_mod_meta(enabled_mod.id.clone(), cache_entry);
-    }
+    let cache_entry = fingerprint.map(|fp| {
+        let mut entry = CachedModMeta::from_override_meta(fp, &mod_meta);
+        entry.broken_overrides = broken.clone();
+        entry
+    });
 
-    Ok(mod_meta)
+    Ok(CollectedMod {
+        mod_meta,
+        broken,
+        cache_entry,
+    })
+}
+
+/// Output of pass 1 across all enabled mods.
+pub(crate) struct OverrideCollection {
+    /// Merged `path_hash -> OverrideMeta`, with the winning mod's entry per hash.
+    pub(crate) all_meta: HashMap<u64, OverrideMeta>,
+    /// Per-mod WAD footprints, parallel to the enabled mod list.
+    pub(crate) mod_wad_reports: Vec<ModWadReport>,
+    /// Hashes overridden by more than one mod.
+    pub(crate) conflicts: Vec<Conflict>,
+    /// Hex-named overrides matching no game chunk; empty unless strict hex names are enabled.
+    pub(crate) suspect_hex_overrides: Vec<SuspectHexOverride>,
+    /// Override files skipped because they hold no asset data, in mod order.
+    pub(crate) broken_overrides: Vec<BrokenOverride>,
 }
 
 impl OverlayBuilder {
@@ -301,12 +530,17 @@ impl OverlayBuilder {
     /// For cache misses, reads files, computes hashes, records source locations,
     /// and drops the bytes immediately.
     ///
-    /// Returns `path_hash -> OverrideMeta` for all overrides across all mods.
+    /// Returns `path_hash -> OverrideMeta` for all overrides across all mods,
+    /// along with per-mod WAD reports and cross-mod conflicts.
     pub(crate) fn collect_all_override_metadata(
         &mut self,
         game_index: &GameIndex,
-    ) -> Result<(HashMap<u64, OverrideMeta>, Vec<ModWadReport>)> {
+    ) -> Result<OverrideCollection> {
         let game_dir = &self.game_dir;
+        let transform = self.override_transform.as_ref();
+        let retry = &self.read_retries;
+        let event_sink = self.event_sink.clone();
+        let events = event_sink.as_ref();
         let meta_cache_path = self.state_dir.join("override_meta.bin");
         let game_fp = game_index.game_fingerprint();
 
@@ -322,19 +556,48 @@ impl OverlayBuilder {
             .map(|m| m.cache_fingerprint())
             .collect();
 
-        // For each mod: either use cache or collect fresh metadata.
+        // For each mod: either use cache or collect fresh metadata. Mods are read
+        // in parallel; each one's build events are buffered and replayed in mod
+        // order below, so the event log does not depend on which read finishes
+        // first.
+        let collected = self
+            .enabled_mods
+            .par_iter_mut()
+            .zip(fingerprints.par_iter())
+            .map(|(enabled_mod, &fingerprint)| {
+                let buffer = Arc::new(Mutex::new(Vec::new()));
+                let mod_events: Option<EventSink> = events.map(|_| {
+                    let buffer = Arc::clone(&buffer);
+                    Arc::new(move |event| buffer.lock().unwrap().push(event)) as EventSink
+                });
+                let collected = collect_or_cache_mod_metadata(
+                    enabled_mod,
+                    fingerprint,
+                    &meta_cache,
+                    game_index,
+                    game_dir,
+                    transform,
+                    retry,
+                    mod_events.as_ref(),
+                )?;
+                let buffered = std::mem::take(&mut *buffer.lock().unwrap());
+                Ok((collected, buffered))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Merge on this thread in mod order, so results are deterministic.
         let mut per_mod_results: Vec<HashMap<u64, OverrideMeta>> =
-            Vec::with_capacity(self.enabled_mods.len());
-
-        for (idx, enabled_mod) in self.enabled_mods.iter_mut().enumerate() {
-            let mod_meta = collect_or_cache_mod_metadata(
-                enabled_mod,
-                fingerprints[idx],
-                &mut meta_cache,
-                game_index,
-                game_dir,
-            )?;
-            per_mod_results.push(mod_meta);
+            Vec::with_capacity(collected.len());
+        let mut broken_overrides = Vec::new();
+        for (enabled_mod, (mut collected, buffered)) in self.enabled_mods.iter().zip(collected) {
+            for event in buffered {
+                emit_event(events, event);
+            }
+            if let Some(cache_entry) = collected.cache_entry {
+                meta_cache.set_mod_meta(enabled_mod.id.clone(), cache_entry);
+            }
+            per_mod_results.push(collected.mod_meta);
+            broken_overrides.append(&mut collected.broken);
         }
 
         // Build per-mod WAD reports while we still have the un-merged data.
@@ -342,13 +605,29 @@ impl OverlayBuilder {
         // single mod's metadata only.
         let mod_wad_reports =
             self.build_mod_wad_reports(&per_mod_results, &fingerprints, game_index);
+        let mut conflicts = self.detect_conflicts(&per_mod_results, game_index);
+        let suspect_hex_overrides = if self.strict_hex_names {
+            find_suspect_hex_overrides(&per_mod_results, game_index)
+        } else {
+            Vec::new()
+        };
 
-        // Merge in reverse order (last mod first → first mod wins via last-writer-wins)
+        // Keep the highest-ranked override for each hash, as detect_conflicts
+        // reports it.
+        let layer_priorities = self.layer_priorities();
         let mut all_meta: HashMap<u64, OverrideMeta> = HashMap::new();
-
-        for mod_meta in per_mod_results.into_iter().rev() {
+        let mut ranks: HashMap<u64, OverrideRank> = HashMap::new();
+        for (mod_index, mod_meta) in per_mod_results.into_iter().enumerate() {
             for (hash, meta) in mod_meta {
-                all_meta.insert(hash, meta);
+                let layer_priority = layer_priorities[mod_index]
+                    .get(meta.source.layer())
+                    .copied()
+                    .unwrap_or(0);
+                let rank = self.override_rank(mod_index, layer_priority);
+                if ranks.get(&hash).is_none_or(|existing| rank > *existing) {
+                    ranks.insert(hash, rank);
+                    all_meta.insert(hash, meta);
+                }
             }
         }
 
@@ -358,7 +637,8 @@ impl OverlayBuilder {
         );
 
         // Filter on metadata (SubChunkTOC + lazy)
-        filter_override_metadata(&mut all_meta, game_index, game_dir);
+        filter_override_metadata(&mut all_meta, game_index, &self.game_dir, events);
+        conflicts.retain(|c| all_meta.contains_key(&c.path_hash));
 
         // Prune cache to only keep enabled mods
         let enabled_ids: Vec<String> = self.enabled_mods.iter().map(|m| m.id.clone()).collect();
@@ -369,7 +649,13 @@ impl OverlayBuilder {
             tracing::warn!("Failed to save override meta cache: {}", e);
         }
 
-        Ok((all_meta, mod_wad_reports))
+        Ok(OverrideCollection {
+            all_meta,
+            mod_wad_reports,
+            conflicts,
+            suspect_hex_overrides,
+            broken_overrides,
+        })
     }
 
     /// Pair each enabled mod with its un-merged metadata and turn it into a
@@ -387,7 +673,13 @@ impl OverlayBuilder {
             .zip(per_mod_results.iter())
             .zip(fingerprints.iter())
             .map(|((enabled_mod, mod_meta), fp)| {
-                ModWadReport::from_meta(enabled_mod.id.clone(), mod_meta, *fp, game_index)
+                ModWadReport::from_meta(
+                    enabled_mod.id.clone(),
+                    enabled_mod.content.format_version(),
+                    mod_meta,
+                    *fp,
+                    game_index,
+                )
             })
             .collect()
     }
@@ -397,6 +689,7 @@ impl OverlayBuilder {
 mod tests {
     use super::*;
     use crate::meta_cache::CachedOverride;
+    use crate::testing::mock_project;
     use ltk_mod_project::{ModProject, ModProjectLayer};
     use std::sync::{Arc, Mutex};
 
@@ -404,23 +697,14 @@ mod tests {
     struct MockModContent {
         layers: Vec<ModProjectLayer>,
         queried_layers: Arc<Mutex<Vec<String>>>,
+        format_version: ContentFormatVersion,
     }
 
     impl ModContentProvider for MockModContent {
         fn mod_project(&mut self) -> Result<ModProject> {
             Ok(ModProject {
-                name: "test-mod".to_string(),
-                display_name: "Test Mod".to_string(),
-                version: "1.0.0".to_string(),
-                description: "test".to_string(),
-                authors: vec![],
-                license: None,
-                tags: vec![],
-                champions: vec![],
-                maps: vec![],
-                transformers: vec![],
                 layers: self.layers.clone(),
-                thumbnail: None,
+                ..mock_project("test-mod")
             })
         }
 
@@ -450,6 +734,10 @@ mod tests {
         fn read_raw_override_file(&mut self, _rel_path: &Utf8Path) -> Result<Vec<u8>> {
             Ok(vec![])
         }
+
+        fn format_version(&self) -> ContentFormatVersion {
+            self.format_version
+        }
     }
 
     fn make_layers(names: &[&str]) -> Vec<ModProjectLayer> {
@@ -483,10 +771,18 @@ mod tests {
             content: Box::new(MockModContent {
                 layers: make_layers(&["base", "high_res", "extras"]),
                 queried_layers: Arc::clone(&queried),
+                format_version: ContentFormatVersion::FsV1,
             }),
             enabled_layers: None,
         };
-        let _ = collect_single_mod_metadata(&mut mod_all, &game_index, game_dir);
+        let _ = collect_single_mod_metadata(
+            &mut mod_all,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        );
         let all_queried: Vec<String> = queried.lock().unwrap().drain(..).collect();
         assert_eq!(all_queried, vec!["base", "high_res", "extras"]); // sorted by priority then name
 
@@ -497,15 +793,53 @@ mod tests {
             content: Box::new(MockModContent {
                 layers: make_layers(&["base", "high_res", "extras"]),
                 queried_layers: Arc::clone(&queried),
+                format_version: ContentFormatVersion::FsV1,
             }),
             enabled_layers: Some(HashSet::from(["extras".to_string()])),
         };
-        let _ = collect_single_mod_metadata(&mut mod_filtered, &game_index, game_dir);
+        let _ = collect_single_mod_metadata(
+            &mut mod_filtered,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        );
         let filtered_queried: Vec<String> = queried.lock().unwrap().drain(..).collect();
         assert_eq!(filtered_queried, vec!["base", "extras"]);
         // "high_res" should NOT appear, but "base" is always included
     }
 
+    #[test]
+    fn layerless_formats_only_read_the_base_layer() {
+        let queried = Arc::new(Mutex::new(Vec::new()));
+        let tmp = tempfile::tempdir().unwrap();
+        let game_dir_std = tmp.path().join("Game");
+        std::fs::create_dir_all(game_dir_std.join("DATA").join("FINAL")).unwrap();
+        let game_dir = Utf8Path::from_path(&game_dir_std).unwrap();
+        let game_index = GameIndex::build(game_dir).unwrap();
+
+        let mut fantome = EnabledMod {
+            id: "legacy".to_string(),
+            content: Box::new(MockModContent {
+                layers: make_layers(&["base", "high_res"]),
+                queried_layers: Arc::clone(&queried),
+                format_version: ContentFormatVersion::FantomeLegacy,
+            }),
+            enabled_layers: None,
+        };
+        collect_single_mod_metadata(
+            &mut fantome,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(*queried.lock().unwrap(), vec!["base"]);
+    }
+
     struct OverrideMockContent {
         layers: Vec<ModProjectLayer>,
         /// WAD name -> list of (rel_path, bytes) overrides to return.
@@ -515,18 +849,8 @@ mod tests {
     impl ModContentProvider for OverrideMockContent {
         fn mod_project(&mut self) -> Result<ModProject> {
             Ok(ModProject {
-                name: "test-mod".to_string(),
-                display_name: "Test Mod".to_string(),
-                version: "1.0.0".to_string(),
-                description: "test".to_string(),
-                authors: vec![],
-                license: None,
-                tags: vec![],
-                champions: vec![],
-                maps: vec![],
-                transformers: vec![],
                 layers: self.layers.clone(),
-                thumbnail: None,
+                ..mock_project("test-mod")
             })
         }
 
@@ -580,6 +904,9 @@ mod tests {
             hash_index,
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         let mut wad_overrides = HashMap::new();
@@ -611,7 +938,15 @@ mod tests {
             enabled_layers: None,
         };
 
-        let meta = collect_single_mod_metadata(&mut enabled_mod, &game_index, game_dir).unwrap();
+        let meta = collect_single_mod_metadata(
+            &mut enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
         assert_eq!(meta.len(), 2);
 
         let entry = &meta[&0xAAAA];
@@ -635,6 +970,9 @@ mod tests {
             hash_index: HashMap::new(),
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         let mut wad_overrides = HashMap::new();
@@ -657,7 +995,15 @@ mod tests {
             enabled_layers: None,
         };
 
-        let meta = collect_single_mod_metadata(&mut enabled_mod, &game_index, game_dir).unwrap();
+        let meta = collect_single_mod_metadata(
+            &mut enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
 
         assert_eq!(meta.len(), 1);
         assert!(
@@ -679,6 +1025,9 @@ mod tests {
             hash_index,
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         // The mod overrides a known chunk (0xAAAA, maps to Ahri.wad) and ships a brand-new
@@ -707,7 +1056,15 @@ mod tests {
             enabled_layers: None,
         };
 
-        let meta = collect_single_mod_metadata(&mut enabled_mod, &game_index, game_dir).unwrap();
+        let meta = collect_single_mod_metadata(
+            &mut enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
 
         let ahri = Utf8Path::new("DATA/FINAL/Champions/Ahri.wad.client");
         assert_eq!(meta[&0xAAAA].fallback_wad.as_deref(), Some(ahri));
@@ -718,6 +1075,278 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_new_asset_routed_by_its_path() {
+        let ahri = Utf8PathBuf::from("DATA/FINAL/Champions/Ahri.wad.client");
+        let annie = Utf8PathBuf::from("DATA/FINAL/Champions/Annie.wad.client");
+        let stamp = crate::game_index::WadStamp {
+            size: 0,
+            modified: 0,
+        };
+        let game_index = GameIndex {
+            hash_index: HashMap::from([(0xAAAA_u64, vec![ahri.clone()])]),
+            wad_stamps: HashMap::from([(ahri.clone(), stamp), (annie.clone(), stamp)]),
+            ..GameIndex::new()
+        };
+
+        // Mostly Ahri overrides, plus a new Annie asset under a WAD the game lacks.
+        let new_asset = "data/characters/annie/skins/skin99.bin";
+        let wad_overrides = HashMap::from([
+            (
+                "Ahri.wad.client".to_string(),
+                vec![(Utf8PathBuf::from("000000000000aaaa.bin"), b"a".to_vec())],
+            ),
+            (
+                "BrandNew.wad.client".to_string(),
+                vec![(Utf8PathBuf::from(new_asset), b"new".to_vec())],
+            ),
+        ]);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let game_dir = Utf8Path::from_path(tmp.path()).unwrap();
+        let mut enabled_mod = EnabledMod {
+            id: "new-asset-mod".to_string(),
+            content: Box::new(OverrideMockContent {
+                layers: make_layers(&["base"]),
+                wad_overrides,
+            }),
+            enabled_layers: None,
+        };
+
+        let meta = collect_single_mod_metadata(
+            &mut enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
+
+        let new_hash = ltk_modpkg::utils::hash_chunk_name(new_asset);
+        assert_eq!(meta[&new_hash].fallback_wad.as_ref(), Some(&annie));
+        assert_eq!(meta[&0xAAAA].fallback_wad.as_ref(), Some(&ahri));
+    }
+
+    #[test]
+    fn test_override_transform_applied_before_hashing() {
+        let game_index = GameIndex {
+            wad_index: HashMap::new(),
+            hash_index: HashMap::new(),
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+
+        let mut wad_overrides = HashMap::new();
+        wad_overrides.insert(
+            "Test.wad.client".to_string(),
+            vec![
+                (Utf8PathBuf::from("000000000000aaaa.bin"), b"old".to_vec()),
+                (Utf8PathBuf::from("000000000000bbbb.bin"), b"keep".to_vec()),
+            ],
+        );
+
+        let tmp = tempfile::tempdir().unwrap();
+        let game_dir_std = tmp.path().join("Game");
+        std::fs::create_dir_all(game_dir_std.join("DATA").join("FINAL")).unwrap();
+        let game_dir = Utf8Path::from_path(&game_dir_std).unwrap();
+
+        let mut enabled_mod = EnabledMod {
+            id: "transform-mod".to_string(),
+            content: Box::new(OverrideMockContent {
+                layers: make_layers(&["base"]),
+                wad_overrides,
+            }),
+            enabled_layers: None,
+        };
+
+        let transform: OverrideTransform =
+            Arc::new(|path_hash, _bytes| (path_hash == 0xAAAA).then(|| b"new!!".to_vec()));
+        let meta = collect_single_mod_metadata(
+            &mut enabled_mod,
+            &game_index,
+            game_dir,
+            Some(&transform),
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(meta[&0xAAAA].content_hash, xxh3_64(b"new!!"));
+        assert_eq!(meta[&0xAAAA].uncompressed_size, 5);
+        assert_eq!(meta[&0xBBBB].content_hash, xxh3_64(b"keep"));
+    }
+
+    #[test]
+    fn test_priority_order_selects_winner() {
+        let mut hash_index = HashMap::new();
+        hash_index.insert(
+            0xAAAA_u64,
+            vec![Utf8PathBuf::from("DATA/FINAL/Champions/Ahri.wad.client")],
+        );
+        let game_index = GameIndex {
+            wad_index: HashMap::new(),
+            hash_index,
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        std::fs::create_dir_all(root.join("Game/DATA/FINAL")).unwrap();
+
+        let make_mod = |id: &str, bytes: &[u8]| {
+            let mut wad_overrides = HashMap::new();
+            wad_overrides.insert(
+                "Ahri.wad.client".to_string(),
+                vec![(Utf8PathBuf::from("000000000000aaaa.bin"), bytes.to_vec())],
+            );
+            EnabledMod {
+                id: id.to_string(),
+                content: Box::new(OverrideMockContent {
+                    layers: make_layers(&["base"]),
+                    wad_overrides,
+                }),
+                enabled_layers: None,
+            }
+        };
+
+        for (order, expected) in [
+            (PriorityOrder::FrontWins, b"front"),
+            (PriorityOrder::BackWins, b"back!"),
+        ] {
+            let mut builder =
+                OverlayBuilder::new(root.join("Game"), root.join("overlay"), root.to_path_buf())
+                    .with_priority_order(order);
+            builder.set_enabled_mods(vec![
+                make_mod("front", b"front"),
+                make_mod("back", b"back!"),
+            ]);
+
+            let meta = builder
+                .collect_all_override_metadata(&game_index)
+                .unwrap()
+                .all_meta;
+            assert_eq!(meta[&0xAAAA].content_hash, xxh3_64(expected), "{:?}", order);
+        }
+    }
+
+    /// Delegates to `inner` after sleeping, to make reads finish out of order.
+    struct SlowContent {
+        delay: std::time::Duration,
+        inner: OverrideMockContent,
+    }
+
+    impl ModContentProvider for SlowContent {
+        fn mod_project(&mut self) -> Result<ModProject> {
+            std::thread::sleep(self.delay);
+            self.inner.mod_project()
+        }
+
+        fn list_layer_wads(&mut self, layer: &str) -> Result<Vec<String>> {
+            self.inner.list_layer_wads(layer)
+        }
+
+        fn read_wad_overrides(
+            &mut self,
+            layer: &str,
+            wad_name: &str,
+        ) -> Result<Vec<(Utf8PathBuf, Vec<u8>)>> {
+            self.inner.read_wad_overrides(layer, wad_name)
+        }
+
+        fn read_wad_override_file(
+            &mut self,
+            layer: &str,
+            wad_name: &str,
+            rel_path: &Utf8Path,
+        ) -> Result<Vec<u8>> {
+            self.inner.read_wad_override_file(layer, wad_name, rel_path)
+        }
+
+        fn read_raw_override_file(&mut self, rel_path: &Utf8Path) -> Result<Vec<u8>> {
+            self.inner.read_raw_override_file(rel_path)
+        }
+    }
+
+    #[test]
+    fn test_parallel_collection_is_deterministic() {
+        let mut hash_index = HashMap::new();
+        hash_index.insert(
+            0xAAAA_u64,
+            vec![Utf8PathBuf::from("DATA/FINAL/Champions/Ahri.wad.client")],
+        );
+        let game_index = GameIndex {
+            wad_index: HashMap::new(),
+            hash_index,
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        std::fs::create_dir_all(root.join("Game/DATA/FINAL")).unwrap();
+
+        // The highest-priority mod is the slowest to read.
+        let ids: Vec<String> = (0..6).map(|i| format!("mod{i}")).collect();
+        let mods = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let mut wad_overrides = HashMap::new();
+                wad_overrides.insert(
+                    "Ahri.wad.client".to_string(),
+                    vec![(
+                        Utf8PathBuf::from("000000000000aaaa.bin"),
+                        id.as_bytes().to_vec(),
+                    )],
+                );
+                EnabledMod {
+                    id: id.clone(),
+                    content: Box::new(SlowContent {
+                        delay: std::time::Duration::from_millis(5 * (6 - i as u64)),
+                        inner: OverrideMockContent {
+                            layers: make_layers(&["base"]),
+                            wad_overrides,
+                        },
+                    }),
+                    enabled_layers: None,
+                }
+            })
+            .collect();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let mut builder =
+            OverlayBuilder::new(root.join("Game"), root.join("overlay"), root.to_path_buf())
+                .with_event_sink(move |event| sink.lock().unwrap().push(event));
+        builder.set_enabled_mods(mods);
+
+        let collection = builder.collect_all_override_metadata(&game_index).unwrap();
+        assert_eq!(collection.all_meta[&0xAAAA].content_hash, xxh3_64(b"mod0"));
+        assert_eq!(collection.conflicts[0].winner, "mod0");
+
+        let collected: Vec<String> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                BuildEvent::ModCollected { mod_id, .. } => Some(mod_id.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(collected, ids);
+    }
+
     #[test]
     fn test_reconstruct_from_cache() {
         let cached = CachedModMeta {
@@ -744,6 +1373,7 @@ mod tests {
                     linked_bins: Vec::new(),
                 },
             ],
+            broken_overrides: Vec::new(),
         };
 
         let meta = cached.reconstruct("test-mod");
@@ -5,36 +5,82 @@
 //!
 //! # Two-Pass Build Algorithm
 //!
-//! 1. Validate that `game_dir/DATA/FINAL` exists.
+//! 1. Resolve `game_dir` to the `Game/` directory (the install root is also
+//!    accepted) and validate that `DATA/FINAL` exists.
 //! 2. Build (or load from cache) a [`GameIndex`] from all `.wad.client` files.
 //! 3. Load the saved [`OverlayState`] and choose a build strategy:
 //!    - **Skip**: mod list, game fingerprint, and per-WAD fingerprints all match,
 //!      and every overlay WAD file still exists on disk.
 //!    - **Incremental**: game fingerprint and state version match but mod list
 //!      differs. Compute per-WAD override fingerprints and only rebuild WADs
-//!      whose fingerprint changed. Remove stale WADs no longer needed.
-//!    - **Full rebuild**: state version or game fingerprint mismatch. Wipe all
-//!      overlay WAD files and rebuild everything from scratch.
+//!      whose fingerprint changed. Stale WADs no longer needed are dropped.
+//!    - **Full rebuild**: state version or game fingerprint mismatch. Rebuild
+//!      every overlay WAD from scratch.
 //! 4. **Pass 1**: Collect lightweight override metadata (hashes, sizes, source
 //!    locations) from all mods. Uses a persistent metadata cache to skip
 //!    unchanged mods entirely. Bytes are hashed and then dropped.
 //! 5. Distribute override hashes to WADs, partition into rebuild/reuse sets.
 //! 6. **Pass 2**: Re-read override bytes only for WADs that need rebuilding.
-//!    Call [`build_patched_wad`](crate::wad_builder::build_patched_wad).
-//! 7. Persist the new [`OverlayState`] with per-WAD fingerprints.
-
+//!    Call [`build_patched_wad`](crate::wad_builder::build_patched_wad), writing
+//!    into the staging root (`overlay_root/.staging`), which also holds the
+//!    reused WADs.
+//! 7. Swap the staged `DATA` tree in for the live one and persist the new
+//!    [`OverlayState`] with per-WAD fingerprints.
+//!
+//! Steps 1-5 write nothing to the overlay, so [`OverlayBuilder::plan`] can run
+//! them alone as a dry run.
+
+#[cfg(feature = "bench-internals")]
+#[doc(hidden)]
+pub mod bench_support;
+mod broken_overrides;
+mod compression_hints;
+mod conflicts;
+mod events;
+mod hex_names;
+mod layer_ordering;
+mod lint;
 mod metadata;
+mod plan;
+mod progress;
+mod raw_overrides;
 mod resolve;
-
-use crate::content::ModContentProvider;
+mod retry;
+mod semantic_conflicts;
+mod signatures;
+mod snapshot;
+mod spill;
+mod staging;
+mod string_overrides;
+
+pub use broken_overrides::{BrokenOverride, BrokenOverrideKind, BuildWarning};
+pub use events::{BuildEvent, FilterReason};
+pub use hex_names::SuspectHexOverride;
+pub use layer_ordering::{LayerOrderingIssue, LayerRef};
+pub use lint::{LintedOverride, ModLintReport};
+pub use plan::{OverlayPlan, PlannedWad};
+pub use retry::RetryPolicy;
+pub use semantic_conflicts::{SemanticConflict, SemanticConflictKind};
+pub use signatures::{ModSignature, SignaturePolicy, SignatureStatus, SignatureTrust};
+pub use snapshot::{BuildResultDiff, BuildResultSnapshot};
+pub use staging::STAGING_DIR_NAME;
+
+pub(crate) use metadata::layers_in_order;
+
+use crate::backup::WadBackup;
+use crate::content::{ContentFormatVersion, ModContentProvider};
 use crate::error::{Error, Result};
 use crate::game_index::GameIndex;
 use crate::linked_bins::{collect_linked_bin_offenders, LinkedBinOffender};
 use crate::state::OverlayState;
+use crate::wad_builder::WadPatchOptions;
 use camino::{Utf8Path, Utf8PathBuf};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use conflicts::OverrideRank;
+use events::{emit_event, EventSink};
+use ltk_wad::WadChunkCompression;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Shared byte buffer for override data distributed across multiple WADs.
 ///
@@ -57,14 +103,46 @@ pub(crate) enum OverrideSource {
         mod_id: String,
         rel_path: Utf8PathBuf,
     },
+    /// Override synthesized by the builder, such as a string table with the
+    /// mods' string overrides applied. Held in memory; never cached.
+    Generated {
+        rel_path: Utf8PathBuf,
+        bytes: SharedBytes,
+    },
+}
+
+/// File extension of the overlay WADs the builder writes unless
+/// [`OverlayBuilder::with_output_extension`] sets another one.
+pub const DEFAULT_OUTPUT_EXTENSION: &str = ".wad.client";
+
+/// Path of the overlay WAD for the game-relative `wad_path` when overlay WADs
+/// are written with `output_extension`.
+pub(crate) fn overlay_wad_rel_path(wad_path: &Utf8Path, output_extension: &str) -> Utf8PathBuf {
+    if output_extension == DEFAULT_OUTPUT_EXTENSION {
+        return wad_path.to_path_buf();
+    }
+    let file_name = wad_path.file_name().unwrap_or_default();
+    let stem_len = if file_name
+        .to_ascii_lowercase()
+        .ends_with(DEFAULT_OUTPUT_EXTENSION)
+    {
+        file_name.len() - DEFAULT_OUTPUT_EXTENSION.len()
+    } else {
+        file_name.len()
+    };
+    wad_path.with_file_name(format!("{}{}", &file_name[..stem_len], output_extension))
 }
 
+/// Stand-in mod ID reported for [`OverrideSource::Generated`] overrides.
+pub(crate) const GENERATED_MOD_ID: &str = "<generated>";
+
 impl OverrideSource {
     /// The id of the mod this override came from.
     pub(crate) fn mod_id(&self) -> &str {
         match self {
             OverrideSource::LayerWad { mod_id, .. } => mod_id,
             OverrideSource::Raw { mod_id, .. } => mod_id,
+            OverrideSource::Generated { .. } => GENERATED_MOD_ID,
         }
     }
 
@@ -73,6 +151,16 @@ impl OverrideSource {
         match self {
             OverrideSource::LayerWad { rel_path, .. } => rel_path.as_path(),
             OverrideSource::Raw { rel_path, .. } => rel_path.as_path(),
+            OverrideSource::Generated { rel_path, .. } => rel_path.as_path(),
+        }
+    }
+
+    /// The layer this override belongs to. RAW and generated overrides count as
+    /// part of the base layer.
+    pub(crate) fn layer(&self) -> &str {
+        match self {
+            OverrideSource::LayerWad { layer, .. } => layer,
+            OverrideSource::Raw { .. } | OverrideSource::Generated { .. } => BASE_LAYER_NAME,
         }
     }
 }
@@ -91,6 +179,12 @@ pub struct OverrideMeta {
     /// property-bin (`PROP`/`PTCH`); empty otherwise. Parsed once in pass 1 and
     /// cached so the linked-bin pre-flight needs no re-decompression.
     pub(crate) linked_bins: Vec<String>,
+    /// Compression pinned by the mod's [`ModProject::compression`] hints, used
+    /// instead of picking one from the file type. Re-derived from the project on
+    /// every build, so it is never stored in the metadata cache.
+    ///
+    /// [`ModProject::compression`]: ltk_mod_project::ModProject::compression
+    pub(crate) compression: Option<WadChunkCompression>,
 }
 
 /// A mod to be included in the overlay build.
@@ -99,7 +193,8 @@ pub struct OverrideMeta {
 /// Mods are processed in the order they appear in the `enabled_mods` list passed to
 /// [`OverlayBuilder::set_enabled_mods`]. Position 0 (first in the list) has the
 /// **highest** priority — when two mods override the same path hash, the mod
-/// closer to the front of the list wins.
+/// closer to the front of the list wins. [`OverlayBuilder::with_priority_order`]
+/// can reverse this.
 pub struct EnabledMod {
     /// Unique identifier for the mod (used in state tracking and logging).
     pub id: String,
@@ -207,7 +302,8 @@ impl OverlayProgress {
 
 /// Stages of the overlay build pipeline.
 ///
-/// Emitted in order: `Indexing` -> `CollectingOverrides` -> `PatchingWad` (repeated) -> `Complete`.
+/// Emitted in order: `Indexing` -> `CollectingOverrides` -> `ApplyingStringOverrides` ->
+/// `PatchingWad` (repeated) -> `Complete`.
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum OverlayStage {
@@ -217,7 +313,7 @@ pub enum OverlayStage {
     CollectingOverrides,
     /// Building a patched WAD file in the overlay directory.
     PatchingWad,
-    /// Applying string table overrides (reserved for future use).
+    /// Applying the mods' string overrides to the game's string tables.
     ApplyingStringOverrides,
     /// Build finished successfully.
     Complete,
@@ -228,27 +324,198 @@ pub enum OverlayStage {
 pub struct OverlayBuildResult {
     /// Root directory of the overlay (mirrors the game's `DATA/FINAL` structure).
     pub overlay_root: Utf8PathBuf,
+    /// File extension of the overlay WADs (see
+    /// [`OverlayBuilder::with_output_extension`]).
+    pub output_extension: String,
     /// WAD files that were freshly built during this run.
     pub wads_built: Vec<Utf8PathBuf>,
     /// WAD files reused from a previous build (unchanged fingerprint).
     pub wads_reused: Vec<Utf8PathBuf>,
-    /// Detected conflicts between mods (not yet implemented).
+    /// Chunks overridden by more than one mod, sorted by path hash.
+    ///
+    /// Empty when the build was skipped because nothing changed.
     pub conflicts: Vec<Conflict>,
+    /// Mods that declare the same champion, map or exclusive category (see
+    /// [`OverlayBuilder::analyze_semantic_conflicts`]).
+    ///
+    /// Computed on every build, including skipped ones.
+    pub semantic_conflicts: Vec<SemanticConflict>,
+    /// Which build strategy was used and why.
+    pub rebuild_reason: RebuildReason,
     /// Wall-clock time for the entire build.
     pub build_time: Duration,
+    /// The [time budget](OverlayBuilder::with_time_budget) ran out before every
+    /// WAD was patched. The overlay is missing those WADs and must be finished
+    /// with another [`build`](OverlayBuilder::build) before the game is launched.
+    pub incomplete: bool,
+    /// Statistics for each WAD in [`wads_built`](Self::wads_built), in the same
+    /// order.
+    pub wad_stats: Vec<WadBuildStats>,
+    /// Problems that did not stop the build, such as override files skipped
+    /// because they are empty or git LFS pointers.
+    ///
+    /// When the build was skipped because nothing changed, only untrusted mods
+    /// (see [`OverlayBuilder::with_signature_policy`]) are reported.
+    pub warnings: Vec<BuildWarning>,
+    /// Signature status of each enabled mod, in enabled-mod order.
+    ///
+    /// Empty under [`SignaturePolicy::Ignore`], the default.
+    pub signatures: Vec<ModSignature>,
+    /// Net change in bytes of the overlay WADs' total size since the previous
+    /// build; positive when the overlay grew.
+    ///
+    /// `None` when the previous overlay state does not record WAD sizes, as
+    /// with states written by older versions of this crate.
+    pub size_delta: Option<i64>,
+}
+
+/// What one WAD patched during a build contains.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WadBuildStats {
+    /// Path of the WAD relative to the game directory (e.g. `DATA/FINAL/Champions/Aatrox.wad.client`).
+    pub path: Utf8PathBuf,
+    /// Number of chunks written from mod overrides, including new entries.
+    pub override_count: usize,
+    /// Number of chunks in the game's original WAD.
+    pub original_chunk_count: usize,
+    /// Size of the overlay WAD in bytes.
+    pub bytes_written: u64,
+}
+
+/// Why [`OverlayBuilder::build`] did (or did not) rebuild the overlay.
+///
+/// Lets callers tell the user what happened, e.g. "League was updated,
+/// rebuilding mods", without inferring it from built/reused counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RebuildReason {
+    /// Nothing changed since the last build; no WADs were touched.
+    ExactMatchSkipped,
+    /// Only WADs whose overrides changed were rebuilt (also used to restore
+    /// overlay WADs missing from disk).
+    Incremental,
+    /// The game was patched since the last build, so every WAD was rebuilt.
+    FullRebuildGamePatched,
+    /// The saved overlay state was written by an incompatible version of this
+    /// crate, so every WAD was rebuilt.
+    FullRebuildVersionBump,
+    /// No previous overlay state exists (first build, or after
+    /// [`OverlayBuilder::rebuild_all`]), so every WAD was built.
+    FullRebuildNoPreviousState,
+    /// The overlay was built for another profile (see
+    /// [`OverlayBuilder::with_profile_id`]), so every WAD was rebuilt.
+    FullRebuildProfileChanged,
+    /// The overlay was written with another output extension (see
+    /// [`OverlayBuilder::with_output_extension`]), so every WAD was rebuilt.
+    FullRebuildOutputExtensionChanged,
+    /// The previous build was interrupted. The WADs it finished that are still
+    /// up to date were kept and every other WAD was rebuilt.
+    ResumedInterruptedBuild,
+    /// No mods are enabled; the overlay was cleared.
+    EmptyModList,
 }
 
-/// A conflict where multiple mods override the same chunk (not yet implemented).
-#[derive(Debug, Clone)]
+impl OverlayBuildResult {
+    /// Render a short multi-line, human-readable report of this build.
+    ///
+    /// Lists the WADs built (with the bytes written) and reused, conflicts and
+    /// every [warning](Self::warnings).
+    ///
+    /// Shared by every front end so build output reads the same everywhere.
+    pub fn summary(&self) -> String {
+        let redundant = self
+            .conflicts
+            .iter()
+            .filter(|c| c.kind == ConflictKind::Redundant)
+            .count();
+        let divergent = self.conflicts.len() - redundant;
+
+        let bytes_written: u64 = self.wad_stats.iter().map(|stats| stats.bytes_written).sum();
+
+        let mut summary = format!(
+            "Overlay: {}\n\
+             WADs built: {} ({} written)\n\
+             WADs reused: {}\n\
+             Conflicts: {} ({} divergent, {} redundant)\n\
+             Build time: {:.2}s\n\
+             Warnings: {}",
+            self.overlay_root,
+            self.wads_built.len(),
+            ltk_modpkg::utils::format_size(bytes_written),
+            self.wads_reused.len(),
+            self.conflicts.len(),
+            divergent,
+            redundant,
+            self.build_time.as_secs_f64(),
+            self.warnings.len(),
+        );
+        for warning in &self.warnings {
+            summary.push_str(&format!("\n  - {}", warning));
+        }
+        if self.incomplete {
+            summary.push_str("\nIncomplete: time budget exceeded, build again before launching");
+        }
+        summary
+    }
+
+    /// Game WADs left out of the overlay, which load unmodified from the game.
+    ///
+    /// Together with [`wads_built`](Self::wads_built) and
+    /// [`wads_reused`](Self::wads_reused) this covers every WAD in `game_index`,
+    /// for patchers that need an explicit list of what to redirect. Paths are
+    /// the game paths from the index, sorted.
+    pub fn passthrough_wads(&self, game_index: &GameIndex) -> Vec<Utf8PathBuf> {
+        let overlaid: Vec<&Utf8Path> = self
+            .wads_built
+            .iter()
+            .chain(&self.wads_reused)
+            .map(|wad| wad.strip_prefix(&self.overlay_root).unwrap_or(wad))
+            .collect();
+
+        let mut passthrough: Vec<Utf8PathBuf> = game_index
+            .wad_index
+            .values()
+            .flatten()
+            .filter(|game_wad| {
+                let overlay_wad = overlay_wad_rel_path(game_wad, &self.output_extension);
+                !overlaid.iter().any(|wad| overlay_wad.ends_with(wad))
+            })
+            .cloned()
+            .collect();
+        passthrough.sort();
+        passthrough
+    }
+}
+
+/// Whether the mods in a [`Conflict`] actually disagree about the chunk's bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// At least one contribution differs from the winner; the losing mods'
+    /// changes are not applied.
+    Divergent,
+    /// Every contribution has the same bytes as the winner, so the result is
+    /// identical whichever mod wins (e.g. a shared recolor shipped by several mods).
+    Redundant,
+}
+
+/// A chunk that multiple enabled mods override.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Conflict {
     /// xxHash3 path hash of the conflicting chunk.
     pub path_hash: u64,
-    /// Human-readable path (if available from hash tables).
+    /// Path of the winning override file. For files named by their hash, the
+    /// path from the hashtable set with [`OverlayBuilder::with_hashtable`], if it
+    /// has one.
     pub path: String,
     /// All mods that contributed an override for this chunk.
     pub contributing_mods: Vec<ModContribution>,
     /// The mod whose override was used (last-writer-wins).
     pub winner: String,
+    /// Whether the contributions differ from the winner's bytes.
+    pub kind: ConflictKind,
 }
 
 /// One game WAD a mod's overrides land in, paired with how many land there.
@@ -286,6 +553,10 @@ pub struct AffectedWad {
 pub struct ModWadReport {
     /// Mod identifier (matches [`EnabledMod::id`]).
     pub mod_id: String,
+    /// Storage format the mod was read from, from
+    /// [`ModContentProvider::format_version`].
+    #[serde(default)]
+    pub format_version: ContentFormatVersion,
     /// The game WADs the mod's overrides land in, each with its per-WAD override
     /// count. Sorted by [`AffectedWad::path`] and deduplicated. Use
     /// [`wad_paths`](Self::wad_paths) for the paths alone.
@@ -316,6 +587,7 @@ impl ModWadReport {
     /// pointed at). A hash present in several WADs counts toward each.
     pub(crate) fn from_meta(
         mod_id: String,
+        format_version: ContentFormatVersion,
         mod_meta: &HashMap<u64, OverrideMeta>,
         content_fingerprint: Option<u64>,
         game_index: &GameIndex,
@@ -333,6 +605,7 @@ impl ModWadReport {
 
         Self {
             mod_id,
+            format_version,
             affected_wads: counts
                 .into_iter()
                 .map(|(path, override_count)| AffectedWad {
@@ -348,7 +621,8 @@ impl ModWadReport {
 }
 
 /// Details about one mod's contribution to a conflicting chunk.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ModContribution {
     /// Unique mod identifier.
     pub mod_id: String,
@@ -360,10 +634,63 @@ pub struct ModContribution {
     pub priority: i32,
     /// Position in the enabled mods list (0-based).
     pub install_order: usize,
+    /// xxHash3 of the uncompressed override bytes this mod provides.
+    pub content_hash: u64,
+}
+
+/// How the position of a mod in the enabled list maps to its priority.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityOrder {
+    /// The first mod in the list has the highest priority (default).
+    #[default]
+    FrontWins,
+    /// The last mod in the list has the highest priority, as in bottom-up load orders.
+    BackWins,
+}
+
+/// How the winning override is chosen when several mods override the same chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    /// The mod closest to the front of the enabled list wins (default).
+    #[default]
+    FirstWins,
+    /// The mod closest to the back of the enabled list wins.
+    LastWins,
+    /// The override from the layer with the highest priority wins, whichever mod
+    /// it comes from. Ties go to the mod that wins under the [`PriorityOrder`].
+    HighestPriority,
 }
 
 pub(crate) type ProgressCallback = Arc<dyn Fn(OverlayProgress) + Send + Sync>;
 
+/// Hook that may rewrite an override's bytes, keyed by its chunk path hash.
+///
+/// Returning `Some` replaces the bytes; `None` leaves them unchanged.
+pub(crate) type OverrideTransform = Arc<dyn Fn(u64, &[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Predicate over game-relative WAD paths; WADs it rejects receive no overrides.
+pub(crate) type WadFilter = Arc<dyn Fn(&Utf8Path) -> bool + Send + Sync>;
+
+/// Net change from `prev_total` bytes to `new_total` bytes, if `prev_total` is
+/// known.
+fn size_delta(prev_total: Option<u64>, new_total: u64) -> Option<i64> {
+    prev_total.map(|prev| new_total as i64 - prev as i64)
+}
+
+/// Run `transform` (if any) over one override's bytes.
+pub(crate) fn apply_override_transform(
+    transform: Option<&OverrideTransform>,
+    path_hash: u64,
+    bytes: Vec<u8>,
+) -> Vec<u8> {
+    match transform.and_then(|t| t(path_hash, &bytes)) {
+        Some(replaced) => replaced,
+        None => bytes,
+    }
+}
+
 /// Orchestrates the overlay build pipeline.
 ///
 /// Create a builder with [`new`](Self::new), configure it with
@@ -382,6 +709,36 @@ pub struct OverlayBuilder {
     enabled_mods: Vec<EnabledMod>,
     blocked_wads: HashSet<String>,
     progress_callback: Option<ProgressCallback>,
+    /// Optional receiver of structured build events, set via
+    /// [`with_event_sink`](Self::with_event_sink).
+    event_sink: Option<EventSink>,
+    /// Optional hook applied to every override's bytes in both passes, set via
+    /// [`with_override_transform`](Self::with_override_transform).
+    override_transform: Option<OverrideTransform>,
+    /// Optional predicate restricting which WADs are patched, set via
+    /// [`with_wad_filter`](Self::with_wad_filter).
+    wad_filter: Option<WadFilter>,
+    /// Identifies `wad_filter` in the overlay state, set via
+    /// [`with_wad_filter_key`](Self::with_wad_filter_key).
+    wad_filter_key: Option<String>,
+    /// Optional staging directory where patched WADs are written before being
+    /// moved into `overlay_root`, set via [`with_scratch_dir`](Self::with_scratch_dir).
+    scratch_dir: Option<Utf8PathBuf>,
+    /// Which end of `enabled_mods` wins when mods override the same chunk.
+    priority_order: PriorityOrder,
+    /// How the winner among conflicting overrides is chosen.
+    conflict_strategy: ConflictStrategy,
+    /// Time after which no new WAD is patched, set via
+    /// [`with_time_budget`](Self::with_time_budget).
+    time_budget: Option<Duration>,
+    /// Hashtable file attached to the game index, set via
+    /// [`with_hashtable`](Self::with_hashtable).
+    hashtable_path: Option<Utf8PathBuf>,
+    /// In-memory budget for override bytes during pass 2, set via
+    /// [`with_spill`](Self::with_spill). Overrides beyond it are spilled to disk.
+    spill_max_memory: Option<u64>,
+    /// Layout options passed to [`build_patched_wad_with_options`](crate::wad_builder::build_patched_wad_with_options).
+    wad_patch_options: WadPatchOptions,
     /// Per-mod WAD reports captured during the most recent successful
     /// [`build`](Self::build), drained via [`take_mod_wad_reports`](Self::take_mod_wad_reports).
     last_mod_wad_reports: Vec<ModWadReport>,
@@ -389,6 +746,38 @@ pub struct OverlayBuilder {
     /// [`build`](Self::build), drained via
     /// [`take_linked_bin_offenders`](Self::take_linked_bin_offenders).
     last_linked_bin_offenders: Vec<LinkedBinOffender>,
+    /// Whether to cross-check hex-named overrides against the game index.
+    strict_hex_names: bool,
+    /// Hex-named overrides matching no game chunk from the most recent
+    /// [`build`](Self::build), drained via
+    /// [`take_suspect_hex_overrides`](Self::take_suspect_hex_overrides).
+    last_suspect_hex_overrides: Vec<SuspectHexOverride>,
+    /// Retry policy for provider override reads, set via
+    /// [`with_read_retries`](Self::with_read_retries).
+    read_retries: RetryPolicy,
+    /// Whether to back up replaced overlay WADs, set via
+    /// [`with_backup`](Self::with_backup).
+    backup: bool,
+    /// Whether WADs with no overrides are linked from the game instead of
+    /// rewritten, set via [`with_link_unmodified_wads`](Self::with_link_unmodified_wads).
+    link_unmodified_wads: bool,
+    /// Profile the overlay is built for, set via
+    /// [`with_profile_id`](Self::with_profile_id).
+    profile_id: Option<String>,
+    /// Suffix of the overlay WAD files, set via
+    /// [`with_output_extension`](Self::with_output_extension).
+    output_extension: String,
+    /// How untrusted mods are handled, set via
+    /// [`with_signature_policy`](Self::with_signature_policy).
+    signature_policy: SignaturePolicy,
+    /// Certificates signatures are verified against, set via
+    /// [`with_signature_trust`](Self::with_signature_trust).
+    signature_trust: SignatureTrust,
+    /// Overrides injected without a mod, set via
+    /// [`set_raw_overrides`](Self::set_raw_overrides).
+    raw_overrides: HashMap<u64, SharedBytes>,
+    /// WADs to add raw overrides to when their hash is in no game WAD.
+    raw_override_targets: HashMap<u64, Utf8PathBuf>,
 }
 
 impl OverlayBuilder {
@@ -396,8 +785,9 @@ impl OverlayBuilder {
     ///
     /// # Arguments
     ///
-    /// * `game_dir` — Path to the League of Legends `Game/` directory. Must contain
-    ///   a `DATA/FINAL` subdirectory with `.wad.client` files.
+    /// * `game_dir` — Path to the League of Legends `Game/` directory (containing
+    ///   `DATA/FINAL`), or the install root above it. Normalized with
+    ///   [`ltk_mod_core::resolve_game_dir`] when building.
     /// * `overlay_root` — Directory where patched WAD files will be written
     ///   (e.g. `profiles/default/overlay`).
     /// * `state_dir` — Directory for `overlay.json` and `game_index.bin`
@@ -410,8 +800,30 @@ impl OverlayBuilder {
             enabled_mods: Vec::new(),
             blocked_wads: HashSet::new(),
             progress_callback: None,
+            event_sink: None,
+            override_transform: None,
+            wad_filter: None,
+            wad_filter_key: None,
+            scratch_dir: None,
+            priority_order: PriorityOrder::default(),
+            conflict_strategy: ConflictStrategy::default(),
+            time_budget: None,
+            hashtable_path: None,
+            spill_max_memory: None,
+            wad_patch_options: WadPatchOptions::default(),
             last_mod_wad_reports: Vec::new(),
             last_linked_bin_offenders: Vec::new(),
+            strict_hex_names: false,
+            last_suspect_hex_overrides: Vec::new(),
+            read_retries: RetryPolicy::default(),
+            backup: false,
+            link_unmodified_wads: false,
+            profile_id: None,
+            output_extension: DEFAULT_OUTPUT_EXTENSION.to_string(),
+            signature_policy: SignaturePolicy::default(),
+            signature_trust: SignatureTrust::default(),
+            raw_overrides: HashMap::new(),
+            raw_override_targets: HashMap::new(),
         }
     }
 
@@ -436,6 +848,16 @@ impl OverlayBuilder {
         std::mem::take(&mut self.last_linked_bin_offenders)
     }
 
+    /// Drain the suspect hex-named overrides found during the most recent
+    /// [`build`](Self::build).
+    ///
+    /// Always empty unless [`with_strict_hex_names`](Self::with_strict_hex_names)
+    /// is enabled. Also empty when the build was skipped because nothing changed,
+    /// since no mod content is read in that case.
+    pub fn take_suspect_hex_overrides(&mut self) -> Vec<SuspectHexOverride> {
+        std::mem::take(&mut self.last_suspect_hex_overrides)
+    }
+
     /// Analyze a single mod's WAD footprint without building or modifying any
     /// overlay artifacts.
     ///
@@ -450,24 +872,25 @@ impl OverlayBuilder {
         state_dir: &Utf8Path,
         enabled_mod: &mut EnabledMod,
     ) -> Result<ModWadReport> {
-        let data_final_dir = game_dir.join("DATA").join("FINAL");
-        if !data_final_dir.as_std_path().exists() {
-            return Err(format!(
-                "League path does not contain Game/DATA/FINAL. Game dir: '{}'",
-                game_dir
-            )
-            .into());
-        }
+        let game_dir = &ltk_mod_core::resolve_game_dir(game_dir)?;
 
         std::fs::create_dir_all(state_dir.as_std_path())?;
         let cache_path = state_dir.join("game_index.bin");
         let game_index = GameIndex::load_or_build(game_dir, &cache_path)?;
 
         let fingerprint = enabled_mod.cache_fingerprint();
-        let mod_meta = metadata::collect_single_mod_metadata(enabled_mod, &game_index, game_dir)?;
+        let mod_meta = metadata::collect_single_mod_metadata(
+            enabled_mod,
+            &game_index,
+            game_dir,
+            None,
+            &RetryPolicy::default(),
+            None,
+        )?;
 
         Ok(ModWadReport::from_meta(
             enabled_mod.id.clone(),
+            enabled_mod.content.format_version(),
             &mod_meta,
             fingerprint,
             &game_index,
@@ -486,6 +909,292 @@ impl OverlayBuilder {
         self
     }
 
+    /// Register a sink for structured [`BuildEvent`]s.
+    ///
+    /// The sink receives the key steps of each build (WADs resolved, overrides
+    /// added and filtered, WADs patched, ...) as they happen, independently of
+    /// any `tracing` subscriber. It may be called from worker threads.
+    pub fn with_event_sink<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(BuildEvent) + Send + Sync + 'static,
+    {
+        self.event_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Register a hook that can rewrite override bytes before they are written.
+    ///
+    /// The hook receives each override's chunk path hash and bytes. Returning
+    /// `Some(bytes)` replaces the override; returning `None` keeps it as-is.
+    ///
+    /// The transform runs when override metadata is collected (pass 1) and again
+    /// when bytes are re-read for patching (pass 2), so content hashes and per-WAD
+    /// fingerprints reflect the transformed bytes. It must therefore be
+    /// deterministic. Because a closure cannot be fingerprinted, the persistent
+    /// override metadata cache is bypassed while a transform is registered.
+    pub fn with_override_transform<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(u64, &[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.override_transform = Some(Arc::new(transform));
+        self
+    }
+
+    /// Stage patched WADs in `scratch_dir` before moving them into the build's
+    /// staging root inside the overlay root.
+    ///
+    /// Useful for pointing the write-heavy part of a build at fast or volatile
+    /// storage such as a RAM disk. Each WAD is written under `scratch_dir` using its
+    /// game-relative path, then renamed into place. When the two directories are on
+    /// different filesystems the WAD is copied instead and the staged file removed.
+    pub fn with_scratch_dir(mut self, scratch_dir: Utf8PathBuf) -> Self {
+        self.scratch_dir = Some(scratch_dir);
+        self
+    }
+
+    /// Choose which end of the enabled mod list has the highest priority.
+    ///
+    /// Defaults to [`PriorityOrder::FrontWins`]. Use [`PriorityOrder::BackWins`] when
+    /// the mod list comes from a UI that presents load order bottom-up, instead of
+    /// reversing the list before calling [`set_enabled_mods`](Self::set_enabled_mods).
+    pub fn with_priority_order(mut self, order: PriorityOrder) -> Self {
+        self.priority_order = order;
+        if self.conflict_strategy != ConflictStrategy::HighestPriority {
+            self.conflict_strategy = match order {
+                PriorityOrder::FrontWins => ConflictStrategy::FirstWins,
+                PriorityOrder::BackWins => ConflictStrategy::LastWins,
+            };
+        }
+        self
+    }
+
+    /// Choose how the winner is picked when several mods override the same chunk.
+    ///
+    /// Defaults to [`ConflictStrategy::FirstWins`]. `FirstWins` and `LastWins` are
+    /// the same as [`with_priority_order`](Self::with_priority_order) with
+    /// [`PriorityOrder::FrontWins`] and [`PriorityOrder::BackWins`].
+    /// [`ConflictStrategy::HighestPriority`] compares the priority of the layers
+    /// the overrides come from first, and keeps the current priority order as the
+    /// tie-break.
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.conflict_strategy = strategy;
+        match strategy {
+            ConflictStrategy::FirstWins => self.priority_order = PriorityOrder::FrontWins,
+            ConflictStrategy::LastWins => self.priority_order = PriorityOrder::BackWins,
+            ConflictStrategy::HighestPriority => {}
+        }
+        self
+    }
+
+    /// Stop starting new WAD builds once `budget` has passed since the build began.
+    ///
+    /// WADs already being patched are finished; the rest are left out and the
+    /// result is flagged [`incomplete`](OverlayBuildResult::incomplete). Only the
+    /// finished WADs are recorded in the overlay state, so the next build patches
+    /// the remaining ones. The finished WADs are still swapped in, so unlike an
+    /// interrupted build an incomplete overlay is served: it must be finished
+    /// before launching the game, since the WADs it lacks load unmodified.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Resolve path hashes with a `hashes.game.txt` hashtable.
+    ///
+    /// Conflicts on hex-named override files then report the asset's real path,
+    /// and log messages name assets instead of printing bare hashes. See
+    /// [`GameIndex::with_hashtable`]; a missing file is ignored.
+    pub fn with_hashtable(mut self, path: Utf8PathBuf) -> Self {
+        self.hashtable_path = Some(path);
+        self
+    }
+
+    /// Cap the memory used by override bytes while patching.
+    ///
+    /// Before re-reading override bytes for the WADs being rebuilt, the builder sums
+    /// their sizes and, if the total exceeds `max_memory_bytes`, marks the largest
+    /// overrides to be written to a temporary file instead of held in memory. Spilled
+    /// overrides are read back one at a time while patching. The temp file is created
+    /// in the scratch directory if set, otherwise in the state directory, and is
+    /// removed when the build finishes.
+    pub fn with_spill(mut self, max_memory_bytes: u64) -> Self {
+        self.spill_max_memory = Some(max_memory_bytes);
+        self
+    }
+
+    /// Hard link WADs that end up with no overrides instead of rewriting them.
+    ///
+    /// A WAD can be routed to the build and still have nothing to apply, e.g.
+    /// when every override targeting it resolved to nothing. By default such a
+    /// WAD is rewritten chunk by chunk like any other; with this option the game
+    /// WAD is hard linked into the overlay, or copied when the overlay is on a
+    /// different filesystem. Linked WADs are never written through: a later
+    /// build replaces them with a new file.
+    pub fn with_link_unmodified_wads(mut self, link: bool) -> Self {
+        self.link_unmodified_wads = link;
+        self
+    }
+
+    /// Record the profile the overlay is built for in its state.
+    ///
+    /// For setups where several profiles share one overlay root. An overlay
+    /// built for another profile, or by a build without a profile, is never
+    /// reused: the next build rebuilds every WAD with
+    /// [`RebuildReason::FullRebuildProfileChanged`]. Profiles with their own
+    /// state directory never see another profile's state, so this only costs
+    /// the one rebuild after it is first set.
+    pub fn with_profile_id(mut self, profile_id: impl Into<String>) -> Self {
+        self.profile_id = Some(profile_id.into());
+        self
+    }
+
+    /// Set layout options used when writing patched WADs.
+    ///
+    /// Only affects WADs written by this build; WADs reused from a previous
+    /// incremental build keep the layout they were written with.
+    pub fn with_wad_patch_options(mut self, options: WadPatchOptions) -> Self {
+        self.wad_patch_options = options;
+        self
+    }
+
+    /// Set the Zstd level used to compress overrides, 3 by default.
+    ///
+    /// Higher levels (up to 22) shrink overlays meant to be shared; level 1 is the
+    /// fastest for local testing. An out-of-range level fails the build with
+    /// [`Error::Other`]. Shorthand for the `compression_level` of
+    /// [`with_wad_patch_options`](Self::with_wad_patch_options).
+    pub fn with_compression_level(mut self, level: i32) -> Self {
+        self.wad_patch_options.compression_level = level;
+        self
+    }
+
+    /// Read every patched WAD back after writing it and check each chunk against
+    /// its TOC checksum, failing the build with [`Error::Other`] on a mismatch.
+    ///
+    /// Off by default since it costs a second pass over each WAD. Shorthand for
+    /// the `verify_output` of [`with_wad_patch_options`](Self::with_wad_patch_options).
+    pub fn with_verify_output(mut self, verify: bool) -> Self {
+        self.wad_patch_options.verify_output = verify;
+        self
+    }
+
+    /// Compress small overrides harder, and store them uncompressed when that
+    /// does not shrink them.
+    ///
+    /// Meant for mods with hundreds of tiny config overrides; see
+    /// [`WadPatchOptions::tune_small_overrides`]. Shorthand for the
+    /// `tune_small_overrides` of [`with_wad_patch_options`](Self::with_wad_patch_options).
+    pub fn with_tune_small_overrides(mut self, tune: bool) -> Self {
+        self.wad_patch_options.tune_small_overrides = tune;
+        self
+    }
+
+    /// Cross-check hex-named overrides against the game index.
+    ///
+    /// An override whose file stem is 16 hex digits (e.g. `0123456789abcdef.dds`)
+    /// is inserted under that literal hash. When enabled, each such override whose
+    /// hash is in no game WAD and that names no target WAD to be added to is
+    /// logged as a warning and reported through
+    /// [`take_suspect_hex_overrides`](Self::take_suspect_hex_overrides), since the
+    /// file was most likely meant to be a named asset. The override is still applied.
+    ///
+    /// Packed WADs inside `.fantome` archives are unpacked to hex names, so custom
+    /// chunks they add are reported too.
+    pub fn with_strict_hex_names(mut self, strict: bool) -> Self {
+        self.strict_hex_names = strict;
+        self
+    }
+
+    /// Retry failed [`read_wad_overrides`](ModContentProvider::read_wad_overrides),
+    /// [`read_wad_override_file`](ModContentProvider::read_wad_override_file) and
+    /// [`read_raw_override_file`](ModContentProvider::read_raw_override_file)
+    /// calls according to `policy`.
+    ///
+    /// Defaults to a single attempt, which suits filesystem and archive providers.
+    /// Network-backed providers can use this so one transient fetch failure does
+    /// not fail the whole build.
+    pub fn with_read_retries(mut self, policy: RetryPolicy) -> Self {
+        self.read_retries = policy;
+        self
+    }
+
+    /// Keep the previous version of every overlay WAD a build replaces.
+    ///
+    /// Before a build overwrites or deletes an overlay WAD, it is moved to
+    /// `overlay_root/.backup/<relpath>`, so a bad build can be undone with
+    /// [`rollback`](crate::rollback). Each build that changes the overlay
+    /// replaces the previous backup. Disabled by default.
+    pub fn with_backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+
+    /// Write overlay WADs with `extension` in place of `.wad.client`.
+    ///
+    /// For patchers that look for overlay WADs under another name, e.g.
+    /// `.wad.client.overlay` turns `Champions/Aatrox.wad.client` into
+    /// `Champions/Aatrox.wad.client.overlay` in the overlay. Defaults to
+    /// [`DEFAULT_OUTPUT_EXTENSION`]. The overlay state keeps recording game
+    /// paths along with the extension, which [`validate`](crate::validate()) and
+    /// [`overlay_extension_stats`](crate::overlay_extension_stats) read back.
+    /// Changing the extension rebuilds the whole overlay under the new one.
+    pub fn with_output_extension(mut self, extension: impl Into<String>) -> Self {
+        self.output_extension = extension.into();
+        self
+    }
+
+    /// Check the signatures of mods backed by signable archives before building.
+    ///
+    /// Each enabled mod's [`verify_signature`](ModContentProvider::verify_signature)
+    /// is checked against the [trust](Self::with_signature_trust) before any
+    /// override is read. [`SignaturePolicy::Require`] fails the build on an
+    /// unsigned or unverifiable `.modpkg`, [`SignaturePolicy::Warn`] builds it
+    /// with a warning. Formats that cannot be signed are never rejected. Every
+    /// mod's status is reported in [`OverlayBuildResult::signatures`]. Defaults
+    /// to [`SignaturePolicy::Ignore`].
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signature_policy = policy;
+        self
+    }
+
+    /// Set the trust anchors for [`with_signature_policy`](Self::with_signature_policy).
+    ///
+    /// Without any anchor, no signature verifies.
+    pub fn with_signature_trust(mut self, trust: SignatureTrust) -> Self {
+        self.signature_trust = trust;
+        self
+    }
+
+    /// Only patch the WADs `filter` accepts.
+    ///
+    /// `filter` is called with game-relative WAD paths such as
+    /// `DATA/FINAL/Maps/Shipping/Map11.wad.client`. A rejected WAD receives no
+    /// overrides and is never built; overrides that would only have landed in
+    /// rejected WADs are logged as dropped.
+    ///
+    /// A closure cannot be compared with the one the overlay was built with, so
+    /// give each filter a [key](Self::with_wad_filter_key) for
+    /// [`build`](Self::build) to notice when it changes.
+    pub fn with_wad_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Utf8Path) -> bool + Send + Sync + 'static,
+    {
+        self.wad_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Identify the [WAD filter](Self::with_wad_filter) with `key`, such as a
+    /// serialization of the settings it was made from.
+    ///
+    /// The key is recorded in the overlay state. A build with a different key
+    /// than the overlay was built with is never skipped as unchanged: the WADs
+    /// the filter now rejects are dropped from the overlay and those it now
+    /// accepts are built.
+    pub fn with_wad_filter_key(mut self, key: impl Into<String>) -> Self {
+        self.wad_filter_key = Some(key.into());
+        self
+    }
+
     /// Set WAD filenames to block from patching.
     ///
     /// Filenames are automatically lowercased for case-insensitive matching.
@@ -496,8 +1205,9 @@ impl OverlayBuilder {
 
     /// Set the ordered list of mods to include in the overlay.
     ///
-    /// Order matters: the first mod in the list (index 0) has the highest priority.
-    /// When two mods override the same chunk, the mod closer to the front wins.
+    /// Order matters: by default the first mod in the list (index 0) has the highest
+    /// priority, and when two mods override the same chunk the mod closer to the front
+    /// wins. See [`with_priority_order`](Self::with_priority_order) to flip this.
     pub fn set_enabled_mods(&mut self, mods: Vec<EnabledMod>) {
         self.enabled_mods = mods;
     }
@@ -509,158 +1219,183 @@ impl OverlayBuilder {
     ///    (only re-patch WADs whose override fingerprint changed).
     /// 3. Otherwise → full rebuild (wipe and rebuild everything).
     pub fn build(&mut self) -> Result<OverlayBuildResult> {
-        let start_time = std::time::Instant::now();
+        let start_time = Instant::now();
 
         // Reset per-build outputs; each return path sets these as appropriate.
         self.last_linked_bin_offenders = Vec::new();
-
-        let effective_blocked = self.effective_blocked_wads();
+        self.last_suspect_hex_overrides = Vec::new();
 
         tracing::info!("Building overlay...");
         tracing::debug!("Game dir: {}", self.game_dir);
         tracing::debug!("Overlay root: {}", self.overlay_root);
         tracing::debug!("Enabled mods: {}", self.enabled_mods.len());
-        tracing::debug!("Blocked WADs: {:?}", effective_blocked);
-
-        self.emit_progress(OverlayProgress::stage(OverlayStage::Indexing));
-
-        let data_final_dir = self.game_dir.join("DATA").join("FINAL");
-        if !data_final_dir.as_std_path().exists() {
-            return Err(format!(
-                "League path does not contain Game/DATA/FINAL. Game dir: '{}'",
-                self.game_dir
-            )
-            .into());
-        }
+        tracing::debug!("Blocked WADs: {:?}", self.effective_blocked_wads());
 
         std::fs::create_dir_all(self.overlay_root.as_std_path())?;
-        std::fs::create_dir_all(self.state_dir.as_std_path())?;
+        let rolled_back = crate::backup::take_rollback_marker(&self.overlay_root)?;
+        let (signatures, signature_warnings) = self.check_signatures()?;
 
-        let cache_path = self.state_dir.join("game_index.bin");
-        let game_index = GameIndex::load_or_build(&self.game_dir, &cache_path)?;
+        let plan::PreparedBuild {
+            game_index,
+            enabled_ids,
+            effective_blocked,
+            prev_state,
+            rebuild_reason,
+            semantic_conflicts,
+            work,
+        } = self.prepare_build(rolled_back)?;
 
-        // Load previous state
         let state_path = self.state_dir.join("overlay.json");
-        let enabled_ids: Vec<String> = self.enabled_mods.iter().map(|m| m.id.clone()).collect();
-        let prev_state = OverlayState::load(&state_path)?;
-
-        // --- Handle empty mod list ---
-        if self.enabled_mods.is_empty() {
-            tracing::info!("Overlay: no enabled mods, cleaning overlay");
-            self.clean_overlay_wads()?;
-            let state = OverlayState::new(
-                Vec::new(),
-                game_index.game_fingerprint(),
-                effective_blocked.clone(),
-                BTreeMap::new(),
-            );
-            state.save(&state_path)?;
+        let checkpoint_path = self.state_dir.join(progress::PROGRESS_FILE_NAME);
+        // After an interruption, the interrupted build's backup already holds
+        // the overlay it started from.
+        let mut backup = (self.backup && rebuild_reason != RebuildReason::ResumedInterruptedBuild)
+            .then(|| WadBackup::new(&self.overlay_root));
+
+        let Some(plan::PlannedWork {
+            all_meta,
+            wad_hash_sets,
+            conflicts,
+            warnings,
+            wads_to_build,
+            wads_to_reuse,
+            new_wad_fingerprints,
+        }) = work
+        else {
+            let prev_total_size = prev_state.as_ref().map_or(Some(0), |s| s.total_wad_size());
+            let (wads_reused, size_delta) = match rebuild_reason {
+                RebuildReason::ExactMatchSkipped => {
+                    tracing::info!("Overlay: exact match, skipping build");
+                    let wads: Vec<&String> = prev_state
+                        .iter()
+                        .flat_map(|state| state.wad_fingerprints.keys())
+                        .collect();
+                    for wad in &wads {
+                        self.emit_event(BuildEvent::WadReused {
+                            wad: Utf8PathBuf::from(wad.as_str()),
+                        });
+                    }
+                    let wads = wads
+                        .into_iter()
+                        .map(|k| self.overlay_wad_path(Utf8Path::new(k)))
+                        .collect();
+                    (wads, Some(0))
+                }
+                _ => {
+                    tracing::info!("Overlay: no enabled mods, cleaning overlay");
+                    self.clean_overlay_wads(backup.as_mut())?;
+                    let mut state = OverlayState::new(
+                        Vec::new(),
+                        game_index.game_fingerprint(),
+                        effective_blocked,
+                        BTreeMap::new(),
+                    );
+                    state.output_extension = self.output_extension.clone();
+                    state.save(&state_path)?;
+                    progress::remove_checkpoint(&checkpoint_path)?;
+                    (Vec::new(), size_delta(prev_total_size, 0))
+                }
+            };
             self.emit_progress(OverlayProgress::stage(OverlayStage::Complete));
             return Ok(OverlayBuildResult {
                 overlay_root: self.overlay_root.clone(),
+                output_extension: self.output_extension.clone(),
                 wads_built: Vec::new(),
-                wads_reused: Vec::new(),
+                wads_reused,
                 conflicts: Vec::new(),
+                semantic_conflicts,
+                rebuild_reason,
                 build_time: start_time.elapsed(),
+                incomplete: false,
+                wad_stats: Vec::new(),
+                warnings: signature_warnings,
+                signatures,
+                size_delta,
             });
-        }
+        };
 
-        if let Some(ref state) = prev_state {
-            if state.matches(
-                &enabled_ids,
-                game_index.game_fingerprint(),
-                &effective_blocked,
-            ) {
-                if self.validate_wads_exist(state) {
-                    tracing::info!("Overlay: exact match, skipping build");
-                    self.last_linked_bin_offenders = state.linked_bin_offenders.clone();
-                    self.emit_progress(OverlayProgress::stage(OverlayStage::Complete));
-                    let reused: Vec<Utf8PathBuf> = state
-                        .wad_fingerprints
-                        .keys()
-                        .map(|k| self.overlay_root.join(k))
-                        .collect();
-                    return Ok(OverlayBuildResult {
-                        overlay_root: self.overlay_root.clone(),
-                        wads_built: Vec::new(),
-                        wads_reused: reused,
-                        conflicts: Vec::new(),
-                        build_time: start_time.elapsed(),
-                    });
-                } else {
-                    tracing::info!(
-                        "Overlay: state matched but some WADs missing, doing incremental repair"
-                    );
-                }
+        match rebuild_reason {
+            RebuildReason::Incremental => self.start_staging(&wads_to_reuse)?,
+            RebuildReason::ResumedInterruptedBuild => {
+                tracing::info!(
+                    "Overlay: resuming interrupted build, {} WAD(s) already finished",
+                    wads_to_reuse.len()
+                );
+                self.resume_staging(&wads_to_reuse)?;
             }
-        }
-
-        // Determine if incremental build is possible
-        let can_incremental = prev_state
-            .as_ref()
-            .is_some_and(|s| s.supports_incremental(game_index.game_fingerprint()));
-
-        if !can_incremental {
-            tracing::info!(
-                "Overlay: full rebuild required (state version or game fingerprint mismatch)"
-            );
-            self.clean_overlay_wads()?;
-        }
-
-        self.emit_progress(OverlayProgress::stage(OverlayStage::CollectingOverrides));
-
-        let (all_meta, mod_wad_reports) = self.collect_all_override_metadata(&game_index)?;
-        self.last_mod_wad_reports = mod_wad_reports;
-
-        let mut wad_hash_sets = self.distribute_override_hashes(&all_meta, &game_index);
-
-        wad_hash_sets.retain(|path, _| {
-            let blocked = self.is_wad_blocked(path);
-            if blocked {
-                tracing::info!("Blocked WAD from patching: {}", path);
+            _ => {
+                tracing::info!("Overlay: full rebuild required ({:?})", rebuild_reason);
+                self.start_staging(&[])?;
             }
-            !blocked
-        });
-
-        // Validate property-bin linked dependencies against the overlay WADs we are
-        // about to write. Runs over every enabled mod's overrides (built and reused
-        // alike) since distribution precedes the rebuild/reuse partition.
-        self.last_linked_bin_offenders =
-            collect_linked_bin_offenders(&all_meta, &wad_hash_sets, &game_index);
-        if !self.last_linked_bin_offenders.is_empty() {
-            tracing::info!(
-                "Linked-bin check: {} mod(s) reference unresolved linked bins",
-                self.last_linked_bin_offenders.len()
-            );
         }
-
-        let (wads_to_build, wads_to_reuse, new_wad_fingerprints) =
-            self.partition_wads_from_meta(&wad_hash_sets, &all_meta, &prev_state, can_incremental);
+        let checkpoint = progress::BuildCheckpoint::create(
+            &checkpoint_path,
+            game_index.game_fingerprint(),
+            &new_wad_fingerprints,
+            &wads_to_reuse,
+        )?;
 
         let wad_overrides =
             self.resolve_overrides_for_wads(&wads_to_build, &wad_hash_sets, &all_meta)?;
 
-        let built_paths = self.patch_wads_parallel(wads_to_build, wad_overrides)?;
-
-        if can_incremental {
-            if let Some(ref state) = prev_state {
-                self.clean_stale_wads(state, &new_wad_fingerprints)?;
+        let deadline = self.time_budget.map(|budget| start_time + budget);
+        let (built_paths, wad_stats, skipped) = self.patch_wads_parallel(
+            wads_to_build,
+            wad_overrides,
+            &all_meta,
+            deadline,
+            &checkpoint,
+        )?;
+        drop(checkpoint);
+        let incomplete = !skipped.is_empty();
+        let mut new_wad_fingerprints = new_wad_fingerprints;
+        if incomplete {
+            tracing::warn!(
+                "Overlay: time budget exceeded, {} WAD(s) left for the next build",
+                skipped.len()
+            );
+            for wad in &skipped {
+                new_wad_fingerprints.remove(wad.as_str());
             }
         }
 
+        let built_wads: Vec<Utf8PathBuf> =
+            wad_stats.iter().map(|stats| stats.path.clone()).collect();
+        if let Some(state) = prev_state
+            .as_ref()
+            .filter(|_| rebuild_reason == RebuildReason::Incremental)
+        {
+            self.report_stale_wads(state, &new_wad_fingerprints);
+        }
+        self.swap_in_staging(&built_wads, &wads_to_reuse, backup.as_mut())?;
+
+        for wad in &wads_to_reuse {
+            self.emit_event(BuildEvent::WadReused { wad: wad.clone() });
+        }
         let reused_paths: Vec<Utf8PathBuf> = wads_to_reuse
             .iter()
-            .map(|p| self.overlay_root.join(p))
+            .map(|p| self.overlay_wad_path(p))
             .collect();
 
+        let wad_sizes = self.collect_wad_sizes(&wad_stats, &wads_to_reuse, prev_state.as_ref());
+        let prev_total_size = prev_state.as_ref().map_or(Some(0), |s| s.total_wad_size());
+        let size_delta = size_delta(prev_total_size, wad_sizes.values().sum());
+
         let mut state = OverlayState::new(
             enabled_ids,
             game_index.game_fingerprint(),
             effective_blocked,
             new_wad_fingerprints,
         );
+        state.wad_sizes = wad_sizes;
         state.linked_bin_offenders = self.last_linked_bin_offenders.clone();
+        state.conflict_strategy = self.conflict_strategy;
+        state.incomplete = incomplete;
+        state.profile_id = self.profile_id.clone();
+        state.output_extension = self.output_extension.clone();
+        state.wad_filter_key = self.wad_filter_key.clone();
         state.save(&state_path)?;
+        progress::remove_checkpoint(&checkpoint_path)?;
 
         let total_wads = built_paths.len() as u32;
         self.emit_progress(OverlayProgress {
@@ -679,13 +1414,34 @@ impl OverlayBuilder {
 
         Ok(OverlayBuildResult {
             overlay_root: self.overlay_root.clone(),
+            output_extension: self.output_extension.clone(),
             wads_built: built_paths,
             wads_reused: reused_paths,
-            conflicts: Vec::new(),
+            conflicts,
+            semantic_conflicts,
+            rebuild_reason,
             build_time: start_time.elapsed(),
+            incomplete,
+            wad_stats,
+            warnings: signature_warnings.into_iter().chain(warnings).collect(),
+            signatures,
+            size_delta,
         })
     }
 
+    /// Whether the last build stopped partway through, by crashing or failing.
+    ///
+    /// The overlay is then still the one the last completed build left, since
+    /// builds are staged and only swapped in once finished. The next
+    /// [`build`](Self::build) resumes the staged one, keeping the WADs that were
+    /// finished and are still up to date.
+    pub fn has_interrupted_build(&self) -> bool {
+        self.state_dir
+            .join(progress::PROGRESS_FILE_NAME)
+            .as_std_path()
+            .exists()
+    }
+
     /// Force a full rebuild, ignoring the saved overlay state.
     ///
     /// Use this when the user explicitly requests a rebuild or when you know
@@ -696,7 +1452,29 @@ impl OverlayBuilder {
         if state_path.as_std_path().exists() {
             std::fs::remove_file(state_path.as_std_path())?;
         }
-        self.clean_overlay_wads()?;
+        progress::remove_checkpoint(&self.state_dir.join(progress::PROGRESS_FILE_NAME))?;
+        // Without a saved state, build() wipes the overlay itself (backing it
+        // up first when enabled).
+        self.build()
+    }
+
+    /// Remove one mod from the enabled list and update the overlay without it.
+    ///
+    /// Runs an incremental [`build`](Self::build) against the remaining mods, so
+    /// only WADs whose overrides change are re-patched and WADs left with no
+    /// overrides are deleted. Removing the last mod cleans the overlay entirely.
+    ///
+    /// Returns [`Error::ModNotEnabled`] if no enabled mod has the given ID; the
+    /// mod list is left untouched in that case.
+    pub fn remove_mod(&mut self, mod_id: &str) -> Result<OverlayBuildResult> {
+        let index = self
+            .enabled_mods
+            .iter()
+            .position(|m| m.id == mod_id)
+            .ok_or_else(|| Error::ModNotEnabled(mod_id.to_string()))?;
+        self.enabled_mods.remove(index);
+
+        tracing::info!("Removing mod '{}' from overlay", mod_id);
         self.build()
     }
 
@@ -704,10 +1482,51 @@ impl OverlayBuilder {
     // Private helpers
     // -----------------------------------------------------------------------
 
+    /// Overlay path, relative to the overlay root, of the WAD at the
+    /// game-relative `wad_path`, with the
+    /// [output extension](Self::with_output_extension) applied.
+    pub(crate) fn overlay_wad_rel_path(&self, wad_path: &Utf8Path) -> Utf8PathBuf {
+        overlay_wad_rel_path(wad_path, &self.output_extension)
+    }
+
+    /// Absolute overlay path of the WAD at the game-relative `wad_path`.
+    pub(crate) fn overlay_wad_path(&self, wad_path: &Utf8Path) -> Utf8PathBuf {
+        self.overlay_root.join(self.overlay_wad_rel_path(wad_path))
+    }
+
+    /// Sizes of the overlay WADs after a build, keyed by game-relative path.
+    ///
+    /// Built WADs take their size from `wad_stats`. Reused WADs keep the size
+    /// recorded in `prev_state`, or are measured on disk when it has none.
+    fn collect_wad_sizes(
+        &self,
+        wad_stats: &[WadBuildStats],
+        wads_to_reuse: &[Utf8PathBuf],
+        prev_state: Option<&OverlayState>,
+    ) -> BTreeMap<String, u64> {
+        let mut sizes: BTreeMap<String, u64> = wad_stats
+            .iter()
+            .map(|stats| (stats.path.to_string(), stats.bytes_written))
+            .collect();
+        for wad in wads_to_reuse {
+            let recorded = prev_state.and_then(|state| state.wad_sizes.get(wad.as_str()));
+            let size = match recorded {
+                Some(&size) => Some(size),
+                None => std::fs::metadata(self.overlay_wad_path(wad).as_std_path())
+                    .map(|m| m.len())
+                    .ok(),
+            };
+            if let Some(size) = size {
+                sizes.insert(wad.to_string(), size);
+            }
+        }
+        sizes
+    }
+
     /// Check that all WADs listed in the state actually exist on disk.
     fn validate_wads_exist(&self, state: &OverlayState) -> bool {
         for wad_path in state.wad_fingerprints.keys() {
-            let full_path = self.overlay_root.join(wad_path);
+            let full_path = self.overlay_wad_path(Utf8Path::new(wad_path));
             if !full_path.as_std_path().exists() {
                 tracing::warn!("Expected overlay WAD missing: {}", full_path);
                 return false;
@@ -716,56 +1535,46 @@ impl OverlayBuilder {
         true
     }
 
-    /// Remove overlay WADs that were in the previous state but are no longer needed.
+    /// Report the overlay WADs of the previous state that the new overlay drops.
     ///
-    /// Also cleans up empty parent directories left behind.
-    fn clean_stale_wads(
+    /// They leave the overlay when the staged build is swapped in.
+    fn report_stale_wads(
         &self,
         prev_state: &OverlayState,
         new_wad_fingerprints: &BTreeMap<String, u64>,
-    ) -> Result<()> {
+    ) {
         for old_wad_path in prev_state.wad_fingerprints.keys() {
             if !new_wad_fingerprints.contains_key(old_wad_path) {
-                let stale_path = self.overlay_root.join(old_wad_path);
+                let stale_path = self.overlay_wad_path(Utf8Path::new(old_wad_path));
                 if stale_path.as_std_path().exists() {
                     tracing::info!("Removing stale WAD: {}", stale_path);
-                    std::fs::remove_file(stale_path.as_std_path())?;
+                    self.emit_event(BuildEvent::StaleWadRemoved {
+                        wad: Utf8PathBuf::from(old_wad_path.as_str()),
+                    });
                 }
-                self.cleanup_empty_parents(&stale_path);
             }
         }
-        Ok(())
     }
 
-    /// Remove all WAD files from the overlay directory.
-    fn clean_overlay_wads(&self) -> Result<()> {
+    /// Remove all WAD files from the overlay directory, moving them into
+    /// `backup` first when given, along with any staged build.
+    fn clean_overlay_wads(&self, backup: Option<&mut WadBackup>) -> Result<()> {
+        staging::remove_dir_if_exists(&self.staging_root())?;
         let data_dir = self.overlay_root.join("DATA");
         if data_dir.as_std_path().exists() {
-            std::fs::remove_dir_all(data_dir.as_std_path())?;
-        }
-        Ok(())
-    }
-
-    /// Clean up empty parent directories after removing a stale WAD.
-    fn cleanup_empty_parents(&self, path: &Utf8Path) {
-        let mut current = path.parent();
-        while let Some(dir) = current {
-            if dir == self.overlay_root {
-                break;
-            }
-            if dir.as_std_path().exists() {
-                match std::fs::read_dir(dir.as_std_path()) {
-                    Ok(mut entries) => {
-                        if entries.next().is_some() {
-                            break;
-                        }
-                        let _ = std::fs::remove_dir(dir.as_std_path());
-                    }
-                    Err(_) => break,
+            if let Some(backup) = backup {
+                for wad_path in
+                    crate::backup::collect_overlay_wad_paths(&data_dir, &self.output_extension)?
+                {
+                    let rel_path = wad_path
+                        .strip_prefix(&self.overlay_root)
+                        .expect("overlay WADs live under the overlay root");
+                    backup.preserve(rel_path)?;
                 }
             }
-            current = dir.parent();
+            std::fs::remove_dir_all(data_dir.as_std_path())?;
         }
+        Ok(())
     }
 
     /// Compute the effective blocklist from user-configured blocked WADs.
@@ -776,12 +1585,24 @@ impl OverlayBuilder {
         all
     }
 
+    /// Whether the [WAD filter](Self::with_wad_filter), if any, accepts `wad_path`.
+    pub(crate) fn is_wad_accepted(&self, wad_path: &Utf8Path) -> bool {
+        self.wad_filter
+            .as_ref()
+            .is_none_or(|filter| filter(wad_path))
+    }
+
     /// Check if a WAD path is blocked from patching.
     fn is_wad_blocked(&self, wad_path: &Utf8Path) -> bool {
         let filename = wad_path.file_name().unwrap_or("").to_ascii_lowercase();
         self.blocked_wads.contains(&filename)
     }
 
+    /// Send a build event to the sink, if one was registered.
+    fn emit_event(&self, event: BuildEvent) {
+        emit_event(self.event_sink.as_ref(), event);
+    }
+
     /// Emit a progress event if a callback was registered.
     fn emit_progress(&self, progress: OverlayProgress) {
         if let Some(callback) = &self.progress_callback {
@@ -794,6 +1615,7 @@ impl OverlayBuilder {
 mod tests {
     use super::*;
     use crate::content::FsModContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
 
     #[test]
     fn test_builder_creation() {
@@ -826,6 +1648,60 @@ mod tests {
         assert_eq!(builder.enabled_mods.len(), 1);
     }
 
+    #[test]
+    fn test_build_result_summary() {
+        let conflict = |kind| Conflict {
+            path_hash: 1,
+            path: "data/a.bin".to_string(),
+            contributing_mods: Vec::new(),
+            winner: "a".to_string(),
+            kind,
+        };
+        let result = OverlayBuildResult {
+            overlay_root: Utf8PathBuf::from("/profile/overlay"),
+            output_extension: DEFAULT_OUTPUT_EXTENSION.to_string(),
+            wads_built: vec![Utf8PathBuf::from("a"), Utf8PathBuf::from("b")],
+            wads_reused: vec![Utf8PathBuf::from("c")],
+            conflicts: vec![
+                conflict(ConflictKind::Divergent),
+                conflict(ConflictKind::Redundant),
+                conflict(ConflictKind::Redundant),
+            ],
+            semantic_conflicts: Vec::new(),
+            rebuild_reason: RebuildReason::Incremental,
+            build_time: Duration::from_millis(1500),
+            incomplete: false,
+            wad_stats: [1536, 512]
+                .into_iter()
+                .map(|bytes_written| WadBuildStats {
+                    path: Utf8PathBuf::from("a"),
+                    override_count: 1,
+                    original_chunk_count: 1,
+                    bytes_written,
+                })
+                .collect(),
+            warnings: vec![BuildWarning::BrokenOverride(BrokenOverride {
+                mod_id: "a".to_string(),
+                source_wad: None,
+                rel_path: Utf8PathBuf::from("data/empty.bin"),
+                kind: BrokenOverrideKind::Empty,
+            })],
+            signatures: Vec::new(),
+            size_delta: None,
+        };
+
+        assert_eq!(
+            result.summary(),
+            "Overlay: /profile/overlay\n\
+             WADs built: 2 (2.0 KiB written)\n\
+             WADs reused: 1\n\
+             Conflicts: 3 (1 divergent, 2 redundant)\n\
+             Build time: 1.50s\n\
+             Warnings: 1\n  \
+             - mod 'a' override 'data/empty.bin' is empty; skipped"
+        );
+    }
+
     #[test]
     fn test_override_meta_types() {
         let meta = OverrideMeta {
@@ -839,6 +1715,7 @@ mod tests {
             },
             fallback_wad: None,
             linked_bins: Vec::new(),
+            compression: None,
         };
         assert_eq!(meta.content_hash, 0x1234);
         assert_eq!(meta.uncompressed_size, 100);
@@ -856,6 +1733,7 @@ mod tests {
             },
             fallback_wad: None,
             linked_bins: Vec::new(),
+            compression: None,
         }
     }
 
@@ -865,6 +1743,9 @@ mod tests {
             hash_index: hashes,
             game_fingerprint: 7,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         }
     }
 
@@ -885,8 +1766,13 @@ mod tests {
         mod_meta.insert(0xBA5E, dummy_meta());
         mod_meta.insert(0x5C1, dummy_meta());
 
-        let report =
-            ModWadReport::from_meta("aatrox-skin".to_string(), &mod_meta, None, &game_index);
+        let report = ModWadReport::from_meta(
+            "aatrox-skin".to_string(),
+            ContentFormatVersion::Unknown,
+            &mod_meta,
+            None,
+            &game_index,
+        );
 
         // Champion WAD holds both overrides; each map holds only the spilled base
         // chunk — the asymmetry that lets a consumer pick the champion as primary.
@@ -928,7 +1814,13 @@ mod tests {
         let mut mod_meta = HashMap::new();
         mod_meta.insert(0xABCD, meta);
 
-        let report = ModWadReport::from_meta("new".to_string(), &mod_meta, None, &game_index);
+        let report = ModWadReport::from_meta(
+            "new".to_string(),
+            ContentFormatVersion::Unknown,
+            &mod_meta,
+            None,
+            &game_index,
+        );
         assert_eq!(
             report.affected_wads,
             vec![AffectedWad {
@@ -937,4 +1829,923 @@ mod tests {
             }]
         );
     }
+
+    /// Write a filesystem mod overriding `(wad, path)` pairs with `bytes`.
+    fn write_mock_mod(mod_dir: &Utf8Path, overrides: &[(&str, &str)], bytes: &[u8]) {
+        let files: Vec<String> = overrides
+            .iter()
+            .map(|(wad, path)| format!("base/{wad}/{path}"))
+            .collect();
+        let files: Vec<(&str, &[u8])> = files.iter().map(|f| (f.as_str(), bytes)).collect();
+        write_fs_mod(mod_dir, &mock_project("mock"), &files).unwrap();
+    }
+
+    #[test]
+    fn remove_mod_rebuilds_only_affected_wads() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/C.wad.client").with_path("data/c.bin", b"c".to_vec()),
+                ),
+        )
+        .unwrap();
+
+        let enabled = |id: &str| EnabledMod {
+            id: id.to_string(),
+            content: Box::new(FsModContent::new(root.join("mods").join(id))),
+            enabled_layers: None,
+        };
+        write_mock_mod(
+            &root.join("mods/drop"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("B.wad.client", "data/b.bin"),
+            ],
+            b"drop",
+        );
+        write_mock_mod(
+            &root.join("mods/keep"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("C.wad.client", "data/c.bin"),
+            ],
+            b"keep",
+        );
+
+        let overlay_root = root.join("overlay");
+        let mut builder = OverlayBuilder::new(game_dir, overlay_root.clone(), root.to_path_buf());
+        builder.set_enabled_mods(vec![enabled("drop"), enabled("keep")]);
+        assert_eq!(builder.build().unwrap().wads_built.len(), 3);
+
+        assert!(matches!(
+            builder.remove_mod("missing"),
+            Err(Error::ModNotEnabled(id)) if id == "missing"
+        ));
+        assert_eq!(builder.enabled_mods.len(), 2);
+
+        // A loses the winning override, B loses its only one, C is untouched.
+        let result = builder.remove_mod("drop").unwrap();
+        assert_eq!(result.rebuild_reason, RebuildReason::Incremental);
+        assert_eq!(result.wads_built.len(), 1);
+        assert!(result.wads_built[0].ends_with("Champions/A.wad.client"));
+        assert_eq!(result.wads_reused.len(), 1);
+        assert!(result.wads_reused[0].ends_with("Champions/C.wad.client"));
+        assert!(!overlay_root
+            .join("DATA/FINAL/Champions/B.wad.client")
+            .exists());
+
+        let result = builder.remove_mod("keep").unwrap();
+        assert_eq!(result.rebuild_reason, RebuildReason::EmptyModList);
+        assert!(!overlay_root
+            .join("DATA/FINAL/Champions/A.wad.client")
+            .exists());
+    }
+
+    #[test]
+    fn wad_stats_count_the_overrides_of_each_built_wad() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/a.bin", b"a".to_vec())
+                    .with_path("data/b.bin", b"b".to_vec())
+                    .with_path("data/c.bin", b"c".to_vec()),
+            ),
+        )
+        .unwrap();
+        let mod_dir = root.join("mods/skin");
+        let files = [
+            ("A.wad.client", "data/a.bin"),
+            ("A.wad.client", "data/b.bin"),
+        ];
+        write_mock_mod(&mod_dir, &files, b"patched");
+
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(mod_dir)),
+            enabled_layers: None,
+        }]);
+        let result = builder.build().unwrap();
+
+        assert_eq!(result.wad_stats.len(), 1);
+        let stats = &result.wad_stats[0];
+        assert_eq!(stats.path, "DATA/FINAL/Champions/A.wad.client");
+        assert_eq!(stats.override_count, files.len());
+        assert_eq!(stats.original_chunk_count, 3);
+        assert_eq!(
+            stats.bytes_written,
+            std::fs::metadata(&result.wads_built[0]).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn broken_override_files_are_skipped_with_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/a.bin", b"a".to_vec())
+                    .with_path("data/b.bin", b"b".to_vec())
+                    .with_path("data/c.bin", b"c".to_vec()),
+            ),
+        )
+        .unwrap();
+        let mod_dir = root.join("mods/skin");
+        write_mock_mod(&mod_dir, &[("A.wad.client", "data/a.bin")], b"patched");
+        let wad_dir = mod_dir.join("content/base/A.wad.client");
+        std::fs::write(wad_dir.join("data/b.bin"), b"").unwrap();
+        std::fs::write(
+            wad_dir.join("data/c.bin"),
+            "version https://git-lfs.github.com/spec/v1\n\
+             oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+             size 12345\n",
+        )
+        .unwrap();
+
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf());
+        let enabled_mod = || EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(mod_dir.clone())),
+            enabled_layers: None,
+        };
+        builder.set_enabled_mods(vec![enabled_mod()]);
+        let result = builder.build().unwrap();
+
+        let broken = |rel_path: &str, kind| {
+            BuildWarning::BrokenOverride(BrokenOverride {
+                mod_id: "skin".to_string(),
+                source_wad: Some(("base".to_string(), "A.wad.client".to_string())),
+                rel_path: Utf8PathBuf::from(rel_path),
+                kind,
+            })
+        };
+        let expected = vec![
+            broken("data/b.bin", BrokenOverrideKind::Empty),
+            broken("data/c.bin", BrokenOverrideKind::LfsPointer),
+        ];
+        let mut warnings = result.warnings.clone();
+        warnings.sort_by_key(|w| w.to_string());
+        assert_eq!(warnings, expected);
+        assert_eq!(result.wad_stats[0].override_count, 1);
+
+        // A full rebuild reads the mod from the metadata cache and still reports them.
+        builder.set_enabled_mods(vec![enabled_mod()]);
+        let mut warnings = builder.rebuild_all().unwrap().warnings;
+        warnings.sort_by_key(|w| w.to_string());
+        assert_eq!(warnings, expected);
+    }
+
+    #[test]
+    fn wad_filter_excludes_wads_from_the_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client")
+                        .with_path("data/shared.bin", b"shared".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Maps/Shipping/Map11.wad.client")
+                        .with_path("data/shared.bin", b"shared".to_vec())
+                        .with_path("data/map.bin", b"map".to_vec()),
+                ),
+        )
+        .unwrap();
+        let mod_dir = root.join("mods/skin");
+        write_mock_mod(
+            &mod_dir,
+            &[
+                ("A.wad.client", "data/shared.bin"),
+                ("Map11.wad.client", "data/map.bin"),
+            ],
+            b"patched",
+        );
+
+        let build = |maps: bool| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf())
+                    .with_wad_filter(move |wad| maps || !wad.starts_with("DATA/FINAL/Maps"))
+                    .with_wad_filter_key(if maps { "all" } else { "no-maps" });
+            builder.set_enabled_mods(vec![EnabledMod {
+                id: "skin".to_string(),
+                content: Box::new(FsModContent::new(mod_dir.clone())),
+                enabled_layers: None,
+            }]);
+            builder.build().unwrap()
+        };
+        let map11 = root.join("overlay/DATA/FINAL/Maps/Shipping/Map11.wad.client");
+
+        let result = build(false);
+        assert_eq!(result.wads_built.len(), 1);
+        assert!(result.wads_built[0].ends_with("Champions/A.wad.client"));
+        assert!(!root.join("overlay/DATA/FINAL/Maps").exists());
+
+        // Changing the filter key defeats the exact-match skip in both directions.
+        let result = build(true);
+        assert_eq!(result.rebuild_reason, RebuildReason::Incremental);
+        assert!(map11.exists());
+        assert_eq!(build(true).rebuild_reason, RebuildReason::ExactMatchSkipped);
+
+        let result = build(false);
+        assert_eq!(result.rebuild_reason, RebuildReason::Incremental);
+        assert!(!map11.exists());
+        assert_eq!(result.wads_reused.len(), 1);
+    }
+
+    #[test]
+    fn build_reports_conflicts_between_fs_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+            ),
+        )
+        .unwrap();
+
+        let enabled = |id: &str| EnabledMod {
+            id: id.to_string(),
+            content: Box::new(FsModContent::new(root.join("mods").join(id))),
+            enabled_layers: None,
+        };
+        write_mock_mod(
+            &root.join("mods/first"),
+            &[("A.wad.client", "data/a.bin")],
+            b"first",
+        );
+        write_mock_mod(
+            &root.join("mods/second"),
+            &[("A.wad.client", "data/a.bin")],
+            b"second",
+        );
+
+        let overlay_root = root.join("overlay");
+        let mut builder = OverlayBuilder::new(game_dir, overlay_root.clone(), root.to_path_buf());
+        builder.set_enabled_mods(vec![enabled("first"), enabled("second")]);
+        let result = builder.build().unwrap();
+
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(
+            conflict.path_hash,
+            ltk_modpkg::utils::hash_chunk_name("data/a.bin")
+        );
+        assert_eq!(conflict.path, "data/a.bin");
+        assert_eq!(conflict.winner, "first");
+        assert_eq!(conflict.kind, ConflictKind::Divergent);
+        let contributors: Vec<&str> = conflict
+            .contributing_mods
+            .iter()
+            .map(|c| c.mod_id.as_str())
+            .collect();
+        assert_eq!(contributors, vec!["first", "second"]);
+
+        // Diagnostic only: the front mod's bytes still win.
+        let bytes = std::fs::read(overlay_root.join("DATA/FINAL/Champions/A.wad.client")).unwrap();
+        let mut wad = ltk_wad::Wad::mount(std::io::Cursor::new(bytes)).unwrap();
+        let chunk = *wad.chunks().get(conflict.path_hash).unwrap();
+        assert_eq!(&*wad.load_chunk_decompressed(&chunk).unwrap(), b"first");
+    }
+
+    #[test]
+    fn passthrough_wads_are_the_game_wads_not_overlaid() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Maps/Map11.wad.client").with_path("data/m.bin", b"m".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/skin"),
+            &[("A.wad.client", "data/a.bin")],
+            b"patched",
+        );
+
+        let mut builder =
+            OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(root.join("mods/skin"))),
+            enabled_layers: None,
+        }]);
+        let result = builder.build().unwrap();
+
+        let game_index = GameIndex::build(&game_dir).unwrap();
+        assert_eq!(
+            result.passthrough_wads(&game_index),
+            vec![
+                game_dir.join("DATA/FINAL/Champions/B.wad.client"),
+                game_dir.join("DATA/FINAL/Maps/Map11.wad.client"),
+            ]
+        );
+    }
+
+    #[test]
+    fn exhausted_time_budget_leaves_an_incomplete_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/skin"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("B.wad.client", "data/b.bin"),
+            ],
+            b"patched",
+        );
+        let build = |budget: Option<Duration>| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+            if let Some(budget) = budget {
+                builder = builder.with_time_budget(budget);
+            }
+            builder.set_enabled_mods(vec![EnabledMod {
+                id: "skin".to_string(),
+                content: Box::new(FsModContent::new(root.join("mods/skin"))),
+                enabled_layers: None,
+            }]);
+            builder.build().unwrap()
+        };
+
+        let partial = build(Some(Duration::ZERO));
+        assert!(partial.incomplete);
+        assert!(partial.wads_built.is_empty());
+        let state = OverlayState::load(&root.join("overlay.json"))
+            .unwrap()
+            .unwrap();
+        assert!(state.incomplete);
+        assert!(state.wad_fingerprints.is_empty());
+
+        let finished = build(None);
+        assert!(!finished.incomplete);
+        assert_eq!(finished.rebuild_reason, RebuildReason::Incremental);
+        assert_eq!(finished.wads_built.len(), 2);
+        assert_eq!(build(None).rebuild_reason, RebuildReason::ExactMatchSkipped);
+    }
+
+    #[test]
+    fn overlay_built_for_another_profile_is_rebuilt() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+            ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/skin"),
+            &[("A.wad.client", "data/a.bin")],
+            b"patched",
+        );
+        let build = |profile: Option<&str>| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+            if let Some(profile) = profile {
+                builder = builder.with_profile_id(profile);
+            }
+            builder.set_enabled_mods(vec![EnabledMod {
+                id: "skin".to_string(),
+                content: Box::new(FsModContent::new(root.join("mods/skin"))),
+                enabled_layers: None,
+            }]);
+            builder.build().unwrap().rebuild_reason
+        };
+
+        assert_eq!(build(None), RebuildReason::FullRebuildNoPreviousState);
+        assert_eq!(build(Some("a")), RebuildReason::FullRebuildProfileChanged);
+        assert_eq!(build(Some("a")), RebuildReason::ExactMatchSkipped);
+        assert_eq!(build(Some("b")), RebuildReason::FullRebuildProfileChanged);
+        let state = OverlayState::load(&root.join("overlay.json"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.profile_id.as_deref(), Some("b"));
+        assert_eq!(build(None), RebuildReason::ExactMatchSkipped);
+    }
+
+    #[test]
+    fn overlay_wads_use_the_output_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/a"),
+            &[("A.wad.client", "data/a.bin")],
+            b"patched",
+        );
+        write_mock_mod(
+            &root.join("mods/b"),
+            &[("B.wad.client", "data/b.bin")],
+            b"patched",
+        );
+        let build = |mod_ids: &[&str]| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf())
+                    .with_output_extension(".wad.client.overlay");
+            builder.set_enabled_mods(
+                mod_ids
+                    .iter()
+                    .map(|id| EnabledMod {
+                        id: id.to_string(),
+                        content: Box::new(FsModContent::new(root.join("mods").join(id))),
+                        enabled_layers: None,
+                    })
+                    .collect(),
+            );
+            builder.build().unwrap()
+        };
+        let wad = |name: &str| root.join("overlay/DATA/FINAL/Champions").join(name);
+
+        let first = build(&["a", "b"]);
+        assert_eq!(
+            first.wads_built,
+            vec![wad("A.wad.client.overlay"), wad("B.wad.client.overlay")]
+        );
+        assert!(wad("A.wad.client.overlay").exists());
+        assert!(!wad("A.wad.client").exists());
+        assert_eq!(
+            build(&["a", "b"]).rebuild_reason,
+            RebuildReason::ExactMatchSkipped
+        );
+
+        // A missing overlay WAD is noticed under its extension and restored
+        std::fs::remove_file(wad("B.wad.client.overlay")).unwrap();
+        let restored = build(&["a", "b"]);
+        assert_eq!(restored.rebuild_reason, RebuildReason::Incremental);
+        assert_eq!(restored.wads_built, vec![wad("B.wad.client.overlay")]);
+
+        // Dropping a mod removes its stale WAD
+        let dropped = build(&["a"]);
+        assert_eq!(dropped.wads_reused, vec![wad("A.wad.client.overlay")]);
+        assert!(!wad("B.wad.client.overlay").exists());
+    }
+
+    #[test]
+    fn validate_stats_and_rollback_follow_the_output_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/C.wad.client").with_path("data/c.bin", b"c".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/first"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("B.wad.client", "data/b.bin"),
+            ],
+            b"first",
+        );
+        write_mock_mod(
+            &root.join("mods/second"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("C.wad.client", "data/c.bin"),
+            ],
+            b"second",
+        );
+        let enabled = |id: &str| EnabledMod {
+            id: id.to_string(),
+            content: Box::new(FsModContent::new(root.join("mods").join(id))),
+            enabled_layers: None,
+        };
+        let overlay_root = root.join("overlay");
+        let wad = |name: &str| overlay_root.join("DATA/FINAL/Champions").join(name);
+        let validate = || crate::validate::validate_with_state_dir(&overlay_root, root).unwrap();
+
+        let mut builder =
+            OverlayBuilder::new(game_dir.clone(), overlay_root.clone(), root.to_path_buf())
+                .with_output_extension(".wad.client.overlay")
+                .with_backup(true);
+        builder.set_enabled_mods(vec![enabled("first")]);
+        let first = builder.build().unwrap();
+        let first_a = std::fs::read(wad("A.wad.client.overlay")).unwrap();
+
+        let validation = validate();
+        assert!(validation.is_ok(), "{:?}", validation.issues);
+        assert_eq!(validation.wads_checked, 2);
+        let stats = crate::asset_stats::overlay_extension_stats_with_state_dir(&overlay_root, root)
+            .unwrap();
+        assert_eq!(stats.values().map(|s| s.count).sum::<usize>(), 2);
+        let game_index = GameIndex::build(&game_dir).unwrap();
+        assert_eq!(
+            first.passthrough_wads(&game_index),
+            vec![game_dir.join("DATA/FINAL/Champions/C.wad.client")]
+        );
+
+        // A is rebuilt, B goes stale and C is new.
+        builder.set_enabled_mods(vec![enabled("second")]);
+        builder.build().unwrap();
+        assert!(!wad("B.wad.client.overlay").exists());
+        assert!(validate().is_ok());
+
+        crate::rollback(&overlay_root).unwrap();
+        assert_eq!(std::fs::read(wad("A.wad.client.overlay")).unwrap(), first_a);
+        assert!(wad("B.wad.client.overlay").exists());
+        assert!(!wad("C.wad.client.overlay").exists());
+
+        builder.set_enabled_mods(vec![enabled("first")]);
+        builder.build().unwrap();
+        assert!(validate().is_ok());
+
+        // Switching extensions rebuilds everything under the new one.
+        let mut builder = OverlayBuilder::new(game_dir, overlay_root.clone(), root.to_path_buf());
+        builder.set_enabled_mods(vec![enabled("first")]);
+        assert_eq!(
+            builder.build().unwrap().rebuild_reason,
+            RebuildReason::FullRebuildOutputExtensionChanged
+        );
+        assert!(wad("A.wad.client").exists());
+        assert!(!wad("A.wad.client.overlay").exists());
+        assert!(validate().is_ok());
+    }
+
+    #[test]
+    fn build_reports_size_delta_against_previous_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/a"),
+            &[("A.wad.client", "data/a.bin")],
+            b"patched",
+        );
+        write_mock_mod(
+            &root.join("mods/b"),
+            &[("B.wad.client", "data/b.bin")],
+            b"patched",
+        );
+        let state_path = root.join("overlay.json");
+        let build = |mod_ids: &[&str]| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+            builder.set_enabled_mods(
+                mod_ids
+                    .iter()
+                    .map(|id| EnabledMod {
+                        id: id.to_string(),
+                        content: Box::new(FsModContent::new(root.join("mods").join(id))),
+                        enabled_layers: None,
+                    })
+                    .collect(),
+            );
+            builder.build().unwrap()
+        };
+        let wad_size = |name: &str| {
+            std::fs::metadata(root.join("overlay/DATA/FINAL/Champions").join(name))
+                .unwrap()
+                .len() as i64
+        };
+
+        let first = build(&["a"]);
+        let a_size = wad_size("A.wad.client");
+        assert_eq!(first.size_delta, Some(a_size));
+        let state = OverlayState::load(&state_path).unwrap().unwrap();
+        assert_eq!(state.total_wad_size(), Some(a_size as u64));
+
+        let second = build(&["a", "b"]);
+        assert_eq!(second.size_delta, Some(wad_size("B.wad.client")));
+        assert_eq!(build(&["a", "b"]).size_delta, Some(0));
+
+        // A state written before sizes were recorded gives an unknown delta,
+        // after which the sizes are known again
+        let mut state = OverlayState::load(&state_path).unwrap().unwrap();
+        state.wad_sizes.clear();
+        state.save(&state_path).unwrap();
+        assert_eq!(build(&["a"]).size_delta, None);
+        assert_eq!(build(&[]).size_delta, Some(-a_size));
+    }
+
+    #[test]
+    fn interrupted_build_resumes_from_its_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+        write_mock_mod(
+            &root.join("mods/skin"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("B.wad.client", "data/b.bin"),
+            ],
+            b"patched",
+        );
+        let mut builder =
+            OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(root.join("mods/skin"))),
+            enabled_layers: None,
+        }]);
+        builder.build().unwrap();
+
+        // Leave the staging root as a build cut off while writing B would: A is
+        // checkpointed, B is half-written and overlay.json is the old one.
+        let state = OverlayState::load(&root.join("overlay.json"))
+            .unwrap()
+            .unwrap();
+        let (wad_a, fingerprint_a) = state
+            .wad_fingerprints
+            .iter()
+            .find(|(wad, _)| wad.ends_with("A.wad.client"))
+            .unwrap();
+        let wad_b = state
+            .wad_fingerprints
+            .keys()
+            .find(|wad| wad.ends_with("B.wad.client"))
+            .unwrap();
+        std::fs::write(
+            root.join(progress::PROGRESS_FILE_NAME),
+            format!(
+                "game {:016x}\n{:016x} {}\n",
+                state.game_fingerprint, fingerprint_a, wad_a
+            ),
+        )
+        .unwrap();
+        let staged = |wad: &str| root.join("overlay").join(STAGING_DIR_NAME).join(wad);
+        std::fs::create_dir_all(staged(wad_b).parent().unwrap()).unwrap();
+        std::fs::copy(root.join("overlay").join(wad_a), staged(wad_a)).unwrap();
+        std::fs::write(staged(wad_b), b"half").unwrap();
+        assert!(builder.has_interrupted_build());
+        // The live overlay is still the last complete one
+        let live_b = std::fs::read(root.join("overlay").join(wad_b)).unwrap();
+        assert!(ltk_wad::Wad::mount(std::io::Cursor::new(live_b)).is_ok());
+        assert!(
+            crate::validate::validate_with_state_dir(&root.join("overlay"), root)
+                .unwrap()
+                .is_ok()
+        );
+
+        let resumed = builder.build().unwrap();
+        assert_eq!(
+            resumed.rebuild_reason,
+            RebuildReason::ResumedInterruptedBuild
+        );
+        assert_eq!(resumed.wads_reused, vec![root.join("overlay").join(wad_a)]);
+        assert_eq!(resumed.wads_built, vec![root.join("overlay").join(wad_b)]);
+        assert!(!builder.has_interrupted_build());
+        let patched_b = std::fs::File::open(root.join("overlay").join(wad_b)).unwrap();
+        assert!(ltk_wad::Wad::mount(patched_b).is_ok());
+        assert!(!root.join("overlay").join(STAGING_DIR_NAME).exists());
+
+        assert_eq!(
+            builder.build().unwrap().rebuild_reason,
+            RebuildReason::ExactMatchSkipped
+        );
+    }
+
+    #[test]
+    fn conflict_strategy_picks_the_winning_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/a.bin", b"a".to_vec())
+                    .with_path("data/b.bin", b"b".to_vec()),
+            ),
+        )
+        .unwrap();
+
+        // Each mod has a "high" layer above base. "front" overrides a.bin in base
+        // and b.bin in high; "back" does the opposite.
+        let write_mod = |id: &str, base_path: &str, high_path: &str| {
+            let mod_dir = root.join("mods").join(id);
+            let mut project = mock_project(id);
+            project.layers.push(ltk_mod_project::ModProjectLayer {
+                name: "high".to_string(),
+                display_name: None,
+                priority: 10,
+                description: None,
+                string_overrides: HashMap::new(),
+            });
+            write_fs_mod(
+                &mod_dir,
+                &project,
+                &[
+                    (&format!("base/A.wad.client/{base_path}"), id.as_bytes()),
+                    (&format!("high/A.wad.client/{high_path}"), id.as_bytes()),
+                ],
+            )
+            .unwrap();
+        };
+        write_mod("front", "data/a.bin", "data/b.bin");
+        write_mod("back", "data/b.bin", "data/a.bin");
+
+        for (strategy, expected) in [
+            (ConflictStrategy::FirstWins, ["front", "front"]),
+            (ConflictStrategy::LastWins, ["back", "back"]),
+            (ConflictStrategy::HighestPriority, ["back", "front"]),
+        ] {
+            let state_dir = root.join(format!("{:?}", strategy));
+            let overlay_root = state_dir.join("overlay");
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), overlay_root.clone(), state_dir)
+                    .with_conflict_strategy(strategy);
+            builder.set_enabled_mods(
+                ["front", "back"]
+                    .into_iter()
+                    .map(|id| EnabledMod {
+                        id: id.to_string(),
+                        content: Box::new(FsModContent::new(root.join("mods").join(id))),
+                        enabled_layers: None,
+                    })
+                    .collect(),
+            );
+            let result = builder.build().unwrap();
+
+            let mut winners: Vec<(&str, &str)> = result
+                .conflicts
+                .iter()
+                .map(|c| (c.path.as_str(), c.winner.as_str()))
+                .collect();
+            winners.sort_unstable();
+            assert_eq!(
+                winners,
+                vec![("data/a.bin", expected[0]), ("data/b.bin", expected[1])],
+                "{:?}",
+                strategy
+            );
+
+            let bytes =
+                std::fs::read(overlay_root.join("DATA/FINAL/Champions/A.wad.client")).unwrap();
+            let mut wad = ltk_wad::Wad::mount(std::io::Cursor::new(bytes)).unwrap();
+            for (path, winner) in ["data/a.bin", "data/b.bin"].into_iter().zip(expected) {
+                let hash = ltk_modpkg::utils::hash_chunk_name(path);
+                let chunk = *wad.chunks().get(hash).unwrap();
+                assert_eq!(
+                    &*wad.load_chunk_decompressed(&chunk).unwrap(),
+                    winner.as_bytes(),
+                    "{:?} {}",
+                    strategy,
+                    path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn backup_lets_rollback_restore_the_previous_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/C.wad.client").with_path("data/c.bin", b"c".to_vec()),
+                ),
+        )
+        .unwrap();
+
+        let enabled = |id: &str| EnabledMod {
+            id: id.to_string(),
+            content: Box::new(FsModContent::new(root.join("mods").join(id))),
+            enabled_layers: None,
+        };
+        write_mock_mod(
+            &root.join("mods/first"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("B.wad.client", "data/b.bin"),
+            ],
+            b"first",
+        );
+        write_mock_mod(
+            &root.join("mods/second"),
+            &[
+                ("A.wad.client", "data/a.bin"),
+                ("C.wad.client", "data/c.bin"),
+            ],
+            b"second",
+        );
+
+        let overlay_root = root.join("overlay");
+        let wad = |name: &str| overlay_root.join("DATA/FINAL/Champions").join(name);
+        assert!(matches!(
+            crate::rollback(&overlay_root),
+            Err(Error::NoBackup(_))
+        ));
+
+        let mut builder = OverlayBuilder::new(game_dir, overlay_root.clone(), root.to_path_buf())
+            .with_backup(true);
+        builder.set_enabled_mods(vec![enabled("first")]);
+        builder.build().unwrap();
+        let first_a = std::fs::read(wad("A.wad.client")).unwrap();
+
+        // A is rebuilt, B goes stale and C is new.
+        builder.set_enabled_mods(vec![enabled("second")]);
+        builder.build().unwrap();
+        let second_a = std::fs::read(wad("A.wad.client")).unwrap();
+        assert_ne!(first_a, second_a);
+        assert!(overlay_root
+            .join(".backup/DATA/FINAL/Champions/A.wad.client")
+            .exists());
+        assert!(!wad("B.wad.client").exists());
+        // The backup is not part of the overlay.
+        assert!(
+            crate::validate::validate_with_state_dir(&overlay_root, root)
+                .unwrap()
+                .is_ok()
+        );
+
+        crate::rollback(&overlay_root).unwrap();
+        assert_eq!(std::fs::read(wad("A.wad.client")).unwrap(), first_a);
+        assert!(wad("B.wad.client").exists());
+        assert!(!wad("C.wad.client").exists());
+        assert!(!overlay_root.join(".backup").exists());
+
+        // The saved state describes the undone build, so the next one starts over.
+        let result = builder.build().unwrap();
+        assert_eq!(
+            result.rebuild_reason,
+            RebuildReason::FullRebuildNoPreviousState
+        );
+        assert_eq!(std::fs::read(wad("A.wad.client")).unwrap(), second_a);
+        assert!(!wad("B.wad.client").exists());
+        assert!(wad("C.wad.client").exists());
+    }
 }
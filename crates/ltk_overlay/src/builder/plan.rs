@@ -0,0 +1,470 @@
+//! Dry-run planning shared by [`OverlayBuilder::build`] and [`OverlayBuilder::plan`].
+//!
+//! Everything up to the rebuild/reuse partition happens in
+//! [`OverlayBuilder::prepare_build`], which never touches the overlay root or
+//! `overlay.json`. `build()` acts on its result; `plan()` only reports it.
+
+use super::*;
+
+/// What [`OverlayBuilder::plan`] found a build would do.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayPlan {
+    /// The strategy a build would use.
+    pub rebuild_reason: RebuildReason,
+    /// Overlay WADs a build would patch, sorted by path.
+    pub wads_to_build: Vec<PlannedWad>,
+    /// Overlay WADs a build would leave as they are, sorted by path.
+    pub wads_to_reuse: Vec<PlannedWad>,
+    /// Chunks overridden by more than one mod, as in
+    /// [`OverlayBuildResult::conflicts`].
+    pub conflicts: Vec<Conflict>,
+    /// As in [`OverlayBuildResult::semantic_conflicts`].
+    pub semantic_conflicts: Vec<SemanticConflict>,
+}
+
+impl OverlayPlan {
+    /// Estimated bytes a build would write.
+    pub fn estimated_bytes_to_write(&self) -> u64 {
+        self.wads_to_build.iter().map(|w| w.estimated_bytes).sum()
+    }
+}
+
+/// One overlay WAD in an [`OverlayPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedWad {
+    /// Path of the overlay WAD.
+    pub path: Utf8PathBuf,
+    /// Number of overrides routed to this WAD.
+    pub override_count: usize,
+    /// Rough size of the patched WAD: the game WAD's size plus the uncompressed
+    /// size of every override routed to it. Overrides are usually compressed
+    /// and replace existing chunks, so this errs high.
+    pub estimated_bytes: u64,
+}
+
+/// The decisions of a build, made before anything is written.
+pub(crate) struct PreparedBuild {
+    pub(crate) game_index: GameIndex,
+    /// Enabled mod IDs, highest priority first, as persisted in the state.
+    pub(crate) enabled_ids: Vec<String>,
+    pub(crate) effective_blocked: Vec<String>,
+    pub(crate) prev_state: Option<OverlayState>,
+    pub(crate) rebuild_reason: RebuildReason,
+    pub(crate) semantic_conflicts: Vec<SemanticConflict>,
+    /// `None` when no overrides were collected: the mod list is empty or the
+    /// previous build can be reused as is.
+    pub(crate) work: Option<PlannedWork>,
+}
+
+/// Overrides and their distribution, for builds that patch WADs.
+pub(crate) struct PlannedWork {
+    pub(crate) all_meta: HashMap<u64, OverrideMeta>,
+    pub(crate) wad_hash_sets: BTreeMap<Utf8PathBuf, HashSet<u64>>,
+    pub(crate) conflicts: Vec<Conflict>,
+    pub(crate) warnings: Vec<BuildWarning>,
+    pub(crate) wads_to_build: Vec<Utf8PathBuf>,
+    pub(crate) wads_to_reuse: Vec<Utf8PathBuf>,
+    pub(crate) new_wad_fingerprints: BTreeMap<String, u64>,
+}
+
+impl OverlayBuilder {
+    /// Work out what [`build`](Self::build) would do, without writing the overlay.
+    ///
+    /// Runs game indexing, override collection and distribution exactly as a
+    /// build does, then reports which WADs would be patched or reused. Nothing
+    /// is written to the overlay root and `overlay.json` is left alone; the game
+    /// index and override metadata caches in the state directory may still be
+    /// refreshed. The per-build reports ([`take_mod_wad_reports`] and friends)
+    /// are filled in as by a build.
+    ///
+    /// When the previous build can be reused as is, its WADs are listed with no
+    /// overrides collected, so their counts and sizes are zero.
+    ///
+    /// [`take_mod_wad_reports`]: Self::take_mod_wad_reports
+    pub fn plan(&mut self) -> Result<OverlayPlan> {
+        self.last_linked_bin_offenders = Vec::new();
+        self.last_suspect_hex_overrides = Vec::new();
+
+        let rolled_back = crate::backup::has_rollback_marker(&self.overlay_root);
+        let prepared = self.prepare_build(rolled_back)?;
+
+        let Some(work) = prepared.work else {
+            let wads_to_reuse = match prepared.rebuild_reason {
+                RebuildReason::ExactMatchSkipped => prepared
+                    .prev_state
+                    .iter()
+                    .flat_map(|state| state.wad_fingerprints.keys())
+                    .map(|wad| PlannedWad {
+                        path: self.overlay_wad_path(Utf8Path::new(wad)),
+                        override_count: 0,
+                        estimated_bytes: 0,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            return Ok(OverlayPlan {
+                rebuild_reason: prepared.rebuild_reason,
+                wads_to_build: Vec::new(),
+                wads_to_reuse,
+                conflicts: Vec::new(),
+                semantic_conflicts: prepared.semantic_conflicts,
+            });
+        };
+
+        let planned = |wads: &[Utf8PathBuf]| -> Vec<PlannedWad> {
+            wads.iter()
+                .map(|wad| {
+                    let hashes = work.wad_hash_sets.get(wad);
+                    let override_bytes: u64 = hashes
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|hash| work.all_meta.get(hash))
+                        .map(|meta| meta.uncompressed_size as u64)
+                        .sum();
+                    let game_wad_bytes = std::fs::metadata(self.game_dir.join(wad).as_std_path())
+                        .map(|m| m.len())
+                        .unwrap_or(0);
+                    PlannedWad {
+                        path: self.overlay_wad_path(wad),
+                        override_count: hashes.map_or(0, |h| h.len()),
+                        estimated_bytes: game_wad_bytes + override_bytes,
+                    }
+                })
+                .collect()
+        };
+
+        Ok(OverlayPlan {
+            rebuild_reason: prepared.rebuild_reason,
+            wads_to_build: planned(&work.wads_to_build),
+            wads_to_reuse: planned(&work.wads_to_reuse),
+            conflicts: work.conflicts,
+            semantic_conflicts: prepared.semantic_conflicts,
+        })
+    }
+
+    /// Report the conflicts between the enabled mods, without building.
+    ///
+    /// Indexes the game and collects every override as a build does, then stops
+    /// before distributing them to WADs. Unlike [`plan`](Self::plan), the saved
+    /// overlay state is ignored, so the conflicts are reported even when the
+    /// previous build could be reused. Suited to checking a candidate mod set
+    /// before enabling it. As with `plan`, only the caches in the state directory
+    /// may be written.
+    pub fn scan_conflicts(&mut self) -> Result<Vec<Conflict>> {
+        if self.enabled_mods.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.game_dir = ltk_mod_core::resolve_game_dir(&self.game_dir)?;
+        std::fs::create_dir_all(self.state_dir.as_std_path())?;
+        let cache_path = self.state_dir.join("game_index.bin");
+        let mut game_index = GameIndex::load_or_build(&self.game_dir, &cache_path)?;
+        if let Some(path) = &self.hashtable_path {
+            game_index = game_index.with_hashtable(path)?;
+        }
+
+        Ok(self.collect_all_override_metadata(&game_index)?.conflicts)
+    }
+
+    /// Index the game, pick a build strategy and, unless the build can be
+    /// skipped, collect and distribute every override.
+    ///
+    /// Writes nothing but the caches in the state directory. `rolled_back`
+    /// discards the saved overlay state, as after a
+    /// [`rollback`](crate::rollback).
+    pub(crate) fn prepare_build(&mut self, rolled_back: bool) -> Result<PreparedBuild> {
+        self.wad_patch_options.validate()?;
+        let effective_blocked = self.effective_blocked_wads();
+
+        self.emit_progress(OverlayProgress::stage(OverlayStage::Indexing));
+
+        self.game_dir = ltk_mod_core::resolve_game_dir(&self.game_dir)?;
+
+        std::fs::create_dir_all(self.state_dir.as_std_path())?;
+
+        let cache_path = self.state_dir.join("game_index.bin");
+        let mut game_index = GameIndex::load_or_build(&self.game_dir, &cache_path)?;
+        if let Some(path) = &self.hashtable_path {
+            game_index = game_index.with_hashtable(path)?;
+        }
+
+        // Persisted highest-priority first, so flipping the priority order of the same
+        // list is seen as a mod list change.
+        let mut enabled_ids: Vec<String> = self.enabled_mods.iter().map(|m| m.id.clone()).collect();
+        if self.priority_order == PriorityOrder::BackWins {
+            enabled_ids.reverse();
+        }
+        enabled_ids.extend(self.raw_overrides_state_id());
+        let mut prev_state = OverlayState::load(&self.state_dir.join("overlay.json"))?;
+        let mut checkpoint = progress::load_checkpoint(
+            &self.state_dir.join(progress::PROGRESS_FILE_NAME),
+            game_index.game_fingerprint(),
+        )?;
+        if rolled_back {
+            tracing::info!("Overlay: rolled back since the last build, ignoring saved state");
+            prev_state = None;
+            checkpoint = None;
+        }
+        let other_profile = prev_state
+            .as_ref()
+            .is_some_and(|state| state.built_for_other_profile(self.profile_id.as_deref()));
+        if other_profile {
+            tracing::info!("Overlay: built for another profile, rebuilding");
+            checkpoint = None;
+        }
+
+        let mut prepared = PreparedBuild {
+            game_index,
+            enabled_ids,
+            effective_blocked,
+            prev_state,
+            rebuild_reason: RebuildReason::EmptyModList,
+            semantic_conflicts: Vec::new(),
+            work: None,
+        };
+
+        if self.enabled_mods.is_empty() && self.raw_overrides.is_empty() {
+            self.emit_event(BuildEvent::StrategyChosen {
+                reason: prepared.rebuild_reason,
+            });
+            return Ok(prepared);
+        }
+
+        prepared.semantic_conflicts = self.analyze_semantic_conflicts();
+
+        if let Some(state) = prepared
+            .prev_state
+            .as_ref()
+            .filter(|_| checkpoint.is_none() && !other_profile)
+        {
+            if state.matches(
+                &prepared.enabled_ids,
+                prepared.game_index.game_fingerprint(),
+                &prepared.effective_blocked,
+            ) && state.conflict_strategy == self.conflict_strategy
+                && state.output_extension == self.output_extension
+                && state.wad_filter_key == self.wad_filter_key
+                && !state.incomplete
+            {
+                if self.validate_wads_exist(state) {
+                    self.last_linked_bin_offenders = state.linked_bin_offenders.clone();
+                    prepared.rebuild_reason = RebuildReason::ExactMatchSkipped;
+                    self.emit_event(BuildEvent::StrategyChosen {
+                        reason: prepared.rebuild_reason,
+                    });
+                    return Ok(prepared);
+                } else {
+                    tracing::info!(
+                        "Overlay: state matched but some WADs missing, doing incremental repair"
+                    );
+                }
+            }
+        }
+
+        // Determine if incremental build is possible
+        let game_index = &prepared.game_index;
+        let game_fp = game_index.game_fingerprint();
+        prepared.rebuild_reason = match prepared.prev_state.as_ref() {
+            _ if other_profile => RebuildReason::FullRebuildProfileChanged,
+            _ if checkpoint.is_some() => RebuildReason::ResumedInterruptedBuild,
+            Some(state) if state.output_extension != self.output_extension => {
+                RebuildReason::FullRebuildOutputExtensionChanged
+            }
+            Some(state) if state.supports_incremental(game_fp) => RebuildReason::Incremental,
+            Some(state) if state.game_fingerprint != game_fp => {
+                RebuildReason::FullRebuildGamePatched
+            }
+            Some(_) => RebuildReason::FullRebuildVersionBump,
+            None => RebuildReason::FullRebuildNoPreviousState,
+        };
+        let finished_wads = match prepared.rebuild_reason {
+            RebuildReason::Incremental => prepared
+                .prev_state
+                .as_ref()
+                .map(|state| &state.wad_fingerprints),
+            RebuildReason::ResumedInterruptedBuild => checkpoint.as_ref(),
+            _ => None,
+        };
+        let finished_root = match prepared.rebuild_reason {
+            RebuildReason::ResumedInterruptedBuild => self.staging_root(),
+            _ => self.overlay_root.clone(),
+        };
+        self.emit_event(BuildEvent::StrategyChosen {
+            reason: prepared.rebuild_reason,
+        });
+
+        self.emit_progress(OverlayProgress::stage(OverlayStage::CollectingOverrides));
+
+        let metadata::OverrideCollection {
+            mut all_meta,
+            mod_wad_reports,
+            conflicts,
+            suspect_hex_overrides,
+            broken_overrides,
+        } = self.collect_all_override_metadata(game_index)?;
+        self.last_mod_wad_reports = mod_wad_reports;
+        self.last_suspect_hex_overrides = suspect_hex_overrides;
+
+        self.emit_progress(OverlayProgress::stage(
+            OverlayStage::ApplyingStringOverrides,
+        ));
+        self.apply_raw_overrides(&mut all_meta);
+        self.apply_string_overrides(&mut all_meta, game_index)?;
+
+        let mut wad_hash_sets = self.distribute_override_hashes(&all_meta, game_index);
+
+        wad_hash_sets.retain(|path, _| {
+            let blocked = self.is_wad_blocked(path);
+            if blocked {
+                tracing::info!("Blocked WAD from patching: {}", path);
+                self.emit_event(BuildEvent::WadBlocked { wad: path.clone() });
+            }
+            !blocked
+        });
+
+        // Validate property-bin linked dependencies against the overlay WADs we are
+        // about to write. Runs over every enabled mod's overrides (built and reused
+        // alike) since distribution precedes the rebuild/reuse partition.
+        self.last_linked_bin_offenders =
+            collect_linked_bin_offenders(&all_meta, &wad_hash_sets, game_index);
+        if !self.last_linked_bin_offenders.is_empty() {
+            tracing::info!(
+                "Linked-bin check: {} mod(s) reference unresolved linked bins",
+                self.last_linked_bin_offenders.len()
+            );
+        }
+
+        let (wads_to_build, wads_to_reuse, new_wad_fingerprints) =
+            self.partition_wads_from_meta(&wad_hash_sets, &all_meta, finished_wads, &finished_root);
+
+        prepared.work = Some(PlannedWork {
+            all_meta,
+            wad_hash_sets,
+            conflicts,
+            warnings: broken_overrides
+                .into_iter()
+                .map(BuildWarning::BrokenOverride)
+                .collect(),
+            wads_to_build,
+            wads_to_reuse,
+            new_wad_fingerprints,
+        });
+        Ok(prepared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
+
+    #[test]
+    fn plan_reports_work_without_writing_the_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+
+        let mod_dir = root.join("mods/skin");
+        write_fs_mod(
+            &mod_dir,
+            &mock_project("skin"),
+            &[("base/A.wad.client/data/a.bin", b"patched")],
+        )
+        .unwrap();
+
+        let overlay_root = root.join("overlay");
+        std::fs::create_dir_all(&overlay_root).unwrap();
+        let state_dir = root.join("state");
+        let mut builder = OverlayBuilder::new(game_dir.clone(), overlay_root.clone(), state_dir);
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "skin".to_string(),
+            content: Box::new(FsModContent::new(mod_dir)),
+            enabled_layers: None,
+        }]);
+
+        let plan = builder.plan().unwrap();
+        assert_eq!(
+            plan.rebuild_reason,
+            RebuildReason::FullRebuildNoPreviousState
+        );
+        assert!(plan.wads_to_reuse.is_empty());
+        assert_eq!(plan.wads_to_build.len(), 1);
+        let wad = &plan.wads_to_build[0];
+        assert!(wad.path.ends_with("Champions/A.wad.client"));
+        assert_eq!(wad.override_count, 1);
+        let game_wad = game_dir.join("DATA/FINAL/Champions/A.wad.client");
+        assert_eq!(
+            wad.estimated_bytes,
+            std::fs::metadata(game_wad).unwrap().len() + b"patched".len() as u64
+        );
+        assert_eq!(
+            std::fs::read_dir(&overlay_root).unwrap().count(),
+            0,
+            "plan() must not write to the overlay root"
+        );
+
+        // A build does what the plan said.
+        let result = builder.build().unwrap();
+        assert_eq!(result.rebuild_reason, plan.rebuild_reason);
+        assert_eq!(result.wads_built, vec![wad.path.clone()]);
+    }
+
+    #[test]
+    fn scan_conflicts_ignores_the_saved_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+            ),
+        )
+        .unwrap();
+
+        let mut enabled = Vec::new();
+        for (id, bytes) in [("first", "first override"), ("second", "second override")] {
+            let mod_dir = root.join("mods").join(id);
+            write_fs_mod(
+                &mod_dir,
+                &mock_project(id),
+                &[("base/A.wad.client/data/a.bin", bytes.as_bytes())],
+            )
+            .unwrap();
+            enabled.push(EnabledMod {
+                id: id.to_string(),
+                content: Box::new(FsModContent::new(mod_dir)),
+                enabled_layers: None,
+            });
+        }
+
+        let overlay_root = root.join("overlay");
+        let mut builder = OverlayBuilder::new(game_dir, overlay_root.clone(), root.join("state"));
+        builder.set_enabled_mods(enabled);
+
+        let conflicts = builder.scan_conflicts().unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].winner, "first");
+        assert_eq!(conflicts[0].contributing_mods.len(), 2);
+        assert!(
+            !overlay_root.exists(),
+            "scan_conflicts() must not write the overlay"
+        );
+
+        builder.build().unwrap();
+        assert_eq!(builder.plan().unwrap().conflicts, Vec::new());
+        assert_eq!(builder.scan_conflicts().unwrap(), conflicts);
+    }
+}
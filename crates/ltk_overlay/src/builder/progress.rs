@@ -0,0 +1,151 @@
+//! Checkpoints that let an interrupted build resume.
+//!
+//! A build patches its WADs into a staging root (see [`staging`](super::staging))
+//! and `overlay.json` is only written once that is swapped in, so after a crash
+//! or a failed build the overlay and its state are still the previous ones.
+//! While WADs are patched, `overlay.progress` in the state directory lists every
+//! staged WAD that is finished, with the fingerprint it was built for. The file
+//! is removed once `overlay.json` describes the new overlay, so while it exists
+//! a build is pending: the exact-match skip is off and
+//! [`OverlayBuilder::has_interrupted_build`] reports it.
+//!
+//! The next build keeps the listed staged WADs whose fingerprint still matches
+//! and patches the rest. A WAD is only listed after it has been moved into
+//! place, so a WAD cut off mid-write is never kept.
+
+use super::*;
+use std::fs::File;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Name of the checkpoint file inside the state directory.
+pub(crate) const PROGRESS_FILE_NAME: &str = "overlay.progress";
+
+/// Read the checkpoint left by an interrupted build.
+///
+/// Returns `None` when there is none, and the finished WADs with their
+/// fingerprints otherwise. A checkpoint from before a game patch keeps no WADs,
+/// and a line cut off by the interruption is ignored.
+pub(crate) fn load_checkpoint(
+    path: &Utf8Path,
+    game_fingerprint: u64,
+) -> Result<Option<BTreeMap<String, u64>>> {
+    let contents = match std::fs::read_to_string(path.as_std_path()) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = contents
+        .split_inclusive('\n')
+        .filter_map(|line| line.strip_suffix('\n'));
+    let header = format!("game {:016x}", game_fingerprint);
+    if lines.next() != Some(header.as_str()) {
+        tracing::info!("Overlay: discarding build checkpoint from another game version");
+        return Ok(Some(BTreeMap::new()));
+    }
+
+    let finished = lines
+        .filter_map(|line| {
+            let (fingerprint, wad) = line.split_once(' ')?;
+            let fingerprint = u64::from_str_radix(fingerprint, 16).ok()?;
+            Some((wad.to_string(), fingerprint))
+        })
+        .collect();
+    Ok(Some(finished))
+}
+
+/// Delete the checkpoint once `overlay.json` describes the overlay on disk.
+pub(crate) fn remove_checkpoint(path: &Utf8Path) -> Result<()> {
+    match std::fs::remove_file(path.as_std_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// The checkpoint of the running build.
+pub(crate) struct BuildCheckpoint<'a> {
+    file: Mutex<File>,
+    fingerprints: &'a BTreeMap<String, u64>,
+}
+
+impl<'a> BuildCheckpoint<'a> {
+    /// Start the checkpoint of a build, listing the `finished` WADs it keeps.
+    ///
+    /// `fingerprints` holds the fingerprint of every WAD the build produces.
+    pub(crate) fn create(
+        path: &Utf8Path,
+        game_fingerprint: u64,
+        fingerprints: &'a BTreeMap<String, u64>,
+        finished: &[Utf8PathBuf],
+    ) -> Result<Self> {
+        let mut contents = format!("game {:016x}\n", game_fingerprint);
+        for wad in finished {
+            if let Some(fingerprint) = fingerprints.get(wad.as_str()) {
+                contents.push_str(&format!("{:016x} {}\n", fingerprint, wad));
+            }
+        }
+
+        let mut file = File::create(path.as_std_path())?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_data()?;
+        Ok(Self {
+            file: Mutex::new(file),
+            fingerprints,
+        })
+    }
+
+    /// Record that the overlay WAD at `wad` (relative to the overlay root) is
+    /// finished.
+    pub(crate) fn record(&self, wad: &Utf8Path) -> Result<()> {
+        let Some(fingerprint) = self.fingerprints.get(wad.as_str()) else {
+            return Ok(());
+        };
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(format!("{:016x} {}\n", fingerprint, wad).as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_round_trips_and_ignores_torn_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Utf8Path::from_path(dir.path())
+            .unwrap()
+            .join(PROGRESS_FILE_NAME);
+        assert_eq!(load_checkpoint(&path, 7).unwrap(), None);
+
+        let fingerprints = BTreeMap::from([
+            ("DATA/A.wad.client".to_string(), 1),
+            ("DATA/B.wad.client".to_string(), 2),
+        ]);
+        let checkpoint = BuildCheckpoint::create(
+            &path,
+            7,
+            &fingerprints,
+            &[Utf8PathBuf::from("DATA/A.wad.client")],
+        )
+        .unwrap();
+        checkpoint
+            .record(Utf8Path::new("DATA/B.wad.client"))
+            .unwrap();
+        drop(checkpoint);
+        assert_eq!(load_checkpoint(&path, 7).unwrap(), Some(fingerprints));
+
+        let mut torn = std::fs::read_to_string(&path).unwrap();
+        torn.push_str("0000000000000003 DATA/C.wad");
+        std::fs::write(&path, torn).unwrap();
+        assert_eq!(load_checkpoint(&path, 7).unwrap().unwrap().len(), 2);
+
+        assert_eq!(load_checkpoint(&path, 8).unwrap(), Some(BTreeMap::new()));
+
+        remove_checkpoint(&path).unwrap();
+        remove_checkpoint(&path).unwrap();
+        assert_eq!(load_checkpoint(&path, 7).unwrap(), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Overrides handed to the builder directly instead of through a mod.
+//!
+//! [`OverlayBuilder::set_raw_overrides`] is an escape hatch for tools that
+//! compute chunk bytes themselves. The overrides skip the mod and layer
+//! machinery entirely and are added to the collected overrides as generated
+//! ones, so distribution and patching treat them like any other.
+
+use super::*;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Prefix of the entry standing in for the raw overrides in the persisted
+/// mod list, followed by their fingerprint.
+const RAW_OVERRIDES_STATE_ID: &str = "<raw-overrides>";
+
+impl OverlayBuilder {
+    /// Set overrides to patch in without a [`ModContentProvider`], keyed by
+    /// chunk path hash.
+    ///
+    /// They are distributed to every game WAD holding their hash and win over
+    /// the enabled mods' overrides for the same chunk. A hash in no game WAD is
+    /// added to the game-relative WAD path `target_hints` gives for it (e.g.
+    /// `DATA/FINAL/Champions/Aatrox.wad.client`) and dropped if it has none.
+    /// Replaces the raw overrides of any previous call; an empty map clears them.
+    pub fn set_raw_overrides(
+        &mut self,
+        overrides: HashMap<u64, Vec<u8>>,
+        target_hints: HashMap<u64, Utf8PathBuf>,
+    ) {
+        self.raw_overrides = overrides
+            .into_iter()
+            .map(|(path_hash, bytes)| (path_hash, SharedBytes::from(bytes)))
+            .collect();
+        self.raw_override_targets = target_hints;
+    }
+
+    /// The entry recording the raw overrides in the persisted mod list, so a
+    /// change to them defeats the exact-match skip. `None` when there are none.
+    pub(crate) fn raw_overrides_state_id(&self) -> Option<String> {
+        if self.raw_overrides.is_empty() {
+            return None;
+        }
+        let mut entries: Vec<(u64, u64, Option<&str>)> = self
+            .raw_overrides
+            .iter()
+            .map(|(&path_hash, bytes)| {
+                let target = self.raw_override_targets.get(&path_hash);
+                (path_hash, xxh3_64(bytes), target.map(|t| t.as_str()))
+            })
+            .collect();
+        entries.sort_unstable();
+
+        let mut buf = Vec::with_capacity(entries.len() * 17);
+        for (path_hash, content_hash, target) in entries {
+            buf.extend_from_slice(&path_hash.to_le_bytes());
+            buf.extend_from_slice(&content_hash.to_le_bytes());
+            buf.extend_from_slice(target.unwrap_or_default().as_bytes());
+            buf.push(0);
+        }
+        Some(format!("{}:{:016x}", RAW_OVERRIDES_STATE_ID, xxh3_64(&buf)))
+    }
+
+    /// Add the raw overrides to `all_meta`, replacing any mod override of the
+    /// same chunk.
+    pub(crate) fn apply_raw_overrides(&self, all_meta: &mut HashMap<u64, OverrideMeta>) {
+        for (&path_hash, bytes) in &self.raw_overrides {
+            if let Some(replaced) = all_meta.get(&path_hash) {
+                tracing::debug!(
+                    "Raw override {:016x} replaces the override from mod '{}'",
+                    path_hash,
+                    replaced.source.mod_id()
+                );
+            }
+            all_meta.insert(
+                path_hash,
+                OverrideMeta {
+                    content_hash: xxh3_64(bytes),
+                    uncompressed_size: bytes.len(),
+                    source: OverrideSource::Generated {
+                        rel_path: Utf8PathBuf::from(format!("{:016x}", path_hash)),
+                        bytes: Arc::clone(bytes),
+                    },
+                    fallback_wad: self.raw_override_targets.get(&path_hash).cloned(),
+                    linked_bins: Vec::new(),
+                    compression: None,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+    use ltk_wad::Wad;
+    use std::fs::File;
+
+    fn chunk_bytes(wad_path: &Utf8Path, path_hash: u64) -> Vec<u8> {
+        let mut wad = Wad::mount(File::open(wad_path.as_std_path()).unwrap()).unwrap();
+        let chunk = *wad.chunks().get(path_hash).unwrap();
+        wad.load_chunk_decompressed(&chunk).unwrap().to_vec()
+    }
+
+    #[test]
+    fn raw_overrides_are_patched_without_a_mod() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+            ),
+        )
+        .unwrap();
+        let existing = ltk_modpkg::utils::hash_chunk_name("data/a.bin");
+        let added = ltk_modpkg::utils::hash_chunk_name("data/generated.bin");
+        let wad = Utf8PathBuf::from("DATA/FINAL/Champions/A.wad.client");
+        let build = |bytes: &[u8]| {
+            let mut builder =
+                OverlayBuilder::new(game_dir.clone(), root.join("overlay"), root.to_path_buf());
+            builder.set_raw_overrides(
+                HashMap::from([(existing, bytes.to_vec()), (added, b"generated".to_vec())]),
+                HashMap::from([(added, wad.clone())]),
+            );
+            builder.build().unwrap()
+        };
+
+        let first = build(b"procedural");
+        assert_eq!(
+            first.rebuild_reason,
+            RebuildReason::FullRebuildNoPreviousState
+        );
+        let overlay_wad = root.join("overlay").join(&wad);
+        assert_eq!(first.wads_built, vec![overlay_wad.clone()]);
+        assert_eq!(chunk_bytes(&overlay_wad, existing), b"procedural");
+        assert_eq!(chunk_bytes(&overlay_wad, added), b"generated");
+
+        assert_eq!(
+            build(b"procedural").rebuild_reason,
+            RebuildReason::ExactMatchSkipped
+        );
+        let changed = build(b"regenerated");
+        assert_eq!(changed.rebuild_reason, RebuildReason::Incremental);
+        assert_eq!(chunk_bytes(&overlay_wad, existing), b"regenerated");
+    }
+}
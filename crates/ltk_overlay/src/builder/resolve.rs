@@ -3,10 +3,15 @@
 //! Routes override hashes to affected WADs, partitions into rebuild/reuse sets,
 //! re-reads bytes for WADs that need rebuilding, and patches WADs in parallel.
 
+use super::hex_names::is_hex_named;
+use super::progress::BuildCheckpoint;
+use super::spill::{plan_spill, ResolvedOverride, SpillStore};
 use super::*;
 use crate::utils::compute_wad_fingerprint_from_meta;
-use crate::wad_builder::build_patched_wad;
+use crate::wad_builder::{build_patched_wad_pinned, PatchedWadStats};
+use ltk_wad::Wad;
 use rayon::prelude::*;
+use std::fs::File;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 impl OverlayBuilder {
@@ -22,16 +27,44 @@ impl OverlayBuilder {
         let mut wad_hash_sets: BTreeMap<Utf8PathBuf, HashSet<u64>> = BTreeMap::new();
         let mut new_entry_count = 0usize;
         let mut dropped_count = 0usize;
+        let mut filtered_count = 0usize;
 
         for (&path_hash, meta) in all_meta {
             if let Some(wad_paths) = game_index.find_wads_with_hash(path_hash) {
-                for wad_path in wad_paths {
+                let mut routed = false;
+                for wad_path in wad_paths.iter().filter(|wad| self.is_wad_accepted(wad)) {
                     wad_hash_sets
                         .entry(wad_path.clone())
                         .or_default()
                         .insert(path_hash);
+                    routed = true;
+                }
+                if !routed {
+                    filtered_count += 1;
+                    tracing::debug!(
+                        "Override {} from mod '{}' ('{}') only targets WADs excluded by \
+                         the WAD filter; dropping it",
+                        game_index.describe_hash(path_hash),
+                        meta.source.mod_id(),
+                        meta.source.rel_path(),
+                    );
+                }
+                continue;
+            }
+
+            if let Some(fallback) = &meta.fallback_wad {
+                if !self.is_wad_accepted(fallback) {
+                    filtered_count += 1;
+                    tracing::debug!(
+                        "Override {} from mod '{}' ('{}') targets '{}', which the WAD \
+                         filter excludes; dropping it",
+                        game_index.describe_hash(path_hash),
+                        meta.source.mod_id(),
+                        meta.source.rel_path(),
+                        fallback
+                    );
+                    continue;
                 }
-            } else if let Some(fallback) = &meta.fallback_wad {
                 wad_hash_sets
                     .entry(fallback.clone())
                     .or_default()
@@ -39,10 +72,24 @@ impl OverlayBuilder {
                 new_entry_count += 1;
             } else {
                 dropped_count += 1;
+                // A path-derived name that matches no game WAD is an intentional
+                // new asset. A hex name is a hash taken from the game itself, so
+                // a miss means the asset was removed or renamed by a game patch.
+                // Strict mode has already reported these during metadata
+                // collection.
+                if !self.strict_hex_names && is_hex_named(path_hash, meta) {
+                    tracing::warn!(
+                        "Mod='{}' override '{}' targets game asset {}, which no game WAD \
+                         contains anymore; it may have been removed by a game patch",
+                        meta.source.mod_id(),
+                        meta.source.rel_path(),
+                        game_index.describe_hash(path_hash)
+                    );
+                }
                 tracing::debug!(
-                    "Override {:016x} from mod '{}' ('{}') matches no game WAD and has no \
+                    "Override {} from mod '{}' ('{}') matches no game WAD and has no \
                      fallback target; skipping",
-                    path_hash,
+                    game_index.describe_hash(path_hash),
                     meta.source.mod_id(),
                     meta.source.rel_path(),
                 );
@@ -55,6 +102,12 @@ impl OverlayBuilder {
                 new_entry_count
             );
         }
+        if filtered_count > 0 {
+            tracing::info!(
+                "Dropped {} override(s) that only target WADs excluded by the WAD filter",
+                filtered_count
+            );
+        }
         if dropped_count > 0 {
             tracing::warn!(
                 "{} override(s) could not be routed to any game WAD (no hash match and no \
@@ -72,13 +125,18 @@ impl OverlayBuilder {
 
     /// Compute per-WAD fingerprints from metadata and partition into rebuild vs reuse.
     ///
+    /// A WAD is reused when `finished_wads` (the previous build's fingerprints,
+    /// or those of an interrupted build) lists it with the same fingerprint and it
+    /// exists under `finished_root` (the overlay root, or the staging root of an
+    /// interrupted build).
+    ///
     /// Returns `(wads_to_build, wads_to_reuse, new_wad_fingerprints)`.
     pub(crate) fn partition_wads_from_meta(
         &self,
         wad_hash_sets: &BTreeMap<Utf8PathBuf, HashSet<u64>>,
         all_meta: &HashMap<u64, OverrideMeta>,
-        prev_state: &Option<OverlayState>,
-        can_incremental: bool,
+        finished_wads: Option<&BTreeMap<String, u64>>,
+        finished_root: &Utf8Path,
     ) -> (Vec<Utf8PathBuf>, Vec<Utf8PathBuf>, BTreeMap<String, u64>) {
         let new_wad_fingerprints: BTreeMap<String, u64> = wad_hash_sets
             .iter()
@@ -95,17 +153,13 @@ impl OverlayBuilder {
 
         for (wad_path_str, &new_fp) in &new_wad_fingerprints {
             let wad_path = Utf8PathBuf::from(wad_path_str);
-            let overlay_wad = self.overlay_root.join(&wad_path);
-
-            if can_incremental {
-                if let Some(ref state) = prev_state {
-                    if let Some(old_fp) = state.wad_fingerprint(wad_path_str) {
-                        if old_fp == new_fp && overlay_wad.as_std_path().exists() {
-                            tracing::debug!("Reusing WAD: {}", wad_path);
-                            wads_to_reuse.push(wad_path);
-                            continue;
-                        }
-                    }
+            let overlay_wad = finished_root.join(self.overlay_wad_rel_path(&wad_path));
+
+            if let Some(&old_fp) = finished_wads.and_then(|wads| wads.get(wad_path_str)) {
+                if old_fp == new_fp && overlay_wad.as_std_path().exists() {
+                    tracing::debug!("Reusing WAD: {}", wad_path);
+                    wads_to_reuse.push(wad_path);
+                    continue;
                 }
             }
 
@@ -119,14 +173,18 @@ impl OverlayBuilder {
     /// Re-read override bytes for WADs that need rebuilding (pass 2).
     ///
     /// Groups needed overrides by source mod, reads each file once via the
-    /// targeted `read_wad_override_file` / `read_raw_override_file` methods,
-    /// wraps bytes in `Arc` for cross-WAD sharing, and distributes to per-WAD maps.
+    /// targeted `read_wad_override_file` / `read_raw_override_file` methods
+    /// (retried per [`with_read_retries`](Self::with_read_retries)), wraps
+    /// bytes in `Arc` for cross-WAD sharing, and distributes to per-WAD maps.
+    ///
+    /// When a spill budget is configured, overrides selected by [`plan_spill`] are
+    /// written to a temp file as they are read and only their offsets are kept.
     pub(crate) fn resolve_overrides_for_wads(
         &mut self,
         wads_to_build: &[Utf8PathBuf],
         wad_hash_sets: &BTreeMap<Utf8PathBuf, HashSet<u64>>,
         all_meta: &HashMap<u64, OverrideMeta>,
-    ) -> Result<BTreeMap<Utf8PathBuf, HashMap<u64, SharedBytes>>> {
+    ) -> Result<BTreeMap<Utf8PathBuf, HashMap<u64, ResolvedOverride>>> {
         // Collect all unique path_hashes needed across WADs to build
         let needed_hashes: HashSet<u64> = wads_to_build
             .iter()
@@ -139,12 +197,18 @@ impl OverlayBuilder {
         }
 
         // Group needed hashes by source mod ID
+        // Generated overrides are already in memory and need no provider.
         let mut by_mod: HashMap<&str, Vec<u64>> = HashMap::new();
+        let mut generated: Vec<(u64, SharedBytes)> = Vec::new();
         for &path_hash in &needed_hashes {
             if let Some(meta) = all_meta.get(&path_hash) {
                 let mod_id = match &meta.source {
                     OverrideSource::LayerWad { mod_id, .. } => mod_id.as_str(),
                     OverrideSource::Raw { mod_id, .. } => mod_id.as_str(),
+                    OverrideSource::Generated { bytes, .. } => {
+                        generated.push((path_hash, Arc::clone(bytes)));
+                        continue;
+                    }
                 };
                 by_mod.entry(mod_id).or_default().push(path_hash);
             }
@@ -158,8 +222,24 @@ impl OverlayBuilder {
             .map(|(i, m)| (m.id.clone(), i))
             .collect();
 
+        // Decide up front which overrides go to disk, using pass 1 sizes.
+        let to_spill = match self.spill_max_memory {
+            Some(max_memory_bytes) => plan_spill(&needed_hashes, all_meta, max_memory_bytes),
+            None => HashSet::new(),
+        };
+        let mut spill_store = if to_spill.is_empty() {
+            None
+        } else {
+            let spill_dir = self.scratch_dir.as_ref().unwrap_or(&self.state_dir);
+            Some(SpillStore::new_in(spill_dir)?)
+        };
+        let mut spilled: Vec<(u64, u64, usize)> = Vec::with_capacity(to_spill.len());
+
         // Read bytes from each mod via targeted read methods
-        let mut resolved: HashMap<u64, SharedBytes> = HashMap::with_capacity(needed_hashes.len());
+        let transform = self.override_transform.clone();
+        let retry = self.read_retries;
+        let mut resolved: HashMap<u64, ResolvedOverride> =
+            HashMap::with_capacity(needed_hashes.len());
 
         for (mod_id, hashes) in &by_mod {
             let Some(&idx) = mod_id_to_index.get(*mod_id) else {
@@ -178,23 +258,65 @@ impl OverlayBuilder {
                         wad_name,
                         rel_path,
                         ..
-                    } => provider.read_wad_override_file(layer, wad_name, rel_path)?,
-                    OverrideSource::Raw { rel_path, .. } => {
-                        provider.read_raw_override_file(rel_path)?
-                    }
+                    } => retry.run(
+                        &format_args!(
+                            "mod={} layer='{}' WAD='{}' file='{}'",
+                            mod_id, layer, wad_name, rel_path
+                        ),
+                        || provider.read_wad_override_file(layer, wad_name, rel_path),
+                    )?,
+                    OverrideSource::Raw { rel_path, .. } => retry.run(
+                        &format_args!("mod={} raw file='{}'", mod_id, rel_path),
+                        || provider.read_raw_override_file(rel_path),
+                    )?,
+                    OverrideSource::Generated { bytes, .. } => bytes.to_vec(),
                 };
-                resolved.insert(path_hash, Arc::from(bytes));
+                let bytes = apply_override_transform(transform.as_ref(), path_hash, bytes);
+                match spill_store.as_mut() {
+                    Some(store) if to_spill.contains(&path_hash) => {
+                        let (offset, len) = store.append(&bytes)?;
+                        spilled.push((path_hash, offset, len));
+                    }
+                    _ => {
+                        resolved.insert(path_hash, ResolvedOverride::Memory(Arc::from(bytes)));
+                    }
+                }
+            }
+        }
+
+        for (path_hash, bytes) in generated {
+            resolved.insert(path_hash, ResolvedOverride::Memory(bytes));
+        }
+
+        if let Some(store) = spill_store {
+            tracing::info!(
+                "Spilled {} override(s) ({} bytes) to disk to stay within memory budget",
+                spilled.len(),
+                store.len()
+            );
+            let store = Arc::new(store);
+            for (path_hash, offset, len) in spilled {
+                resolved.insert(
+                    path_hash,
+                    ResolvedOverride::Spilled {
+                        store: Arc::clone(&store),
+                        offset,
+                        len,
+                    },
+                );
             }
         }
 
         // Distribute resolved bytes to per-WAD maps
-        let mut wad_overrides: BTreeMap<Utf8PathBuf, HashMap<u64, SharedBytes>> = BTreeMap::new();
+        let mut wad_overrides: BTreeMap<Utf8PathBuf, HashMap<u64, ResolvedOverride>> =
+            BTreeMap::new();
         for wad_path in wads_to_build {
             if let Some(hashes) = wad_hash_sets.get(wad_path) {
-                let mut per_wad: HashMap<u64, SharedBytes> = HashMap::with_capacity(hashes.len());
+                let mut per_wad: HashMap<u64, ResolvedOverride> =
+                    HashMap::with_capacity(hashes.len());
                 for &hash in hashes {
                     if let Some(bytes) = resolved.get(&hash) {
-                        per_wad.insert(hash, Arc::clone(bytes));
+                        per_wad.insert(hash, bytes.clone());
                     }
                 }
                 wad_overrides.insert(wad_path.clone(), per_wad);
@@ -207,18 +329,32 @@ impl OverlayBuilder {
     /// Patch WADs in parallel, emitting progress after each one completes.
     ///
     /// Consumes `wad_overrides` so each parallel task owns its data, enabling
-    /// progressive deallocation as each WAD finishes patching.
+    /// progressive deallocation as each WAD finishes patching. Compression pinned
+    /// on an override's metadata in `all_meta` is forwarded to the patcher.
+    ///
+    /// WADs left with no overrides are hard linked from the game instead when
+    /// [`with_link_unmodified_wads`](OverlayBuilder::with_link_unmodified_wads)
+    /// is set. WADs not yet started when `deadline` passes are skipped. Returns the
+    /// overlay paths of the patched WADs, their statistics, and the relative paths
+    /// of the skipped ones.
     pub(crate) fn patch_wads_parallel(
         &self,
         wads_to_build: Vec<Utf8PathBuf>,
-        mut wad_overrides: BTreeMap<Utf8PathBuf, HashMap<u64, SharedBytes>>,
-    ) -> Result<Vec<Utf8PathBuf>> {
+        mut wad_overrides: BTreeMap<Utf8PathBuf, HashMap<u64, ResolvedOverride>>,
+        all_meta: &HashMap<u64, OverrideMeta>,
+        deadline: Option<Instant>,
+        checkpoint: &BuildCheckpoint,
+    ) -> Result<(Vec<Utf8PathBuf>, Vec<WadBuildStats>, Vec<Utf8PathBuf>)> {
         let total_wads = wads_to_build.len() as u32;
         let completed = AtomicU32::new(0);
         let reported = AtomicU32::new(0);
         let game_dir = &self.game_dir;
-        let overlay_root = &self.overlay_root;
+        let staging_root = self.staging_root();
+        let scratch_dir = self.scratch_dir.as_deref();
+        let wad_patch_options = self.wad_patch_options;
+        let link_unmodified_wads = self.link_unmodified_wads;
         let progress_callback = &self.progress_callback;
+        let event_sink = self.event_sink.as_ref();
 
         let emit = |progress: OverlayProgress| {
             if let Some(callback) = progress_callback {
@@ -234,7 +370,7 @@ impl OverlayBuilder {
         });
 
         // Extract per-WAD overrides so each parallel task owns its data.
-        let per_wad_work: Vec<(Utf8PathBuf, HashMap<u64, SharedBytes>)> = wads_to_build
+        let per_wad_work: Vec<(Utf8PathBuf, HashMap<u64, ResolvedOverride>)> = wads_to_build
             .into_iter()
             .map(|path| {
                 let overrides = wad_overrides.remove(&path).unwrap_or_default();
@@ -243,11 +379,20 @@ impl OverlayBuilder {
             .collect();
         drop(wad_overrides);
 
-        per_wad_work
+        let outcomes: Vec<(Utf8PathBuf, Option<WadBuildStats>)> = per_wad_work
             .into_par_iter()
-            .map(|(relative_game_path, mut overrides)| {
+            .map(|(relative_game_path, overrides)| {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return Ok((relative_game_path, None));
+                }
+
                 let src_wad_path = game_dir.join(&relative_game_path);
-                let dst_wad_path = overlay_root.join(&relative_game_path);
+                let overlay_rel_path = self.overlay_wad_rel_path(&relative_game_path);
+                let dst_wad_path = staging_root.join(&overlay_rel_path);
+                let write_path = match scratch_dir {
+                    Some(scratch) => scratch.join(&overlay_rel_path),
+                    None => dst_wad_path.clone(),
+                };
 
                 tracing::info!(
                     "Patching WAD src={} dst={} overrides={}",
@@ -257,11 +402,42 @@ impl OverlayBuilder {
                 );
 
                 let override_hashes: HashSet<u64> = overrides.keys().copied().collect();
-                build_patched_wad(&src_wad_path, &dst_wad_path, &override_hashes, |hash| {
-                    overrides.remove(&hash).ok_or_else(|| {
-                        Error::Other(format!("Missing override data for hash {:016x}", hash))
-                    })
-                })?;
+                let override_count = override_hashes.len();
+                let stats = if override_count == 0 && link_unmodified_wads {
+                    link_or_copy_wad(&src_wad_path, &dst_wad_path)?;
+                    WadBuildStats {
+                        path: relative_game_path.clone(),
+                        override_count: 0,
+                        original_chunk_count: Wad::mount(File::open(src_wad_path.as_std_path())?)?
+                            .chunks()
+                            .len(),
+                        bytes_written: std::fs::metadata(dst_wad_path.as_std_path())?.len(),
+                    }
+                } else {
+                    let stats = patch_wad(
+                        &src_wad_path,
+                        &write_path,
+                        &dst_wad_path,
+                        &override_hashes,
+                        wad_patch_options,
+                        all_meta,
+                        overrides,
+                    )?;
+                    WadBuildStats {
+                        path: relative_game_path.clone(),
+                        override_count: stats.overrides_applied,
+                        original_chunk_count: stats.original_chunks,
+                        bytes_written: stats.bytes_written,
+                    }
+                };
+                checkpoint.record(&relative_game_path)?;
+                emit_event(
+                    event_sink,
+                    BuildEvent::WadPatched {
+                        wad: relative_game_path.clone(),
+                        override_count,
+                    },
+                );
 
                 let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
                 let current = reported.fetch_max(done, Ordering::Relaxed).max(done);
@@ -277,8 +453,270 @@ impl OverlayBuilder {
                     total: total_wads,
                 });
 
-                Ok(dst_wad_path)
+                Ok((relative_game_path, Some(stats)))
             })
-            .collect()
+            .collect::<Result<_>>()?;
+
+        let mut built = Vec::new();
+        let mut wad_stats = Vec::new();
+        let mut skipped = Vec::new();
+        for (relative_game_path, stats) in outcomes {
+            match stats {
+                Some(stats) => {
+                    built.push(self.overlay_wad_path(&relative_game_path));
+                    wad_stats.push(stats);
+                }
+                None => skipped.push(relative_game_path),
+            }
+        }
+        Ok((built, wad_stats, skipped))
+    }
+}
+
+/// Write the patched copy of `src_wad_path`, staging it at `write_path` when
+/// that differs from `dst_wad_path`.
+fn patch_wad(
+    src_wad_path: &Utf8Path,
+    write_path: &Utf8Path,
+    dst_wad_path: &Utf8Path,
+    override_hashes: &HashSet<u64>,
+    wad_patch_options: WadPatchOptions,
+    all_meta: &HashMap<u64, OverrideMeta>,
+    mut overrides: HashMap<u64, ResolvedOverride>,
+) -> Result<PatchedWadStats> {
+    let compression_pins: HashMap<u64, WadChunkCompression> = override_hashes
+        .iter()
+        .filter_map(|hash| Some((*hash, all_meta.get(hash)?.compression?)))
+        .collect();
+
+    // The overlay WAD may be a hard link to the game WAD from an earlier build;
+    // writing through it would overwrite the game file.
+    remove_overlay_wad(write_path)?;
+    let stats = build_patched_wad_pinned(
+        src_wad_path,
+        write_path,
+        override_hashes,
+        wad_patch_options,
+        &compression_pins,
+        |hash| {
+            overrides
+                .remove(&hash)
+                .ok_or_else(|| {
+                    Error::Other(format!("Missing override data for hash {:016x}", hash))
+                })?
+                .load()
+        },
+    )?;
+
+    if write_path != dst_wad_path {
+        move_staged_wad(write_path, dst_wad_path)?;
+    }
+    Ok(stats)
+}
+
+/// Place an unmodified game WAD in the overlay without rewriting it.
+///
+/// Hard links `src` to `dst`, falling back to a copy when linking fails (e.g.
+/// the overlay is on a different filesystem than the game). An existing `dst`
+/// is replaced.
+pub(crate) fn link_or_copy_wad(src: &Utf8Path, dst: &Utf8Path) -> Result<()> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent.as_std_path())?;
+    }
+    remove_overlay_wad(dst)?;
+
+    match std::fs::hard_link(src.as_std_path(), dst.as_std_path()) {
+        Ok(()) => {
+            tracing::info!("Linked unmodified WAD src={} dst={}", src, dst);
+        }
+        Err(e) => {
+            tracing::debug!(
+                "Hard link {} -> {} failed ({}), copying instead",
+                src,
+                dst,
+                e
+            );
+            std::fs::copy(src.as_std_path(), dst.as_std_path())?;
+            tracing::info!("Copied unmodified WAD src={} dst={}", src, dst);
+        }
+    }
+    Ok(())
+}
+
+/// Remove the overlay WAD at `path` if there is one.
+fn remove_overlay_wad(path: &Utf8Path) -> Result<()> {
+    match std::fs::remove_file(path.as_std_path()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Move a WAD staged in the scratch directory to its final overlay location.
+///
+/// Tries a rename first and falls back to copy + remove when the scratch
+/// directory lives on a different filesystem than the overlay root.
+pub(crate) fn move_staged_wad(staged: &Utf8Path, dst: &Utf8Path) -> Result<()> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent.as_std_path())?;
+    }
+
+    match std::fs::rename(staged.as_std_path(), dst.as_std_path()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            tracing::debug!(
+                "Scratch dir is on a different filesystem, copying {} -> {}",
+                staged,
+                dst
+            );
+            remove_overlay_wad(dst)?;
+            std::fs::copy(staged.as_std_path(), dst.as_std_path())?;
+            std::fs::remove_file(staged.as_std_path())?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_project, StubContent};
+
+    #[test]
+    fn move_staged_wad_creates_parents_and_replaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+
+        let staged = root.join("scratch/DATA/FINAL/Champions/Aatrox.wad.client");
+        let dst = root.join("overlay/DATA/FINAL/Champions/Aatrox.wad.client");
+        std::fs::create_dir_all(staged.parent().unwrap()).unwrap();
+        std::fs::write(&staged, b"new").unwrap();
+
+        move_staged_wad(&staged, &dst).unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"new");
+        assert!(!staged.exists());
+
+        std::fs::write(&staged, b"newer").unwrap();
+        move_staged_wad(&staged, &dst).unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"newer");
+    }
+
+    #[test]
+    fn unmodified_wad_is_linked_and_never_written_through() {
+        use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+            ),
+        )
+        .unwrap();
+        let rel = Utf8PathBuf::from("DATA/FINAL/Champions/A.wad.client");
+        let src = game_dir.join(&rel);
+        let original = std::fs::read(&src).unwrap();
+
+        let builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf())
+            .with_link_unmodified_wads(true);
+        let fingerprints = BTreeMap::new();
+        let checkpoint =
+            BuildCheckpoint::create(&root.join("overlay.progress"), 0, &fingerprints, &[]).unwrap();
+        let (built, stats, _) = builder
+            .patch_wads_parallel(
+                vec![rel.clone()],
+                BTreeMap::new(),
+                &HashMap::new(),
+                None,
+                &checkpoint,
+            )
+            .unwrap();
+        // WADs are patched into the staging root, to be swapped in later
+        let dst = builder.staged_wad_path(&rel);
+        assert_eq!(built, vec![root.join("overlay").join(&rel)]);
+        assert_eq!(stats[0].override_count, 0);
+        assert_eq!(stats[0].original_chunk_count, 1);
+        assert_eq!(stats[0].bytes_written, original.len() as u64);
+        assert_eq!(std::fs::read(&dst).unwrap(), original);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let ino = |path: &Utf8Path| std::fs::metadata(path).unwrap().ino();
+            assert_eq!(ino(&dst), ino(&src));
+        }
+
+        let hash = ltk_modpkg::utils::hash_chunk_name("data/a.bin");
+        let overrides = BTreeMap::from([(
+            rel.clone(),
+            HashMap::from([(hash, ResolvedOverride::Memory(Arc::from(&b"patched"[..])))]),
+        )]);
+        builder
+            .patch_wads_parallel(vec![rel], overrides, &HashMap::new(), None, &checkpoint)
+            .unwrap();
+        assert_ne!(std::fs::read(&dst).unwrap(), original);
+        assert_eq!(std::fs::read(&src).unwrap(), original);
+    }
+
+    #[test]
+    fn pass_two_reads_are_retried() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        let wad = Utf8PathBuf::from("DATA/FINAL/Champions/Ahri.wad.client");
+        let wad_hash_sets = BTreeMap::from([(wad.clone(), HashSet::from([0xAAAA, 0xBBBB]))]);
+        let meta = |source| OverrideMeta {
+            content_hash: 0,
+            uncompressed_size: 4,
+            source,
+            fallback_wad: None,
+            linked_bins: Vec::new(),
+            compression: None,
+        };
+        let all_meta = HashMap::from([
+            (
+                0xAAAA,
+                meta(OverrideSource::LayerWad {
+                    mod_id: "flaky".to_string(),
+                    layer: "base".to_string(),
+                    wad_name: "Ahri.wad.client".to_string(),
+                    rel_path: Utf8PathBuf::from("a.bin"),
+                }),
+            ),
+            (
+                0xBBBB,
+                meta(OverrideSource::Raw {
+                    mod_id: "flaky".to_string(),
+                    rel_path: Utf8PathBuf::from("b.bin"),
+                }),
+            ),
+        ]);
+
+        for (max_attempts, succeeds) in [(1, false), (3, true)] {
+            let mut builder =
+                OverlayBuilder::new(root.join("Game"), root.join("overlay"), root.to_path_buf())
+                    .with_read_retries(
+                        RetryPolicy::new(max_attempts).with_initial_backoff(Duration::ZERO),
+                    );
+            builder.set_enabled_mods(vec![EnabledMod {
+                id: "flaky".to_string(),
+                content: Box::new(
+                    StubContent::new(mock_project("flaky"))
+                        .with_bytes(b"data".to_vec())
+                        .with_read_failures(2),
+                ),
+                enabled_layers: None,
+            }]);
+
+            let resolved = builder.resolve_overrides_for_wads(
+                std::slice::from_ref(&wad),
+                &wad_hash_sets,
+                &all_meta,
+            );
+            assert_eq!(resolved.is_ok(), succeeds, "{max_attempts} attempt(s)");
+            if let Ok(resolved) = resolved {
+                assert_eq!(resolved[&wad].len(), 2);
+            }
+        }
     }
 }
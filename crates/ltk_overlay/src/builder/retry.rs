@@ -0,0 +1,147 @@
+//! Retry policy for content provider reads.
+//!
+//! Filesystem and archive providers either succeed or fail deterministically, so
+//! the builder makes a single attempt by default. Network-backed providers can
+//! hit transient failures; [`OverlayBuilder::with_read_retries`] retries each
+//! [`read_wad_overrides`](crate::ModContentProvider::read_wad_overrides) call
+//! of the metadata pass, and each targeted override read of the resolve pass,
+//! with exponential backoff instead of failing the whole build.
+
+use super::*;
+
+/// How often, and how patiently, to retry a failed content read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. `0` is treated as `1`.
+    pub max_attempts: u32,
+    /// Delay before the first retry. Doubled after every failed retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for a single delay.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// A single attempt with no retries.
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy making up to `max_attempts` attempts with the default backoff.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// The delay to wait after failed attempt number `attempt` (1-based).
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Run `read`, retrying failures until it succeeds or attempts run out.
+    ///
+    /// `what` names the read in retry warnings, and is only formatted when one
+    /// is logged. The last error is returned once every attempt has failed.
+    pub(crate) fn run<T>(
+        &self,
+        what: &dyn std::fmt::Display,
+        mut read: impl FnMut() -> Result<T>,
+    ) -> Result<T> {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match read() {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < max_attempts => {
+                    let delay = self.backoff(attempt);
+                    tracing::warn!(
+                        "Reading {} failed (attempt {}/{}): {}; retrying in {:?}",
+                        what,
+                        attempt,
+                        max_attempts,
+                        e,
+                        delay
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy::new(10)
+            .with_initial_backoff(Duration::from_millis(10))
+            .with_max_backoff(Duration::from_millis(50));
+        let delays: Vec<u128> = (1..=5).map(|a| policy.backoff(a).as_millis()).collect();
+        assert_eq!(delays, vec![10, 20, 40, 50, 50]);
+        assert_eq!(policy.backoff(40), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn run_retries_until_success_or_exhaustion() {
+        let policy = RetryPolicy::new(3).with_initial_backoff(Duration::ZERO);
+
+        let mut calls = 0;
+        let value = policy
+            .run(&"flaky", || {
+                calls += 1;
+                if calls < 3 {
+                    Err(Error::Other(format!("transient {calls}")))
+                } else {
+                    Ok(calls)
+                }
+            })
+            .unwrap();
+        assert_eq!(value, 3);
+
+        let mut calls = 0;
+        let err = policy
+            .run(&"broken", || -> Result<()> {
+                calls += 1;
+                Err(Error::Other(format!("failure {calls}")))
+            })
+            .unwrap_err();
+        assert_eq!(calls, 3);
+        assert_eq!(
+            err.to_string(),
+            Error::Other("failure 3".to_string()).to_string()
+        );
+
+        let mut calls = 0;
+        let _ = RetryPolicy::default().run(&"once", || -> Result<()> {
+            calls += 1;
+            Err(Error::Other("nope".to_string()))
+        });
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! Semantic (declaration-level) conflicts between mods.
+//!
+//! Chunk-level [`Conflict`]s only catch mods that override the same path. Two
+//! skins for the same champion usually replace *different* files, so they never
+//! collide on a hash yet still show up in game as a mix of both. This module
+//! compares what each mod declares in its [`ModProject`] instead.
+
+use super::*;
+use ltk_mod_project::{canonical_champion, ModProject, ModTag, WellKnownModTag};
+
+/// Tags for categories where only one mod can sensibly be active at a time.
+const EXCLUSIVE_TAGS: [WellKnownModTag; 3] = [
+    WellKnownModTag::Hud,
+    WellKnownModTag::Font,
+    WellKnownModTag::Announcer,
+];
+
+/// What a [`SemanticConflict`] is about.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SemanticConflictKind {
+    /// Several mods list the same champion in [`ModProject::champions`].
+    Champion,
+    /// Several `map-skin` mods list the same map in [`ModProject::maps`].
+    Map,
+    /// Several mods carry the same exclusive tag (`hud`, `font`, `announcer`).
+    Tag,
+}
+
+/// Mods that declare the same champion, map or exclusive category.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticConflict {
+    pub kind: SemanticConflictKind,
+    /// The champion, map or tag, as spelled by the first mod that declares it.
+    pub subject: String,
+    /// IDs of the mods involved, in enabled-list order.
+    pub mod_ids: Vec<String>,
+}
+
+impl OverlayBuilder {
+    /// Report enabled mods whose project declarations overlap.
+    ///
+    /// Reads only each mod's project. Champions are matched through
+    /// [`canonical_champion`], so aliases like `Wukong` and `MonkeyKing` collide;
+    /// unknown champion names fall back to a case-insensitive match. Maps are only compared between mods tagged `map-skin`, and tags only for
+    /// the exclusive categories. Mods whose project cannot be read are skipped.
+    ///
+    /// Conflicts are sorted by kind, then subject.
+    pub fn analyze_semantic_conflicts(&mut self) -> Vec<SemanticConflict> {
+        let mut subjects: BTreeMap<(SemanticConflictKind, String), (String, Vec<String>)> =
+            BTreeMap::new();
+
+        for enabled_mod in &mut self.enabled_mods {
+            let project = match enabled_mod.content.mod_project() {
+                Ok(project) => project,
+                Err(e) => {
+                    tracing::debug!(
+                        "Skipping mod '{}' in semantic conflict analysis: {}",
+                        enabled_mod.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let mut declared: BTreeMap<(SemanticConflictKind, String), String> = BTreeMap::new();
+            for (kind, subject) in declared_subjects(&project) {
+                declared
+                    .entry((kind, subject_key(kind, &subject)))
+                    .or_insert(subject);
+            }
+            for (key, subject) in declared {
+                subjects
+                    .entry(key)
+                    .or_insert_with(|| (subject, Vec::new()))
+                    .1
+                    .push(enabled_mod.id.clone());
+            }
+        }
+
+        subjects
+            .into_iter()
+            .filter(|(_, (_, mod_ids))| mod_ids.len() > 1)
+            .map(|((kind, _), (subject, mod_ids))| {
+                tracing::warn!(
+                    "Mods {:?} all modify {:?} '{}' and may show up mixed in game",
+                    mod_ids,
+                    kind,
+                    subject
+                );
+                SemanticConflict {
+                    kind,
+                    subject,
+                    mod_ids,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The key two declarations must share to conflict.
+fn subject_key(kind: SemanticConflictKind, subject: &str) -> String {
+    match (kind, canonical_champion(subject)) {
+        (SemanticConflictKind::Champion, Some(champion)) => champion.as_str().to_lowercase(),
+        _ => subject.to_lowercase(),
+    }
+}
+
+/// The champions, maps and exclusive tags a project declares.
+fn declared_subjects(project: &ModProject) -> Vec<(SemanticConflictKind, String)> {
+    let mut subjects: Vec<(SemanticConflictKind, String)> = project
+        .champions
+        .iter()
+        .map(|champion| (SemanticConflictKind::Champion, champion.clone()))
+        .collect();
+
+    let is_map_skin = project
+        .tags
+        .contains(&ModTag::Known(WellKnownModTag::MapSkin));
+    if is_map_skin {
+        subjects.extend(
+            project
+                .maps
+                .iter()
+                .map(|map| (SemanticConflictKind::Map, map.to_string())),
+        );
+    }
+
+    subjects.extend(
+        project
+            .tags
+            .iter()
+            .filter(|tag| matches!(tag, ModTag::Known(known) if EXCLUSIVE_TAGS.contains(known)))
+            .map(|tag| (SemanticConflictKind::Tag, tag.to_string())),
+    );
+
+    subjects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_project, StubContent};
+    use ltk_mod_project::{ModMap, WellKnownMap};
+
+    fn mock_mod(
+        id: &str,
+        champions: Vec<&'static str>,
+        maps: Vec<ModMap>,
+        tags: Vec<WellKnownModTag>,
+    ) -> EnabledMod {
+        EnabledMod {
+            id: id.to_string(),
+            content: Box::new(StubContent::new(ModProject {
+                tags: tags.into_iter().map(ModTag::Known).collect(),
+                champions: champions.iter().map(|c| c.to_string()).collect(),
+                maps,
+                ..mock_project("mock")
+            })),
+            enabled_layers: None,
+        }
+    }
+
+    #[test]
+    fn reports_shared_champions_maps_and_exclusive_tags() {
+        let rift = || ModMap::Known(WellKnownMap::SummonersRift);
+        let mut builder = OverlayBuilder::new(
+            Utf8PathBuf::from("/game"),
+            Utf8PathBuf::from("/profile/overlay"),
+            Utf8PathBuf::from("/profile"),
+        );
+        builder.set_enabled_mods(vec![
+            mock_mod(
+                "a",
+                vec!["Ahri", "MonkeyKing"],
+                vec![rift()],
+                vec![WellKnownModTag::Hud],
+            ),
+            mock_mod(
+                "b",
+                vec!["ahri", "Wukong"],
+                vec![rift()],
+                vec![WellKnownModTag::MapSkin, WellKnownModTag::Sfx],
+            ),
+            mock_mod(
+                "c",
+                vec![],
+                vec![rift()],
+                vec![WellKnownModTag::MapSkin, WellKnownModTag::Hud],
+            ),
+        ]);
+
+        let conflicts = builder.analyze_semantic_conflicts();
+        assert_eq!(
+            conflicts,
+            vec![
+                SemanticConflict {
+                    kind: SemanticConflictKind::Champion,
+                    subject: "Ahri".to_string(),
+                    mod_ids: vec!["a".to_string(), "b".to_string()],
+                },
+                SemanticConflict {
+                    kind: SemanticConflictKind::Champion,
+                    subject: "MonkeyKing".to_string(),
+                    mod_ids: vec!["a".to_string(), "b".to_string()],
+                },
+                SemanticConflict {
+                    kind: SemanticConflictKind::Map,
+                    subject: "summoners-rift".to_string(),
+                    mod_ids: vec!["b".to_string(), "c".to_string()],
+                },
+                SemanticConflict {
+                    kind: SemanticConflictKind::Tag,
+                    subject: "hud".to_string(),
+                    mod_ids: vec!["a".to_string(), "c".to_string()],
+                },
+            ]
+        );
+    }
+}
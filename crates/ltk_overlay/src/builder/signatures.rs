@@ -0,0 +1,368 @@
+//! Signature checks for mods backed by signable archives.
+//!
+//! With [`OverlayBuilder::with_signature_policy`], every enabled mod's content
+//! provider is asked to verify its archive's signature (see
+//! [`ModContentProvider::verify_signature`]) before any override is collected.
+//! Only `.modpkg` packages can be signed; other formats report
+//! [`SignatureStatus::NotSignable`] and are never rejected.
+
+use super::*;
+use ltk_modpkg::{error::ModpkgError, Modpkg};
+use ltk_pki::pki::verify::VerifyContextBuilder;
+use std::io::{Read, Seek};
+
+/// What [`OverlayBuilder::build`] does with mods whose archive is not signed by
+/// a trusted certificate.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SignaturePolicy {
+    /// Fail the build with [`Error::UntrustedMod`] when a signable archive is
+    /// unsigned or its signature does not verify.
+    Require,
+    /// Build untrusted mods anyway, with a [`BuildWarning::UntrustedMod`] for each.
+    Warn,
+    /// Do not check signatures.
+    #[default]
+    Ignore,
+}
+
+/// The result of checking one mod's signature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureStatus {
+    /// The archive is signed by a certificate chaining to a trust anchor, and
+    /// its contents match the signature.
+    Trusted,
+    /// The archive carries no signature.
+    Unsigned,
+    /// The archive is signed, but the signature could not be verified.
+    Invalid { reason: String },
+    /// The content format cannot carry a signature (directories, `.fantome`, `.tar`).
+    NotSignable,
+}
+
+impl SignatureStatus {
+    /// Whether a [`SignaturePolicy`] other than `Ignore` objects to this status.
+    pub fn is_untrusted(&self) -> bool {
+        matches!(
+            self,
+            SignatureStatus::Unsigned | SignatureStatus::Invalid { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for SignatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureStatus::Trusted => f.write_str("signed by a trusted certificate"),
+            SignatureStatus::Unsigned => f.write_str("not signed"),
+            SignatureStatus::Invalid { reason } => write!(f, "invalid signature: {}", reason),
+            SignatureStatus::NotSignable => f.write_str("format cannot be signed"),
+        }
+    }
+}
+
+/// The signature status of one enabled mod, as reported in
+/// [`OverlayBuildResult::signatures`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSignature {
+    pub mod_id: String,
+    pub status: SignatureStatus,
+}
+
+/// The certificates mod signatures are verified against.
+///
+/// Certificates and revocation lists embedded in each package's signature are
+/// added on top of these trust anchors when that package is verified.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureTrust {
+    /// DER-encoded trust anchor certificates.
+    anchors: Vec<Vec<u8>>,
+    /// Verification time in seconds since the Unix epoch; now when `None`.
+    unix_time: Option<u64>,
+}
+
+impl SignatureTrust {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust signatures chaining to the DER-encoded certificate `der`.
+    pub fn with_anchor_der(mut self, der: impl Into<Vec<u8>>) -> Self {
+        self.anchors.push(der.into());
+        self
+    }
+
+    /// Check certificate validity at `secs` seconds since the Unix epoch
+    /// instead of the current time.
+    pub fn with_unix_time_sec(mut self, secs: u64) -> Self {
+        self.unix_time = Some(secs);
+        self
+    }
+
+    /// Verify the signature of `modpkg`.
+    ///
+    /// Problems with the signature itself are reported as
+    /// [`SignatureStatus::Invalid`]; only I/O failures and unusable trust
+    /// anchors are returned as errors.
+    pub fn verify_modpkg<R: Read + Seek>(&self, modpkg: &mut Modpkg<R>) -> Result<SignatureStatus> {
+        let signed_data = match modpkg.signed_data() {
+            Ok(Some(signed_data)) => signed_data,
+            Ok(None) => return Ok(SignatureStatus::Unsigned),
+            Err(e) => {
+                return Ok(SignatureStatus::Invalid {
+                    reason: e.to_string(),
+                })
+            }
+        };
+
+        let mut ctx = VerifyContextBuilder::default();
+        if let Some(secs) = self.unix_time {
+            ctx.with_unix_time_sec(secs);
+        }
+        for anchor in &self.anchors {
+            ctx.add_anchor_der(anchor)
+                .map_err(|e| Error::Other(format!("Invalid signature trust anchor: {}", e)))?;
+        }
+        let embedded = ctx
+            .add_certs_from_signed_data(&signed_data)
+            .and_then(|ctx| ctx.add_crls_from_signed_data(&signed_data));
+        if let Err(e) = embedded {
+            return Ok(SignatureStatus::Invalid {
+                reason: e.to_string(),
+            });
+        }
+
+        match modpkg.verify_signature(&ctx.finalize()) {
+            Ok(()) => Ok(SignatureStatus::Trusted),
+            Err(ModpkgError::Io(e)) => Err(e.into()),
+            Err(e) => Ok(SignatureStatus::Invalid {
+                reason: e.to_string(),
+            }),
+        }
+    }
+}
+
+impl OverlayBuilder {
+    /// Check every enabled mod's signature against the signature policy.
+    ///
+    /// Returns each mod's status, in enabled-mod order, and a warning for each
+    /// untrusted mod under [`SignaturePolicy::Warn`]. Under
+    /// [`SignaturePolicy::Require`] the first untrusted mod fails the build.
+    pub(super) fn check_signatures(&mut self) -> Result<(Vec<ModSignature>, Vec<BuildWarning>)> {
+        if self.signature_policy == SignaturePolicy::Ignore {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut signatures = Vec::with_capacity(self.enabled_mods.len());
+        let mut warnings = Vec::new();
+        for enabled_mod in &mut self.enabled_mods {
+            let signature = ModSignature {
+                mod_id: enabled_mod.id.clone(),
+                status: enabled_mod
+                    .content
+                    .verify_signature(&self.signature_trust)?,
+            };
+            tracing::debug!("Mod '{}' signature: {}", signature.mod_id, signature.status);
+
+            if signature.status.is_untrusted() {
+                if self.signature_policy == SignaturePolicy::Require {
+                    return Err(Error::UntrustedMod {
+                        mod_id: signature.mod_id,
+                        status: signature.status,
+                    });
+                }
+                tracing::warn!(
+                    "Mod '{}' is untrusted ({}); building it anyway",
+                    signature.mod_id,
+                    signature.status
+                );
+                warnings.push(BuildWarning::UntrustedMod(signature.clone()));
+            }
+            signatures.push(signature);
+        }
+
+        Ok((signatures, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::modpkg_content::ModpkgContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
+    use ltk_modpkg::builder::{ModpkgBuilder, ModpkgChunkBuilder, ModpkgLayerBuilder};
+    use ltk_pki::pki::sign::SignerKeys;
+    use std::io::{Cursor, Write};
+
+    /// Inside the validity window of the test certificates and CRLs.
+    const TEST_TIME: u64 = 1752525266;
+
+    fn test_trust() -> SignatureTrust {
+        SignatureTrust::new()
+            .with_anchor_der(
+                include_bytes!("../../../ltk_modpkg/test-data/signing/root.der").as_slice(),
+            )
+            .with_unix_time_sec(TEST_TIME)
+    }
+
+    fn modpkg_mod(id: &str, signed: bool) -> EnabledMod {
+        let mut builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/skin.bin")
+                    .unwrap()
+                    .with_wad("A.wad.client"),
+            );
+        if signed {
+            builder = builder.with_signature(
+                SignerKeys::from_pem_str(include_str!(
+                    "../../../ltk_modpkg/test-data/signing/signer.pem"
+                ))
+                .unwrap(),
+            );
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+        builder
+            .build_to_writer(&mut cursor, |_, cursor| {
+                cursor.write_all(b"patched")?;
+                Ok(())
+            })
+            .unwrap();
+        cursor.set_position(0);
+        EnabledMod {
+            id: id.to_string(),
+            content: Box::new(ModpkgContent::new(
+                Modpkg::mount_from_reader(cursor).unwrap(),
+            )),
+            enabled_layers: None,
+        }
+    }
+
+    fn builder_with(root: &Utf8Path, policy: SignaturePolicy) -> OverlayBuilder {
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Champions/A.wad.client")
+                    .with_path("data/skin.bin", b"original".to_vec()),
+            ),
+        )
+        .unwrap();
+        OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf())
+            .with_signature_policy(policy)
+            .with_signature_trust(test_trust())
+    }
+
+    #[test]
+    fn require_builds_trusted_and_unsignable_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let fs_dir = root.join("mods/fs");
+        write_fs_mod(
+            &fs_dir,
+            &mock_project("fs"),
+            &[("base/A.wad.client/data/skin.bin", b"fs")],
+        )
+        .unwrap();
+
+        let mut builder = builder_with(root, SignaturePolicy::Require);
+        builder.set_enabled_mods(vec![
+            modpkg_mod("signed", true),
+            EnabledMod {
+                id: "fs".to_string(),
+                content: Box::new(FsModContent::new(fs_dir)),
+                enabled_layers: None,
+            },
+        ]);
+        let result = builder.build().unwrap();
+
+        assert_eq!(result.wads_built.len(), 1);
+        assert_eq!(
+            result.signatures,
+            vec![
+                ModSignature {
+                    mod_id: "signed".to_string(),
+                    status: SignatureStatus::Trusted,
+                },
+                ModSignature {
+                    mod_id: "fs".to_string(),
+                    status: SignatureStatus::NotSignable,
+                },
+            ]
+        );
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn require_fails_the_build_for_an_unsigned_modpkg() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+
+        let mut builder = builder_with(root, SignaturePolicy::Require);
+        builder.set_enabled_mods(vec![modpkg_mod("unsigned", false)]);
+        let err = builder.build().unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::UntrustedMod { ref mod_id, status: SignatureStatus::Unsigned }
+                if mod_id == "unsigned"
+        ));
+        assert!(!root.join("overlay/DATA").exists());
+    }
+
+    #[test]
+    fn require_rejects_signatures_without_a_trust_anchor() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+
+        let mut builder = builder_with(root, SignaturePolicy::Require)
+            .with_signature_trust(SignatureTrust::new().with_unix_time_sec(TEST_TIME));
+        builder.set_enabled_mods(vec![modpkg_mod("signed", true)]);
+
+        assert!(matches!(
+            builder.build().unwrap_err(),
+            Error::UntrustedMod {
+                status: SignatureStatus::Invalid { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn warn_builds_untrusted_mods_with_a_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+
+        let mut builder = builder_with(root, SignaturePolicy::Warn);
+        builder.set_enabled_mods(vec![modpkg_mod("unsigned", false)]);
+        let result = builder.build().unwrap();
+
+        let unsigned = ModSignature {
+            mod_id: "unsigned".to_string(),
+            status: SignatureStatus::Unsigned,
+        };
+        assert_eq!(result.wads_built.len(), 1);
+        assert_eq!(result.signatures, vec![unsigned.clone()]);
+        assert_eq!(result.warnings, vec![BuildWarning::UntrustedMod(unsigned)]);
+    }
+
+    #[test]
+    fn ignore_skips_signature_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+
+        let mut builder = builder_with(root, SignaturePolicy::Ignore);
+        builder.set_enabled_mods(vec![modpkg_mod("unsigned", false)]);
+        let result = builder.build().unwrap();
+
+        assert_eq!(result.wads_built.len(), 1);
+        assert!(result.signatures.is_empty());
+        assert!(result.warnings.is_empty());
+    }
+}
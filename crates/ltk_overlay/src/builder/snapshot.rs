@@ -0,0 +1,345 @@
+//! Normalized, serializable build results for regression tests.
+//!
+//! An [`OverlayBuildResult`] holds absolute paths and a wall-clock time, so two
+//! builds of the same input never compare equal. [`BuildResultSnapshot`] keeps
+//! only what a build decided, in a stable order, so it can be committed as a
+//! golden file and compared against later builds with
+//! [`OverlayBuildResult::diff`].
+
+use super::*;
+use std::fmt;
+
+/// What an overlay build decided, normalized for comparison across runs.
+///
+/// WAD paths are relative to the overlay root with forward slashes, and every
+/// list is sorted. Build time and the overlay root itself are left out.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildResultSnapshot {
+    pub rebuild_reason: RebuildReason,
+    pub wads_built: Vec<String>,
+    pub wads_reused: Vec<String>,
+    /// Sorted by path hash, each with its contributions in install order.
+    pub conflicts: Vec<Conflict>,
+    /// Sorted by kind, then subject.
+    pub semantic_conflicts: Vec<SemanticConflict>,
+}
+
+/// Differences between a build and a baseline [`BuildResultSnapshot`].
+///
+/// `added` entries are in the build but not the baseline, `removed` entries the
+/// other way round. A conflict whose details changed shows up in both.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildResultDiff {
+    /// `(baseline, current)` when the rebuild reason changed.
+    pub rebuild_reason: Option<(RebuildReason, RebuildReason)>,
+    pub wads_built_added: Vec<String>,
+    pub wads_built_removed: Vec<String>,
+    pub wads_reused_added: Vec<String>,
+    pub wads_reused_removed: Vec<String>,
+    pub conflicts_added: Vec<Conflict>,
+    pub conflicts_removed: Vec<Conflict>,
+    pub semantic_conflicts_added: Vec<SemanticConflict>,
+    pub semantic_conflicts_removed: Vec<SemanticConflict>,
+}
+
+impl OverlayBuildResult {
+    /// The normalized form of this result. See [`BuildResultSnapshot`].
+    pub fn snapshot(&self) -> BuildResultSnapshot {
+        let relative = |wads: &[Utf8PathBuf]| -> Vec<String> {
+            let mut wads: Vec<String> = wads
+                .iter()
+                .map(|wad| {
+                    wad.strip_prefix(&self.overlay_root)
+                        .unwrap_or(wad)
+                        .as_str()
+                        .replace('\\', "/")
+                })
+                .collect();
+            wads.sort();
+            wads
+        };
+
+        let mut conflicts = self.conflicts.clone();
+        for conflict in &mut conflicts {
+            conflict
+                .contributing_mods
+                .sort_by_key(|contribution| contribution.install_order);
+        }
+        conflicts.sort_by_key(|conflict| conflict.path_hash);
+
+        let mut semantic_conflicts = self.semantic_conflicts.clone();
+        semantic_conflicts.sort_by(|a, b| (a.kind, &a.subject).cmp(&(b.kind, &b.subject)));
+
+        BuildResultSnapshot {
+            rebuild_reason: self.rebuild_reason,
+            wads_built: relative(&self.wads_built),
+            wads_reused: relative(&self.wads_reused),
+            conflicts,
+            semantic_conflicts,
+        }
+    }
+
+    /// Compare this build against a baseline snapshot, e.g. a golden file.
+    pub fn diff(&self, baseline: &BuildResultSnapshot) -> BuildResultDiff {
+        self.snapshot().diff(baseline)
+    }
+}
+
+impl BuildResultSnapshot {
+    /// Compare this snapshot against a baseline.
+    pub fn diff(&self, baseline: &BuildResultSnapshot) -> BuildResultDiff {
+        let (wads_built_added, wads_built_removed) =
+            added_and_removed(&baseline.wads_built, &self.wads_built);
+        let (wads_reused_added, wads_reused_removed) =
+            added_and_removed(&baseline.wads_reused, &self.wads_reused);
+        let (conflicts_added, conflicts_removed) =
+            added_and_removed(&baseline.conflicts, &self.conflicts);
+        let (semantic_conflicts_added, semantic_conflicts_removed) =
+            added_and_removed(&baseline.semantic_conflicts, &self.semantic_conflicts);
+
+        BuildResultDiff {
+            rebuild_reason: (self.rebuild_reason != baseline.rebuild_reason)
+                .then_some((baseline.rebuild_reason, self.rebuild_reason)),
+            wads_built_added,
+            wads_built_removed,
+            wads_reused_added,
+            wads_reused_removed,
+            conflicts_added,
+            conflicts_removed,
+            semantic_conflicts_added,
+            semantic_conflicts_removed,
+        }
+    }
+}
+
+/// Entries only in `current`, then entries only in `baseline`, in list order.
+fn added_and_removed<T: PartialEq + Clone>(baseline: &[T], current: &[T]) -> (Vec<T>, Vec<T>) {
+    let only_in = |from: &[T], other: &[T]| -> Vec<T> {
+        from.iter()
+            .filter(|item| !other.contains(item))
+            .cloned()
+            .collect()
+    };
+    (only_in(current, baseline), only_in(baseline, current))
+}
+
+impl BuildResultDiff {
+    /// Whether the build matched the baseline.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// One line per difference, `+` for added and `-` for removed entries.
+impl fmt::Display for BuildResultDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((baseline, current)) = self.rebuild_reason {
+            writeln!(f, "~ rebuild reason: {:?} -> {:?}", baseline, current)?;
+        }
+        for (sign, label, wads) in [
+            ("+", "built", &self.wads_built_added),
+            ("-", "built", &self.wads_built_removed),
+            ("+", "reused", &self.wads_reused_added),
+            ("-", "reused", &self.wads_reused_removed),
+        ] {
+            for wad in wads {
+                writeln!(f, "{} {}: {}", sign, label, wad)?;
+            }
+        }
+        for (sign, conflicts) in [("+", &self.conflicts_added), ("-", &self.conflicts_removed)] {
+            for conflict in conflicts {
+                writeln!(
+                    f,
+                    "{} conflict: {} ({:016x}) {:?}, winner {}",
+                    sign, conflict.path, conflict.path_hash, conflict.kind, conflict.winner
+                )?;
+            }
+        }
+        for (sign, conflicts) in [
+            ("+", &self.semantic_conflicts_added),
+            ("-", &self.semantic_conflicts_removed),
+        ] {
+            for conflict in conflicts {
+                writeln!(
+                    f,
+                    "{} semantic conflict: {:?} '{}' between {:?}",
+                    sign, conflict.kind, conflict.subject, conflict.mod_ids
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::testing::{make_fake_game_dir, mock_project, write_fs_mod, FakeGameSpec, FakeWad};
+
+    fn conflict(path_hash: u64, winner: &str) -> Conflict {
+        Conflict {
+            path_hash,
+            path: format!("data/{path_hash}.bin"),
+            contributing_mods: Vec::new(),
+            winner: winner.to_string(),
+            kind: ConflictKind::Divergent,
+        }
+    }
+
+    fn result(wads_built: &[&str], conflicts: Vec<Conflict>) -> OverlayBuildResult {
+        let overlay_root = Utf8PathBuf::from("/profile/overlay");
+        OverlayBuildResult {
+            wads_built: wads_built.iter().map(|w| overlay_root.join(w)).collect(),
+            overlay_root,
+            output_extension: DEFAULT_OUTPUT_EXTENSION.to_string(),
+            wads_reused: Vec::new(),
+            conflicts,
+            semantic_conflicts: Vec::new(),
+            rebuild_reason: RebuildReason::FullRebuildNoPreviousState,
+            build_time: Duration::from_millis(10),
+            incomplete: false,
+            wad_stats: Vec::new(),
+            warnings: Vec::new(),
+            signatures: Vec::new(),
+            size_delta: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_is_normalized_and_round_trips() {
+        let build = result(
+            &[
+                "DATA/FINAL/Maps/Map11.wad.client",
+                "DATA/FINAL/Champions/Ahri.wad.client",
+            ],
+            vec![conflict(2, "b"), conflict(1, "a")],
+        );
+        let snapshot = build.snapshot();
+        assert_eq!(
+            snapshot.wads_built,
+            vec![
+                "DATA/FINAL/Champions/Ahri.wad.client",
+                "DATA/FINAL/Maps/Map11.wad.client"
+            ]
+        );
+        assert_eq!(
+            snapshot
+                .conflicts
+                .iter()
+                .map(|c| c.path_hash)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let golden = serde_json::to_string_pretty(&snapshot).unwrap();
+        let baseline: BuildResultSnapshot = serde_json::from_str(&golden).unwrap();
+        assert!(build.diff(&baseline).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_entries() {
+        let baseline = result(
+            &["DATA/FINAL/Champions/Ahri.wad.client"],
+            vec![conflict(1, "a"), conflict(2, "a")],
+        )
+        .snapshot();
+        let mut build = result(
+            &["DATA/FINAL/Champions/Annie.wad.client"],
+            vec![conflict(1, "a"), conflict(2, "b")],
+        );
+        build.rebuild_reason = RebuildReason::Incremental;
+
+        let diff = build.diff(&baseline);
+        assert_eq!(
+            diff.rebuild_reason,
+            Some((
+                RebuildReason::FullRebuildNoPreviousState,
+                RebuildReason::Incremental
+            ))
+        );
+        assert_eq!(
+            diff.wads_built_added,
+            vec!["DATA/FINAL/Champions/Annie.wad.client"]
+        );
+        assert_eq!(
+            diff.wads_built_removed,
+            vec!["DATA/FINAL/Champions/Ahri.wad.client"]
+        );
+        assert_eq!(diff.conflicts_added, vec![conflict(2, "b")]);
+        assert_eq!(diff.conflicts_removed, vec![conflict(2, "a")]);
+        assert_eq!(
+            diff.to_string(),
+            "~ rebuild reason: FullRebuildNoPreviousState -> Incremental\n\
+             + built: DATA/FINAL/Champions/Annie.wad.client\n\
+             - built: DATA/FINAL/Champions/Ahri.wad.client\n\
+             + conflict: data/2.bin (0000000000000002) Divergent, winner b\n\
+             - conflict: data/2.bin (0000000000000002) Divergent, winner a\n"
+        );
+    }
+
+    #[test]
+    fn build_matches_the_golden_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client").with_path("data/a.bin", b"a".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client").with_path("data/b.bin", b"b".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Maps/Map11.wad.client").with_path("data/m.bin", b"m".to_vec()),
+                ),
+        )
+        .unwrap();
+        for (id, files) in [
+            (
+                "first",
+                &[("base/A.wad.client/data/a.bin", b"first".as_slice())][..],
+            ),
+            (
+                "second",
+                &[
+                    ("base/A.wad.client/data/a.bin", b"second".as_slice()),
+                    ("base/B.wad.client/data/b.bin", b"second".as_slice()),
+                ][..],
+            ),
+            (
+                "third",
+                &[("base/B.wad.client/data/b.bin", b"second".as_slice())][..],
+            ),
+        ] {
+            write_fs_mod(&root.join("mods").join(id), &mock_project(id), files).unwrap();
+        }
+
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(
+            ["first", "second", "third"]
+                .into_iter()
+                .map(|id| EnabledMod {
+                    id: id.to_string(),
+                    content: Box::new(FsModContent::new(root.join("mods").join(id))),
+                    enabled_layers: None,
+                })
+                .collect(),
+        );
+        let result = builder.build().unwrap();
+
+        let golden: BuildResultSnapshot = serde_json::from_str(include_str!(
+            "../../test-data/snapshots/conflicting_mods.json"
+        ))
+        .unwrap();
+        let diff = result.diff(&golden);
+        assert!(
+            diff.is_empty(),
+            "build differs from the golden snapshot:\n{diff}\ncurrent snapshot:\n{}",
+            serde_json::to_string_pretty(&result.snapshot()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! On-disk spill store for override bytes that exceed the in-memory budget.
+//!
+//! When [`OverlayBuilder::with_spill`] is set, pass 2 plans which overrides to keep
+//! in memory before reading anything: the largest overrides are marked for spilling
+//! until the remaining total fits the budget. Marked overrides are appended to an
+//! anonymous temp file as they are read and only their offsets are kept; patching
+//! reads them back one at a time.
+
+use super::*;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+
+/// Append-only blob store backed by an anonymous temp file.
+///
+/// The file is removed by the OS when the store is dropped.
+pub(crate) struct SpillStore {
+    file: Mutex<File>,
+    len: u64,
+}
+
+impl SpillStore {
+    /// Create a new store in `dir`.
+    pub(crate) fn new_in(dir: &Utf8Path) -> Result<Self> {
+        std::fs::create_dir_all(dir.as_std_path())?;
+        Ok(Self {
+            file: Mutex::new(tempfile::tempfile_in(dir.as_std_path())?),
+            len: 0,
+        })
+    }
+
+    /// Append `bytes` and return the `(offset, len)` they were written at.
+    pub(crate) fn append(&mut self, bytes: &[u8]) -> Result<(u64, usize)> {
+        let offset = self.len;
+        let file = self
+            .file
+            .get_mut()
+            .map_err(|_| Error::Other("Spill store lock poisoned".to_string()))?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(bytes)?;
+        self.len += bytes.len() as u64;
+        Ok((offset, bytes.len()))
+    }
+
+    /// Read back `len` bytes written at `offset`.
+    pub(crate) fn read(&self, offset: u64, len: usize) -> Result<Vec<u8>> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| Error::Other("Spill store lock poisoned".to_string()))?;
+        let mut buf = vec![0u8; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Total number of bytes spilled to disk.
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+/// Override bytes resolved in pass 2, either held in memory or spilled to disk.
+#[derive(Clone)]
+pub(crate) enum ResolvedOverride {
+    Memory(SharedBytes),
+    Spilled {
+        store: Arc<SpillStore>,
+        offset: u64,
+        len: usize,
+    },
+}
+
+impl ResolvedOverride {
+    /// Load the override bytes, reading them back from disk if spilled.
+    pub(crate) fn load(self) -> Result<SharedBytes> {
+        match self {
+            ResolvedOverride::Memory(bytes) => Ok(bytes),
+            ResolvedOverride::Spilled { store, offset, len } => {
+                Ok(Arc::from(store.read(offset, len)?))
+            }
+        }
+    }
+}
+
+/// Pick which overrides to spill so the in-memory total stays within `max_memory_bytes`.
+///
+/// Spills the largest overrides first (ties broken by hash for determinism) until the
+/// remaining total fits. Sizes come from pass 1 metadata.
+pub(crate) fn plan_spill(
+    needed_hashes: &HashSet<u64>,
+    all_meta: &HashMap<u64, OverrideMeta>,
+    max_memory_bytes: u64,
+) -> HashSet<u64> {
+    let mut sized: Vec<(u64, u64)> = needed_hashes
+        .iter()
+        .filter_map(|&h| all_meta.get(&h).map(|m| (h, m.uncompressed_size as u64)))
+        .collect();
+    let mut in_memory: u64 = sized.iter().map(|(_, size)| size).sum();
+
+    sized.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut spill = HashSet::new();
+    for (hash, size) in sized {
+        if in_memory <= max_memory_bytes {
+            break;
+        }
+        spill.insert(hash);
+        in_memory -= size;
+    }
+    spill
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta_with_size(size: usize) -> OverrideMeta {
+        OverrideMeta {
+            content_hash: 0,
+            uncompressed_size: size,
+            source: OverrideSource::Raw {
+                mod_id: "m".to_string(),
+                rel_path: Utf8PathBuf::from("x.bin"),
+            },
+            fallback_wad: None,
+            linked_bins: Vec::new(),
+            compression: None,
+        }
+    }
+
+    #[test]
+    fn plan_spill_spills_largest_first() {
+        let all_meta: HashMap<u64, OverrideMeta> = [(1, 100), (2, 500), (3, 300), (4, 50)]
+            .into_iter()
+            .map(|(h, size)| (h, meta_with_size(size)))
+            .collect();
+        let needed: HashSet<u64> = all_meta.keys().copied().collect();
+
+        // Total 950; a 400 budget requires dropping 500 (-> 450) then 300 (-> 150).
+        assert_eq!(plan_spill(&needed, &all_meta, 400), HashSet::from([2, 3]));
+        assert!(plan_spill(&needed, &all_meta, 950).is_empty());
+        assert_eq!(plan_spill(&needed, &all_meta, 0).len(), 4);
+    }
+
+    #[test]
+    fn spill_store_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(tmp.path()).unwrap();
+
+        let mut store = SpillStore::new_in(dir).unwrap();
+        let (off_a, len_a) = store.append(b"hello").unwrap();
+        let (off_b, len_b) = store.append(b"world!!").unwrap();
+        assert_eq!(store.len(), 12);
+
+        let store = Arc::new(store);
+        let b = ResolvedOverride::Spilled {
+            store: Arc::clone(&store),
+            offset: off_b,
+            len: len_b,
+        };
+        assert_eq!(&*b.load().unwrap(), b"world!!");
+        assert_eq!(store.read(off_a, len_a).unwrap(), b"hello");
+    }
+}
@@ -0,0 +1,130 @@
+//! Staged builds, so a half-built overlay is never served.
+//!
+//! A build writes its WADs under `overlay_root/.staging/` rather than into the
+//! overlay itself. The WADs it keeps from the live overlay are hard-linked (or
+//! copied) into the staging root first, so once every WAD is patched the
+//! staging root holds the complete new overlay. Only then is its `DATA` tree
+//! swapped in for the live one, with two renames. A crash or failure before
+//! that leaves the previous overlay untouched, and the checkpoint (see
+//! [`progress`](super::progress)) lists the WADs finished in the staging root
+//! so the next build resumes from there.
+//!
+//! With [`OverlayBuilder::with_backup`], the live WADs the swap replaces or
+//! drops are moved into the backup right before it.
+
+use super::resolve::link_or_copy_wad;
+use super::*;
+use crate::backup::{collect_overlay_wad_paths, WadBackup};
+
+/// Directory inside the overlay root that a build writes its WADs into.
+pub const STAGING_DIR_NAME: &str = ".staging";
+
+/// Where the live `DATA` tree goes, inside the staging root, while the staged
+/// one takes its place.
+const RETIRED_DATA_DIR_NAME: &str = "DATA.old";
+
+impl OverlayBuilder {
+    /// Root the running build writes its WADs into.
+    pub(crate) fn staging_root(&self) -> Utf8PathBuf {
+        self.overlay_root.join(STAGING_DIR_NAME)
+    }
+
+    /// Staged path of the overlay WAD for the game-relative `wad_path`.
+    pub(crate) fn staged_wad_path(&self, wad_path: &Utf8Path) -> Utf8PathBuf {
+        self.staging_root()
+            .join(self.overlay_wad_rel_path(wad_path))
+    }
+
+    /// Start a fresh staging root holding the `reused` WADs of the live overlay.
+    ///
+    /// Whatever an earlier build left in the staging root is discarded.
+    pub(crate) fn start_staging(&self, reused: &[Utf8PathBuf]) -> Result<()> {
+        let staging_root = self.staging_root();
+        remove_dir_if_exists(&staging_root)?;
+        std::fs::create_dir_all(staging_root.as_std_path())?;
+        for wad in reused {
+            link_or_copy_wad(&self.overlay_wad_path(wad), &self.staged_wad_path(wad))?;
+        }
+        Ok(())
+    }
+
+    /// Keep the staging root of an interrupted build, removing the staged WADs
+    /// it left unfinished and keeping the `finished` ones.
+    pub(crate) fn resume_staging(&self, finished: &[Utf8PathBuf]) -> Result<()> {
+        let staging_root = self.staging_root();
+        remove_dir_if_exists(&staging_root.join(RETIRED_DATA_DIR_NAME))?;
+        let staged_data = staging_root.join("DATA");
+        if !staged_data.as_std_path().exists() {
+            std::fs::create_dir_all(staging_root.as_std_path())?;
+            return Ok(());
+        }
+
+        let finished: HashSet<Utf8PathBuf> = finished
+            .iter()
+            .map(|wad| self.staged_wad_path(wad))
+            .collect();
+        for wad_path in collect_overlay_wad_paths(&staged_data, &self.output_extension)? {
+            if !finished.contains(&wad_path) {
+                tracing::info!("Removing unfinished WAD: {}", wad_path);
+                std::fs::remove_file(wad_path.as_std_path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the live overlay with the staged one.
+    ///
+    /// `built` and `reused` are the game-relative WADs of the new overlay.
+    /// With a `backup`, every live WAD the swap replaces or drops is preserved
+    /// first, and the built WADs the live overlay lacks are recorded as added.
+    pub(crate) fn swap_in_staging(
+        &self,
+        built: &[Utf8PathBuf],
+        reused: &[Utf8PathBuf],
+        backup: Option<&mut WadBackup>,
+    ) -> Result<()> {
+        let live_data = self.overlay_root.join("DATA");
+        if let Some(backup) = backup {
+            let mut kept: HashSet<Utf8PathBuf> = reused
+                .iter()
+                .map(|wad| self.overlay_wad_rel_path(wad))
+                .collect();
+            for wad in built {
+                let rel_path = self.overlay_wad_rel_path(wad);
+                backup.preserve(&rel_path)?;
+                kept.insert(rel_path);
+            }
+            if live_data.as_std_path().exists() {
+                for wad_path in collect_overlay_wad_paths(&live_data, &self.output_extension)? {
+                    let rel_path = wad_path
+                        .strip_prefix(&self.overlay_root)
+                        .expect("overlay WADs live under the overlay root");
+                    if !kept.contains(rel_path) {
+                        backup.preserve(rel_path)?;
+                    }
+                }
+            }
+        }
+
+        let staging_root = self.staging_root();
+        let retired = staging_root.join(RETIRED_DATA_DIR_NAME);
+        remove_dir_if_exists(&retired)?;
+        if live_data.as_std_path().exists() {
+            std::fs::rename(live_data.as_std_path(), retired.as_std_path())?;
+        }
+        let staged_data = staging_root.join("DATA");
+        if staged_data.as_std_path().exists() {
+            std::fs::rename(staged_data.as_std_path(), live_data.as_std_path())?;
+        }
+        tracing::info!("Swapped the staged overlay into {}", self.overlay_root);
+        remove_dir_if_exists(&staging_root)
+    }
+}
+
+/// Remove the directory at `path` and everything in it, if it exists.
+pub(crate) fn remove_dir_if_exists(path: &Utf8Path) -> Result<()> {
+    match std::fs::remove_dir_all(path.as_std_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
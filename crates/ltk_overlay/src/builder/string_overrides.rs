@@ -0,0 +1,460 @@
+//! Resolution and application of layer string overrides.
+//!
+//! Each [`ModProjectLayer`](ltk_mod_project::ModProjectLayer) can override
+//! string table fields per locale, with the `default` locale applying to every
+//! locale. This module works out which value wins across all enabled mods and,
+//! during a build, writes the winners into each locale's `lol.stringtable`.
+//!
+//! Application is best-effort: [`patch_stringtable`] can only replace fields
+//! the game's table already has, so unknown fields are skipped with a warning.
+
+use super::conflicts::OverrideRank;
+use super::*;
+use crate::stringtable::{patch_stringtable, stringtable_chunk_path};
+use ltk_mod_project::ModProjectLayer;
+use ltk_wad::Wad;
+use std::fs::File;
+use std::io::BufReader;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Locale key whose overrides apply to every locale.
+const DEFAULT_LOCALE: &str = "default";
+
+/// Lowercased locale -> field -> winning value.
+pub(crate) type LocaleStringOverrides = BTreeMap<String, BTreeMap<String, String>>;
+
+impl OverlayBuilder {
+    /// The value string table field `field` will have in `locale` once all
+    /// enabled mods are applied, or `None` if no mod overrides it.
+    ///
+    /// Overriding layers are ranked like chunk overrides under the configured
+    /// [`ConflictStrategy`], and the highest-ranked one wins. Within a mod, only
+    /// active layers count and the highest-priority layer wins; within a layer,
+    /// an entry for `locale` (matched case-insensitively) beats one for `default`.
+    pub fn resolve_string_override(&mut self, locale: &str, field: &str) -> Result<Option<String>> {
+        let locale = locale.to_ascii_lowercase();
+        let mut resolved = self.collect_string_overrides(std::slice::from_ref(&locale))?;
+        Ok(resolved
+            .remove(&locale)
+            .and_then(|mut fields| fields.remove(field)))
+    }
+
+    /// The winning string overrides for each of `locales` (lowercased), with the
+    /// precedence described in [`resolve_string_override`](Self::resolve_string_override).
+    ///
+    /// Locales no mod overrides anything in are omitted.
+    pub(crate) fn collect_string_overrides(
+        &mut self,
+        locales: &[String],
+    ) -> Result<LocaleStringOverrides> {
+        let mut active_layers = Vec::with_capacity(self.enabled_mods.len());
+        for enabled_mod in &mut self.enabled_mods {
+            let project = enabled_mod.content.mod_project()?;
+            let layers: Vec<ModProjectLayer> = project
+                .layers
+                .into_iter()
+                .filter(|layer| {
+                    enabled_mod.is_layer_active(&layer.name) && !layer.string_overrides.is_empty()
+                })
+                .collect();
+            active_layers.push(layers);
+        }
+
+        let builder = &*self;
+        let mut ranked: Vec<(OverrideRank, &ModProjectLayer)> = active_layers
+            .iter()
+            .enumerate()
+            .flat_map(|(mod_index, layers)| {
+                layers
+                    .iter()
+                    .map(move |layer| (builder.override_rank(mod_index, layer.priority), layer))
+            })
+            .collect();
+        // Apply lowest rank first so later writes win; within a mod, layers
+        // the strategy ranks alike go in ascending priority.
+        ranked.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then(a.priority.cmp(&b.priority))
+                .then(a.name.cmp(&b.name))
+        });
+
+        let mut resolved = LocaleStringOverrides::new();
+        for (_, layer) in ranked {
+            apply_layer_string_overrides(layer, locales, &mut resolved);
+        }
+        Ok(resolved)
+    }
+
+    /// Patch each game locale's string table with the mods' string overrides and
+    /// add the results to `all_meta` as generated overrides.
+    ///
+    /// Skipped for a locale when a mod already replaces its whole string table,
+    /// since that file wins over individual field overrides.
+    pub(crate) fn apply_string_overrides(
+        &mut self,
+        all_meta: &mut HashMap<u64, OverrideMeta>,
+        game_index: &GameIndex,
+    ) -> Result<()> {
+        let locale_wads = game_index.locale_wads();
+        let locales: Vec<String> = locale_wads.keys().cloned().collect();
+        let resolved = self.collect_string_overrides(&locales)?;
+
+        for (locale, fields) in resolved {
+            let wad_path = &locale_wads[&locale];
+            let rel_path = Utf8PathBuf::from(stringtable_chunk_path(&locale));
+            let path_hash = ltk_modpkg::utils::hash_chunk_name(rel_path.as_str());
+
+            if let Some(existing) = all_meta.get(&path_hash) {
+                tracing::warn!(
+                    "Mod='{}' replaces '{}'; skipping {} string override(s) for locale '{}'",
+                    existing.source.mod_id(),
+                    rel_path,
+                    fields.len(),
+                    locale
+                );
+                continue;
+            }
+
+            let Some(original) = read_game_chunk(wad_path, path_hash)? else {
+                tracing::warn!(
+                    "'{}' has no '{}'; skipping string overrides for locale '{}'",
+                    wad_path,
+                    rel_path,
+                    locale
+                );
+                continue;
+            };
+
+            let patch = match patch_stringtable(&original, &fields) {
+                Ok(patch) => patch,
+                Err(e) => {
+                    tracing::warn!(
+                        "Could not apply string overrides to '{}' ({}): {}",
+                        rel_path,
+                        wad_path,
+                        e
+                    );
+                    continue;
+                }
+            };
+            for field in &patch.missing {
+                tracing::warn!(
+                    "String override field '{}' not found in '{}'; skipping it",
+                    field,
+                    rel_path
+                );
+            }
+            if patch.applied == 0 {
+                continue;
+            }
+            tracing::info!(
+                "Applied {} string override(s) to '{}'",
+                patch.applied,
+                rel_path
+            );
+
+            all_meta.insert(
+                path_hash,
+                OverrideMeta {
+                    content_hash: xxh3_64(&patch.bytes),
+                    uncompressed_size: patch.bytes.len(),
+                    source: OverrideSource::Generated {
+                        rel_path,
+                        bytes: Arc::from(patch.bytes),
+                    },
+                    fallback_wad: wad_path
+                        .strip_prefix(&self.game_dir)
+                        .ok()
+                        .map(Utf8Path::to_path_buf),
+                    linked_bins: Vec::new(),
+                    compression: None,
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge one layer's string overrides for `locales` into `resolved`, writing
+/// `default` before the specific locale so the latter wins.
+fn apply_layer_string_overrides(
+    layer: &ModProjectLayer,
+    locales: &[String],
+    resolved: &mut LocaleStringOverrides,
+) {
+    for locale in locales {
+        for wanted in [DEFAULT_LOCALE, locale.as_str()] {
+            let fields = layer
+                .string_overrides
+                .iter()
+                .filter(|(key, _)| key.eq_ignore_ascii_case(wanted))
+                .flat_map(|(_, fields)| fields);
+            for (field, value) in fields {
+                resolved
+                    .entry(locale.clone())
+                    .or_default()
+                    .insert(field.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Read one chunk from a game WAD, or `None` if the WAD does not contain it.
+fn read_game_chunk(wad_path: &Utf8Path, path_hash: u64) -> Result<Option<Vec<u8>>> {
+    let mut wad = Wad::mount(BufReader::new(File::open(wad_path.as_std_path())?))?;
+    let Some(chunk) = wad.chunks().get(path_hash).copied() else {
+        return Ok(None);
+    };
+    Ok(Some(wad.load_chunk_decompressed(&chunk)?.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_project, StubContent};
+    use ltk_mod_project::ModProject;
+
+    fn layer(name: &str, priority: i32, overrides: &[(&str, &str, &str)]) -> ModProjectLayer {
+        let mut string_overrides: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (locale, field, value) in overrides {
+            string_overrides
+                .entry(locale.to_string())
+                .or_default()
+                .insert(field.to_string(), value.to_string());
+        }
+        ModProjectLayer {
+            name: name.to_string(),
+            display_name: None,
+            priority,
+            description: None,
+            string_overrides,
+        }
+    }
+
+    fn mock_mod(id: &str, layers: Vec<ModProjectLayer>) -> EnabledMod {
+        EnabledMod {
+            id: id.to_string(),
+            content: Box::new(StubContent::new(ModProject {
+                layers,
+                ..mock_project("mock")
+            })),
+            enabled_layers: None,
+        }
+    }
+
+    fn builder(mods: Vec<EnabledMod>) -> OverlayBuilder {
+        let mut builder = OverlayBuilder::new(
+            Utf8PathBuf::from("/game"),
+            Utf8PathBuf::from("/profile/overlay"),
+            Utf8PathBuf::from("/profile"),
+        );
+        builder.set_enabled_mods(mods);
+        builder
+    }
+
+    #[test]
+    fn highest_priority_mod_and_layer_win() {
+        let mods = || {
+            vec![
+                mock_mod(
+                    "a",
+                    vec![
+                        layer("base", 0, &[("default", "game_title", "A base")]),
+                        layer("chroma", 10, &[("en_US", "game_title", "A chroma")]),
+                    ],
+                ),
+                mock_mod(
+                    "b",
+                    vec![layer(
+                        "base",
+                        0,
+                        &[
+                            ("default", "game_title", "B"),
+                            ("default", "game_subtitle", "B subtitle"),
+                        ],
+                    )],
+                ),
+            ]
+        };
+
+        let mut front = builder(mods());
+        let resolve = |b: &mut OverlayBuilder, locale, field| {
+            b.resolve_string_override(locale, field).unwrap()
+        };
+        assert_eq!(
+            resolve(&mut front, "en_us", "game_title").as_deref(),
+            Some("A chroma")
+        );
+        assert_eq!(
+            resolve(&mut front, "ko_kr", "game_title").as_deref(),
+            Some("A base")
+        );
+        assert_eq!(
+            resolve(&mut front, "ko_kr", "game_subtitle").as_deref(),
+            Some("B subtitle")
+        );
+        assert_eq!(resolve(&mut front, "en_us", "missing"), None);
+
+        let mut back = builder(mods()).with_priority_order(PriorityOrder::BackWins);
+        assert_eq!(
+            resolve(&mut back, "en_us", "game_title").as_deref(),
+            Some("B")
+        );
+    }
+
+    #[test]
+    fn conflict_strategy_ranks_layers_across_mods() {
+        let mods = || {
+            vec![
+                mock_mod(
+                    "a",
+                    vec![layer("base", 0, &[("default", "game_title", "A base")])],
+                ),
+                mock_mod(
+                    "b",
+                    vec![
+                        layer("base", 0, &[("default", "game_title", "B base")]),
+                        layer("chroma", 10, &[("default", "game_title", "B chroma")]),
+                    ],
+                ),
+            ]
+        };
+        let resolve = |mut b: OverlayBuilder| {
+            b.resolve_string_override("en_us", "game_title")
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(resolve(builder(mods())), "A base");
+        assert_eq!(
+            resolve(builder(mods()).with_conflict_strategy(ConflictStrategy::LastWins)),
+            "B chroma"
+        );
+        // The higher layer priority beats mod order
+        assert_eq!(
+            resolve(builder(mods()).with_conflict_strategy(ConflictStrategy::HighestPriority)),
+            "B chroma"
+        );
+    }
+
+    #[test]
+    fn inactive_layers_are_ignored() {
+        let mut enabled = mock_mod(
+            "a",
+            vec![
+                layer("base", 0, &[("default", "game_title", "base")]),
+                layer("chroma", 10, &[("default", "game_title", "chroma")]),
+            ],
+        );
+        enabled.enabled_layers = Some(HashSet::new());
+
+        let mut builder = builder(vec![enabled]);
+        assert_eq!(
+            builder
+                .resolve_string_override("en_us", "game_title")
+                .unwrap()
+                .as_deref(),
+            Some("base")
+        );
+    }
+
+    #[test]
+    fn build_writes_overrides_into_the_locale_string_table() {
+        use crate::stringtable::tests::{read_field, write_stringtable};
+        use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let table = write_stringtable(5, &[("game_title", "League"), ("game_mode", "Classic")]);
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new().with_wad(
+                FakeWad::new("Localized/Global.en_US.wad.client")
+                    .with_path("data/menu/en_us/lol.stringtable", table),
+            ),
+        )
+        .unwrap();
+
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(vec![mock_mod(
+            "a",
+            vec![layer(
+                "base",
+                0,
+                &[
+                    ("default", "game_title", "Modded"),
+                    ("default", "unknown_field", "ignored"),
+                ],
+            )],
+        )]);
+        let result = builder.build().unwrap();
+        assert_eq!(result.wads_built.len(), 1);
+
+        let bytes = std::fs::read(&result.wads_built[0]).unwrap();
+        let mut wad = Wad::mount(std::io::Cursor::new(bytes)).unwrap();
+        let chunk = *wad
+            .chunks()
+            .get(ltk_modpkg::utils::hash_chunk_name(
+                "data/menu/en_us/lol.stringtable",
+            ))
+            .unwrap();
+        let table = wad.load_chunk_decompressed(&chunk).unwrap();
+        assert_eq!(read_field(&table, "game_title").as_deref(), Some("Modded"));
+        assert_eq!(read_field(&table, "game_mode").as_deref(), Some("Classic"));
+    }
+
+    #[test]
+    fn build_fans_locale_overrides_out_to_each_locale_wad() {
+        use crate::stringtable::tests::{read_field, write_stringtable};
+        use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let mut spec = FakeGameSpec::new();
+        for locale in ["en_US", "ko_KR"] {
+            let chunk_path = stringtable_chunk_path(&locale.to_ascii_lowercase());
+            spec = spec.with_wad(
+                FakeWad::new(format!("Localized/Global.{locale}.wad.client")).with_path(
+                    &chunk_path,
+                    write_stringtable(5, &[("game_title", "League")]),
+                ),
+            );
+        }
+        let game_dir = make_fake_game_dir(root, &spec).unwrap();
+
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.to_path_buf());
+        builder.set_enabled_mods(vec![mock_mod(
+            "a",
+            vec![layer(
+                "base",
+                0,
+                &[
+                    ("default", "game_title", "Default"),
+                    ("ko_KR", "game_title", "Korean"),
+                ],
+            )],
+        )]);
+        let result = builder.build().unwrap();
+        assert_eq!(result.wads_built.len(), 2);
+
+        for (locale, expected) in [("en_us", "Default"), ("ko_kr", "Korean")] {
+            let wad_path = result
+                .wads_built
+                .iter()
+                .find(|wad| wad.as_str().to_ascii_lowercase().contains(locale))
+                .unwrap();
+            let mut wad =
+                Wad::mount(std::io::Cursor::new(std::fs::read(wad_path).unwrap())).unwrap();
+            let chunk = *wad
+                .chunks()
+                .get(ltk_modpkg::utils::hash_chunk_name(&stringtable_chunk_path(
+                    locale,
+                )))
+                .unwrap();
+            let table = wad.load_chunk_decompressed(&chunk).unwrap();
+            assert_eq!(read_field(&table, "game_title").as_deref(), Some(expected));
+        }
+    }
+}
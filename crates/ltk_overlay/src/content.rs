@@ -11,7 +11,8 @@
 //! Archive-backed implementations (`.modpkg`, `.fantome`) live in the `ltk-manager`
 //! crate where the archive format dependencies are available.
 
-use crate::error::Result;
+use crate::builder::{SignatureStatus, SignatureTrust};
+use crate::error::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use ltk_mod_project::ModProject;
 use xxhash_rust::xxh3::xxh3_64;
@@ -41,6 +42,56 @@ pub fn archive_fingerprint(path: &Utf8Path) -> Result<Option<u64>> {
     Ok(Some(xxh3_64(&buf)))
 }
 
+/// The storage format a [`ModContentProvider`] reads mod content from.
+///
+/// Reported per mod in [`ModWadReport`](crate::ModWadReport) and logged during
+/// builds. The builder also adapts to it: formats without layer support only
+/// have their `base` layer read.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentFormatVersion {
+    /// A mod project directory on disk (`mod.config.json` + `content/<layer>/`).
+    FsV1,
+    /// A `.modpkg` archive.
+    ModpkgV1,
+    /// A `.fantome` archive. Legacy format with a single `base` layer.
+    FantomeLegacy,
+    /// A `.fantome` archive with content for layers besides `base` under `LAYERS/`.
+    FantomeLayered,
+    /// A `.tar` / `.tar.gz` archive laid out like a mod project directory.
+    TarV1,
+    /// The provider does not declare its format.
+    #[default]
+    Unknown,
+}
+
+impl ContentFormatVersion {
+    /// Whether content in this format can have layers besides `base`.
+    pub fn supports_layers(self) -> bool {
+        self != ContentFormatVersion::FantomeLegacy
+    }
+
+    /// Whether this format is deprecated and mods should be repackaged.
+    pub fn is_deprecated(self) -> bool {
+        self == ContentFormatVersion::FantomeLegacy
+    }
+}
+
+impl std::fmt::Display for ContentFormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ContentFormatVersion::FsV1 => "directory (v1)",
+            ContentFormatVersion::ModpkgV1 => "modpkg (v1)",
+            ContentFormatVersion::FantomeLegacy => "fantome (legacy)",
+            ContentFormatVersion::FantomeLayered => "fantome (layered)",
+            ContentFormatVersion::TarV1 => "tar (v1)",
+            ContentFormatVersion::Unknown => "unknown",
+        })
+    }
+}
+
 /// Abstracts how mod content is accessed during overlay building.
 ///
 /// Implementors provide access to mod project metadata, layer structure,
@@ -143,6 +194,49 @@ pub trait ModContentProvider: Send + Sync {
     ///
     /// Used in pass 2 to re-read only the bytes needed for WADs being rebuilt.
     fn read_raw_override_file(&mut self, rel_path: &Utf8Path) -> Result<Vec<u8>>;
+
+    /// The storage format this provider reads.
+    ///
+    /// The default implementation returns [`ContentFormatVersion::Unknown`].
+    fn format_version(&self) -> ContentFormatVersion {
+        ContentFormatVersion::Unknown
+    }
+
+    /// Verify the signature of the archive this content is read from.
+    ///
+    /// Called before any override is read when the builder has a
+    /// [`SignaturePolicy`](crate::SignaturePolicy) other than `Ignore`. The
+    /// default implementation returns [`SignatureStatus::NotSignable`], for
+    /// formats that cannot carry a signature.
+    fn verify_signature(&mut self, trust: &SignatureTrust) -> Result<SignatureStatus> {
+        let _ = trust;
+        Ok(SignatureStatus::NotSignable)
+    }
+
+    /// Read the mod's thumbnail image, as stored.
+    ///
+    /// Returns `None` if the mod has no thumbnail. The default implementation
+    /// returns `None`.
+    fn thumbnail(&mut self) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Read the mod's README.
+    ///
+    /// Returns `None` if the mod has no README. The default implementation
+    /// returns `None`.
+    fn readme(&mut self) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Read `path`, or return `None` if it does not exist.
+fn read_optional(path: &Utf8Path) -> Result<Option<Vec<u8>>> {
+    match std::fs::read(path.as_std_path()) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
 }
 
 /// Filesystem-backed mod content provider.
@@ -153,6 +247,7 @@ pub trait ModContentProvider: Send + Sync {
 /// ```text
 /// mod_dir/
 ///   mod.config.json              # Project metadata and layer definitions
+///   mod.config.d/                # Optional config fragments merged onto it
 ///   content/
 ///     base/                      # Layer name (matches a layer in mod.config.json)
 ///       Aatrox.wad.client/       # WAD target directory
@@ -166,7 +261,9 @@ pub trait ModContentProvider: Send + Sync {
 /// ```
 ///
 /// Only subdirectories under each layer whose name ends in `.wad.client`
-/// (case-insensitive) are recognized as WAD targets.
+/// (case-insensitive) are recognized as WAD targets. The thumbnail is the file
+/// named by the project's `thumbnail` field, `thumbnail.webp` by default, and the
+/// README is `README.md`, both next to `mod.config.json`.
 pub struct FsModContent {
     mod_dir: Utf8PathBuf,
 }
@@ -182,9 +279,9 @@ impl FsModContent {
 
 impl ModContentProvider for FsModContent {
     fn mod_project(&mut self) -> Result<ModProject> {
-        let config_path = self.mod_dir.join("mod.config.json");
-        let contents = std::fs::read_to_string(config_path.as_std_path())?;
-        Ok(serde_json::from_str(&contents)?)
+        Ok(ltk_mod_project::load_config_with_includes(
+            self.mod_dir.as_std_path(),
+        )?)
     }
 
     fn list_layer_wads(&mut self, layer: &str) -> Result<Vec<String>> {
@@ -329,49 +426,51 @@ impl ModContentProvider for FsModContent {
         let file_path = self.mod_dir.join("content").join(rel_path);
         Ok(std::fs::read(file_path.as_std_path())?)
     }
+
+    fn format_version(&self) -> ContentFormatVersion {
+        ContentFormatVersion::FsV1
+    }
+
+    fn thumbnail(&mut self) -> Result<Option<Vec<u8>>> {
+        let name = self
+            .mod_project()?
+            .thumbnail
+            .unwrap_or_else(|| "thumbnail.webp".to_string());
+        read_optional(&self.mod_dir.join(name))
+    }
+
+    fn readme(&mut self) -> Result<Option<String>> {
+        match read_optional(&self.mod_dir.join("README.md"))? {
+            Some(bytes) => Ok(Some(String::from_utf8(bytes).map_err(|e| {
+                Error::Other(format!("README.md is not valid UTF-8: {}", e))
+            })?)),
+            None => Ok(None),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{mock_project, write_fs_mod};
     use camino::Utf8PathBuf;
     use std::fs;
     use tempfile::tempdir;
 
     fn create_test_mod_dir() -> tempfile::TempDir {
         let dir = tempdir().unwrap();
-        let mod_dir = dir.path();
-
-        // Create mod.config.json
-        let project = ltk_mod_project::ModProject {
-            name: "test-mod".to_string(),
-            display_name: "Test Mod".to_string(),
-            version: "1.0.0".to_string(),
-            description: "A test mod".to_string(),
-            authors: vec![],
-            license: None,
-            tags: vec![],
-            champions: vec![],
-            maps: vec![],
-            transformers: vec![],
-            layers: ltk_mod_project::default_layers(),
-            thumbnail: None,
-        };
-        fs::write(
-            mod_dir.join("mod.config.json"),
-            serde_json::to_string_pretty(&project).unwrap(),
+        let mod_dir = Utf8Path::from_path(dir.path()).unwrap();
+        let mut project = mock_project("test-mod");
+        project.display_name = "Test Mod".to_string();
+        write_fs_mod(
+            mod_dir,
+            &project,
+            &[
+                ("base/Test.wad.client/file1.bin", b"data1"),
+                ("base/Test.wad.client/subdir/file2.bin", b"data2"),
+            ],
         )
         .unwrap();
-
-        // Create content/base/Test.wad.client/ with some files
-        let wad_dir = mod_dir.join("content/base/Test.wad.client");
-        fs::create_dir_all(&wad_dir).unwrap();
-        fs::write(wad_dir.join("file1.bin"), b"data1").unwrap();
-
-        let sub_dir = wad_dir.join("subdir");
-        fs::create_dir_all(&sub_dir).unwrap();
-        fs::write(sub_dir.join("file2.bin"), b"data2").unwrap();
-
         dir
     }
 
@@ -386,6 +485,24 @@ mod tests {
         assert_eq!(project.display_name, "Test Mod");
     }
 
+    #[test]
+    fn test_fs_mod_project_merges_config_fragments() {
+        let dir = create_test_mod_dir();
+        let fragment_dir = dir.path().join(ltk_mod_project::CONFIG_FRAGMENT_DIR);
+        fs::create_dir_all(&fragment_dir).unwrap();
+        fs::write(
+            fragment_dir.join("chroma.json"),
+            r#"{ "layers": [{ "name": "chroma", "priority": 10 }] }"#,
+        )
+        .unwrap();
+        let mod_dir = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let mut provider = FsModContent::new(mod_dir);
+
+        let project = provider.mod_project().unwrap();
+        assert_eq!(project.layers.len(), 2);
+        assert_eq!(project.layers[1].name, "chroma");
+    }
+
     #[test]
     fn test_fs_list_layer_wads() {
         let dir = create_test_mod_dir();
@@ -426,4 +543,19 @@ mod tests {
         assert!(paths.contains(&"file1.bin".to_string()));
         assert!(paths.contains(&"subdir/file2.bin".to_string()));
     }
+
+    #[test]
+    fn test_fs_thumbnail_and_readme() {
+        let dir = create_test_mod_dir();
+        let mod_dir = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let mut provider = FsModContent::new(mod_dir);
+
+        assert_eq!(provider.thumbnail().unwrap(), None);
+        assert_eq!(provider.readme().unwrap(), None);
+
+        fs::write(dir.path().join("thumbnail.webp"), b"RIFF").unwrap();
+        fs::write(dir.path().join("README.md"), "# Test Mod\n").unwrap();
+        assert_eq!(provider.thumbnail().unwrap(), Some(b"RIFF".to_vec()));
+        assert_eq!(provider.readme().unwrap().as_deref(), Some("# Test Mod\n"));
+    }
 }
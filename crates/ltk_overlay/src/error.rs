@@ -45,10 +45,25 @@ pub enum Error {
     #[error("Invalid mod directory: {0}")]
     InvalidModDir(Utf8PathBuf),
 
-    /// A mod's `mod.config.json` is missing or malformed.
+    /// A mod's `mod.config.json` (or one of its config fragments) is missing or malformed.
     #[error("Invalid mod config: {0}")]
     InvalidModConfig(String),
 
+    /// An operation named a mod that is not in the enabled mod list.
+    #[error("Mod not enabled: {0}")]
+    ModNotEnabled(String),
+
+    /// A mod was rejected by [`SignaturePolicy::Require`](crate::SignaturePolicy::Require).
+    #[error("Mod '{mod_id}' is not trusted: {status}")]
+    UntrustedMod {
+        mod_id: String,
+        status: crate::SignatureStatus,
+    },
+
+    /// [`rollback`](crate::rollback) found no backup in the overlay directory.
+    #[error("No overlay backup to roll back to in {0}")]
+    NoBackup(Utf8PathBuf),
+
     /// The overlay directory exists but its WAD files are corrupted.
     #[error("Overlay validation failed: {0}")]
     ValidationFailed(String),
@@ -62,6 +77,22 @@ pub enum Error {
     Other(String),
 }
 
+impl From<ltk_mod_core::GameDirError> for Error {
+    fn from(e: ltk_mod_core::GameDirError) -> Self {
+        Error::InvalidGameDir(e.to_string())
+    }
+}
+
+impl From<ltk_mod_project::ModProjectError> for Error {
+    fn from(e: ltk_mod_project::ModProjectError) -> Self {
+        match e {
+            ltk_mod_project::ModProjectError::Io(e) => Error::Io(e),
+            ltk_mod_project::ModProjectError::Json(e) => Error::Json(e),
+            other => Error::InvalidModConfig(other.to_string()),
+        }
+    }
+}
+
 impl From<String> for Error {
     fn from(s: String) -> Self {
         Error::Other(s)
@@ -1,17 +1,18 @@
 //! Content provider for `.fantome` ZIP archives.
 //!
-//! Fantome archives only support a single "base" layer. WAD content is stored
-//! under the `WAD/` directory, either as:
+//! Base-layer WAD content is stored under the `WAD/` directory. Archives written
+//! by `ltk_fantome::pack_to_fantome_with_layers` also store the content of other
+//! layers under `LAYERS/{layer}/`, laid out the same way. WADs are stored either as:
 //! - **Directory WADs**: `WAD/{name}.wad.client/{file}` — individual override files
 //! - **Packed WADs**: `WAD/{name}.wad.client` — complete WAD files unpacked in-memory into overrides
 //!
 //! Raw overrides (game asset paths not pre-organized into WAD directories) are stored
 //! under the `RAW/` directory.
 
-use crate::content::{archive_fingerprint, ModContentProvider};
+use crate::content::{archive_fingerprint, ContentFormatVersion, ModContentProvider};
 use crate::error::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
-use ltk_mod_project::{default_layers, ModProject, ModProjectAuthor};
+use ltk_mod_project::{ModDependency, ModMap, ModProject, ModProjectAuthor, ModTag};
 use ltk_wad::Wad;
 use std::collections::HashMap;
 use std::io::{self, Cursor, Read, Seek};
@@ -48,20 +49,26 @@ fn read_zip_entry_bytes(entry: &mut ZipFile<'_>) -> io::Result<Vec<u8>> {
 struct FantomeIndex {
     /// The exact entry name for META/info.json (case-insensitive match).
     info_entry: Option<String>,
+    /// Layer name -> the WADs stored for it (`WAD/` is the `base` layer).
+    layers: HashMap<String, LayerIndex>,
+    /// RAW entries: (full_zip_path, relative_path).
+    raw_entries: Vec<(String, String)>,
+}
+
+/// The WADs stored for one layer of a fantome archive.
+#[derive(Default)]
+struct LayerIndex {
     /// Lowercase WAD name -> [(full_zip_path, relative_path)]. Lowercase keys
     /// make lookups case-insensitive; the stored path keeps the real casing.
     wad_dir_entries: HashMap<String, Vec<(String, String)>>,
     /// Lowercase WAD name -> full_zip_path, for WADs stored as single files.
     packed_wad_paths: HashMap<String, String>,
-    /// RAW entries: (full_zip_path, relative_path).
-    raw_entries: Vec<(String, String)>,
 }
 
 impl FantomeIndex {
     fn build<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Self {
         let mut info_entry = None;
-        let mut wad_dir_entries: HashMap<String, Vec<(String, String)>> = HashMap::new();
-        let mut packed_wad_paths: HashMap<String, String> = HashMap::new();
+        let mut layers: HashMap<String, LayerIndex> = HashMap::new();
         let mut raw_entries: Vec<(String, String)> = Vec::new();
 
         for i in 0..archive.len() {
@@ -78,16 +85,17 @@ impl FantomeIndex {
                 continue;
             }
 
-            // WAD/ entries (prefix matched case-insensitively, e.g. `wad/`)
-            if let Some(relative) = strip_prefix_ci(&name, "WAD/") {
+            // WAD/ and LAYERS/{layer}/ entries (prefix matched case-insensitively, e.g. `wad/`)
+            if let Some((layer, relative)) = content_entry_layer(&name) {
                 if relative.is_empty() || is_dir {
                     continue;
                 }
 
+                let layer_index = layers.entry(layer.to_string()).or_default();
                 if !relative.contains('/') && is_wad_file_name(relative) {
-                    // Packed WAD file directly under WAD/.
+                    // Packed WAD file directly under the layer.
                     let key = relative.to_ascii_lowercase();
-                    packed_wad_paths.insert(key, name);
+                    layer_index.packed_wad_paths.insert(key, name);
                 } else if let Some(wad_name) = relative.split('/').next() {
                     if is_wad_file_name(wad_name) {
                         let rel = relative
@@ -98,7 +106,11 @@ impl FantomeIndex {
                             // Own the key/rel so `name` is free to move below.
                             let key = wad_name.to_ascii_lowercase();
                             let rel = rel.to_string();
-                            wad_dir_entries.entry(key).or_default().push((name, rel));
+                            layer_index
+                                .wad_dir_entries
+                                .entry(key)
+                                .or_default()
+                                .push((name, rel));
                         }
                     }
                 }
@@ -116,13 +128,19 @@ impl FantomeIndex {
 
         Self {
             info_entry,
-            wad_dir_entries,
-            packed_wad_paths,
+            layers,
             raw_entries,
         }
     }
 
-    /// All WAD names in the archive, as lowercase keys.
+    /// Whether the archive stores content for a layer other than `base`.
+    fn has_layer_content(&self) -> bool {
+        self.layers.keys().any(|layer| layer != "base")
+    }
+}
+
+impl LayerIndex {
+    /// All WAD names in the layer, as lowercase keys.
     fn wad_names(&self) -> Vec<String> {
         let mut names: Vec<String> = self.wad_dir_entries.keys().cloned().collect();
         for wad_name in self.packed_wad_paths.keys() {
@@ -134,17 +152,33 @@ impl FantomeIndex {
     }
 }
 
+/// Split a ZIP entry name into its layer and the path below it.
+///
+/// Like [`ltk_fantome::content_entry_layer`], but matches the `WAD/` and
+/// `LAYERS/` prefixes case-insensitively.
+fn content_entry_layer(name: &str) -> Option<(&str, &str)> {
+    if let Some(relative) = strip_prefix_ci(name, "WAD/") {
+        return Some(("base", relative));
+    }
+    let rest = strip_prefix_ci(name, "LAYERS/")?;
+    match rest.split_once('/') {
+        Some((layer, relative)) if !layer.is_empty() => Some((layer, relative)),
+        _ => None,
+    }
+}
+
 /// Content provider that reads directly from a `.fantome` ZIP archive.
 ///
-/// Fantome archives only support a single "base" layer. WAD content is stored
-/// under the `WAD/` directory, either as:
+/// Base-layer WAD content is stored under the `WAD/` directory and the content
+/// of every other layer under `LAYERS/{layer}/`, either as:
 /// - **Directory WADs**: `WAD/{name}.wad.client/{file}` — individual override files
 /// - **Packed WADs**: `WAD/{name}.wad.client` — complete WAD files unpacked in-memory into overrides
 pub struct FantomeContent<R: Read + Seek> {
     archive: ZipArchive<R>,
     index: FantomeIndex,
     archive_path: Option<Utf8PathBuf>,
-    packed_wads: HashMap<String, Wad<Cursor<Vec<u8>>>>,
+    /// (layer, lowercase WAD name) -> mounted packed WAD.
+    packed_wads: HashMap<(String, String), Wad<Cursor<Vec<u8>>>>,
 }
 
 impl<R: Read + Seek> FantomeContent<R> {
@@ -154,8 +188,14 @@ impl<R: Read + Seek> FantomeContent<R> {
         let index = FantomeIndex::build(&mut archive);
 
         // Mount all packed WADs upfront, reading via the stored entry path.
-        let mut packed_wads: HashMap<String, Wad<Cursor<Vec<u8>>>> = HashMap::new();
-        for (wad_key, zip_path) in &index.packed_wad_paths {
+        let mut packed_wads: HashMap<(String, String), Wad<Cursor<Vec<u8>>>> = HashMap::new();
+        let packed_wad_paths = index.layers.iter().flat_map(|(layer, layer_index)| {
+            layer_index
+                .packed_wad_paths
+                .iter()
+                .map(move |(wad_key, zip_path)| (layer, wad_key, zip_path))
+        });
+        for (layer, wad_key, zip_path) in packed_wad_paths {
             let mut entry = archive.by_name(zip_path).map_err(|e| {
                 Error::Other(format!("Failed to read packed WAD '{}': {}", zip_path, e))
             })?;
@@ -167,7 +207,7 @@ impl<R: Read + Seek> FantomeContent<R> {
             })?;
 
             let wad = Wad::mount(Cursor::new(wad_data))?;
-            packed_wads.insert(wad_key.clone(), wad);
+            packed_wads.insert((layer.clone(), wad_key.clone()), wad);
         }
 
         Ok(Self {
@@ -205,6 +245,7 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         let info: ltk_fantome::FantomeInfo = serde_json::from_str(info_content)
             .map_err(|e| Error::Other(format!("Failed to parse fantome info.json: {}", e)))?;
 
+        let layers = info.project_layers(self.index.layers.keys().map(String::as_str));
         Ok(ModProject {
             name: slug::slugify(&info.name),
             display_name: info.name,
@@ -212,20 +253,31 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
             description: info.description,
             authors: vec![ModProjectAuthor::Name(info.author)],
             license: None,
-            tags: Vec::new(),
-            champions: Vec::new(),
-            maps: Vec::new(),
+            tags: info.tags.into_iter().map(ModTag::from).collect(),
+            champions: info.champions,
+            maps: info.maps.into_iter().map(ModMap::from).collect(),
+            dependencies: info
+                .dependencies
+                .into_iter()
+                .map(|d| ModDependency {
+                    name: d.name,
+                    version_req: d.version_req,
+                })
+                .collect(),
             transformers: Vec::new(),
-            layers: default_layers(),
+            compression: HashMap::new(),
+            layers,
             thumbnail: None,
         })
     }
 
     fn list_layer_wads(&mut self, layer: &str) -> Result<Vec<String>> {
-        if layer != "base" {
-            return Ok(Vec::new());
-        }
-        Ok(self.index.wad_names())
+        Ok(self
+            .index
+            .layers
+            .get(layer)
+            .map(LayerIndex::wad_names)
+            .unwrap_or_default())
     }
 
     fn read_wad_overrides(
@@ -233,14 +285,15 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         layer: &str,
         wad_name: &str,
     ) -> Result<Vec<(Utf8PathBuf, Vec<u8>)>> {
-        if layer != "base" {
-            return Ok(Vec::new());
-        }
-
         let wad_key = wad_name.to_ascii_lowercase();
 
         // Try directory-style entries first
-        if let Some(entries) = self.index.wad_dir_entries.get(&wad_key) {
+        let entries = self
+            .index
+            .layers
+            .get(layer)
+            .and_then(|layer_index| layer_index.wad_dir_entries.get(&wad_key));
+        if let Some(entries) = entries {
             let entry_names: Vec<(String, String)> = entries.clone();
             let mut results = Vec::with_capacity(entry_names.len());
 
@@ -258,7 +311,7 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         }
 
         // Try packed WAD — extract all chunks as hex-hash files
-        if let Some(wad) = self.packed_wads.get_mut(&wad_key) {
+        if let Some(wad) = self.packed_wads.get_mut(&(layer.to_string(), wad_key)) {
             let path_hashes: Vec<u64> = wad.chunks().iter().map(|c| c.path_hash).collect();
             let mut results = Vec::with_capacity(path_hashes.len());
 
@@ -300,13 +353,6 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         wad_name: &str,
         rel_path: &Utf8Path,
     ) -> Result<Vec<u8>> {
-        if layer != "base" {
-            return Err(Error::Other(format!(
-                "Fantome archives only support 'base' layer, got '{}'",
-                layer
-            )));
-        }
-
         let wad_key = wad_name.to_ascii_lowercase();
 
         // Look up the stored entry path rather than reconstructing it, since the
@@ -314,8 +360,9 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         let want = rel_path.as_str().replace('\\', "/");
         let zip_path = self
             .index
-            .wad_dir_entries
-            .get(&wad_key)
+            .layers
+            .get(layer)
+            .and_then(|layer_index| layer_index.wad_dir_entries.get(&wad_key))
             .and_then(|entries| {
                 entries
                     .iter()
@@ -333,7 +380,7 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         }
 
         // Try packed WAD — extract specific chunk by hex hash filename
-        if let Some(wad) = self.packed_wads.get_mut(&wad_key) {
+        if let Some(wad) = self.packed_wads.get_mut(&(layer.to_string(), wad_key)) {
             let file_stem = Utf8Path::new(rel_path.file_name().unwrap_or(""))
                 .file_stem()
                 .unwrap_or("");
@@ -352,8 +399,11 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
         }
 
         Err(Error::Other(format!(
-            "Override file not found in fantome archive: WAD/{}/{}",
-            wad_name, rel_path
+            "Override file not found in fantome archive: {}/{}/{} ({} layer)",
+            if layer == "base" { "WAD" } else { "LAYERS" },
+            wad_name,
+            rel_path,
+            layer
         )))
     }
 
@@ -390,6 +440,14 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for FantomeContent<R> {
             None => Ok(None),
         }
     }
+
+    fn format_version(&self) -> ContentFormatVersion {
+        if self.index.has_layer_content() {
+            ContentFormatVersion::FantomeLayered
+        } else {
+            ContentFormatVersion::FantomeLegacy
+        }
+    }
 }
 
 fn is_wad_file_name(name: &str) -> bool {
@@ -506,6 +564,7 @@ mod tests {
             tags: Vec::new(),
             champions: Vec::new(),
             maps: Vec::new(),
+            dependencies: Vec::new(),
             layers: std::collections::HashMap::new(),
         })
         .unwrap()
@@ -532,6 +591,42 @@ mod tests {
         assert_eq!(project.version, "1.0.0");
     }
 
+    #[test]
+    fn mod_project_reads_tags_champions_maps_and_dependencies() {
+        let mut info: ltk_fantome::FantomeInfo =
+            serde_json::from_slice(&make_info_json("Tagged")).unwrap();
+        info.tags = vec!["champion-skin".to_string(), "spooky".to_string()];
+        info.champions = vec!["Aatrox".to_string()];
+        info.maps = vec!["aram".to_string()];
+        info.dependencies = vec![ltk_fantome::FantomeDependency {
+            name: "old-summoners-rift".to_string(),
+            version_req: "^1.2".to_string(),
+        }];
+        let cursor = make_fantome_zip(&[("META/info.json", &serde_json::to_vec(&info).unwrap())]);
+        let mut content = FantomeContent::new(cursor).unwrap();
+
+        let project = content.mod_project().unwrap();
+        assert_eq!(
+            project.tags,
+            vec![
+                ModTag::Known(ltk_mod_project::WellKnownModTag::ChampionSkin),
+                ModTag::Custom("spooky".to_string()),
+            ]
+        );
+        assert_eq!(project.champions, vec!["Aatrox"]);
+        assert_eq!(
+            project.maps,
+            vec![ModMap::Known(ltk_mod_project::WellKnownMap::Aram)]
+        );
+        assert_eq!(
+            project.dependencies,
+            vec![ModDependency {
+                name: "old-summoners-rift".to_string(),
+                version_req: "^1.2".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn mod_project_missing_info_json() {
         let cursor = make_fantome_zip(&[("WAD/test.wad.client/file", b"data")]);
@@ -551,6 +646,7 @@ mod tests {
                 tags: Vec::new(),
                 champions: Vec::new(),
                 maps: Vec::new(),
+                dependencies: Vec::new(),
                 layers: std::collections::HashMap::new(),
             })
             .unwrap()
@@ -699,6 +795,72 @@ mod tests {
         assert!(overrides.is_empty());
     }
 
+    #[test]
+    fn reads_layer_content_under_layers_dir() {
+        let mut info: ltk_fantome::FantomeInfo =
+            serde_json::from_slice(&make_info_json("Layered")).unwrap();
+        info.layers.insert(
+            "chroma".to_string(),
+            ltk_fantome::FantomeLayerInfo {
+                name: "chroma".to_string(),
+                display_name: None,
+                priority: 5,
+                string_overrides: HashMap::new(),
+            },
+        );
+        let wad_bytes = make_packed_wad_bytes(b"packed chroma");
+        let cursor = make_fantome_zip(&[
+            ("META/info.json", &serde_json::to_vec(&info).unwrap()),
+            ("WAD/Aatrox.wad.client/data/skin.bin", b"base"),
+            ("LAYERS/chroma/Aatrox.wad.client/data/skin.bin", b"chroma"),
+            ("LAYERS/chroma/Map11.wad.client", &wad_bytes),
+        ]);
+        let mut content = FantomeContent::new(cursor).unwrap();
+
+        assert_eq!(
+            content.format_version(),
+            ContentFormatVersion::FantomeLayered
+        );
+        let project = content.mod_project().unwrap();
+        let layers: Vec<(&str, i32)> = project
+            .layers
+            .iter()
+            .map(|l| (l.name.as_str(), l.priority))
+            .collect();
+        assert_eq!(layers, [("base", 0), ("chroma", 5)]);
+
+        assert_eq!(
+            content.list_layer_wads("base").unwrap(),
+            ["aatrox.wad.client"]
+        );
+        let mut chroma_wads = content.list_layer_wads("chroma").unwrap();
+        chroma_wads.sort();
+        assert_eq!(chroma_wads, ["aatrox.wad.client", "map11.wad.client"]);
+
+        let skin = Utf8Path::new("data/skin.bin");
+        assert_eq!(
+            content
+                .read_wad_override_file("base", "Aatrox.wad.client", skin)
+                .unwrap(),
+            b"base"
+        );
+        assert_eq!(
+            content
+                .read_wad_override_file("chroma", "Aatrox.wad.client", skin)
+                .unwrap(),
+            b"chroma"
+        );
+        let packed = content
+            .read_wad_overrides("chroma", "Map11.wad.client")
+            .unwrap();
+        assert_eq!(packed.len(), 1);
+        assert_eq!(packed[0].1, b"packed chroma");
+        assert!(content
+            .read_wad_overrides("base", "Map11.wad.client")
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn read_raw_overrides_from_raw_dir() {
         let cursor = make_fantome_zip(&[
@@ -7,7 +7,9 @@
 //! 1. **Filename lookup** ([`find_wad`](GameIndex::find_wad)) — Resolve a WAD name
 //!    like `"Aatrox.wad.client"` (as listed by a mod) to its full filesystem path.
 //!    The lookup is case-insensitive. If the same filename appears in multiple
-//!    locations, an [`AmbiguousWad`](crate::Error::AmbiguousWad) error is returned.
+//!    locations, an [`AmbiguousWad`](crate::Error::AmbiguousWad) error is returned
+//!    unless a path hint ([`find_wad_with_hint`](GameIndex::find_wad_with_hint))
+//!    picks one, as for locale WADs copied into several folders.
 //!
 //! 2. **Hash lookup** ([`find_wads_with_hash`](GameIndex::find_wads_with_hash)) —
 //!    Given a chunk path hash (`u64`), return *all* WAD files that contain a chunk
@@ -18,21 +20,31 @@
 //! of all WADs. This fingerprint is persisted in [`OverlayState`](crate::state::OverlayState)
 //! and used to detect game patches that invalidate the overlay.
 //!
+//! A community hashtable (`hashes.game.txt`) can be attached with
+//! [`with_hashtable`](GameIndex::with_hashtable) to turn path hashes back into
+//! readable paths for conflicts and log messages.
+//!
 //! The index can be cached to disk as MessagePack via [`save`](GameIndex::save) /
 //! [`load_or_build`](GameIndex::load_or_build) to avoid re-mounting every WAD on
-//! subsequent builds when the game hasn't been patched.
+//! subsequent builds when the game hasn't been patched. After a patch, only the
+//! WADs whose size or modification time changed are re-mounted
+//! ([`refresh`](GameIndex::refresh)).
 
 use crate::error::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs::File,
+    sync::OnceLock,
 };
 use walkdir::WalkDir;
 
+/// Folder, relative to the game directory, that holds the locale WADs.
+pub const LOCALIZED_WAD_DIR: &str = "DATA/FINAL/Localized";
+
 /// Version tag for the cache format.
-const CACHE_VERSION: u32 = 3;
+const CACHE_VERSION: u32 = 4;
 
 /// Serializable representation of a [`GameIndex`] for disk caching.
 ///
@@ -47,6 +59,17 @@ struct GameIndexCache {
     /// Chunk path hash -> WAD relative paths.
     hash_index: HashMap<u64, Vec<Utf8PathBuf>>,
     subchunktoc_blocked: Vec<u64>,
+    /// WAD relative path -> size and modification time when mounted.
+    wad_stamps: HashMap<Utf8PathBuf, WadStamp>,
+}
+
+/// Size and modification time of a game WAD when it was indexed, used by
+/// [`GameIndex::refresh`] to find the WADs a patch changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WadStamp {
+    pub size: u64,
+    /// Nanoseconds since the Unix epoch.
+    pub modified: u64,
 }
 
 /// Index of all WAD files in a League of Legends game directory.
@@ -80,8 +103,37 @@ pub struct GameIndex {
     /// computed and hashed. Mod overrides matching these hashes are stripped during
     /// the build to prevent mods from corrupting the game's sub-chunk loading.
     pub subchunktoc_blocked: HashSet<u64>,
+
+    /// Chunk path hash -> path, from a hashtable loaded with
+    /// [`with_hashtable`](Self::with_hashtable). Empty by default and never
+    /// written to the index cache.
+    pub hashtable: HashMap<u64, String>,
+
+    /// WAD path (relative to game dir) -> size and modification time when it
+    /// was mounted. Lets [`refresh`](Self::refresh) re-mount only changed WADs.
+    pub wad_stamps: HashMap<Utf8PathBuf, WadStamp>,
+
+    /// WAD filename (lowercased) -> WAD paths relative to the game dir, derived
+    /// from `wad_stamps` the first time [`guess_target_wad`](Self::guess_target_wad)
+    /// needs it.
+    pub(crate) wad_names: WadNameCache,
 }
 
+/// Lazily built filename index of a [`GameIndex`].
+///
+/// It is derived from the index's other fields, so it never makes two indexes
+/// unequal.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WadNameCache(OnceLock<HashMap<String, Vec<Utf8PathBuf>>>);
+
+impl PartialEq for WadNameCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for WadNameCache {}
+
 impl GameIndex {
     /// Create a new empty game index.
     pub fn new() -> Self {
@@ -90,6 +142,9 @@ impl GameIndex {
             hash_index: HashMap::new(),
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         }
     }
 
@@ -116,8 +171,10 @@ impl GameIndex {
         let wad_paths = collect_wad_paths_sorted(&data_final_dir)?;
 
         let wad_index = build_wad_filename_index(&wad_paths);
-        let (hash_index, wad_relative_paths) = build_game_hash_index(game_dir, &wad_paths);
+        let (hash_index, wad_relative_paths) =
+            build_game_hash_index(game_dir, &wad_paths, mount_and_extract_hashes);
         let game_fingerprint = calculate_game_fingerprint(&wad_paths);
+        let wad_stamps = collect_wad_stamps(game_dir, &wad_paths);
         let subchunktoc_blocked = build_subchunktoc_blocked(&wad_relative_paths);
 
         tracing::info!(
@@ -133,6 +190,9 @@ impl GameIndex {
             hash_index,
             game_fingerprint,
             subchunktoc_blocked,
+            hashtable: HashMap::new(),
+            wad_stamps,
+            wad_names: Default::default(),
         })
     }
 
@@ -143,8 +203,10 @@ impl GameIndex {
     /// 2. The cache version matches the current format
     /// 3. The game fingerprint (derived from WAD file sizes/timestamps) matches
     ///
-    /// If the cache is stale or missing, a fresh index is built and saved to
-    /// the cache path (best-effort — save failures are logged but not fatal).
+    /// If the cache is stale, it is [refreshed](Self::refresh) by re-mounting only
+    /// the changed WADs. If it is missing or cannot be refreshed, a fresh index is
+    /// built. Either way the result is saved to the cache path (best-effort — save
+    /// failures are logged but not fatal).
     ///
     /// # Arguments
     ///
@@ -153,7 +215,7 @@ impl GameIndex {
     pub fn load_or_build(game_dir: &Utf8Path, cache_path: &Utf8Path) -> Result<Self> {
         // Try loading from cache
         match Self::load_cache(cache_path) {
-            Ok(Some(cached)) => {
+            Ok(Some(mut cached)) => {
                 // Verify the game hasn't been patched by computing a fresh fingerprint
                 let data_final_dir = game_dir.join("DATA").join("FINAL");
                 let current_fp = calculate_game_fingerprint_from_dir(&data_final_dir)?;
@@ -166,10 +228,19 @@ impl GameIndex {
                     return Ok(cached);
                 }
                 tracing::info!(
-                    "Game index cache stale (fingerprint {:016x} != {:016x}), rebuilding",
+                    "Game index cache stale (fingerprint {:016x} != {:016x}), refreshing",
                     cached.game_fingerprint,
                     current_fp
                 );
+                match cached.refresh(game_dir) {
+                    Ok(_) => {
+                        if let Err(e) = cached.save(cache_path) {
+                            tracing::warn!("Failed to save game index cache: {}", e);
+                        }
+                        return Ok(cached);
+                    }
+                    Err(e) => tracing::warn!("Failed to refresh game index, rebuilding: {}", e),
+                }
             }
             Ok(None) => {
                 tracing::debug!("No game index cache found at {}", cache_path);
@@ -190,6 +261,121 @@ impl GameIndex {
         Ok(index)
     }
 
+    /// Re-index only the given WADs after they were added, modified or removed.
+    ///
+    /// `changed` holds absolute paths under `game_dir/DATA/FINAL`. Each one is
+    /// dropped from the hash index and re-mounted if it still exists. The
+    /// filename index, SubChunkTOC set and fingerprint are recomputed from a
+    /// fresh directory walk, which needs no mounting. Provided every WAD
+    /// mounts, the result equals a full [`build`](Self::build).
+    pub fn update_wads(&mut self, game_dir: &Utf8Path, changed: &[Utf8PathBuf]) -> Result<()> {
+        self.update_wads_with(game_dir, changed, mount_and_extract_hashes)
+    }
+
+    /// Re-index the WADs whose size or modification time changed since they were
+    /// indexed, plus any added or deleted since.
+    ///
+    /// Only those WADs are re-mounted; hash entries of deleted WADs are dropped
+    /// and the rest of the index is kept. Returns the absolute paths of the WADs
+    /// that changed, sorted. [`load_or_build`](Self::load_or_build) uses this when
+    /// the cached index is stale, so a small game patch does not re-mount every WAD.
+    pub fn refresh(&mut self, game_dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+        self.refresh_with(game_dir, mount_and_extract_hashes)
+    }
+
+    fn refresh_with(
+        &mut self,
+        game_dir: &Utf8Path,
+        mount: impl Fn(&Utf8Path, Utf8PathBuf) -> Option<WadMountResult> + Sync,
+    ) -> Result<Vec<Utf8PathBuf>> {
+        let data_final_dir = game_dir.join("DATA").join("FINAL");
+        if !data_final_dir.as_std_path().exists() {
+            return Err(Error::InvalidGameDir(format!(
+                "DATA/FINAL not found in {}",
+                game_dir
+            )));
+        }
+
+        let current = collect_wad_stamps(game_dir, &collect_wad_paths_sorted(&data_final_dir)?);
+        let mut changed: Vec<Utf8PathBuf> = current
+            .iter()
+            .filter(|(wad, stamp)| self.wad_stamps.get(*wad) != Some(*stamp))
+            .chain(
+                self.wad_stamps
+                    .iter()
+                    .filter(|(wad, _)| !current.contains_key(*wad)),
+            )
+            .map(|(wad, _)| game_dir.join(wad))
+            .collect();
+        changed.sort();
+
+        if !changed.is_empty() {
+            self.update_wads_with(game_dir, &changed, mount)?;
+        }
+        Ok(changed)
+    }
+
+    fn update_wads_with(
+        &mut self,
+        game_dir: &Utf8Path,
+        changed: &[Utf8PathBuf],
+        mount: impl Fn(&Utf8Path, Utf8PathBuf) -> Option<WadMountResult> + Sync,
+    ) -> Result<()> {
+        let data_final_dir = game_dir.join("DATA").join("FINAL");
+        if !data_final_dir.as_std_path().exists() {
+            return Err(Error::InvalidGameDir(format!(
+                "DATA/FINAL not found in {}",
+                game_dir
+            )));
+        }
+
+        let wad_paths = collect_wad_paths_sorted(&data_final_dir)?;
+        let changed_rel: HashSet<&Utf8Path> = changed
+            .iter()
+            .filter_map(|path| path.strip_prefix(game_dir).ok())
+            .collect();
+
+        self.hash_index.retain(|_, wads| {
+            wads.retain(|wad| !changed_rel.contains(wad.as_path()));
+            !wads.is_empty()
+        });
+
+        let remount: Vec<Utf8PathBuf> = wad_paths
+            .iter()
+            .filter(|path| {
+                path.strip_prefix(game_dir)
+                    .is_ok_and(|rel| changed_rel.contains(rel))
+            })
+            .cloned()
+            .collect();
+        let (remounted, _) = build_game_hash_index(game_dir, &remount, mount);
+        for (hash, wads) in remounted {
+            let entry = self.hash_index.entry(hash).or_default();
+            for wad in wads {
+                let pos = entry.binary_search(&wad).unwrap_or_else(|pos| pos);
+                entry.insert(pos, wad);
+            }
+        }
+
+        let wad_relative_paths: Vec<Utf8PathBuf> = wad_paths
+            .iter()
+            .filter_map(|path| Some(path.strip_prefix(game_dir).ok()?.to_path_buf()))
+            .collect();
+        self.wad_index = build_wad_filename_index(&wad_paths);
+        self.subchunktoc_blocked = build_subchunktoc_blocked(&wad_relative_paths);
+        self.game_fingerprint = calculate_game_fingerprint(&wad_paths);
+        self.wad_stamps = collect_wad_stamps(game_dir, &wad_paths);
+        self.wad_names = WadNameCache::default();
+
+        tracing::info!(
+            "Game index updated: {} WAD(s) changed, {} re-mounted, fingerprint: {:016x}",
+            changed.len(),
+            remount.len(),
+            self.game_fingerprint
+        );
+        Ok(())
+    }
+
     /// Save the index to a cache file using MessagePack.
     ///
     /// # Arguments
@@ -200,15 +386,41 @@ impl GameIndex {
             std::fs::create_dir_all(parent.as_std_path())?;
         }
 
-        let cache = self.to_cache();
-        let bytes = rmp_serde::to_vec_named(&cache)
-            .map_err(|e| Error::Other(format!("Failed to serialize game index cache: {}", e)))?;
-        std::fs::write(cache_path.as_std_path(), bytes)?;
+        std::fs::write(cache_path.as_std_path(), self.to_bytes()?)?;
 
         tracing::debug!("Game index cache saved to {}", cache_path);
         Ok(())
     }
 
+    /// Serialize the index to the bytes [`save`](Self::save) writes, so it can
+    /// be stored somewhere other than the cache file.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        rmp_serde::to_vec_named(&self.to_cache())
+            .map_err(|e| Error::Other(format!("Failed to serialize game index cache: {}", e)))
+    }
+
+    /// Deserialize an index from bytes returned by [`to_bytes`](Self::to_bytes).
+    ///
+    /// Returns `Ok(None)` if the bytes come from another cache version, in
+    /// which case the index has to be rebuilt with
+    /// [`load_or_build`](Self::load_or_build). The restored index is not
+    /// checked against the game directory until it is next loaded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Option<Self>> {
+        let cache: GameIndexCache = rmp_serde::from_slice(bytes)
+            .map_err(|e| Error::Other(format!("Failed to deserialize game index cache: {}", e)))?;
+
+        if cache.version != CACHE_VERSION {
+            tracing::info!(
+                "Game index cache version mismatch ({} != {}), ignoring",
+                cache.version,
+                CACHE_VERSION
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(Self::from_cache(cache)))
+    }
+
     /// Find a WAD file by its filename (case-insensitive).
     ///
     /// Returns `None` if the WAD is not found, or an error if multiple candidates exist.
@@ -217,6 +429,23 @@ impl GameIndex {
     ///
     /// * `filename` - The WAD filename to search for (e.g., "Aatrox.wad.client")
     pub fn find_wad(&self, filename: &str) -> Result<&Utf8PathBuf> {
+        self.find_wad_with_hint(filename, None)
+    }
+
+    /// Find a WAD file by its filename (case-insensitive), using `hint` to pick
+    /// between copies of the same filename in different folders.
+    ///
+    /// `hint` is a path relative to the game directory (or any suffix of one),
+    /// naming either the folder, e.g. `DATA/FINAL/Localized`, or the WAD itself.
+    /// It is only consulted when the filename is ambiguous; a unique filename
+    /// is returned even if it does not match. Returns
+    /// [`AmbiguousWad`](Error::AmbiguousWad) if the hint does not narrow the
+    /// candidates down to one.
+    pub fn find_wad_with_hint(
+        &self,
+        filename: &str,
+        hint: Option<&Utf8Path>,
+    ) -> Result<&Utf8PathBuf> {
         let key = filename.to_ascii_lowercase();
         let candidates = self
             .wad_index
@@ -224,13 +453,52 @@ impl GameIndex {
             .ok_or_else(|| Error::WadNotFound(Utf8PathBuf::from(filename)))?;
 
         if candidates.len() == 1 {
-            Ok(&candidates[0])
-        } else {
-            Err(Error::AmbiguousWad {
-                name: filename.to_string(),
-                count: candidates.len(),
-            })
+            return Ok(&candidates[0]);
         }
+
+        if let Some(hint) = hint {
+            let hint = hint.as_str().replace('\\', "/").to_ascii_lowercase();
+            let hint = hint.trim_matches('/');
+            let wad_suffix = format!("/{}", hint);
+            let dir_suffix = format!("/{}/{}", hint, key);
+            let matching: Vec<&Utf8PathBuf> = candidates
+                .iter()
+                .filter(|path| {
+                    let path = path.as_str().replace('\\', "/").to_ascii_lowercase();
+                    path.ends_with(&wad_suffix) || path.ends_with(&dir_suffix)
+                })
+                .collect();
+            if let [path] = matching.as_slice() {
+                return Ok(path);
+            }
+        }
+
+        Err(Error::AmbiguousWad {
+            name: filename.to_string(),
+            count: candidates.len(),
+        })
+    }
+
+    /// Locale WADs present in the game, keyed by lowercased locale.
+    ///
+    /// Matches `Global.<locale>.wad.client` filenames (case-insensitive), e.g.
+    /// `DATA/FINAL/Localized/Global.en_US.wad.client` is returned as `en_us`.
+    /// If a filename occurs in several folders, the copy under
+    /// [`LOCALIZED_WAD_DIR`] wins, then the lexicographically first path.
+    pub fn locale_wads(&self) -> BTreeMap<String, Utf8PathBuf> {
+        self.wad_index
+            .iter()
+            .filter_map(|(filename, paths)| {
+                let locale = wad_locale(filename)?;
+                let path = match self
+                    .find_wad_with_hint(filename, Some(Utf8Path::new(LOCALIZED_WAD_DIR)))
+                {
+                    Ok(path) => path,
+                    Err(_) => paths.iter().min()?,
+                };
+                Some((locale, path.clone()))
+            })
+            .collect()
     }
 
     /// Find all WAD files that contain a specific path hash.
@@ -245,6 +513,71 @@ impl GameIndex {
         self.hash_index.get(&path_hash).map(|v| v.as_slice())
     }
 
+    /// Attach the paths from a hashtable file for [`resolve_hash`](Self::resolve_hash).
+    ///
+    /// The file uses the community `hashes.game.txt` format: one `<hex hash> <path>`
+    /// per line. Malformed lines are skipped. A missing file is not an error; the
+    /// index is returned unchanged.
+    pub fn with_hashtable(mut self, path: &Utf8Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path.as_std_path()) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!("No hashtable at {}, hashes stay unresolved", path);
+                return Ok(self);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        for line in contents.lines() {
+            let Some((hash, chunk_path)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            if let Ok(hash) = u64::from_str_radix(hash, 16) {
+                self.hashtable.insert(hash, chunk_path.to_string());
+            }
+        }
+        tracing::info!(
+            "Loaded {} hashtable entries from {}",
+            self.hashtable.len(),
+            path
+        );
+        Ok(self)
+    }
+
+    /// The path a chunk hash was computed from, if the attached hashtable knows it.
+    pub fn resolve_hash(&self, hash: u64) -> Option<&str> {
+        self.hashtable.get(&hash).map(String::as_str)
+    }
+
+    /// Every indexed chunk, keyed by its path, with the WADs that contain it.
+    ///
+    /// Paths come from the hashtable attached with
+    /// [`with_hashtable`](Self::with_hashtable); chunks it does not know are listed
+    /// under their hash as 16 hex digits. WAD paths are relative to the game
+    /// directory and sorted.
+    pub fn export_named_index(&self) -> BTreeMap<String, Vec<Utf8PathBuf>> {
+        self.hash_index
+            .iter()
+            .map(|(&hash, wads)| {
+                let name = match self.resolve_hash(hash) {
+                    Some(path) => path.to_string(),
+                    None => format!("{:016x}", hash),
+                };
+                let mut wads = wads.clone();
+                wads.sort();
+                (name, wads)
+            })
+            .collect()
+    }
+
+    /// `hash` as its path when known, otherwise as 16 hex digits, for log messages.
+    pub(crate) fn describe_hash(&self, hash: u64) -> String {
+        match self.resolve_hash(hash) {
+            Some(path) => format!("{} ({:016x})", path, hash),
+            None => format!("{:016x}", hash),
+        }
+    }
+
     /// Get the game fingerprint.
     pub fn game_fingerprint(&self) -> u64 {
         self.game_fingerprint
@@ -288,6 +621,39 @@ impl GameIndex {
             })
     }
 
+    /// Guess which game WAD a new asset belongs in from its logical path.
+    ///
+    /// Game assets live in the WAD named after the most specific folder that
+    /// has one: `data/characters/aatrox/skins/skin0.bin` belongs in
+    /// `Champions/Aatrox.wad.client`, and `data/maps/shipping/map11/map11.bin`
+    /// in `Maps/Shipping/Map11.wad.client`. The folders of `asset_path` are
+    /// tried deepest first; a folder whose name matches WADs in several game
+    /// folders is skipped. Returns the WAD path relative to the game directory.
+    pub fn guess_target_wad(&self, asset_path: &str) -> Option<Utf8PathBuf> {
+        let wad_names = self.wad_names.0.get_or_init(|| {
+            let mut wad_names: HashMap<String, Vec<Utf8PathBuf>> = HashMap::new();
+            for wad in self.wad_stamps.keys() {
+                if let Some(name) = wad.file_name() {
+                    wad_names
+                        .entry(name.to_ascii_lowercase())
+                        .or_default()
+                        .push(wad.clone());
+                }
+            }
+            wad_names
+        });
+        let asset_path = asset_path.replace('\\', "/");
+        asset_path.split('/').rev().skip(1).find_map(|dir| {
+            match wad_names
+                .get(&format!("{}.wad.client", dir.to_ascii_lowercase()))
+                .map(Vec::as_slice)
+            {
+                Some([wad]) => Some(wad.clone()),
+                _ => None,
+            }
+        })
+    }
+
     /// Compute content hashes on-demand for a batch of path hashes.
     ///
     /// Groups the requested `path_hashes` by the WAD files that contain them
@@ -346,24 +712,13 @@ impl GameIndex {
         }
 
         let bytes = std::fs::read(cache_path.as_std_path())?;
-        let cache: GameIndexCache = match rmp_serde::from_slice(&bytes) {
-            Ok(c) => c,
+        match Self::from_bytes(&bytes) {
+            Ok(index) => Ok(index),
             Err(e) => {
-                tracing::warn!("Failed to deserialize game index cache: {}", e);
-                return Ok(None);
+                tracing::warn!("{}", e);
+                Ok(None)
             }
-        };
-
-        if cache.version != CACHE_VERSION {
-            tracing::info!(
-                "Game index cache version mismatch ({} != {}), ignoring",
-                cache.version,
-                CACHE_VERSION
-            );
-            return Ok(None);
         }
-
-        Ok(Some(Self::from_cache(cache)))
     }
 
     /// Convert from the cache representation to the runtime format.
@@ -373,6 +728,9 @@ impl GameIndex {
             hash_index: cache.hash_index,
             game_fingerprint: cache.game_fingerprint,
             subchunktoc_blocked: cache.subchunktoc_blocked.into_iter().collect(),
+            hashtable: HashMap::new(),
+            wad_stamps: cache.wad_stamps,
+            wad_names: Default::default(),
         }
     }
 
@@ -384,6 +742,7 @@ impl GameIndex {
             wad_index: self.wad_index.clone(),
             hash_index: self.hash_index.clone(),
             subchunktoc_blocked: self.subchunktoc_blocked.iter().copied().collect(),
+            wad_stamps: self.wad_stamps.clone(),
         }
     }
 }
@@ -395,7 +754,17 @@ impl Default for GameIndex {
 }
 
 /// Collect all `.wad.client` file paths under `root`, sorted for deterministic ordering.
-fn collect_wad_paths_sorted(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+pub(crate) fn collect_wad_paths_sorted(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    collect_paths_with_suffix_sorted(root, ".wad.client")
+}
+
+/// Recursively collect the files under `root` whose lowercased name ends with
+/// `suffix`, sorted.
+pub(crate) fn collect_paths_with_suffix_sorted(
+    root: &Utf8Path,
+    suffix: &str,
+) -> Result<Vec<Utf8PathBuf>> {
+    let suffix = suffix.to_ascii_lowercase();
     let mut paths: Vec<Utf8PathBuf> = WalkDir::new(root.as_std_path())
         .into_iter()
         .filter_map(|entry| {
@@ -420,7 +789,7 @@ fn collect_wad_paths_sorted(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
             };
 
             let name = path.file_name()?;
-            if !name.to_ascii_lowercase().ends_with(".wad.client") {
+            if !name.to_ascii_lowercase().ends_with(&suffix) {
                 return None;
             }
 
@@ -447,6 +816,18 @@ fn build_wad_filename_index(wad_paths: &[Utf8PathBuf]) -> HashMap<String, Vec<Ut
     index
 }
 
+/// The lowercased locale of a `Global.<locale>.wad.client` filename.
+pub(crate) fn wad_locale(filename: &str) -> Option<String> {
+    let filename = filename.to_ascii_lowercase();
+    let locale = filename
+        .strip_prefix("global.")?
+        .strip_suffix(".wad.client")?;
+    if locale.is_empty() || locale.contains('.') {
+        return None;
+    }
+    Some(locale.to_string())
+}
+
 /// Hash index result: chunk path hashes -> WAD paths, plus the list of WAD relative paths.
 type HashIndexResult = (HashMap<u64, Vec<Utf8PathBuf>>, Vec<Utf8PathBuf>);
 
@@ -541,7 +922,11 @@ fn content_hashes_for_wad(abs_path: &Utf8Path, wanted: &HashSet<u64>) -> Vec<(u6
 /// Also returns the set of all WAD relative paths (for SubChunkTOC computation).
 /// WAD files that fail to open or mount are skipped with a warning.
 /// WADs are mounted concurrently using rayon.
-fn build_game_hash_index(game_dir: &Utf8Path, wad_paths: &[Utf8PathBuf]) -> HashIndexResult {
+fn build_game_hash_index(
+    game_dir: &Utf8Path,
+    wad_paths: &[Utf8PathBuf],
+    mount: impl Fn(&Utf8Path, Utf8PathBuf) -> Option<WadMountResult> + Sync,
+) -> HashIndexResult {
     // Compute relative paths
     let wad_abs_rel: Vec<(&Utf8PathBuf, Utf8PathBuf)> = wad_paths
         .iter()
@@ -555,7 +940,7 @@ fn build_game_hash_index(game_dir: &Utf8Path, wad_paths: &[Utf8PathBuf]) -> Hash
     use rayon::prelude::*;
     let mount_results: Vec<WadMountResult> = wad_abs_rel
         .into_par_iter()
-        .filter_map(|(abs, rel)| mount_and_extract_hashes(abs, rel))
+        .filter_map(|(abs, rel)| mount(abs, rel))
         .collect();
 
     // Merge results into the hash index
@@ -641,6 +1026,34 @@ fn calculate_game_fingerprint(wad_paths: &[Utf8PathBuf]) -> u64 {
     xxh3_64(&hasher_input)
 }
 
+/// Size and modification time of every WAD in `wad_paths`, keyed by path
+/// relative to `game_dir`. WADs that cannot be stat'ed are left out.
+fn collect_wad_stamps(
+    game_dir: &Utf8Path,
+    wad_paths: &[Utf8PathBuf],
+) -> HashMap<Utf8PathBuf, WadStamp> {
+    wad_paths
+        .iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(path.as_std_path()).ok()?;
+            let modified = metadata
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_nanos() as u64;
+            let relative = path.strip_prefix(game_dir).ok()?.to_path_buf();
+            Some((
+                relative,
+                WadStamp {
+                    size: metadata.len(),
+                    modified,
+                },
+            ))
+        })
+        .collect()
+}
+
 /// Wrapper that performs its own directory walk for cache validation in [`GameIndex::load_or_build`].
 fn calculate_game_fingerprint_from_dir(data_final_dir: &Utf8Path) -> Result<u64> {
     let wad_paths = collect_wad_paths_sorted(data_final_dir)?;
@@ -721,6 +1134,9 @@ mod tests {
             hash_index,
             game_fingerprint: 0x123456,
             subchunktoc_blocked,
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         // Convert to cache and back
@@ -760,6 +1176,9 @@ mod tests {
             hash_index,
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         let result = index.find_best_matching_wad(&[2, 3, 4, 5]);
@@ -782,6 +1201,9 @@ mod tests {
             hash_index: HashMap::new(),
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         assert!(index.find_best_matching_wad(&[1, 2, 3]).is_none());
@@ -800,6 +1222,9 @@ mod tests {
             hash_index,
             game_fingerprint: 0,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         assert!(index.find_best_matching_wad(&[]).is_none());
@@ -818,6 +1243,9 @@ mod tests {
             hash_index: HashMap::new(),
             game_fingerprint: 0xABCDEF,
             subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
         };
 
         let temp = tempfile::NamedTempFile::new().unwrap();
@@ -830,5 +1258,340 @@ mod tests {
         let loaded = GameIndex::load_cache(cache_path).unwrap().unwrap();
         assert_eq!(loaded.game_fingerprint, 0xABCDEF);
         assert!(loaded.find_wad("test.wad.client").is_ok());
+
+        // The bytes are the cache file's contents
+        let bytes = index.to_bytes().unwrap();
+        assert_eq!(bytes, std::fs::read(cache_path).unwrap());
+        let restored = GameIndex::from_bytes(&bytes).unwrap().unwrap();
+        assert_eq!(restored.game_fingerprint, 0xABCDEF);
+        assert!(restored.find_wad("test.wad.client").is_ok());
+        assert!(GameIndex::from_bytes(b"not msgpack").is_err());
+
+        let mut stale = index.to_cache();
+        stale.version = CACHE_VERSION - 1;
+        let stale = rmp_serde::to_vec_named(&stale).unwrap();
+        assert!(GameIndex::from_bytes(&stale).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_update_wads_matches_full_build() {
+        use crate::testing::{make_fake_game_dir, write_fake_wad, FakeGameSpec, FakeWad};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(
+                    FakeWad::new("Champions/A.wad.client")
+                        .with_chunk(1, b"a".to_vec())
+                        .with_chunk(2, b"shared".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Champions/B.wad.client")
+                        .with_chunk(2, b"shared".to_vec())
+                        .with_chunk(3, b"b".to_vec()),
+                ),
+        )
+        .unwrap();
+        let mut index = GameIndex::build(&game_dir).unwrap();
+
+        // Patch A, delete B, add C.
+        let wad = |name: &str| game_dir.join("DATA/FINAL/Champions").join(name);
+        let write = |name: &str, chunks: &[(u64, &[u8])]| {
+            let chunks = chunks.iter().map(|(h, b)| (*h, b.to_vec())).collect();
+            let mut file = File::create(wad(name).as_std_path()).unwrap();
+            write_fake_wad(&mut file, &chunks).unwrap();
+        };
+        write("A.wad.client", &[(1, b"a2"), (4, b"new")]);
+        std::fs::remove_file(wad("B.wad.client").as_std_path()).unwrap();
+        write("C.wad.client", &[(2, b"shared"), (3, b"b")]);
+
+        index
+            .update_wads(
+                &game_dir,
+                &[
+                    wad("A.wad.client"),
+                    wad("B.wad.client"),
+                    wad("C.wad.client"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(index, GameIndex::build(&game_dir).unwrap());
+        assert!(index.find_wad("B.wad.client").is_err());
+    }
+
+    #[test]
+    fn test_refresh_remounts_only_changed_wads() {
+        use crate::testing::{make_fake_game_dir, write_fake_wad, FakeGameSpec, FakeWad};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(FakeWad::new("Champions/A.wad.client").with_chunk(1, b"a".to_vec()))
+                .with_wad(FakeWad::new("Champions/B.wad.client").with_chunk(2, b"b".to_vec()))
+                .with_wad(FakeWad::new("Maps/Map11.wad.client").with_chunk(3, b"m".to_vec())),
+        )
+        .unwrap();
+        let mut index = GameIndex::build(&game_dir).unwrap();
+
+        // Patch A, delete B, add C; the map WAD is untouched.
+        let wad = |name: &str| game_dir.join("DATA/FINAL/Champions").join(name);
+        let write = |name: &str, chunks: &[(u64, &[u8])]| {
+            let chunks = chunks.iter().map(|(h, b)| (*h, b.to_vec())).collect();
+            let mut file = File::create(wad(name).as_std_path()).unwrap();
+            write_fake_wad(&mut file, &chunks).unwrap();
+        };
+        write("A.wad.client", &[(1, b"a2"), (4, b"new")]);
+        std::fs::remove_file(wad("B.wad.client").as_std_path()).unwrap();
+        write("C.wad.client", &[(5, b"c")]);
+
+        let mounted = AtomicUsize::new(0);
+        let changed = index
+            .refresh_with(&game_dir, |abs, rel| {
+                mounted.fetch_add(1, Ordering::Relaxed);
+                mount_and_extract_hashes(abs, rel)
+            })
+            .unwrap();
+
+        assert_eq!(
+            changed,
+            vec![
+                wad("A.wad.client"),
+                wad("B.wad.client"),
+                wad("C.wad.client")
+            ]
+        );
+        assert_eq!(mounted.load(Ordering::Relaxed), 2);
+        assert_eq!(index, GameIndex::build(&game_dir).unwrap());
+        assert!(index.refresh(&game_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_wad_with_hint() {
+        let path = |p: &str| Utf8PathBuf::from(format!("/game/DATA/FINAL/{p}"));
+        let wad_index = HashMap::from([
+            (
+                "global.en_us.wad.client".to_string(),
+                vec![
+                    path("Localized/Global.en_US.wad.client"),
+                    path("Extra/Global.en_US.wad.client"),
+                ],
+            ),
+            (
+                "aatrox.wad.client".to_string(),
+                vec![path("Champions/Aatrox.wad.client")],
+            ),
+        ]);
+        let index = GameIndex {
+            wad_index,
+            ..GameIndex::new()
+        };
+
+        assert!(matches!(
+            index.find_wad("Global.en_US.wad.client"),
+            Err(Error::AmbiguousWad { count: 2, .. })
+        ));
+        for hint in [
+            "DATA/FINAL/Localized",
+            "localized/",
+            "DATA\\FINAL\\Localized",
+            "DATA/FINAL/Localized/Global.en_US.wad.client",
+        ] {
+            assert_eq!(
+                index
+                    .find_wad_with_hint("Global.en_US.wad.client", Some(Utf8Path::new(hint)))
+                    .unwrap(),
+                &path("Localized/Global.en_US.wad.client"),
+                "{hint}"
+            );
+        }
+        assert_eq!(
+            index
+                .find_wad_with_hint("global.en_us.wad.client", Some(Utf8Path::new("Extra")))
+                .unwrap(),
+            &path("Extra/Global.en_US.wad.client")
+        );
+        assert!(index
+            .find_wad_with_hint("Global.en_US.wad.client", Some(Utf8Path::new("Maps")))
+            .is_err());
+        // A unique filename ignores the hint.
+        assert_eq!(
+            index
+                .find_wad_with_hint("Aatrox.wad.client", Some(Utf8Path::new("Maps")))
+                .unwrap(),
+            &path("Champions/Aatrox.wad.client")
+        );
+    }
+
+    #[test]
+    fn test_guess_target_wad() {
+        use crate::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(FakeWad::new("Champions/Aatrox.wad.client").with_chunk(1, b"a".to_vec()))
+                .with_wad(
+                    FakeWad::new("Maps/Shipping/Map11.wad.client").with_chunk(2, b"m".to_vec()),
+                )
+                .with_wad(
+                    FakeWad::new("Maps/Shipping/Common.wad.client").with_chunk(3, b"c".to_vec()),
+                )
+                .with_wad(FakeWad::new("Extra/Common.wad.client").with_chunk(4, b"c".to_vec())),
+        )
+        .unwrap();
+        let index = GameIndex::build(&game_dir).unwrap();
+        let wad = |p: &str| Some(Utf8PathBuf::from(format!("DATA/FINAL/{p}")));
+
+        assert_eq!(
+            index.guess_target_wad("data/characters/aatrox/skins/skin0.bin"),
+            wad("Champions/Aatrox.wad.client")
+        );
+        assert_eq!(
+            index.guess_target_wad("ASSETS\\Characters\\Aatrox\\new.tex"),
+            wad("Champions/Aatrox.wad.client")
+        );
+        assert_eq!(
+            index.guess_target_wad("data/maps/shipping/map11/map11.bin"),
+            wad("Maps/Shipping/Map11.wad.client")
+        );
+        // The deepest folder with a WAD wins.
+        assert_eq!(
+            index.guess_target_wad("data/characters/aatrox/map11/new.bin"),
+            wad("Maps/Shipping/Map11.wad.client")
+        );
+        // An ambiguous folder name is skipped, and a file name is never a match.
+        assert_eq!(
+            index.guess_target_wad("data/characters/aatrox/common/new.bin"),
+            wad("Champions/Aatrox.wad.client")
+        );
+        assert_eq!(index.guess_target_wad("data/common/aatrox"), None);
+        assert_eq!(index.guess_target_wad("aatrox.bin"), None);
+    }
+
+    #[test]
+    fn test_locale_wads() {
+        let path = |p: &str| Utf8PathBuf::from(format!("/game/DATA/FINAL/{p}"));
+        let wad_index = HashMap::from([
+            (
+                "global.en_us.wad.client".to_string(),
+                vec![path("Localized/Global.en_US.wad.client")],
+            ),
+            (
+                "global.ko_kr.wad.client".to_string(),
+                vec![
+                    path("Localized/Global.ko_KR.wad.client"),
+                    path("Extra/Global.ko_KR.wad.client"),
+                ],
+            ),
+            (
+                "global.wad.client".to_string(),
+                vec![path("Global.wad.client")],
+            ),
+            (
+                "aatrox.en_us.wad.client".to_string(),
+                vec![path("Champions/Aatrox.en_US.wad.client")],
+            ),
+        ]);
+        let index = GameIndex {
+            wad_index,
+            hash_index: HashMap::new(),
+            game_fingerprint: 0,
+            subchunktoc_blocked: HashSet::new(),
+            hashtable: HashMap::new(),
+            wad_stamps: HashMap::new(),
+            wad_names: Default::default(),
+        };
+
+        assert_eq!(
+            index.locale_wads(),
+            BTreeMap::from([
+                (
+                    "en_us".to_string(),
+                    path("Localized/Global.en_US.wad.client")
+                ),
+                (
+                    "ko_kr".to_string(),
+                    path("Localized/Global.ko_KR.wad.client")
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_with_hashtable_resolves_known_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let hashtable = root.join("hashes.game.txt");
+        std::fs::write(
+            &hashtable,
+            "0123456789abcdef assets/characters/aatrox/skins/base/aatrox.dds\n\
+             not a hash line\n\
+             00000000deadbeef data/characters/aatrox/aatrox.bin\n",
+        )
+        .unwrap();
+
+        let index = GameIndex::new().with_hashtable(&hashtable).unwrap();
+        assert_eq!(
+            index.resolve_hash(0x0123456789abcdef),
+            Some("assets/characters/aatrox/skins/base/aatrox.dds")
+        );
+        assert_eq!(
+            index.resolve_hash(0xdeadbeef),
+            Some("data/characters/aatrox/aatrox.bin")
+        );
+        assert_eq!(index.resolve_hash(1), None);
+        assert_eq!(index.hashtable.len(), 2);
+
+        let missing = GameIndex::new()
+            .with_hashtable(&root.join("missing.txt"))
+            .unwrap();
+        assert!(missing.hashtable.is_empty());
+    }
+
+    #[test]
+    fn test_export_named_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let hashtable = root.join("hashes.game.txt");
+        std::fs::write(&hashtable, "00000000deadbeef data/shared.bin\n").unwrap();
+
+        let mut index = GameIndex::new();
+        index.hash_index.insert(
+            0xdeadbeef,
+            vec![
+                Utf8PathBuf::from("DATA/FINAL/Maps/Map11.wad.client"),
+                Utf8PathBuf::from("DATA/FINAL/Champions/Aatrox.wad.client"),
+            ],
+        );
+        index.hash_index.insert(
+            0xabc,
+            vec![Utf8PathBuf::from("DATA/FINAL/Champions/Aatrox.wad.client")],
+        );
+        let index = index.with_hashtable(&hashtable).unwrap();
+
+        let named = index.export_named_index();
+        assert_eq!(
+            named.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    "0000000000000abc".to_string(),
+                    vec![Utf8PathBuf::from("DATA/FINAL/Champions/Aatrox.wad.client")]
+                ),
+                (
+                    "data/shared.bin".to_string(),
+                    vec![
+                        Utf8PathBuf::from("DATA/FINAL/Champions/Aatrox.wad.client"),
+                        Utf8PathBuf::from("DATA/FINAL/Maps/Map11.wad.client"),
+                    ]
+                ),
+            ]
+        );
     }
 }
@@ -0,0 +1,196 @@
+//! Author-facing checks of a mod's own content.
+//!
+//! Conflict detection compares mods with each other. These checks look inside
+//! a single mod instead, for content a build accepts without complaint but that
+//! is often a mistake, so the author can confirm it is intended.
+//!
+//! [`find_layer_shadowing`] reports chunks that several layers of the same mod
+//! override. A higher-priority layer replacing a base-layer chunk is how
+//! variants such as chromas work, but a copy left behind in the base layer
+//! after moving an asset to its own layer looks exactly the same to a build.
+//! [`OverlayBuilder::lint_single_mod`](crate::OverlayBuilder::lint_single_mod)
+//! includes these findings in its report.
+
+use crate::builder::{layers_in_order, EnabledMod};
+use crate::error::Result;
+use crate::utils::resolve_chunk_hash;
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// A chunk overridden by more than one layer of a mod.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowedOverride {
+    /// WAD name (lowercased) the layers provide the chunk for.
+    pub wad: String,
+    /// Path of the winning override, relative to its WAD directory.
+    pub rel_path: Utf8PathBuf,
+    /// The chunk's path hash.
+    pub path_hash: u64,
+    /// Layers overriding the chunk, in the order they are applied. The last
+    /// one wins.
+    pub layers: Vec<String>,
+    /// Every layer provides the same bytes, so the shadowed copies change
+    /// nothing.
+    pub identical: bool,
+}
+
+/// The shadowed overrides of one mod, from [`layer_shadowing_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLayerShadowing {
+    /// Mod identifier (matches [`EnabledMod::id`]).
+    pub mod_id: String,
+    /// Chunks overridden by more than one of the mod's layers.
+    pub shadowed: Vec<ShadowedOverride>,
+}
+
+/// The layers of a mod overriding one chunk, gathered by [`find_layer_shadowing`].
+#[derive(Default)]
+struct LayeredChunk {
+    /// Path of the override from the last layer seen.
+    rel_path: Utf8PathBuf,
+    layers: Vec<String>,
+    content_hashes: Vec<u64>,
+}
+
+/// Find the chunks that more than one layer of `enabled_mod` overrides.
+///
+/// Layers are walked in the order a build applies them, and layers excluded
+/// by [`EnabledMod::enabled_layers`] are skipped. Every override is read to
+/// resolve its path hash. Results are sorted by WAD and path.
+pub fn find_layer_shadowing(enabled_mod: &mut EnabledMod) -> Result<Vec<ShadowedOverride>> {
+    let project = enabled_mod.content.mod_project()?;
+    let format_version = enabled_mod.content.format_version();
+
+    let mut seen: BTreeMap<(String, u64), LayeredChunk> = BTreeMap::new();
+    for layer in layers_in_order(project.layers, format_version) {
+        if !enabled_mod.is_layer_active(&layer.name) {
+            continue;
+        }
+        for wad_name in enabled_mod.content.list_layer_wads(&layer.name)? {
+            let overrides = enabled_mod
+                .content
+                .read_wad_overrides(&layer.name, &wad_name)?;
+            for (rel_path, bytes) in overrides {
+                let path_hash = resolve_chunk_hash(&rel_path, &bytes)?;
+                let entry = seen
+                    .entry((wad_name.to_ascii_lowercase(), path_hash))
+                    .or_default();
+                entry.rel_path = rel_path;
+                entry.layers.push(layer.name.clone());
+                entry.content_hashes.push(xxh3_64(&bytes));
+            }
+        }
+    }
+
+    let mut shadowed: Vec<ShadowedOverride> = seen
+        .into_iter()
+        .filter(|(_, chunk)| chunk.layers.len() > 1)
+        .map(|((wad, path_hash), chunk)| ShadowedOverride {
+            wad,
+            rel_path: chunk.rel_path,
+            path_hash,
+            layers: chunk.layers,
+            identical: chunk
+                .content_hashes
+                .windows(2)
+                .all(|pair| pair[0] == pair[1]),
+        })
+        .collect();
+    shadowed.sort_by(|a, b| (&a.wad, &a.rel_path).cmp(&(&b.wad, &b.rel_path)));
+    Ok(shadowed)
+}
+
+/// Run [`find_layer_shadowing`] on every mod, keeping the mods it flags.
+pub fn layer_shadowing_report(mods: &mut [EnabledMod]) -> Result<Vec<ModLayerShadowing>> {
+    let mut report = Vec::new();
+    for enabled_mod in mods {
+        let shadowed = find_layer_shadowing(enabled_mod)?;
+        if !shadowed.is_empty() {
+            report.push(ModLayerShadowing {
+                mod_id: enabled_mod.id.clone(),
+                shadowed,
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::FsModContent;
+    use crate::testing::{mock_project, write_fs_mod};
+    use camino::Utf8Path;
+    use ltk_mod_project::ModProjectLayer;
+    use std::collections::{HashMap, HashSet};
+
+    fn write_layered_mod(mod_dir: &Utf8Path, files: &[(&str, &[u8])]) {
+        let mut project = mock_project("layered");
+        for name in ["chroma", "extra"] {
+            project.layers.push(ModProjectLayer {
+                name: name.to_string(),
+                display_name: None,
+                priority: if name == "chroma" { 10 } else { 5 },
+                description: None,
+                string_overrides: HashMap::new(),
+            });
+        }
+        write_fs_mod(mod_dir, &project, files).unwrap();
+    }
+
+    #[test]
+    fn reports_chunks_overridden_by_several_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        write_layered_mod(
+            &root.join("layered"),
+            &[
+                ("base/A.wad.client/data/skin.bin", b"base"),
+                ("chroma/A.wad.client/data/skin.bin", b"chroma"),
+                ("base/A.wad.client/data/leftover.bin", b"same"),
+                ("extra/a.wad.client/data/leftover.bin", b"same"),
+                ("chroma/A.wad.client/data/leftover.bin", b"same"),
+                ("base/A.wad.client/data/only_base.bin", b"base"),
+                ("base/B.wad.client/data/skin.bin", b"other wad"),
+            ],
+        );
+        write_layered_mod(
+            &root.join("clean"),
+            &[("base/A.wad.client/data/skin.bin", b"base")],
+        );
+        let enabled = |id: &str, enabled_layers: Option<HashSet<String>>| EnabledMod {
+            id: id.to_string(),
+            content: Box::new(FsModContent::new(root.join(id))),
+            enabled_layers,
+        };
+
+        let mut mods = vec![enabled("layered", None), enabled("clean", None)];
+        let report = layer_shadowing_report(&mut mods).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].mod_id, "layered");
+        let shadowed = &report[0].shadowed;
+        assert_eq!(shadowed.len(), 2);
+
+        assert_eq!(shadowed[0].wad, "a.wad.client");
+        assert_eq!(shadowed[0].rel_path, "data/leftover.bin");
+        assert_eq!(shadowed[0].layers, vec!["base", "extra", "chroma"]);
+        assert!(shadowed[0].identical);
+
+        assert_eq!(shadowed[1].rel_path, "data/skin.bin");
+        assert_eq!(
+            shadowed[1].path_hash,
+            ltk_modpkg::utils::hash_chunk_name("data/skin.bin")
+        );
+        assert_eq!(shadowed[1].layers, vec!["base", "chroma"]);
+        assert!(!shadowed[1].identical);
+
+        let mut without_chroma = enabled("layered", Some(HashSet::from(["extra".to_string()])));
+        let shadowed = find_layer_shadowing(&mut without_chroma).unwrap();
+        assert_eq!(shadowed.len(), 1);
+        assert_eq!(shadowed[0].layers, vec!["base", "extra"]);
+    }
+}
@@ -0,0 +1,211 @@
+//! Keeping a [`GameIndex`] current while the game is patched.
+//!
+//! A long-running process can hand its index to [`GameIndex::watch`] (or
+//! [`GameIndex::into_watcher`]) instead of rebuilding it on the next overlay
+//! build. A background thread polls `DATA/FINAL` for WADs whose size or
+//! modification time changed, waits for them to settle, and re-indexes just
+//! those WADs with [`GameIndex::update_wads`].
+//!
+//! Polling is used rather than OS change notifications: a patch touches a few
+//! hundred files at most, and a directory walk with `stat` calls is cheap next
+//! to re-mounting every WAD.
+
+use crate::error::Result;
+use crate::game_index::{collect_wad_paths_sorted, GameIndex};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// How often [`GameIndex::watch`] checks `DATA/FINAL` for changes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// WAD path -> (size, modification time), as seen by one poll.
+type WadStamps = BTreeMap<Utf8PathBuf, (u64, Option<SystemTime>)>;
+
+fn scan_wads(data_final_dir: &Utf8Path) -> Result<WadStamps> {
+    let mut stamps = WadStamps::new();
+    for path in collect_wad_paths_sorted(data_final_dir)? {
+        // A WAD deleted between the walk and the stat is simply left out.
+        if let Ok(metadata) = std::fs::metadata(path.as_std_path()) {
+            stamps.insert(path, (metadata.len(), metadata.modified().ok()));
+        }
+    }
+    Ok(stamps)
+}
+
+/// WADs added, removed or modified between two polls.
+fn changed_wads(before: &WadStamps, after: &WadStamps) -> Vec<Utf8PathBuf> {
+    let mut changed: Vec<Utf8PathBuf> = before
+        .iter()
+        .filter(|(path, stamp)| after.get(*path) != Some(stamp))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        after
+            .keys()
+            .filter(|path| !before.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+/// Handle to a background thread keeping a [`GameIndex`] up to date.
+///
+/// Dropping the watcher stops the thread.
+pub struct GameIndexWatcher {
+    index: Arc<RwLock<GameIndex>>,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl GameIndexWatcher {
+    /// Borrow the current index.
+    ///
+    /// Updates are prepared on a copy and swapped in, so holding the guard
+    /// only delays the swap, not the re-indexing itself.
+    pub fn index(&self) -> RwLockReadGuard<'_, GameIndex> {
+        self.index.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Clone the current index.
+    pub fn snapshot(&self) -> GameIndex {
+        self.index().clone()
+    }
+}
+
+impl Drop for GameIndexWatcher {
+    fn drop(&mut self) {
+        // Disconnecting the channel wakes the thread immediately.
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl GameIndex {
+    /// Build the index for `game_dir` and keep it current in the background.
+    ///
+    /// `DATA/FINAL` is polled every [`DEFAULT_POLL_INTERVAL`]. Once a set of
+    /// changed WADs looks the same on two consecutive polls (so a patch in
+    /// progress is not indexed half-written), they are re-indexed and
+    /// `callback` is called with the updated index, e.g. to
+    /// [`save`](Self::save) it to the cache the builder loads.
+    pub fn watch<F>(game_dir: &Utf8Path, callback: F) -> Result<GameIndexWatcher>
+    where
+        F: Fn(&GameIndex) + Send + 'static,
+    {
+        Self::build(game_dir)?.into_watcher(game_dir, DEFAULT_POLL_INTERVAL, callback)
+    }
+
+    /// Like [`watch`](Self::watch), but starting from this index (e.g. one
+    /// from [`load_or_build`](Self::load_or_build)) and polling every
+    /// `poll_interval`.
+    ///
+    /// The index must describe `game_dir` as it is now: only changes made
+    /// after this call are picked up.
+    pub fn into_watcher<F>(
+        self,
+        game_dir: &Utf8Path,
+        poll_interval: Duration,
+        callback: F,
+    ) -> Result<GameIndexWatcher>
+    where
+        F: Fn(&GameIndex) + Send + 'static,
+    {
+        let game_dir = game_dir.to_path_buf();
+        let data_final_dir = game_dir.join("DATA").join("FINAL");
+        let mut indexed = scan_wads(&data_final_dir)?;
+
+        let index = Arc::new(RwLock::new(self));
+        let (stop, stopped) = mpsc::channel::<()>();
+        let shared = Arc::clone(&index);
+
+        let thread = std::thread::spawn(move || {
+            let mut pending: Option<WadStamps> = None;
+            // Wakes every interval until the watcher is dropped.
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(poll_interval) {
+                let current = match scan_wads(&data_final_dir) {
+                    Ok(current) => current,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Game index watcher failed to scan {}: {}",
+                            data_final_dir,
+                            e
+                        );
+                        continue;
+                    }
+                };
+                if current == indexed {
+                    pending = None;
+                    continue;
+                }
+                if pending.as_ref() != Some(&current) {
+                    tracing::debug!("Game index watcher: WADs changed, waiting for them to settle");
+                    pending = Some(current);
+                    continue;
+                }
+
+                let changed = changed_wads(&indexed, &current);
+                let mut updated = shared.read().unwrap_or_else(|e| e.into_inner()).clone();
+                if let Err(e) = updated.update_wads(&game_dir, &changed) {
+                    tracing::warn!("Game index watcher failed to update the index: {}", e);
+                    continue;
+                }
+
+                *shared.write().unwrap_or_else(|e| e.into_inner()) = updated;
+                callback(&shared.read().unwrap_or_else(|e| e.into_inner()));
+                indexed = current;
+                pending = None;
+            }
+        });
+
+        Ok(GameIndexWatcher {
+            index,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_fake_game_dir, write_fake_wad, FakeGameSpec, FakeWad};
+
+    #[test]
+    fn watcher_picks_up_added_wads() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(
+            root,
+            &FakeGameSpec::new()
+                .with_wad(FakeWad::new("Champions/A.wad.client").with_chunk(1, b"a".to_vec())),
+        )
+        .unwrap();
+
+        let (updated, updates) = mpsc::channel();
+        let watcher = GameIndex::build(&game_dir)
+            .unwrap()
+            .into_watcher(&game_dir, Duration::from_millis(10), move |index| {
+                let _ = updated.send(index.game_fingerprint());
+            })
+            .unwrap();
+        assert!(watcher.index().find_wads_with_hash(2).is_none());
+
+        // Write elsewhere and rename, so no poll sees a half-written WAD.
+        let staged = root.join("B.wad.client");
+        let mut file = std::fs::File::create(&staged).unwrap();
+        write_fake_wad(&mut file, &BTreeMap::from([(2, b"b".to_vec())])).unwrap();
+        drop(file);
+        std::fs::rename(&staged, game_dir.join("DATA/FINAL/Champions/B.wad.client")).unwrap();
+
+        let fingerprint = updates.recv_timeout(Duration::from_secs(10)).unwrap();
+        let expected = GameIndex::build(&game_dir).unwrap();
+        assert_eq!(fingerprint, expected.game_fingerprint());
+        assert_eq!(watcher.snapshot(), expected);
+    }
+}
@@ -18,7 +18,9 @@
 //!    Each override file is resolved to a `u64` path hash (either parsed from a hex
 //!    filename or computed from the normalized path). All overrides are collected
 //!    into a single `HashMap<u64, Vec<u8>>`. When multiple mods override the same
-//!    hash, the first mod in the list (highest priority) wins.
+//!    hash, the first mod in the list (highest priority) wins, unless the builder
+//!    is configured with [`PriorityOrder::BackWins`] or a different
+//!    [`ConflictStrategy`].
 //!
 //! 3. **Distributing to WADs** — Using the hash index, each override is distributed
 //!    to *every* game WAD that contains that path hash ("cross-WAD matching"). This
@@ -30,6 +32,10 @@
 //!    overrides, with optimizations for audio files (kept uncompressed) and chunk
 //!    deduplication.
 //!
+//! Layer string overrides are applied between stages 2 and 3: the winning values
+//! are written into each locale's `lol.stringtable` (see [`stringtable`]), which
+//! is then distributed like any other override.
+//!
 //! # Content Provider Abstraction
 //!
 //! Mod content is accessed through the [`ModContentProvider`] trait, which decouples
@@ -38,6 +44,7 @@
 //! - Filesystem directories ([`FsModContent`])
 //! - `.modpkg` archives ([`ModpkgContent`])
 //! - `.fantome` ZIP archives ([`FantomeContent`])
+//! - `.tar` / `.tar.gz` archives (`TarModContent`, behind the `tar` feature)
 //!
 //! # Incremental Rebuild
 //!
@@ -52,10 +59,24 @@
 //!   re-patched. Stale WADs (no longer needed) are removed.
 //! - **Full rebuild**: game fingerprint or state version changed — all overlay WADs
 //!   are wiped and rebuilt from scratch.
+//! - **Resumed**: the previous build was interrupted. It left an
+//!   `overlay.progress` checkpoint of the WADs it finished; those still up to date
+//!   are kept and the rest are rebuilt. Until then
+//!   [`OverlayBuilder::has_interrupted_build`] returns `true`.
+//!
+//! Every build writes its WADs into a staging root, `overlay_root/.staging`, and
+//! swaps the staged `DATA` tree in for the live one only once it is complete, so
+//! a crashed or failed build never leaves a half-built overlay to be served.
 //!
 //! The game index (`GameIndex`) is also cached to disk to avoid re-mounting every
 //! WAD file on subsequent builds when the game hasn't been patched.
 //!
+//! [`validate()`] checks a built overlay against its `overlay.json`: every WAD
+//! must mount, hold at least one chunk, and match its chunk checksums.
+//!
+//! With [`OverlayBuilder::with_backup`], each build moves the WADs it replaces
+//! into `overlay_root/.backup/`, and [`rollback()`] restores them.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -86,27 +107,54 @@
 //! # Ok(())
 //! # }
 
+pub mod archive_dir;
+pub mod asset_stats;
+pub mod backup;
 pub mod builder;
 pub mod content;
 pub mod error;
 pub mod fantome_content;
 pub mod game_index;
+pub mod healthcheck;
+pub mod index_watcher;
 pub mod linked_bins;
 pub mod meta_cache;
 pub mod modpkg_content;
 pub mod state;
+pub mod stringtable;
+#[cfg(feature = "tar")]
+pub mod tar_content;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod utils;
+pub mod validate;
 pub mod wad_builder;
 
 // Re-export main public API.
+pub use archive_dir::build_from_archive_dir;
+pub use asset_stats::{
+    modpkg_extension_stats, overlay_extension_stats, overlay_extension_stats_with_state_dir,
+    ExtensionStats,
+};
+pub use backup::rollback;
 pub use builder::{
-    AffectedWad, EnabledMod, ModWadReport, OverlayBuildResult, OverlayBuilder, OverlayProgress,
-    OverlayStage, BASE_LAYER_NAME,
+    AffectedWad, BrokenOverride, BrokenOverrideKind, BuildEvent, BuildResultDiff,
+    BuildResultSnapshot, BuildWarning, Conflict, ConflictKind, ConflictStrategy, EnabledMod,
+    FilterReason, LayerOrderingIssue, LayerRef, LintedOverride, ModContribution, ModLintReport,
+    ModSignature, ModWadReport, OverlayBuildResult, OverlayBuilder, OverlayPlan, OverlayProgress,
+    OverlayStage, PlannedWad, PriorityOrder, RebuildReason, RetryPolicy, SemanticConflict,
+    SemanticConflictKind, SignaturePolicy, SignatureStatus, SignatureTrust, SuspectHexOverride,
+    WadBuildStats, BASE_LAYER_NAME, DEFAULT_OUTPUT_EXTENSION,
 };
-pub use content::{FsModContent, ModContentProvider};
+pub use content::{ContentFormatVersion, FsModContent, ModContentProvider};
 pub use error::{Error, Result};
 pub use fantome_content::FantomeContent;
 pub use game_index::GameIndex;
+pub use index_watcher::GameIndexWatcher;
 pub use linked_bins::LinkedBinOffender;
 pub use modpkg_content::ModpkgContent;
 pub use state::OverlayState;
+#[cfg(feature = "tar")]
+pub use tar_content::TarModContent;
+pub use validate::{validate, OverlayValidation, ValidationIssue};
+pub use wad_builder::{WadPatchOptions, DEFAULT_ZSTD_LEVEL, SMALL_OVERRIDE_MAX_SIZE};
@@ -262,6 +262,7 @@ mod tests {
             },
             fallback_wad: None,
             linked_bins: linked.iter().map(|s| s.to_string()).collect(),
+            compression: None,
         }
     }
 
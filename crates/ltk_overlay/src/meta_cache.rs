@@ -4,15 +4,16 @@
 //! pass 1 so that subsequent builds can skip re-reading unchanged mods entirely.
 //! The cache is invalidated per-mod based on [`content_fingerprint`](crate::content::ModContentProvider::content_fingerprint).
 
-use crate::builder::{OverrideMeta, OverrideSource};
+use crate::builder::{BrokenOverride, OverrideMeta, OverrideSource};
 use crate::error::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
-/// Current cache format version. Bump when the serialized format changes.
-const CACHE_VERSION: u32 = 3;
+/// Current cache format version. Bump when the serialized format changes, or
+/// when the cached values would be computed differently (such as the target WAD).
+const CACHE_VERSION: u32 = 5;
 
 /// Serializable cache entry for a single override.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -45,6 +46,9 @@ pub struct CachedModMeta {
     pub content_fingerprint: u64,
     /// All overrides from this mod.
     pub overrides: Vec<CachedOverride>,
+    /// Override files skipped because they hold no asset data.
+    #[serde(default)]
+    pub broken_overrides: Vec<BrokenOverride>,
 }
 
 impl CachedModMeta {
@@ -80,6 +84,7 @@ impl CachedModMeta {
                     source,
                     fallback_wad: entry.target_wad.as_ref().map(Utf8PathBuf::from),
                     linked_bins: entry.linked_bins.clone(),
+                    compression: None,
                 },
             );
         }
@@ -94,7 +99,7 @@ impl CachedModMeta {
     pub fn from_override_meta(fingerprint: u64, mod_meta: &HashMap<u64, OverrideMeta>) -> Self {
         let overrides = mod_meta
             .iter()
-            .map(|(&path_hash, meta)| {
+            .filter_map(|(&path_hash, meta)| {
                 let (source_layer, source_wad_name, source_rel_path) = match &meta.source {
                     OverrideSource::LayerWad {
                         layer,
@@ -109,9 +114,11 @@ impl CachedModMeta {
                     OverrideSource::Raw { rel_path, .. } => {
                         (None, None, rel_path.as_str().to_string())
                     }
+                    // Synthesized by the builder; its inputs are not mod content.
+                    OverrideSource::Generated { .. } => return None,
                 };
 
-                CachedOverride {
+                Some(CachedOverride {
                     path_hash,
                     content_hash: meta.content_hash,
                     uncompressed_size: meta.uncompressed_size,
@@ -120,13 +127,14 @@ impl CachedModMeta {
                     source_wad_name,
                     source_rel_path,
                     linked_bins: meta.linked_bins.clone(),
-                }
+                })
             })
             .collect();
 
         Self {
             content_fingerprint: fingerprint,
             overrides,
+            broken_overrides: Vec::new(),
         }
     }
 }
@@ -260,6 +268,7 @@ mod tests {
                     source_rel_path: "data/file.bin".to_string(),
                     linked_bins: Vec::new(),
                 }],
+                broken_overrides: Vec::new(),
             },
         );
 
@@ -281,6 +290,7 @@ mod tests {
             CachedModMeta {
                 content_fingerprint: 0xDEAD,
                 overrides: Vec::new(),
+                broken_overrides: Vec::new(),
             },
         );
 
@@ -300,6 +310,7 @@ mod tests {
             CachedModMeta {
                 content_fingerprint: 1,
                 overrides: Vec::new(),
+                broken_overrides: Vec::new(),
             },
         );
         cache.set_mod_meta(
@@ -307,6 +318,7 @@ mod tests {
             CachedModMeta {
                 content_fingerprint: 2,
                 overrides: Vec::new(),
+                broken_overrides: Vec::new(),
             },
         );
 
@@ -332,6 +344,7 @@ mod tests {
             CachedModMeta {
                 content_fingerprint: 0xDEAD,
                 overrides: Vec::new(),
+                broken_overrides: Vec::new(),
             },
         );
         cache.save(path).unwrap();
@@ -4,12 +4,16 @@
 //! access to layer structure, WAD targets, and override file data without
 //! extracting to disk.
 
-use crate::content::{archive_fingerprint, ModContentProvider};
+use crate::builder::{SignatureStatus, SignatureTrust};
+use crate::content::{archive_fingerprint, ContentFormatVersion, ModContentProvider};
 use crate::error::{Error, Result};
+use crate::utils::resolve_chunk_hash;
 use camino::{Utf8Path, Utf8PathBuf};
-use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer};
+use ltk_mod_project::{
+    CompressionHint, ModDependency, ModMap, ModProject, ModProjectAuthor, ModProjectLayer, ModTag,
+};
 use ltk_modpkg::Modpkg;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Read, Seek};
 
 /// Content provider that reads directly from a mounted `.modpkg` archive.
@@ -63,6 +67,8 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for ModpkgContent<R> {
             layers.insert(0, ModProjectLayer::base());
         }
 
+        let compression = compression_hints(&metadata.name, metadata.compression);
+
         Ok(ModProject {
             name: metadata.name,
             display_name: metadata.display_name,
@@ -74,10 +80,19 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for ModpkgContent<R> {
                 .map(|a| ModProjectAuthor::Name(a.name))
                 .collect(),
             license: None,
-            tags: Vec::new(),
-            champions: Vec::new(),
-            maps: Vec::new(),
+            tags: metadata.tags.into_iter().map(ModTag::from).collect(),
+            champions: metadata.champions,
+            maps: metadata.maps.into_iter().map(ModMap::from).collect(),
+            dependencies: metadata
+                .dependencies
+                .into_iter()
+                .map(|d| ModDependency {
+                    name: d.name,
+                    version_req: d.version_req,
+                })
+                .collect(),
             transformers: Vec::new(),
+            compression,
             layers,
             thumbnail: None,
         })
@@ -166,12 +181,24 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for ModpkgContent<R> {
     fn read_wad_override_file(
         &mut self,
         layer: &str,
-        _wad_name: &str,
+        wad_name: &str,
         rel_path: &Utf8Path,
     ) -> Result<Vec<u8>> {
         let layer_hash = ltk_modpkg::hash_layer_name(layer);
-        let normalized = ltk_modpkg::utils::normalize_chunk_path(rel_path.as_str());
-        let path_hash = ltk_modpkg::utils::hash_chunk_name(&normalized);
+        // Resolve the same way pass 1 did so hex-named chunks map to their parsed hash.
+        let path_hash = resolve_chunk_hash(rel_path, &[])?;
+
+        // The WAD recorded on the chunk is authoritative; a mismatch means the
+        // caller is routing this override somewhere the package never targeted.
+        match self.modpkg.chunk_wad_name(path_hash, layer_hash) {
+            Some(target) if !target.eq_ignore_ascii_case(wad_name) => {
+                return Err(Error::Other(format!(
+                    "modpkg chunk {:016x} targets WAD '{}', not '{}'",
+                    path_hash, target, wad_name
+                )));
+            }
+            _ => {}
+        }
 
         let bytes = self
             .modpkg
@@ -198,6 +225,38 @@ impl<R: Read + Seek + Send + Sync> ModContentProvider for ModpkgContent<R> {
             None => Ok(None),
         }
     }
+
+    fn format_version(&self) -> ContentFormatVersion {
+        ContentFormatVersion::ModpkgV1
+    }
+
+    fn verify_signature(&mut self, trust: &SignatureTrust) -> Result<SignatureStatus> {
+        trust.verify_modpkg(&mut self.modpkg)
+    }
+}
+
+/// Parse the compression hints stored in modpkg metadata, skipping unknown values.
+fn compression_hints(
+    mod_name: &str,
+    compression: BTreeMap<String, String>,
+) -> HashMap<String, CompressionHint> {
+    compression
+        .into_iter()
+        .filter_map(|(pattern, hint)| {
+            match serde_json::from_value(serde_json::Value::String(hint.clone())) {
+                Ok(hint) => Some((pattern, hint)),
+                Err(_) => {
+                    tracing::warn!(
+                        "Mod='{}' ignoring unknown compression '{}' for pattern '{}'",
+                        mod_name,
+                        hint,
+                        pattern
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -208,6 +267,60 @@ mod tests {
     use ltk_modpkg::{Modpkg, ModpkgCompression};
     use std::io::{Cursor, Write};
 
+    #[test]
+    fn mod_project_reads_metadata_fields() {
+        let mut cursor = Cursor::new(Vec::new());
+        ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_metadata(ltk_modpkg::ModpkgMetadata {
+                name: "tagged".to_string(),
+                display_name: "Tagged".to_string(),
+                tags: vec!["champion-skin".to_string(), "spooky".to_string()],
+                champions: vec!["Aatrox".to_string()],
+                maps: vec!["aram".to_string()],
+                compression: BTreeMap::from([
+                    ("**/*.bnk".to_string(), "none".to_string()),
+                    ("**/*.tex".to_string(), "brotli".to_string()),
+                ]),
+                dependencies: vec![ltk_modpkg::ModpkgDependency {
+                    name: "old-summoners-rift".to_string(),
+                    version_req: "^1.2".to_string(),
+                }],
+                ..Default::default()
+            })
+            .unwrap()
+            .build_to_writer(&mut cursor, |_chunk, _cursor| Ok(()))
+            .unwrap();
+        cursor.set_position(0);
+        let mut content = ModpkgContent::new(Modpkg::mount_from_reader(cursor).unwrap());
+
+        let project = content.mod_project().unwrap();
+        assert_eq!(
+            project.tags,
+            vec![
+                ModTag::Known(ltk_mod_project::WellKnownModTag::ChampionSkin),
+                ModTag::Custom("spooky".to_string()),
+            ]
+        );
+        assert_eq!(project.champions, vec!["Aatrox"]);
+        assert_eq!(
+            project.maps,
+            vec![ModMap::Known(ltk_mod_project::WellKnownMap::Aram)]
+        );
+        // Unknown compression values are skipped
+        assert_eq!(
+            project.compression,
+            HashMap::from([("**/*.bnk".to_string(), CompressionHint::None)])
+        );
+        assert_eq!(
+            project.dependencies,
+            vec![ModDependency {
+                name: "old-summoners-rift".to_string(),
+                version_req: "^1.2".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn list_layer_wads_with_wad_index() {
         let scratch = Vec::new();
@@ -346,4 +459,72 @@ mod tests {
         assert_eq!(loading_overrides[0].0.as_str(), "data/loading.bin");
         assert_eq!(loading_overrides[0].1, b"loading_data");
     }
+
+    #[test]
+    fn read_wad_override_file_uses_chunk_target_wad() {
+        let mut cursor = Cursor::new(Vec::new());
+
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("data/new_asset.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None)
+                    .with_layer("base")
+                    .with_wad("Map11.wad.client"),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_hashed_chunk_name("0123456789abcdef.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::None)
+                    .with_layer("base")
+                    .with_wad("Graves.wad.client"),
+            );
+
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                cursor.write_all(chunk.path.as_bytes())?;
+                Ok(())
+            })
+            .unwrap();
+
+        cursor.set_position(0);
+        let modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+        let layer_hash = ltk_modpkg::hash_layer_name("base");
+        assert_eq!(
+            modpkg.chunk_wad_name(0x0123456789abcdef, layer_hash),
+            Some("graves.wad.client")
+        );
+        let mut content = ModpkgContent::new(modpkg);
+
+        // Hex-named chunks resolve to their parsed hash in pass 2 as well.
+        let hex = content
+            .read_wad_override_file(
+                "base",
+                "graves.wad.client",
+                Utf8Path::new("0123456789abcdef.bin"),
+            )
+            .unwrap();
+        assert!(!hex.is_empty());
+
+        let named = content
+            .read_wad_override_file(
+                "base",
+                "map11.wad.client",
+                Utf8Path::new("data/new_asset.bin"),
+            )
+            .unwrap();
+        assert_eq!(named, b"data/new_asset.bin");
+
+        // Routing a chunk to a WAD it wasn't packed for is rejected.
+        assert!(content
+            .read_wad_override_file(
+                "base",
+                "graves.wad.client",
+                Utf8Path::new("data/new_asset.bin"),
+            )
+            .is_err());
+    }
 }
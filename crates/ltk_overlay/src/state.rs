@@ -8,9 +8,10 @@
 //!   fingerprints): the build is skipped entirely.
 //! - **Incremental** (same version and game fingerprint, but different mods):
 //!   only WADs whose override fingerprints changed are rebuilt.
-//! - **Full rebuild** (version or game fingerprint mismatch): the overlay is
-//!   wiped and rebuilt from scratch.
+//! - **Full rebuild** (version or game fingerprint mismatch, or an overlay
+//!   built for another profile): the overlay is wiped and rebuilt from scratch.
 
+use crate::builder::{ConflictStrategy, DEFAULT_OUTPUT_EXTENSION};
 use crate::error::Result;
 use crate::linked_bins::LinkedBinOffender;
 use camino::Utf8Path;
@@ -37,6 +38,9 @@ const CURRENT_VERSION: u32 = 4;
 ///   "blockedWads": ["scripts.wad.client"],
 ///   "wadFingerprints": {
 ///     "DATA/FINAL/Champions/Aatrox.wad.client": 9876543210
+///   },
+///   "wadSizes": {
+///     "DATA/FINAL/Champions/Aatrox.wad.client": 41943040
 ///   }
 /// }
 /// ```
@@ -70,11 +74,66 @@ pub struct OverlayState {
     #[serde(default)]
     pub wad_fingerprints: BTreeMap<String, u64>,
 
+    /// Size in bytes of each overlay WAD from the last build, keyed like
+    /// `wad_fingerprints`.
+    ///
+    /// Empty in states written before sizes were recorded; their sizes are
+    /// unknown (see [`total_wad_size`](Self::total_wad_size)).
+    #[serde(default)]
+    pub wad_sizes: BTreeMap<String, u64>,
+
     /// Mods whose property-bins reference unresolved linked dependencies, as
     /// computed during the last build. Persisted so the exact-match skip path can
     /// re-surface the same advisory without recomputing.
     #[serde(default)]
     pub linked_bin_offenders: Vec<LinkedBinOffender>,
+
+    /// Conflict strategy the overlay was built with. A change of strategy can
+    /// change winners without changing the mod list, so it defeats the
+    /// exact-match skip.
+    #[serde(default)]
+    pub conflict_strategy: ConflictStrategy,
+
+    /// The build ran out of time before patching every WAD, so
+    /// `wad_fingerprints` lists only the finished ones. Defeats the exact-match
+    /// skip so the next build completes the overlay.
+    #[serde(default)]
+    pub incomplete: bool,
+
+    /// Profile the overlay was built for, when the builder was given one with
+    /// [`OverlayBuilder::with_profile_id`](crate::OverlayBuilder::with_profile_id).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+
+    /// File extension the overlay WADs were written with (see
+    /// [`OverlayBuilder::with_output_extension`](crate::OverlayBuilder::with_output_extension)).
+    /// The WADs keyed in `wad_fingerprints` are on disk under this extension.
+    #[serde(
+        default = "default_output_extension",
+        skip_serializing_if = "is_default_output_extension"
+    )]
+    pub output_extension: String,
+
+    /// Key of the WAD filter the overlay was built with (see
+    /// [`OverlayBuilder::with_wad_filter_key`](crate::OverlayBuilder::with_wad_filter_key)).
+    /// A change of key defeats the exact-match skip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wad_filter_key: Option<String>,
+}
+
+fn default_output_extension() -> String {
+    DEFAULT_OUTPUT_EXTENSION.to_string()
+}
+
+fn is_default_output_extension(extension: &str) -> bool {
+    extension == DEFAULT_OUTPUT_EXTENSION
+}
+
+/// Output extension recorded in the `overlay.json` in `state_dir`, or the
+/// default one when there is no state.
+pub(crate) fn load_output_extension(state_dir: &Utf8Path) -> Result<String> {
+    Ok(OverlayState::load(&state_dir.join("overlay.json"))?
+        .map_or_else(default_output_extension, |state| state.output_extension))
 }
 
 impl Default for OverlayState {
@@ -85,7 +144,13 @@ impl Default for OverlayState {
             game_fingerprint: 0,
             blocked_wads: Vec::new(),
             wad_fingerprints: BTreeMap::new(),
+            wad_sizes: BTreeMap::new(),
             linked_bin_offenders: Vec::new(),
+            conflict_strategy: ConflictStrategy::default(),
+            incomplete: false,
+            profile_id: None,
+            output_extension: default_output_extension(),
+            wad_filter_key: None,
         }
     }
 }
@@ -111,7 +176,13 @@ impl OverlayState {
             game_fingerprint,
             blocked_wads,
             wad_fingerprints,
+            wad_sizes: BTreeMap::new(),
             linked_bin_offenders: Vec::new(),
+            conflict_strategy: ConflictStrategy::default(),
+            incomplete: false,
+            profile_id: None,
+            output_extension: default_output_extension(),
+            wad_filter_key: None,
         }
     }
 
@@ -129,9 +200,20 @@ impl OverlayState {
             return Ok(None);
         }
 
-        let contents = std::fs::read_to_string(path.as_std_path())?;
-        let state: Self = serde_json::from_str(&contents)?;
-        Ok(Some(state))
+        let contents = std::fs::read(path.as_std_path())?;
+        Ok(Some(Self::from_bytes(&contents)?))
+    }
+
+    /// Parse overlay state from the bytes of an `overlay.json` file, such as
+    /// ones returned by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Serialize the state to the bytes [`save`](Self::save) writes, so it can
+    /// be stored somewhere other than the state directory.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
     }
 
     /// Save overlay state to a file.
@@ -146,8 +228,7 @@ impl OverlayState {
             std::fs::create_dir_all(parent.as_std_path())?;
         }
 
-        let contents = serde_json::to_string_pretty(self)?;
-        std::fs::write(path.as_std_path(), contents)?;
+        std::fs::write(path.as_std_path(), self.to_bytes()?)?;
         Ok(())
     }
 
@@ -195,6 +276,26 @@ impl OverlayState {
         self.version == CURRENT_VERSION && self.game_fingerprint == game_fingerprint
     }
 
+    /// Check if the overlay was built for a profile other than `profile_id`.
+    ///
+    /// Always `false` when `profile_id` is `None`. An overlay that recorded no
+    /// profile counts as built for another one, since it cannot be told apart
+    /// from one built by a different profile sharing the overlay root.
+    pub fn built_for_other_profile(&self, profile_id: Option<&str>) -> bool {
+        profile_id.is_some_and(|id| self.profile_id.as_deref() != Some(id))
+    }
+
+    /// Total size in bytes of the overlay WADs from the last build.
+    ///
+    /// Returns `None` if the size of any WAD in `wad_fingerprints` was not
+    /// recorded, as in states written before sizes were.
+    pub fn total_wad_size(&self) -> Option<u64> {
+        self.wad_fingerprints
+            .keys()
+            .map(|wad| self.wad_sizes.get(wad))
+            .sum()
+    }
+
     /// Look up the fingerprint of a specific WAD from the previous build.
     ///
     /// # Arguments
@@ -360,6 +461,61 @@ mod tests {
         assert_eq!(loaded.wad_fingerprints, state.wad_fingerprints);
     }
 
+    #[test]
+    fn test_bytes_round_trip_matches_file() {
+        let temp = NamedTempFile::new().unwrap();
+        let path = Utf8Path::from_path(temp.path()).unwrap();
+
+        let state = OverlayState::new(
+            vec!["mod1".to_string()],
+            0x123456,
+            vec!["blocked.wad.client".to_string()],
+            BTreeMap::from([("DATA/FINAL/test.wad.client".to_string(), 0xABC)]),
+        );
+        state.save(path).unwrap();
+
+        let bytes = state.to_bytes().unwrap();
+        assert_eq!(bytes, std::fs::read(path).unwrap());
+        let restored = OverlayState::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.enabled_mods, state.enabled_mods);
+        assert_eq!(restored.blocked_wads, state.blocked_wads);
+        assert_eq!(restored.wad_fingerprints, state.wad_fingerprints);
+        assert!(OverlayState::from_bytes(b"{ invalid json }").is_err());
+    }
+
+    #[test]
+    fn test_built_for_other_profile() {
+        let mut state = OverlayState::default();
+        assert!(!state.built_for_other_profile(None));
+        assert!(state.built_for_other_profile(Some("a")));
+        assert!(!serde_json::to_string(&state).unwrap().contains("profileId"));
+
+        state.profile_id = Some("a".to_string());
+        assert!(!state.built_for_other_profile(None));
+        assert!(!state.built_for_other_profile(Some("a")));
+        assert!(state.built_for_other_profile(Some("b")));
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains(r#""profileId":"a""#));
+    }
+
+    #[test]
+    fn test_total_wad_size() {
+        let wad = "DATA/FINAL/Champions/Aatrox.wad.client";
+        let old_json = format!(
+            r#"{{"version":4,"enabledMods":["mod1"],"gameFingerprint":1234,"wadFingerprints":{{"{wad}":1}}}}"#
+        );
+        let mut state: OverlayState = serde_json::from_str(&old_json).unwrap();
+        assert!(state.wad_sizes.is_empty());
+        assert_eq!(state.total_wad_size(), None);
+
+        state.wad_sizes.insert(wad.to_string(), 40);
+        assert_eq!(state.total_wad_size(), Some(40));
+        let restored = OverlayState::from_bytes(&state.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.wad_sizes, state.wad_sizes);
+
+        assert_eq!(OverlayState::default().total_wad_size(), Some(0));
+    }
+
     #[test]
     fn test_load_nonexistent() {
         let temp = NamedTempFile::new().unwrap();
@@ -0,0 +1,268 @@
+//! Best-effort editing of `lol.stringtable` (RST) files.
+//!
+//! A string table maps hashed field names to NUL-terminated UTF-8 strings:
+//!
+//! ```text
+//! "RST" | version: u8 | [v2: font config] | count: u32
+//! entries: count × u64   (offset << hash_bits) | truncated key hash
+//! [v2-v4: one mode byte]
+//! data: string bytes, addressed by entry offsets
+//! ```
+//!
+//! This is not a full parser. [`patch_stringtable`] only repoints entries whose
+//! key already exists: each new value is appended to the data section and the
+//! entry's offset updated, leaving every other byte of the file untouched. The
+//! old string stays behind, unreferenced. Fields that cannot be located are
+//! reported back so the caller can warn about them.
+
+use crate::error::{Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use xxhash_rust::{xxh3::xxh3_64, xxh64::xxh64};
+
+/// Path of a locale's string table inside its `Global.<locale>.wad.client`.
+pub fn stringtable_chunk_path(locale: &str) -> String {
+    format!("data/menu/{}/lol.stringtable", locale.to_ascii_lowercase())
+}
+
+/// Result of [`patch_stringtable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringTablePatch {
+    /// The patched string table.
+    pub bytes: Vec<u8>,
+    /// Number of fields whose value was replaced.
+    pub applied: usize,
+    /// Fields with no matching entry in the table, in field order.
+    pub missing: Vec<String>,
+}
+
+/// Positions of the sections of a string table.
+struct RstLayout {
+    hash_bits: u32,
+    entries_start: usize,
+    count: usize,
+    data_start: usize,
+}
+
+impl RstLayout {
+    fn parse(data: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| Error::Other(format!("Invalid string table: {reason}"));
+        let read_u32 = |pos: usize| -> Result<u32> {
+            data.get(pos..pos + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .ok_or_else(|| invalid("truncated header"))
+        };
+
+        if data.get(..3) != Some(b"RST".as_slice()) {
+            return Err(invalid("missing RST magic"));
+        }
+        let version = *data.get(3).ok_or_else(|| invalid("truncated header"))?;
+        let hash_bits = match version {
+            2 | 3 => 40,
+            4 => 39,
+            5 => 38,
+            _ => return Err(invalid(&format!("unsupported version {version}"))),
+        };
+
+        let mut pos = 4;
+        if version == 2 {
+            let has_font_config = *data.get(pos).ok_or_else(|| invalid("truncated header"))?;
+            pos += 1;
+            if has_font_config != 0 {
+                pos += 4 + read_u32(pos)? as usize;
+            }
+        }
+
+        let count = read_u32(pos)? as usize;
+        let entries_start = pos + 4;
+        let mut data_start = entries_start + count * 8;
+        if version < 5 {
+            data_start += 1;
+        }
+        if data_start > data.len() {
+            return Err(invalid("entry table runs past the end of the file"));
+        }
+
+        Ok(Self {
+            hash_bits,
+            entries_start,
+            count,
+            data_start,
+        })
+    }
+
+    fn hash_mask(&self) -> u64 {
+        (1 << self.hash_bits) - 1
+    }
+
+    fn entry(&self, data: &[u8], index: usize) -> u64 {
+        let pos = self.entries_start + index * 8;
+        u64::from_le_bytes(data[pos..pos + 8].try_into().unwrap())
+    }
+
+    /// Truncated key hash -> entry index.
+    fn index_by_hash(&self, data: &[u8]) -> HashMap<u64, usize> {
+        (0..self.count)
+            .map(|index| (self.entry(data, index) & self.hash_mask(), index))
+            .collect()
+    }
+
+    /// The hashes `field` may be stored under. Older tables hash keys with
+    /// xxh64, newer ones with xxh3; both are tried.
+    fn field_hashes(&self, field: &str) -> [u64; 2] {
+        let key = field.to_lowercase();
+        [
+            xxh64(key.as_bytes(), 0) & self.hash_mask(),
+            xxh3_64(key.as_bytes()) & self.hash_mask(),
+        ]
+    }
+}
+
+/// Replace the values of existing fields in a string table.
+///
+/// Fields are matched by name (case-insensitively, as the game hashes them).
+/// Each replaced value is appended to the end of the data section; nothing
+/// else in the table moves. Fields with no existing entry are returned in
+/// [`StringTablePatch::missing`] rather than added.
+pub fn patch_stringtable(
+    data: &[u8],
+    overrides: &BTreeMap<String, String>,
+) -> Result<StringTablePatch> {
+    let layout = RstLayout::parse(data)?;
+    let index_by_hash = layout.index_by_hash(data);
+    let max_offset = 1u64 << (64 - layout.hash_bits);
+
+    let mut bytes = data.to_vec();
+    let mut appended: HashMap<&str, u64> = HashMap::new();
+    let mut applied = 0;
+    let mut missing = Vec::new();
+
+    for (field, value) in overrides {
+        let Some((hash, index)) = layout
+            .field_hashes(field)
+            .into_iter()
+            .find_map(|hash| index_by_hash.get(&hash).map(|&index| (hash, index)))
+        else {
+            missing.push(field.clone());
+            continue;
+        };
+
+        let offset = match appended.get(value.as_str()) {
+            Some(&offset) => offset,
+            None => {
+                let offset = (bytes.len() - layout.data_start) as u64;
+                if offset >= max_offset {
+                    return Err(Error::Other(
+                        "String table data section is too large to append to".to_string(),
+                    ));
+                }
+                bytes.extend_from_slice(value.as_bytes());
+                bytes.push(0);
+                appended.insert(value, offset);
+                offset
+            }
+        };
+
+        let pos = layout.entries_start + index * 8;
+        bytes[pos..pos + 8].copy_from_slice(&((offset << layout.hash_bits) | hash).to_le_bytes());
+        applied += 1;
+    }
+
+    Ok(StringTablePatch {
+        bytes,
+        applied,
+        missing,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Write a string table of the given version, hashing keys with xxh64 for
+    /// versions below 5 and xxh3 otherwise.
+    pub(crate) fn write_stringtable(version: u8, fields: &[(&str, &str)]) -> Vec<u8> {
+        let hash_bits = match version {
+            2 | 3 => 40,
+            4 => 39,
+            _ => 38,
+        };
+        let mask = (1u64 << hash_bits) - 1;
+
+        let mut out = b"RST".to_vec();
+        out.push(version);
+        if version == 2 {
+            out.push(0);
+        }
+        out.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+
+        let mut strings = Vec::new();
+        for (key, value) in fields {
+            let key = key.to_lowercase();
+            let hash = if version < 5 {
+                xxh64(key.as_bytes(), 0)
+            } else {
+                xxh3_64(key.as_bytes())
+            } & mask;
+            out.extend_from_slice(&(((strings.len() as u64) << hash_bits) | hash).to_le_bytes());
+            strings.extend_from_slice(value.as_bytes());
+            strings.push(0);
+        }
+        if version < 5 {
+            out.push(0);
+        }
+        out.extend_from_slice(&strings);
+        out
+    }
+
+    /// Look up a field's value the way the game would.
+    pub(crate) fn read_field(data: &[u8], field: &str) -> Option<String> {
+        let layout = RstLayout::parse(data).unwrap();
+        let index_by_hash = layout.index_by_hash(data);
+        let index = layout
+            .field_hashes(field)
+            .into_iter()
+            .find_map(|hash| index_by_hash.get(&hash).copied())?;
+
+        let start = layout.data_start + (layout.entry(data, index) >> layout.hash_bits) as usize;
+        let end = start + data[start..].iter().position(|&b| b == 0)?;
+        Some(String::from_utf8(data[start..end].to_vec()).unwrap())
+    }
+
+    #[test]
+    fn replaces_existing_fields_and_reports_missing_ones() {
+        for version in [2, 3, 4, 5] {
+            let original = write_stringtable(
+                version,
+                &[("game_title", "League"), ("game_subtitle", "of Legends")],
+            );
+            let overrides = BTreeMap::from([
+                (
+                    "Game_Title".to_string(),
+                    "A much longer replacement".to_string(),
+                ),
+                ("not_a_field".to_string(), "ignored".to_string()),
+            ]);
+
+            let patch = patch_stringtable(&original, &overrides).unwrap();
+            assert_eq!(patch.applied, 1, "v{version}");
+            assert_eq!(patch.missing, vec!["not_a_field".to_string()]);
+            assert!(patch.bytes.starts_with(&original[..4]));
+            assert_eq!(
+                read_field(&patch.bytes, "game_title").as_deref(),
+                Some("A much longer replacement")
+            );
+            assert_eq!(
+                read_field(&patch.bytes, "game_subtitle").as_deref(),
+                Some("of Legends")
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        let overrides = BTreeMap::new();
+        assert!(patch_stringtable(b"PROP", &overrides).is_err());
+        assert!(patch_stringtable(b"RST\x09\0\0\0\0", &overrides).is_err());
+        assert!(patch_stringtable(b"RST\x05\x02\0\0\0", &overrides).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Content provider for `.tar` / `.tar.gz` mod distributions.
+//!
+//! The archive uses the same layout as a mod project directory read by
+//! [`FsModContent`](crate::FsModContent): a `mod.config.json` next to a
+//! `content/<layer>/<Wad>.wad.client/...` tree. The whole tree may also be
+//! wrapped in a single top-level directory, as produced by
+//! `tar -czf my-mod.tar.gz my-mod/`.
+
+use crate::content::{archive_fingerprint, ContentFormatVersion, ModContentProvider};
+use crate::error::{Error, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::read::GzDecoder;
+use ltk_mod_project::ModProject;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const CONFIG_FILE: &str = "mod.config.json";
+
+/// Content provider that reads a mod project packed into a tarball.
+///
+/// Tar archives have no central directory, so the archive is streamed once on
+/// construction and the project config plus every file under `content/` are
+/// kept in memory. Compressed (`.tar.gz`) and plain (`.tar`) archives are told
+/// apart by sniffing the gzip magic bytes, not by file extension.
+pub struct TarModContent {
+    config: Vec<u8>,
+    /// Path relative to the project root (e.g. `content/base/Aatrox.wad.client/x.bin`) -> bytes.
+    files: BTreeMap<String, Vec<u8>>,
+    archive_path: Option<Utf8PathBuf>,
+}
+
+impl TarModContent {
+    /// Stream a `.tar` or `.tar.gz` archive from `reader` and index its contents.
+    ///
+    /// Fails if the archive contains no `mod.config.json` at its root or inside a
+    /// single top-level directory.
+    pub fn new<R: Read>(reader: R) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+        let reader: Box<dyn Read> = if is_gzip {
+            Box::new(GzDecoder::new(reader))
+        } else {
+            Box::new(reader)
+        };
+
+        let mut entries: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = entry.path()?;
+            let Some(name) = path.to_str().map(normalize_entry_path) else {
+                tracing::warn!("Skipping non-UTF-8 tar entry: {}", path.display());
+                continue;
+            };
+
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            entries.insert(name, bytes);
+        }
+
+        let root = find_project_root(&entries)
+            .ok_or_else(|| Error::Other(format!("Missing {} in tar archive", CONFIG_FILE)))?;
+
+        let mut config = Vec::new();
+        let mut files = BTreeMap::new();
+        for (name, bytes) in entries {
+            let Some(rel) = name.strip_prefix(&root) else {
+                continue;
+            };
+            if rel == CONFIG_FILE {
+                config = bytes;
+            } else if rel.starts_with("content/") {
+                files.insert(rel.to_string(), bytes);
+            }
+        }
+
+        Ok(Self {
+            config,
+            files,
+            archive_path: None,
+        })
+    }
+
+    /// Set the archive file path, enabling content fingerprinting for the metadata cache.
+    pub fn with_archive_path(mut self, path: Utf8PathBuf) -> Self {
+        self.archive_path = Some(path);
+        self
+    }
+
+    /// Files under `prefix`, with the prefix stripped from their paths.
+    fn files_under<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a [u8])> {
+        self.files
+            .range(prefix.to_string()..)
+            .map_while(move |(name, bytes)| {
+                name.strip_prefix(prefix).map(|rel| (rel, bytes.as_slice()))
+            })
+    }
+
+    fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| Error::Other(format!("File not found in tar archive: {}", path)))
+    }
+}
+
+impl ModContentProvider for TarModContent {
+    fn mod_project(&mut self) -> Result<ModProject> {
+        Ok(serde_json::from_slice(&self.config)?)
+    }
+
+    fn list_layer_wads(&mut self, layer: &str) -> Result<Vec<String>> {
+        let prefix = format!("content/{}/", layer);
+        let mut wads: Vec<String> = Vec::new();
+        for (rel, _) in self.files_under(&prefix) {
+            let Some((wad_name, _)) = rel.split_once('/') else {
+                continue;
+            };
+            if !wad_name.to_ascii_lowercase().ends_with(".wad.client") {
+                continue;
+            }
+            if wads.last().map(String::as_str) != Some(wad_name) {
+                wads.push(wad_name.to_string());
+            }
+        }
+        Ok(wads)
+    }
+
+    fn read_wad_overrides(
+        &mut self,
+        layer: &str,
+        wad_name: &str,
+    ) -> Result<Vec<(Utf8PathBuf, Vec<u8>)>> {
+        let prefix = format!("content/{}/{}/", layer, wad_name);
+        Ok(self
+            .files_under(&prefix)
+            .map(|(rel, bytes)| (Utf8PathBuf::from(rel), bytes.to_vec()))
+            .collect())
+    }
+
+    fn content_fingerprint(&self) -> Result<Option<u64>> {
+        match &self.archive_path {
+            Some(path) => archive_fingerprint(path),
+            None => Ok(None),
+        }
+    }
+
+    fn read_wad_override_file(
+        &mut self,
+        layer: &str,
+        wad_name: &str,
+        rel_path: &Utf8Path,
+    ) -> Result<Vec<u8>> {
+        let rel = rel_path.as_str().replace('\\', "/");
+        self.read_file(&format!("content/{}/{}/{}", layer, wad_name, rel))
+    }
+
+    fn read_raw_override_file(&mut self, rel_path: &Utf8Path) -> Result<Vec<u8>> {
+        let rel = rel_path.as_str().replace('\\', "/");
+        self.read_file(&format!("content/{}", rel))
+    }
+
+    fn format_version(&self) -> ContentFormatVersion {
+        ContentFormatVersion::TarV1
+    }
+}
+
+/// Normalize a tar entry path to forward slashes without a leading `./`.
+fn normalize_entry_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+/// Find the directory prefix (empty or `"<dir>/"`) that holds `mod.config.json`.
+fn find_project_root(entries: &BTreeMap<String, Vec<u8>>) -> Option<String> {
+    if entries.contains_key(CONFIG_FILE) {
+        return Some(String::new());
+    }
+
+    entries.keys().find_map(|name| {
+        let dir = name.strip_suffix(CONFIG_FILE)?.strip_suffix('/')?;
+        (!dir.is_empty() && !dir.contains('/')).then(|| format!("{}/", dir))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Cursor;
+
+    fn project_json() -> Vec<u8> {
+        let project = crate::testing::mock_project("tar-mod");
+        serde_json::to_vec(&project).unwrap()
+    }
+
+    fn build_tar(root: &str) -> Vec<u8> {
+        let config = project_json();
+        let files: [(&str, &[u8]); 4] = [
+            ("mod.config.json", &config),
+            ("content/base/Test.wad.client/file1.bin", b"data1"),
+            ("content/base/Test.wad.client/subdir/file2.bin", b"data2"),
+            ("content/base/notes.txt", b"ignored"),
+        ];
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("{}{}", root, path), data)
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        std::io::Write::write_all(&mut encoder, bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn assert_reads_project(mut provider: TarModContent) {
+        assert_eq!(provider.mod_project().unwrap().name, "tar-mod");
+        assert_eq!(
+            provider.list_layer_wads("base").unwrap(),
+            vec!["Test.wad.client"]
+        );
+        assert!(provider.list_layer_wads("missing").unwrap().is_empty());
+
+        let overrides = provider
+            .read_wad_overrides("base", "Test.wad.client")
+            .unwrap();
+        let paths: Vec<&str> = overrides.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, vec!["file1.bin", "subdir/file2.bin"]);
+
+        let bytes = provider
+            .read_wad_override_file("base", "Test.wad.client", Utf8Path::new("subdir/file2.bin"))
+            .unwrap();
+        assert_eq!(bytes, b"data2");
+        assert!(provider
+            .read_wad_override_file("base", "Test.wad.client", Utf8Path::new("nope.bin"))
+            .is_err());
+    }
+
+    #[test]
+    fn reads_plain_tar() {
+        let provider = TarModContent::new(Cursor::new(build_tar(""))).unwrap();
+        assert_reads_project(provider);
+    }
+
+    #[test]
+    fn reads_gzipped_tar_with_top_level_dir() {
+        let bytes = gzip(&build_tar("./my-mod/"));
+        let provider = TarModContent::new(Cursor::new(bytes)).unwrap();
+        assert_reads_project(provider);
+    }
+
+    #[test]
+    fn missing_config_is_an_error() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(1);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "content/base/Test.wad.client/a.bin", &b"a"[..])
+            .unwrap();
+        let bytes = builder.into_inner().unwrap();
+
+        assert!(TarModContent::new(Cursor::new(bytes)).is_err());
+    }
+}
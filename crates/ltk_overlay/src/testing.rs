@@ -0,0 +1,320 @@
+//! Test support: synthetic game directories for running the builder offline.
+//!
+//! Enabled by the `testing` feature. [`make_fake_game_dir`] writes small but
+//! valid v3.4 `.wad.client` files under `DATA/FINAL`, so integration tests can
+//! build a [`GameIndex`](crate::GameIndex) and run a full overlay build without
+//! a League install.
+//!
+//! ```no_run
+//! use ltk_overlay::testing::{make_fake_game_dir, FakeGameSpec, FakeWad};
+//! # fn main() -> ltk_overlay::Result<()> {
+//! # let temp_root = camino::Utf8Path::new("/tmp/fake");
+//! let spec = FakeGameSpec::new().with_wad(
+//!     FakeWad::new("Champions/Aatrox.wad.client")
+//!         .with_path("data/characters/aatrox/skin0.bin", b"original".to_vec())
+//!         .with_chunk(0x1234_5678_9abc_def0, b"by hash".to_vec()),
+//! );
+//! let game_dir = make_fake_game_dir(temp_root, &spec)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`mock_project`] and [`write_fs_mod`] cover the other half: a minimal
+//! [`ModProject`] and a filesystem mod directory to hand to
+//! [`FsModContent`](crate::FsModContent). [`StubContent`] is an in-memory
+//! provider for tests that don't need files on disk.
+
+use crate::content::ModContentProvider;
+use crate::error::{Error, Result};
+use crate::wad_builder::{write_wad_header, TOC_ENTRY_SIZE};
+use camino::{Utf8Path, Utf8PathBuf};
+use ltk_mod_project::ModProject;
+use ltk_wad::{WadChunk, WadChunkCompression};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// The game WADs to write, see [`make_fake_game_dir`].
+#[derive(Debug, Clone, Default)]
+pub struct FakeGameSpec {
+    pub wads: Vec<FakeWad>,
+}
+
+impl FakeGameSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a WAD to the game.
+    pub fn with_wad(mut self, wad: FakeWad) -> Self {
+        self.wads.push(wad);
+        self
+    }
+}
+
+/// One game WAD: its location and chunk contents.
+#[derive(Debug, Clone)]
+pub struct FakeWad {
+    /// Path relative to `DATA/FINAL` (e.g. `Champions/Aatrox.wad.client`).
+    pub path: Utf8PathBuf,
+    /// Path hash -> uncompressed chunk bytes.
+    pub chunks: BTreeMap<u64, Vec<u8>>,
+}
+
+impl FakeWad {
+    pub fn new(path: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            chunks: BTreeMap::new(),
+        }
+    }
+
+    /// Add a chunk by path hash, replacing any chunk with the same hash.
+    pub fn with_chunk(mut self, path_hash: u64, bytes: Vec<u8>) -> Self {
+        self.chunks.insert(path_hash, bytes);
+        self
+    }
+
+    /// Add a chunk by asset path, hashed the same way the builder hashes mod files.
+    pub fn with_path(self, path: &str, bytes: Vec<u8>) -> Self {
+        self.with_chunk(ltk_modpkg::utils::hash_chunk_name(path), bytes)
+    }
+}
+
+/// Write the WADs in `spec` to `<parent>/Game/DATA/FINAL` and return the `Game` directory.
+///
+/// Output is deterministic: chunks are stored uncompressed in path hash order,
+/// each with its xxh3 checksum, so the same spec always produces the same bytes.
+pub fn make_fake_game_dir(parent: &Utf8Path, spec: &FakeGameSpec) -> Result<Utf8PathBuf> {
+    let game_dir = parent.join("Game");
+    let final_dir = game_dir.join("DATA/FINAL");
+    std::fs::create_dir_all(final_dir.as_std_path())?;
+
+    for wad in &spec.wads {
+        let path = final_dir.join(&wad.path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir.as_std_path())?;
+        }
+        let mut writer = BufWriter::new(File::create(path.as_std_path())?);
+        write_fake_wad(&mut writer, &wad.chunks)?;
+        writer.flush()?;
+    }
+
+    Ok(game_dir)
+}
+
+/// Write a v3.4 WAD holding `chunks` (path hash -> bytes), stored uncompressed.
+///
+/// The header and TOC entries are written the same way the builder writes
+/// patched WADs.
+pub fn write_fake_wad<W: Write + Seek>(
+    writer: &mut W,
+    chunks: &BTreeMap<u64, Vec<u8>>,
+) -> Result<()> {
+    let toc_offset = write_wad_header(writer, chunks.len() as u32)?;
+
+    let mut data_offset = toc_offset as usize + chunks.len() * TOC_ENTRY_SIZE;
+    for (&path_hash, bytes) in chunks {
+        WadChunk {
+            path_hash,
+            data_offset,
+            compressed_size: bytes.len(),
+            uncompressed_size: bytes.len(),
+            compression_type: WadChunkCompression::None,
+            is_duplicated: false,
+            frame_count: 0,
+            start_frame: 0,
+            checksum: xxh3_64(bytes),
+        }
+        .write_v3_4(writer)?;
+        data_offset += bytes.len();
+    }
+
+    for bytes in chunks.values() {
+        writer.write_all(bytes)?;
+    }
+
+    Ok(())
+}
+
+/// A minimal [`ModProject`] called `name`, with only the base layer.
+///
+/// Tests adjust the fields they care about on the returned value.
+pub fn mock_project(name: &str) -> ModProject {
+    ModProject {
+        name: name.to_string(),
+        display_name: name.to_string(),
+        version: "1.0.0".to_string(),
+        layers: ltk_mod_project::default_layers(),
+        ..Default::default()
+    }
+}
+
+/// Write a filesystem mod: `project` as `mod.config.json`, and each
+/// `(path, bytes)` in `files` under `content/` (e.g. `base/A.wad.client/data/a.bin`).
+pub fn write_fs_mod(
+    mod_dir: &Utf8Path,
+    project: &ModProject,
+    files: &[(&str, &[u8])],
+) -> Result<()> {
+    std::fs::create_dir_all(mod_dir.as_std_path())?;
+    std::fs::write(
+        mod_dir.join("mod.config.json").as_std_path(),
+        serde_json::to_vec_pretty(project)?,
+    )?;
+    for (path, bytes) in files {
+        let file = mod_dir.join("content").join(path);
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir.as_std_path())?;
+        }
+        std::fs::write(file.as_std_path(), bytes)?;
+    }
+    Ok(())
+}
+
+/// An in-memory [`ModContentProvider`] serving a fixed project.
+///
+/// [`list_layer_wads`](ModContentProvider::list_layer_wads) returns the WADs
+/// set with [`with_layer_wads`](Self::with_layer_wads), bulk reads return
+/// nothing, and targeted reads return the same `bytes` for every file, after
+/// failing the first `read_failures` of them.
+#[derive(Debug, Clone)]
+pub struct StubContent {
+    pub project: ModProject,
+    pub layer_wads: HashMap<String, Vec<String>>,
+    pub bytes: Vec<u8>,
+    pub read_failures: u32,
+}
+
+impl StubContent {
+    pub fn new(project: ModProject) -> Self {
+        Self {
+            project,
+            layer_wads: HashMap::new(),
+            bytes: Vec::new(),
+            read_failures: 0,
+        }
+    }
+
+    pub fn with_layer_wads(mut self, layer: &str, wads: &[&str]) -> Self {
+        self.layer_wads.insert(
+            layer.to_string(),
+            wads.iter().map(|wad| wad.to_string()).collect(),
+        );
+        self
+    }
+
+    pub fn with_bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.bytes = bytes.into();
+        self
+    }
+
+    pub fn with_read_failures(mut self, failures: u32) -> Self {
+        self.read_failures = failures;
+        self
+    }
+}
+
+impl ModContentProvider for StubContent {
+    fn mod_project(&mut self) -> Result<ModProject> {
+        Ok(self.project.clone())
+    }
+
+    fn list_layer_wads(&mut self, layer: &str) -> Result<Vec<String>> {
+        Ok(self.layer_wads.get(layer).cloned().unwrap_or_default())
+    }
+
+    fn read_wad_overrides(
+        &mut self,
+        _layer: &str,
+        _wad_name: &str,
+    ) -> Result<Vec<(Utf8PathBuf, Vec<u8>)>> {
+        Ok(Vec::new())
+    }
+
+    fn read_wad_override_file(
+        &mut self,
+        _layer: &str,
+        _wad_name: &str,
+        rel_path: &Utf8Path,
+    ) -> Result<Vec<u8>> {
+        self.read_raw_override_file(rel_path)
+    }
+
+    fn read_raw_override_file(&mut self, _rel_path: &Utf8Path) -> Result<Vec<u8>> {
+        if self.read_failures > 0 {
+            self.read_failures -= 1;
+            return Err(Error::Other("transient read failure".to_string()));
+        }
+        Ok(self.bytes.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnabledMod, FsModContent, GameIndex, OverlayBuilder};
+    use ltk_wad::Wad;
+
+    const SHARED: &str = "assets/shared/particle.tex";
+
+    fn spec() -> FakeGameSpec {
+        FakeGameSpec::new()
+            .with_wad(
+                FakeWad::new("Champions/Aatrox.wad.client")
+                    .with_path("data/characters/aatrox/skin0.bin", b"aatrox".to_vec())
+                    .with_path(SHARED, b"shared".to_vec()),
+            )
+            .with_wad(
+                FakeWad::new("Maps/Shipping/Map11.wad.client")
+                    .with_path(SHARED, b"shared".to_vec()),
+            )
+    }
+
+    #[test]
+    fn fake_game_indexes_and_builds() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let game_dir = make_fake_game_dir(root, &spec()).unwrap();
+
+        let index = GameIndex::build(&game_dir).unwrap();
+        let shared_hash = ltk_modpkg::utils::hash_chunk_name(SHARED);
+        assert_eq!(index.find_wads_with_hash(shared_hash).unwrap().len(), 2);
+
+        let mod_dir = root.join("mod");
+        write_fs_mod(
+            &mod_dir,
+            &mock_project("fake-mod"),
+            &[(&format!("base/Aatrox.wad.client/{SHARED}"), b"modded")],
+        )
+        .unwrap();
+        let mut builder = OverlayBuilder::new(game_dir, root.join("overlay"), root.join("state"));
+        builder.set_enabled_mods(vec![EnabledMod {
+            id: "fake-mod".to_string(),
+            content: Box::new(FsModContent::new(mod_dir)),
+            enabled_layers: None,
+        }]);
+        let result = builder.build().unwrap();
+        assert_eq!(result.wads_built.len(), 2);
+
+        for wad_path in &result.wads_built {
+            let bytes = std::fs::read(wad_path).unwrap();
+            let mut wad = Wad::mount(std::io::Cursor::new(bytes)).unwrap();
+            let chunk = *wad.chunks().get(shared_hash).unwrap();
+            assert_eq!(&*wad.load_chunk_decompressed(&chunk).unwrap(), b"modded");
+        }
+    }
+
+    #[test]
+    fn same_spec_writes_identical_bytes() {
+        let a = tempfile::tempdir().unwrap();
+        let b = tempfile::tempdir().unwrap();
+        let read = |dir: &tempfile::TempDir| {
+            let game_dir =
+                make_fake_game_dir(Utf8Path::from_path(dir.path()).unwrap(), &spec()).unwrap();
+            std::fs::read(game_dir.join("DATA/FINAL/Champions/Aatrox.wad.client")).unwrap()
+        };
+        assert_eq!(read(&a), read(&b));
+    }
+}
@@ -55,6 +55,20 @@ pub fn normalize_rel_path_for_hash(rel_path: &Utf8Path, _bytes: &[u8]) -> String
     joined.replace('\\', "/")
 }
 
+/// Parse a hex-hash filename such as `0123456789abcdef.bin` into its path hash.
+///
+/// Returns `None` unless the file stem is exactly 16 hex digits.
+pub fn parse_hex_chunk_name(rel_path: &Utf8Path) -> Option<u64> {
+    let file_name = rel_path.file_name().unwrap_or("");
+    let file_stem = Utf8Path::new(file_name).file_stem().unwrap_or("");
+
+    if file_stem.len() == 16 && file_stem.chars().all(|c| c.is_ascii_hexdigit()) {
+        u64::from_str_radix(file_stem, 16).ok()
+    } else {
+        None
+    }
+}
+
 /// Resolve the WAD chunk path hash for a mod override file.
 ///
 /// Two resolution strategies:
@@ -68,14 +82,9 @@ pub fn normalize_rel_path_for_hash(rel_path: &Utf8Path, _bytes: &[u8]) -> String
 ///    [`normalize_rel_path_for_hash`] and hashed with
 ///    [`ltk_modpkg::utils::hash_chunk_name`] (xxHash3).
 pub fn resolve_chunk_hash(rel_path: &Utf8Path, bytes: &[u8]) -> Result<u64> {
-    let file_name = rel_path.file_name().unwrap_or("");
-    let file_stem = Utf8Path::new(file_name).file_stem().unwrap_or("");
-
     // If this is a hex-hash filename (as emitted by HexPathResolver), use it directly
-    if file_stem.len() == 16 && file_stem.chars().all(|c| c.is_ascii_hexdigit()) {
-        if let Ok(v) = u64::from_str_radix(file_stem, 16) {
-            return Ok(v);
-        }
+    if let Some(hash) = parse_hex_chunk_name(rel_path) {
+        return Ok(hash);
     }
 
     // Otherwise, compute from normalized path
@@ -127,7 +136,7 @@ pub fn compute_wad_fingerprint_from_meta(
         .iter()
         .filter_map(|&path_hash| {
             let meta = all_meta.get(&path_hash)?;
-            Some((path_hash, meta.content_hash))
+            Some((path_hash, pinned_content_hash(meta)))
         })
         .collect();
     entries.sort_unstable_by_key(|(path_hash, _)| *path_hash);
@@ -135,6 +144,23 @@ pub fn compute_wad_fingerprint_from_meta(
     fingerprint_from_sorted_pairs(&entries)
 }
 
+/// An override's content hash, folded with its pinned compression if it has one.
+///
+/// Unpinned overrides keep their plain content hash so the result still matches
+/// [`compute_wad_overrides_fingerprint`]; pinned ones change the fingerprint so
+/// editing a mod's compression hints rebuilds the affected WADs.
+fn pinned_content_hash(meta: &OverrideMeta) -> u64 {
+    match meta.compression {
+        None => meta.content_hash,
+        Some(compression) => {
+            let mut buf = [0u8; 9];
+            buf[..8].copy_from_slice(&meta.content_hash.to_le_bytes());
+            buf[8] = compression as u8;
+            xxh3_64(&buf)
+        }
+    }
+}
+
 /// Hash sorted `(path_hash, content_hash)` pairs into a single fingerprint.
 fn fingerprint_from_sorted_pairs(entries: &[(u64, u64)]) -> u64 {
     if entries.is_empty() {
@@ -252,6 +278,7 @@ mod tests {
                     },
                     fallback_wad: None,
                     linked_bins: Vec::new(),
+                    compression: None,
                 },
             );
         }
@@ -271,4 +298,33 @@ mod tests {
         let all_meta: HashMap<u64, OverrideMeta> = HashMap::new();
         assert_eq!(compute_wad_fingerprint_from_meta(&wad_hashes, &all_meta), 0);
     }
+
+    #[test]
+    fn test_meta_fingerprint_changes_with_pinned_compression() {
+        use crate::builder::{OverrideMeta, OverrideSource};
+        use ltk_wad::WadChunkCompression;
+
+        let meta = |compression| OverrideMeta {
+            content_hash: 0x1234,
+            uncompressed_size: 3,
+            source: OverrideSource::Raw {
+                mod_id: "test-mod".to_string(),
+                rel_path: Utf8PathBuf::from("dummy.bnk"),
+            },
+            fallback_wad: None,
+            linked_bins: Vec::new(),
+            compression,
+        };
+        let wad_hashes: HashSet<u64> = HashSet::from([1]);
+        let fingerprint = |compression| {
+            compute_wad_fingerprint_from_meta(&wad_hashes, &HashMap::from([(1, meta(compression))]))
+        };
+
+        let unpinned = fingerprint(None);
+        let none = fingerprint(Some(WadChunkCompression::None));
+        let zstd = fingerprint(Some(WadChunkCompression::Zstd));
+        assert_ne!(unpinned, none);
+        assert_ne!(unpinned, zstd);
+        assert_ne!(none, zstd);
+    }
 }
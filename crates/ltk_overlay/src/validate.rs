@@ -0,0 +1,292 @@
+//! Post-build integrity check of an overlay directory.
+//!
+//! [`validate`] mounts every overlay WAD, re-hashes each chunk's stored bytes
+//! against its TOC checksum, and cross-checks the WADs on disk against the
+//! `wadFingerprints` recorded in `overlay.json`. Problems are collected into an
+//! [`OverlayValidation`] report rather than failing on the first one, so a
+//! single run shows everything that is wrong with an overlay.
+
+use crate::backup::collect_overlay_wad_paths;
+use crate::builder::{overlay_wad_rel_path, DEFAULT_OUTPUT_EXTENSION};
+use crate::error::Result;
+use crate::state::OverlayState;
+use camino::Utf8Path;
+use ltk_wad::Wad;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Structured result of [`validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayValidation {
+    /// Number of overlay WADs found on disk and checked.
+    pub wads_checked: usize,
+    /// Total number of chunks whose bytes were checked.
+    pub chunks_checked: usize,
+    /// Every problem found, in WAD path order.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl OverlayValidation {
+    /// Whether the overlay passed every check.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A single problem found by [`validate`].
+///
+/// WAD paths are relative to the overlay root with forward slashes. With the
+/// default output extension they match the keys of
+/// [`OverlayState::wad_fingerprints`]; otherwise they carry the
+/// [`output_extension`](OverlayState::output_extension) the overlay was built with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValidationIssue {
+    /// No `overlay.json` was found, so the WAD list could not be cross-checked.
+    MissingState,
+    /// `overlay.json` lists a WAD that is not on disk.
+    MissingWad { wad: String },
+    /// A WAD on disk is not listed in `overlay.json`.
+    UnlistedWad { wad: String },
+    /// The WAD could not be opened or its header/TOC could not be parsed.
+    Unmountable { wad: String, error: String },
+    /// The WAD mounted but contains no chunks.
+    EmptyWad { wad: String },
+    /// A chunk's stored bytes could not be read (e.g. its range is past the end of the file).
+    UnreadableChunk {
+        wad: String,
+        path_hash: u64,
+        error: String,
+    },
+    /// A chunk's stored bytes do not hash to the checksum in its TOC entry.
+    ChecksumMismatch {
+        wad: String,
+        path_hash: u64,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+/// Validate the overlay at `overlay_root`, reading `overlay.json` from the same directory.
+///
+/// See [`validate_with_state_dir`] for overlays built with a separate state directory.
+pub fn validate(overlay_root: &Utf8Path) -> Result<OverlayValidation> {
+    validate_with_state_dir(overlay_root, overlay_root)
+}
+
+/// Validate the overlay at `overlay_root` against the `overlay.json` in `state_dir`.
+///
+/// Checks that:
+/// - every overlay WAD under `overlay_root` mounts and has at least one chunk,
+///   the WADs being the files with the output extension recorded in `overlay.json`,
+/// - every chunk's stored (compressed) bytes match its xxh3 checksum; chunks
+///   with a zero checksum carry none and are only checked for readability,
+/// - the WADs on disk are exactly the keys of `wadFingerprints`.
+///
+/// Returns `Err` only if the directory cannot be walked or `overlay.json`
+/// exists but cannot be parsed; everything else is reported as a
+/// [`ValidationIssue`].
+pub fn validate_with_state_dir(
+    overlay_root: &Utf8Path,
+    state_dir: &Utf8Path,
+) -> Result<OverlayValidation> {
+    let state = OverlayState::load(&state_dir.join("overlay.json"))?;
+    let output_extension = state
+        .as_ref()
+        .map_or(DEFAULT_OUTPUT_EXTENSION, |state| &state.output_extension);
+
+    let on_disk: BTreeSet<String> = if overlay_root.as_std_path().exists() {
+        collect_overlay_wad_paths(overlay_root, output_extension)?
+            .iter()
+            .filter_map(|path| path.strip_prefix(overlay_root).ok())
+            .map(|rel| rel.as_str().replace('\\', "/"))
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let mut issues = Vec::new();
+    match &state {
+        None => issues.push(ValidationIssue::MissingState),
+        Some(state) => {
+            let listed: BTreeSet<String> = state
+                .wad_fingerprints
+                .keys()
+                .map(|wad| overlay_wad_rel_path(Utf8Path::new(wad), output_extension).into_string())
+                .collect();
+            for wad in listed.difference(&on_disk) {
+                issues.push(ValidationIssue::MissingWad { wad: wad.clone() });
+            }
+            for wad in on_disk.difference(&listed) {
+                issues.push(ValidationIssue::UnlistedWad { wad: wad.clone() });
+            }
+        }
+    }
+
+    let per_wad: Vec<(usize, Vec<ValidationIssue>)> = on_disk
+        .par_iter()
+        .map(|wad| check_wad(wad, &overlay_root.join(wad)))
+        .collect();
+
+    let mut chunks_checked = 0;
+    for (chunks, mut wad_issues) in per_wad {
+        chunks_checked += chunks;
+        issues.append(&mut wad_issues);
+    }
+
+    Ok(OverlayValidation {
+        wads_checked: on_disk.len(),
+        chunks_checked,
+        issues,
+    })
+}
+
+/// Mount one WAD and check every chunk. Returns the number of chunks checked.
+fn check_wad(wad: &str, path: &Utf8Path) -> (usize, Vec<ValidationIssue>) {
+    let unmountable = |error: String| ValidationIssue::Unmountable {
+        wad: wad.to_string(),
+        error,
+    };
+
+    let file = match File::open(path.as_std_path()) {
+        Ok(file) => file,
+        Err(e) => return (0, vec![unmountable(e.to_string())]),
+    };
+    let mut mounted = match Wad::mount(BufReader::new(file)) {
+        Ok(mounted) => mounted,
+        Err(e) => return (0, vec![unmountable(e.to_string())]),
+    };
+
+    let chunks: Vec<_> = mounted.chunks().iter().copied().collect();
+    if chunks.is_empty() {
+        return (
+            0,
+            vec![ValidationIssue::EmptyWad {
+                wad: wad.to_string(),
+            }],
+        );
+    }
+
+    let mut issues = Vec::new();
+    for chunk in &chunks {
+        match mounted.load_chunk_raw(chunk) {
+            Ok(raw) => {
+                let actual = xxh3_64(&raw);
+                if chunk.checksum != 0 && actual != chunk.checksum {
+                    issues.push(ValidationIssue::ChecksumMismatch {
+                        wad: wad.to_string(),
+                        path_hash: chunk.path_hash,
+                        expected: chunk.checksum,
+                        actual,
+                    });
+                }
+            }
+            Err(e) => issues.push(ValidationIssue::UnreadableChunk {
+                wad: wad.to_string(),
+                path_hash: chunk.path_hash,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    (chunks.len(), issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ltk_wad::{WadBuilder, WadChunkBuilder, WadChunkCompression};
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    const WAD: &str = "DATA/FINAL/Champions/Test.wad.client";
+
+    fn write_wad(path: &Utf8Path, chunk_paths: &[&str]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut builder = WadBuilder::default();
+        for chunk_path in chunk_paths {
+            builder = builder.with_chunk(
+                WadChunkBuilder::default()
+                    .with_path(chunk_path)
+                    .with_force_compression(WadChunkCompression::None),
+            );
+        }
+        let mut file = File::create(path).unwrap();
+        builder
+            .build_to_writer(&mut file, |_hash, out| {
+                out.write_all(b"payload")?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    fn write_state(root: &Utf8Path, wads: &[&str]) {
+        let fingerprints: BTreeMap<String, u64> = wads.iter().map(|w| (w.to_string(), 1)).collect();
+        OverlayState::new(Vec::new(), 0, Vec::new(), fingerprints)
+            .save(&root.join("overlay.json"))
+            .unwrap();
+    }
+
+    #[test]
+    fn consistent_overlay_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        write_wad(&root.join(WAD), &["data/a.bin", "data/b.bin"]);
+        write_state(root, &[WAD]);
+
+        let report = validate(root).unwrap();
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.wads_checked, 1);
+        assert_eq!(report.chunks_checked, 2);
+    }
+
+    #[test]
+    fn reports_corruption_and_state_mismatches() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let wad_path = root.join(WAD);
+        write_wad(&wad_path, &["data/a.bin"]);
+        write_wad(&root.join("DATA/FINAL/Empty.wad.client"), &[]);
+        write_state(root, &[WAD, "DATA/FINAL/Gone.wad.client"]);
+
+        // Flip the last byte of the only chunk's data.
+        let mut bytes = std::fs::read(&wad_path).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&wad_path, bytes).unwrap();
+
+        let report = validate(root).unwrap();
+        let kinds: Vec<&str> = report
+            .issues
+            .iter()
+            .map(|issue| match issue {
+                ValidationIssue::MissingWad { .. } => "missing",
+                ValidationIssue::UnlistedWad { .. } => "unlisted",
+                ValidationIssue::EmptyWad { .. } => "empty",
+                ValidationIssue::ChecksumMismatch { .. } => "checksum",
+                other => panic!("unexpected issue: {other:?}"),
+            })
+            .collect();
+        assert_eq!(kinds, vec!["missing", "unlisted", "checksum", "empty"]);
+        assert_eq!(report.wads_checked, 2);
+    }
+
+    #[test]
+    fn missing_state_and_garbage_wad_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        std::fs::create_dir_all(root.join("DATA/FINAL")).unwrap();
+        std::fs::write(root.join("DATA/FINAL/Bad.wad.client"), b"not a wad").unwrap();
+
+        let report = validate(root).unwrap();
+        assert_eq!(report.issues[0], ValidationIssue::MissingState);
+        assert!(matches!(
+            &report.issues[1],
+            ValidationIssue::Unmountable { wad, .. } if wad == "DATA/FINAL/Bad.wad.client"
+        ));
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! The core function is [`build_patched_wad`], which takes an original game WAD file
 //! and a set of override chunks, and produces a new WAD file containing all original
-//! chunks plus the overrides.
+//! chunks plus the overrides. [`build_patched_wad_to_writer`] does the same into any
+//! seekable writer, e.g. an in-memory buffer.
 //!
 //! # Compression Strategy
 //!
@@ -15,20 +16,40 @@
 //! the ideal compression:
 //!
 //! - **Audio files** (Wwise Bank / Wwise Package): stored uncompressed (`None`).
-//! - **Everything else**: compressed with Zstd at level 3.
+//! - **Everything else**: compressed with Zstd, at level 3 unless
+//!   [`WadPatchOptions::compression_level`] says otherwise.
+//!
+//! The overlay builder can pin an override's compression instead, from the mod's
+//! [`ModProject::compression`](ltk_mod_project::ModProject::compression) hints.
+//!
+//! # Small Overrides
+//!
+//! Mods with hundreds of tiny config overrides pay Zstd's per-frame overhead on
+//! each of them. Several small chunks cannot share a frame: the game reads every
+//! chunk on its own, and the multi-frame `ZstdMulti` type only splits a single
+//! chunk into frames (described by the WAD's SubChunkTOC, which mods must not
+//! touch). What a build can do is opt in to
+//! [`WadPatchOptions::tune_small_overrides`], which treats every override of at
+//! most [`SMALL_OVERRIDE_MAX_SIZE`] bytes specially:
+//!
+//! - It is compressed at level 19 or the configured level, whichever is higher.
+//!   At these sizes the higher level costs next to nothing. The Zstd window
+//!   already covers the whole chunk, so raising it would gain nothing.
+//! - It is stored uncompressed if Zstd does not make it smaller, unless its
+//!   compression is pinned.
 
 use crate::error::{Error, Result};
 use byteorder::{WriteBytesExt, LE};
 use camino::Utf8Path;
 use ltk_file::LeagueFileKind;
 use ltk_wad::{FileExt as _, Wad, WadChunk, WadChunkCompression};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufWriter, Cursor, Seek, SeekFrom, Write};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use xxhash_rust::xxh3::xxh3_64;
 
 /// Size of a single v3.4 WAD TOC entry.
-const TOC_ENTRY_SIZE: usize = 32;
+pub(crate) const TOC_ENTRY_SIZE: usize = 32;
 
 /// Write buffer size for the output WAD
 const WRITE_BUFFER_SIZE: usize = 1 << 20; // 1 MiB
@@ -44,17 +65,102 @@ pub struct PatchedWadStats {
     pub new_entries_added: usize,
     /// Number of chunks passed through unchanged from the original WAD.
     pub chunks_passed_through: usize,
+    /// Number of chunks in the source WAD.
+    pub original_chunks: usize,
+    /// Size of the output WAD in bytes.
+    pub bytes_written: u64,
     /// Wall-clock time to build this WAD, in milliseconds.
     pub elapsed_ms: u128,
 }
 
+/// Zstd level used for override chunks unless [`WadPatchOptions`] sets another.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// Largest override, in bytes, that [`WadPatchOptions::tune_small_overrides`]
+/// applies to.
+pub const SMALL_OVERRIDE_MAX_SIZE: usize = 4096;
+
+/// Minimum Zstd level for small overrides when they are tuned.
+const SMALL_OVERRIDE_ZSTD_LEVEL: i32 = 19;
+
+/// Options controlling how [`build_patched_wad_with_options`] lays out the output WAD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WadPatchOptions {
+    /// Write chunk data in the source WAD's original data order instead of by
+    /// path hash. New entries are appended after all original chunks.
+    ///
+    /// The TOC itself is always sorted by path hash, since the game looks
+    /// entries up by binary search; this only affects where chunk data lands.
+    pub preserve_order: bool,
+    /// Zstd level for override chunks compressed with Zstd. Higher levels give
+    /// smaller WADs and slower builds. Must be within
+    /// [`zstd::compression_level_range`]; defaults to [`DEFAULT_ZSTD_LEVEL`].
+    pub compression_level: i32,
+    /// After writing a WAD file, read every chunk back and check it against the
+    /// xxh3 checksum in its TOC entry. Costs a second pass over the output; has no
+    /// effect on the `_to_writer` variants, which cannot read their output back.
+    pub verify_output: bool,
+    /// Compress overrides of at most [`SMALL_OVERRIDE_MAX_SIZE`] bytes at a high
+    /// Zstd level, and store them uncompressed when that does not shrink them.
+    /// See the [module docs](self#small-overrides) for its constraints.
+    pub tune_small_overrides: bool,
+}
+
+impl Default for WadPatchOptions {
+    fn default() -> Self {
+        Self {
+            preserve_order: false,
+            compression_level: DEFAULT_ZSTD_LEVEL,
+            verify_output: false,
+            tune_small_overrides: false,
+        }
+    }
+}
+
+impl WadPatchOptions {
+    pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
+        self.preserve_order = preserve_order;
+        self
+    }
+
+    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    pub fn with_verify_output(mut self, verify_output: bool) -> Self {
+        self.verify_output = verify_output;
+        self
+    }
+
+    pub fn with_tune_small_overrides(mut self, tune_small_overrides: bool) -> Self {
+        self.tune_small_overrides = tune_small_overrides;
+        self
+    }
+
+    /// Check that the options can be used to write a WAD.
+    pub(crate) fn validate(&self) -> Result<()> {
+        let levels = zstd::compression_level_range();
+        if !levels.contains(&self.compression_level) {
+            return Err(Error::Other(format!(
+                "Zstd compression level {} is out of range ({}..={})",
+                self.compression_level,
+                levels.start(),
+                levels.end()
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Build a patched WAD by overlaying mod chunks on top of an original game WAD.
 ///
-/// The output WAD preserves the original chunk order and contains *all* chunks from
-/// the source — those present in `override_hashes` get their data replaced, everything
-/// else is passed through as raw bytes from the original. Override hashes that don't
-/// exist in the source WAD are treated as **new entries** and inserted at the correct
-/// sorted position in the TOC.
+/// The output WAD contains *all* chunks from the source — those present in
+/// `override_hashes` get their data replaced, everything else is passed through as
+/// raw bytes from the original. Override hashes that don't exist in the source WAD
+/// are treated as **new entries** and inserted at the correct sorted position in the
+/// TOC. Chunk data is written in TOC (path hash) order; see
+/// [`WadPatchOptions::preserve_order`] to keep the source layout instead.
 ///
 /// Parent directories for `dst_wad_path` are created automatically.
 ///
@@ -77,8 +183,151 @@ pub fn build_patched_wad<B: AsRef<[u8]>>(
     src_wad_path: &Utf8Path,
     dst_wad_path: &Utf8Path,
     override_hashes: &HashSet<u64>,
+    resolve_override: impl FnMut(u64) -> Result<B>,
+) -> Result<PatchedWadStats> {
+    build_patched_wad_with_options(
+        src_wad_path,
+        dst_wad_path,
+        override_hashes,
+        WadPatchOptions::default(),
+        resolve_override,
+    )
+}
+
+/// Like [`build_patched_wad`], with explicit [`WadPatchOptions`].
+pub fn build_patched_wad_with_options<B: AsRef<[u8]>>(
+    src_wad_path: &Utf8Path,
+    dst_wad_path: &Utf8Path,
+    override_hashes: &HashSet<u64>,
+    options: WadPatchOptions,
+    resolve_override: impl FnMut(u64) -> Result<B>,
+) -> Result<PatchedWadStats> {
+    build_patched_wad_pinned(
+        src_wad_path,
+        dst_wad_path,
+        override_hashes,
+        options,
+        &HashMap::new(),
+        resolve_override,
+    )
+}
+
+/// Like [`build_patched_wad_with_options`], but overrides listed in
+/// `compression_pins` are written with the given compression instead of the one
+/// picked from their file type.
+pub(crate) fn build_patched_wad_pinned<B: AsRef<[u8]>>(
+    src_wad_path: &Utf8Path,
+    dst_wad_path: &Utf8Path,
+    override_hashes: &HashSet<u64>,
+    options: WadPatchOptions,
+    compression_pins: &HashMap<u64, WadChunkCompression>,
+    resolve_override: impl FnMut(u64) -> Result<B>,
+) -> Result<PatchedWadStats> {
+    if let Some(parent) = dst_wad_path.parent() {
+        std::fs::create_dir_all(parent.as_std_path())?;
+    }
+
+    let mut writer =
+        BufWriter::with_capacity(WRITE_BUFFER_SIZE, File::create(dst_wad_path.as_std_path())?);
+    let stats = write_patched_wad(
+        src_wad_path,
+        &mut writer,
+        override_hashes,
+        options,
+        compression_pins,
+        resolve_override,
+    )?;
+    writer.flush()?;
+    drop(writer);
+
+    if options.verify_output {
+        let file = File::open(dst_wad_path.as_std_path())?;
+        verify_wad_checksums(BufReader::new(file))
+            .map_err(|e| Error::Other(format!("Verifying '{}' failed: {}", dst_wad_path, e)))?;
+    }
+
+    tracing::info!(
+        "Patched WAD complete dst={} chunks={} overrides={} new={} passed_through={} elapsed_ms={}",
+        dst_wad_path,
+        stats.chunks_written,
+        stats.overrides_applied,
+        stats.new_entries_added,
+        stats.chunks_passed_through,
+        stats.elapsed_ms
+    );
+
+    Ok(stats)
+}
+
+/// Build a patched WAD into an arbitrary seekable writer.
+///
+/// Same as [`build_patched_wad`], but the output goes to `writer` instead of a
+/// file, e.g. a `Cursor<Vec<u8>>` for in-memory patching. The WAD is written
+/// starting at the writer's current position (offsets in the TOC are relative
+/// to it), and the writer is left positioned at the end of the WAD. The writer
+/// is not flushed.
+pub fn build_patched_wad_to_writer<W: Write + Seek, B: AsRef<[u8]>>(
+    src_wad_path: &Utf8Path,
+    writer: W,
+    override_hashes: &HashSet<u64>,
+    resolve_override: impl FnMut(u64) -> Result<B>,
+) -> Result<PatchedWadStats> {
+    build_patched_wad_to_writer_with_options(
+        src_wad_path,
+        writer,
+        override_hashes,
+        WadPatchOptions::default(),
+        resolve_override,
+    )
+}
+
+/// Like [`build_patched_wad_to_writer`], with explicit [`WadPatchOptions`].
+pub fn build_patched_wad_to_writer_with_options<W: Write + Seek, B: AsRef<[u8]>>(
+    src_wad_path: &Utf8Path,
+    writer: W,
+    override_hashes: &HashSet<u64>,
+    options: WadPatchOptions,
+    resolve_override: impl FnMut(u64) -> Result<B>,
+) -> Result<PatchedWadStats> {
+    write_patched_wad(
+        src_wad_path,
+        writer,
+        override_hashes,
+        options,
+        &HashMap::new(),
+        resolve_override,
+    )
+}
+
+/// Number of chunks in a patched WAD, which its header stores as a `u32`.
+fn patched_chunk_count(base_chunks: usize, new_chunks: usize) -> Result<u32> {
+    base_chunks
+        .checked_add(new_chunks)
+        .and_then(|count| u32::try_from(count).ok())
+        .ok_or_else(|| {
+            Error::Other(format!(
+                "Patched WAD would hold {} original and {} new chunks, more than the \
+                 {} the WAD v3.4 format can index",
+                base_chunks,
+                new_chunks,
+                u32::MAX
+            ))
+        })
+}
+
+/// Core of the `build_patched_wad*` family.
+///
+/// `compression_pins` overrides the type-based compression choice for the
+/// override hashes it contains.
+fn write_patched_wad<W: Write + Seek, B: AsRef<[u8]>>(
+    src_wad_path: &Utf8Path,
+    mut writer: W,
+    override_hashes: &HashSet<u64>,
+    options: WadPatchOptions,
+    compression_pins: &HashMap<u64, WadChunkCompression>,
     mut resolve_override: impl FnMut(u64) -> Result<B>,
 ) -> Result<PatchedWadStats> {
+    options.validate()?;
     let start = std::time::Instant::now();
 
     let file = File::open(src_wad_path.as_std_path())?;
@@ -93,13 +342,13 @@ pub fn build_patched_wad<B: AsRef<[u8]>>(
         .copied()
         .collect();
     new_hashes.sort();
+    let chunk_count = patched_chunk_count(chunks.len(), new_hashes.len())?;
 
     if !new_hashes.is_empty() {
         tracing::info!(
-            "Adding {} new entry/entries to WAD (src={} dst={})",
+            "Adding {} new entry/entries to WAD (src={})",
             new_hashes.len(),
-            src_wad_path,
-            dst_wad_path
+            src_wad_path
         );
     }
 
@@ -112,41 +361,38 @@ pub fn build_patched_wad<B: AsRef<[u8]>>(
     }
     let new_entries_added = new_hashes.len();
 
-    let mut overrides_applied = 0usize;
+    // Order in which chunk data is written. Defaults to TOC (hash) order.
+    let write_order: Vec<u64> = if options.preserve_order {
+        let mut by_offset: Vec<&WadChunk> = chunks.iter().collect();
+        by_offset.sort_by_key(|c| (c.data_offset, c.path_hash));
+        by_offset
+            .into_iter()
+            .map(|c| c.path_hash)
+            .chain(new_hashes.iter().copied())
+            .collect()
+    } else {
+        ordered
+    };
 
-    if let Some(parent) = dst_wad_path.parent() {
-        std::fs::create_dir_all(parent.as_std_path())?;
-    }
-
-    let mut writer = BufWriter::with_capacity(
-        WRITE_BUFFER_SIZE,
-        std::fs::File::create(dst_wad_path.as_std_path())?,
-    );
-
-    // Write header
-    writer.write_u16::<LE>(0x5752)?; // "RW" magic
-    writer.write_u8(3)?; // major version
-    writer.write_u8(4)?; // minor version
+    let mut overrides_applied = 0usize;
 
-    // Write dummy ECDSA signature (256 bytes) + checksum (8 bytes)
-    writer.write_all(&[0u8; 256])?;
-    writer.write_u64::<LE>(0)?;
+    // All offsets in the WAD are relative to where it starts in the writer.
+    let wad_start = writer.stream_position()?;
 
-    // Write chunk count
-    writer.write_u32::<LE>(ordered.len() as u32)?;
+    let toc_offset = write_wad_header(&mut writer, chunk_count)?;
 
     // Write dummy TOC (TOC_ENTRY_SIZE bytes per chunk) — overwritten with real offsets later.
-    let toc_offset = writer.stream_position()?;
-    for _ in &ordered {
+    for _ in &write_order {
         writer.write_all(&[0u8; TOC_ENTRY_SIZE])?;
     }
 
-    let mut data_offset: u64 = toc_offset + (ordered.len() as u64) * TOC_ENTRY_SIZE as u64;
+    let mut data_offset: u64 =
+        toc_offset - wad_start + (write_order.len() as u64) * TOC_ENTRY_SIZE as u64;
 
     // Write chunk data and build final TOC entries
-    let mut final_chunks: Vec<WadChunk> = Vec::with_capacity(ordered.len());
+    let mut final_chunks: Vec<WadChunk> = Vec::with_capacity(write_order.len());
 
-    for &path_hash in &ordered {
+    for &path_hash in &write_order {
         if data_offset > u32::MAX as u64 {
             return Err(Error::Other(format!(
                 "Patched WAD exceeds the 4 GiB limit of the WAD v3.4 format \
@@ -160,9 +406,23 @@ pub fn build_patched_wad<B: AsRef<[u8]>>(
             let override_data = override_bytes.as_ref();
             overrides_applied += 1;
 
-            let kind = LeagueFileKind::identify_from_bytes(override_data);
-            let compression = kind.ideal_compression();
-            let compressed = compress_by_type(override_data, compression)?;
+            let pinned = compression_pins.get(&path_hash).copied();
+            let mut compression = match pinned {
+                Some(pinned) => pinned,
+                None => LeagueFileKind::identify_from_bytes(override_data).ideal_compression(),
+            };
+            let small =
+                options.tune_small_overrides && override_data.len() <= SMALL_OVERRIDE_MAX_SIZE;
+            let level = if small {
+                options.compression_level.max(SMALL_OVERRIDE_ZSTD_LEVEL)
+            } else {
+                options.compression_level
+            };
+            let mut compressed = compress_by_type(override_data, compression, level)?;
+            if small && pinned.is_none() && compressed.len() >= override_data.len() {
+                compression = WadChunkCompression::None;
+                compressed = override_data.to_vec();
+            }
 
             if compressed.len() > u32::MAX as usize || override_data.len() > u32::MAX as usize {
                 return Err(Error::Other(format!(
@@ -220,43 +480,71 @@ pub fn build_patched_wad<B: AsRef<[u8]>>(
         data_offset += bytes_written as u64;
     }
 
-    // Seek back and write final TOC
+    // Seek back and write final TOC (always sorted by path hash)
+    if options.preserve_order {
+        final_chunks.sort_unstable_by_key(|c| c.path_hash);
+    }
     writer.seek(SeekFrom::Start(toc_offset))?;
     for chunk in &final_chunks {
         chunk.write_v3_4(&mut writer)?;
     }
-
-    writer.flush()?;
+    writer.seek(SeekFrom::Start(wad_start + data_offset))?;
 
     let elapsed_ms = start.elapsed().as_millis();
-    let chunks_passed_through = ordered.len() - overrides_applied;
-
-    tracing::info!(
-        "Patched WAD complete dst={} chunks={} overrides={} new={} passed_through={} elapsed_ms={}",
-        dst_wad_path,
-        ordered.len(),
-        overrides_applied,
-        new_entries_added,
-        chunks_passed_through,
-        elapsed_ms
-    );
+    let chunks_passed_through = write_order.len() - overrides_applied;
 
     Ok(PatchedWadStats {
-        chunks_written: ordered.len(),
+        chunks_written: write_order.len(),
         overrides_applied,
         new_entries_added,
         chunks_passed_through,
+        original_chunks: chunks.len(),
+        bytes_written: data_offset,
         elapsed_ms,
     })
 }
 
-/// Compress data using the specified compression type.
-fn compress_by_type(data: &[u8], compression: WadChunkCompression) -> Result<Vec<u8>> {
+/// Write a v3.4 WAD header for `chunk_count` chunks, with a zeroed signature
+/// and checksum, and return the stream position its TOC starts at.
+pub(crate) fn write_wad_header<W: Write + Seek>(writer: &mut W, chunk_count: u32) -> Result<u64> {
+    writer.write_u16::<LE>(0x5752)?; // "RW" magic
+    writer.write_u8(3)?; // major version
+    writer.write_u8(4)?; // minor version
+
+    // Dummy ECDSA signature (256 bytes) + checksum (8 bytes)
+    writer.write_all(&[0u8; 256])?;
+    writer.write_u64::<LE>(0)?;
+
+    writer.write_u32::<LE>(chunk_count)?;
+    Ok(writer.stream_position()?)
+}
+
+/// Check every chunk of the WAD in `reader` against its TOC checksum.
+///
+/// Chunks with a zero checksum carry none and are skipped.
+pub(crate) fn verify_wad_checksums<R: Read + Seek>(reader: R) -> Result<()> {
+    let mut wad = Wad::mount(reader)?;
+    let chunks: Vec<WadChunk> = wad.chunks().iter().copied().collect();
+    for chunk in &chunks {
+        let raw = wad.load_chunk_raw(chunk)?;
+        let actual = xxh3_64(&raw);
+        if chunk.checksum != 0 && actual != chunk.checksum {
+            return Err(Error::Other(format!(
+                "chunk {:016x} has checksum {:016x}, expected {:016x}",
+                chunk.path_hash, actual, chunk.checksum
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Compress data using the specified compression type, at `level` for Zstd.
+fn compress_by_type(data: &[u8], compression: WadChunkCompression, level: i32) -> Result<Vec<u8>> {
     match compression {
         WadChunkCompression::None => Ok(data.to_vec()),
         WadChunkCompression::Zstd => {
             let mut out = Vec::new();
-            let mut encoder = zstd::Encoder::new(BufWriter::new(&mut out), 3)?;
+            let mut encoder = zstd::Encoder::new(BufWriter::new(&mut out), level)?;
             encoder.write_all(data)?;
             encoder.finish()?;
             Ok(out)
@@ -274,14 +562,298 @@ mod tests {
     #[test]
     fn test_compress_by_type_none() {
         let data = b"Hello, world!";
-        let result = compress_by_type(data, WadChunkCompression::None).unwrap();
+        let result = compress_by_type(data, WadChunkCompression::None, DEFAULT_ZSTD_LEVEL).unwrap();
         assert_eq!(result, data);
     }
 
     #[test]
     fn test_compress_by_type_zstd() {
         let data = b"Hello, world!".repeat(100);
-        let compressed = compress_by_type(&data, WadChunkCompression::Zstd).unwrap();
+        let compressed =
+            compress_by_type(&data, WadChunkCompression::Zstd, DEFAULT_ZSTD_LEVEL).unwrap();
         assert!(compressed.len() < data.len());
     }
+
+    #[test]
+    fn test_higher_compression_level_is_not_larger() {
+        let data: Vec<u8> = (0..64 * 1024u32)
+            .map(|i| (i % 251) as u8 ^ (i / 1024) as u8)
+            .collect();
+        let fast = compress_by_type(&data, WadChunkCompression::Zstd, 1).unwrap();
+        let small = compress_by_type(&data, WadChunkCompression::Zstd, 19).unwrap();
+        assert!(small.len() <= fast.len());
+        assert!(fast.len() < data.len());
+    }
+
+    #[test]
+    fn test_out_of_range_compression_level_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10]);
+
+        let result = build_patched_wad_with_options(
+            &src,
+            &root.join("dst.wad.client"),
+            &HashSet::from([10]),
+            WadPatchOptions::default().with_compression_level(100),
+            |hash: u64| Ok(hash.to_be_bytes().to_vec()),
+        );
+        assert!(matches!(result, Err(Error::Other(message)) if message.contains("100")));
+    }
+
+    #[test]
+    fn test_chunk_count_overflow_is_rejected() {
+        assert_eq!(patched_chunk_count(10, 2).unwrap(), 12);
+        assert_eq!(
+            patched_chunk_count(u32::MAX as usize - 1, 1).unwrap(),
+            u32::MAX
+        );
+        let result = patched_chunk_count(u32::MAX as usize, 1);
+        assert!(matches!(result, Err(Error::Other(message)) if message.contains("4294967295")));
+        assert!(patched_chunk_count(usize::MAX, 1).is_err());
+    }
+
+    /// Write an uncompressed v3.4 WAD whose chunk data is laid out in reverse
+    /// hash order, so data order and TOC order differ.
+    fn write_source_wad(path: &Utf8Path, hashes: &[u64]) {
+        let mut hashes = hashes.to_vec();
+        hashes.sort_unstable();
+
+        let mut out = Vec::new();
+        out.write_u16::<LE>(0x5752).unwrap();
+        out.write_u8(3).unwrap();
+        out.write_u8(4).unwrap();
+        out.write_all(&[0u8; 256]).unwrap();
+        out.write_u64::<LE>(0).unwrap();
+        out.write_u32::<LE>(hashes.len() as u32).unwrap();
+
+        let data_start = out.len() + hashes.len() * TOC_ENTRY_SIZE;
+        let chunk_offset = |hash: &u64| {
+            let rank = hashes.len() - 1 - hashes.binary_search(hash).unwrap();
+            data_start + rank * 8
+        };
+        for hash in &hashes {
+            WadChunk {
+                path_hash: *hash,
+                data_offset: chunk_offset(hash),
+                compressed_size: 8,
+                uncompressed_size: 8,
+                compression_type: WadChunkCompression::None,
+                is_duplicated: false,
+                frame_count: 0,
+                start_frame: 0,
+                checksum: xxh3_64(&hash.to_le_bytes()),
+            }
+            .write_v3_4(&mut out)
+            .unwrap();
+        }
+        for hash in hashes.iter().rev() {
+            out.write_all(&hash.to_le_bytes()).unwrap();
+        }
+        std::fs::write(path.as_std_path(), out).unwrap();
+    }
+
+    fn data_order(path: &Utf8Path) -> (Vec<u64>, Vec<u64>) {
+        let bytes = std::fs::read(path.as_std_path()).unwrap();
+        let wad = Wad::mount(Cursor::new(bytes)).unwrap();
+        let toc: Vec<u64> = wad.chunks().iter().map(|c| c.path_hash).collect();
+        let mut by_offset: Vec<&WadChunk> = wad.chunks().iter().collect();
+        by_offset.sort_by_key(|c| c.data_offset);
+        (toc, by_offset.into_iter().map(|c| c.path_hash).collect())
+    }
+
+    #[test]
+    fn test_preserve_order_keeps_source_data_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10, 20, 30, 40, 50]);
+        let (src_toc, src_layout) = data_order(&src);
+
+        let new_hash = 0x0123_4567_89ab_cdef;
+        let overrides: HashSet<u64> = [src_toc[2], new_hash].into_iter().collect();
+        let resolve = |hash: u64| Ok(hash.to_be_bytes().to_vec());
+
+        let sorted_dst = root.join("sorted.wad.client");
+        build_patched_wad(&src, &sorted_dst, &overrides, resolve).unwrap();
+        let (sorted_toc, sorted_layout) = data_order(&sorted_dst);
+        assert!(sorted_toc.is_sorted());
+        assert_eq!(sorted_layout, sorted_toc);
+
+        let preserved_dst = root.join("preserved.wad.client");
+        build_patched_wad_with_options(
+            &src,
+            &preserved_dst,
+            &overrides,
+            WadPatchOptions::default().with_preserve_order(true),
+            resolve,
+        )
+        .unwrap();
+        let (preserved_toc, preserved_layout) = data_order(&preserved_dst);
+
+        assert_eq!(preserved_toc, sorted_toc);
+        let mut expected_layout = src_layout;
+        expected_layout.push(new_hash);
+        assert_eq!(preserved_layout, expected_layout);
+    }
+
+    #[test]
+    fn test_to_writer_matches_file_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10, 20, 30]);
+
+        let overrides: HashSet<u64> = [20, 99].into_iter().collect();
+        let resolve = |hash: u64| Ok(hash.to_be_bytes().to_vec());
+
+        let dst = root.join("out/patched.wad.client");
+        build_patched_wad(&src, &dst, &overrides, resolve).unwrap();
+        let on_disk = std::fs::read(dst.as_std_path()).unwrap();
+
+        // Start mid-stream to check offsets are relative to the WAD start.
+        let mut cursor = Cursor::new(b"prefix".to_vec());
+        cursor.seek(SeekFrom::End(0)).unwrap();
+        let stats = build_patched_wad_to_writer(&src, &mut cursor, &overrides, resolve).unwrap();
+        assert_eq!(stats.chunks_written, 4);
+        assert_eq!(cursor.position() as usize, cursor.get_ref().len());
+
+        let in_memory = cursor.into_inner();
+        assert_eq!(&in_memory[..6], b"prefix");
+        assert_eq!(&in_memory[6..], &on_disk[..]);
+
+        let mut wad = Wad::mount(Cursor::new(&in_memory[6..])).unwrap();
+        let chunk = *wad.chunks().get(99).unwrap();
+        assert_eq!(
+            &*wad.load_chunk_decompressed(&chunk).unwrap(),
+            &99u64.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_compression_pins_override_type_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10, 20, 30]);
+
+        let overrides: HashSet<u64> = [10, 20].into_iter().collect();
+        let pins = HashMap::from([(20, WadChunkCompression::None)]);
+        let dst = root.join("pinned.wad.client");
+        build_patched_wad_pinned(
+            &src,
+            &dst,
+            &overrides,
+            WadPatchOptions::default(),
+            &pins,
+            |_| Ok(vec![7u8; 64]),
+        )
+        .unwrap();
+
+        let wad = Wad::mount(Cursor::new(std::fs::read(dst.as_std_path()).unwrap())).unwrap();
+        let compression = |hash| wad.chunks().get(hash).unwrap().compression_type;
+        assert_eq!(compression(10), WadChunkCompression::Zstd);
+        assert_eq!(compression(20), WadChunkCompression::None);
+    }
+
+    #[test]
+    fn test_tune_small_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10, 20, 30]);
+
+        let overrides: HashSet<u64> = [10, 20, 30].into_iter().collect();
+        let data = |hash: u64| match hash {
+            10 => hash.to_be_bytes().to_vec(),
+            20 => b"mSpellName: string = \"Q\"\n".repeat(64),
+            _ => vec![7u8; SMALL_OVERRIDE_MAX_SIZE * 2],
+        };
+        let build = |options: WadPatchOptions, pins: &HashMap<u64, WadChunkCompression>| {
+            let dst = root.join("tuned.wad.client");
+            build_patched_wad_pinned(&src, &dst, &overrides, options, pins, |hash| Ok(data(hash)))
+                .unwrap();
+            Wad::mount(Cursor::new(std::fs::read(dst.as_std_path()).unwrap())).unwrap()
+        };
+        let tuned = WadPatchOptions::default().with_tune_small_overrides(true);
+
+        let mut wad = build(tuned, &HashMap::new());
+        let chunk = |wad: &Wad<_>, hash| *wad.chunks().get(hash).unwrap();
+        // Zstd would grow the 8-byte override, so it is stored as is.
+        assert_eq!(chunk(&wad, 10).compression_type, WadChunkCompression::None);
+        assert_eq!(chunk(&wad, 20).compression_type, WadChunkCompression::Zstd);
+        assert_eq!(chunk(&wad, 30).compression_type, WadChunkCompression::Zstd);
+        for hash in [10, 20, 30] {
+            let chunk = chunk(&wad, hash);
+            assert_eq!(&*wad.load_chunk_decompressed(&chunk).unwrap(), &data(hash));
+        }
+
+        let untuned = build(WadPatchOptions::default(), &HashMap::new());
+        assert_eq!(
+            chunk(&untuned, 10).compression_type,
+            WadChunkCompression::Zstd
+        );
+        assert!(chunk(&wad, 20).compressed_size <= chunk(&untuned, 20).compressed_size);
+
+        // A pinned compression is kept even when it does not pay off.
+        let pins = HashMap::from([(10, WadChunkCompression::Zstd)]);
+        wad = build(tuned, &pins);
+        assert_eq!(chunk(&wad, 10).compression_type, WadChunkCompression::Zstd);
+    }
+
+    /// Writer that flips the byte written at offset `at`.
+    struct CorruptingWriter<W> {
+        inner: W,
+        at: u64,
+    }
+
+    impl<W: Write + Seek> Write for CorruptingWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let pos = self.inner.stream_position()?;
+            let mut buf = buf.to_vec();
+            if (pos..pos + buf.len() as u64).contains(&self.at) {
+                buf[(self.at - pos) as usize] ^= 0xFF;
+            }
+            self.inner.write(&buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl<W: Seek> Seek for CorruptingWriter<W> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_verify_output_detects_corrupted_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let src = root.join("src.wad.client");
+        write_source_wad(&src, &[10, 20, 30]);
+
+        let overrides: HashSet<u64> = [20].into_iter().collect();
+        let resolve = |hash: u64| Ok(hash.to_be_bytes().to_vec());
+
+        let dst = root.join("out/patched.wad.client");
+        let options = WadPatchOptions::default().with_verify_output(true);
+        build_patched_wad_with_options(&src, &dst, &overrides, options, resolve).unwrap();
+        let len = std::fs::metadata(dst.as_std_path()).unwrap().len();
+
+        // The last byte belongs to the data of the last chunk.
+        let mut writer = CorruptingWriter {
+            inner: Cursor::new(Vec::new()),
+            at: len - 1,
+        };
+        build_patched_wad_to_writer(&src, &mut writer, &overrides, resolve).unwrap();
+        let corrupted = writer.inner.into_inner();
+        assert_eq!(corrupted.len() as u64, len);
+
+        let err = verify_wad_checksums(Cursor::new(corrupted)).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err}");
+    }
 }
@@ -71,7 +71,7 @@ fn is_crl_oid(oid: &[u64]) -> bool {
     CRL_OIDS.contains(&oid)
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct SignerKeys {
     pub keys: Vec<(SubjectPublicKeyInfoOwned, RsaPrivateKey)>,
     pub certs: Vec<Certificate>,